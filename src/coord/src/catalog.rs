@@ -16,17 +16,18 @@ use anyhow::bail;
 use chrono::{DateTime, TimeZone, Utc};
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use log::{info, trace};
+use log::{info, trace, warn};
 use ore::collections::CollectionExt;
+use prometheus::{register_int_gauge, IntGauge};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use build_info::DUMMY_BUILD_INFO;
 use dataflow_types::{SinkConnector, SinkConnectorBuilder, SourceConnector};
 use expr::{ExprHumanizer, GlobalId, OptimizedRelationExpr, ScalarExpr};
-use repr::{ColumnType, RelationDesc, ScalarType};
+use repr::{ColumnName, ColumnType, RelationDesc, ScalarType};
 use sql::ast::display::AstDisplay;
-use sql::ast::Expr;
+use sql::ast::{Expr, Ident};
 use sql::catalog::{Catalog as SqlCatalog, CatalogError as SqlCatalogError};
 use sql::names::{DatabaseSpecifier, FullName, PartialName, SchemaName};
 use sql::plan::{Params, Plan, PlanContext};
@@ -54,6 +55,20 @@ const SYSTEM_CONN_ID: u32 = 0;
 // https://github.com/MaterializeInc/materialize/pull/4316#discussion_r496238962
 pub const FIRST_USER_OID: u32 = 20_000;
 
+/// The OID after which `allocate_oid` warns that the OID space is nearly
+/// exhausted, i.e., once 90% of the OIDs available to user objects have been
+/// allocated.
+const OID_HIGH_WATER_MARK: u32 = FIRST_USER_OID + (u32::MAX - FIRST_USER_OID) / 10 * 9;
+
+lazy_static! {
+    static ref OIDS_ALLOCATED: IntGauge = register_int_gauge!(
+        "mz_catalog_oids_allocated",
+        "Number of OIDs allocated since the server started, including those of \
+         dropped objects, which are only reclaimed by a restart"
+    )
+    .unwrap();
+}
+
 /// A `Catalog` keeps track of the SQL objects known to the planner.
 ///
 /// For each object, it keeps track of both forward and reverse dependencies:
@@ -86,6 +101,8 @@ pub struct Catalog {
     temporary_schemas: HashMap<u32, Schema>,
     storage: Arc<Mutex<storage::Connection>>,
     oid_counter: u32,
+    /// Incremented by every call to `transact`.
+    generation: u64,
     config: sql::catalog::CatalogConfig,
 }
 
@@ -94,7 +111,9 @@ pub struct ConnCatalog<'a> {
     catalog: &'a Catalog,
     conn_id: u32,
     database: String,
-    search_path: &'a [&'a str],
+    search_path: Vec<String>,
+    session_user: String,
+    current_user: String,
 }
 
 impl ConnCatalog<'_> {
@@ -128,6 +147,9 @@ pub struct CatalogEntry {
     id: GlobalId,
     oid: u32,
     name: FullName,
+    /// The comments attached by `COMMENT ON` to the item, under `None`, and
+    /// to its columns, under their 0-indexed positions.
+    comments: BTreeMap<Option<usize>, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +160,7 @@ pub enum CatalogItem {
     Sink(Sink),
     Index(Index),
     Type(Type),
+    Func(Func),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,6 +170,8 @@ pub struct Table {
     pub desc: RelationDesc,
     #[serde(skip)]
     pub defaults: Vec<Expr>,
+    pub conn_id: Option<u32>,
+    pub func_uses: Vec<GlobalId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -167,6 +192,15 @@ pub struct Sink {
     pub as_of: Option<u64>,
 }
 
+/// The state of a sink's connector.
+///
+/// A sink is first committed to the catalog as a placeholder in the `Pending`
+/// state, which reserves its ID and name while its connector is built outside
+/// of the coordinator's main loop. The placeholder is then replaced, in a
+/// single catalog transaction, either by the sink in the `Ready` state or by
+/// nothing at all. Placeholders are recorded as pending in the on-disk catalog
+/// until they are replaced, so that the coordinator can remove any that a crash
+/// orphaned when it next boots.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SinkConnectorState {
     Pending(SinkConnectorBuilder),
@@ -180,6 +214,7 @@ pub struct View {
     pub optimized_expr: OptimizedRelationExpr,
     pub desc: RelationDesc,
     pub conn_id: Option<u32>,
+    pub func_uses: Vec<GlobalId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -188,6 +223,8 @@ pub struct Index {
     pub plan_cx: PlanContext,
     pub on: GlobalId,
     pub keys: Vec<ScalarExpr>,
+    pub conn_id: Option<u32>,
+    pub func_uses: Vec<GlobalId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -197,6 +234,17 @@ pub struct Type {
     pub inner: TypeInner,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Func {
+    pub create_sql: String,
+    pub plan_cx: PlanContext,
+    pub params: RelationDesc,
+    pub return_type: ScalarType,
+    #[serde(skip, default = "Expr::null")]
+    pub body: Expr,
+    pub func_uses: Vec<GlobalId>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeInner {
     Array {
@@ -231,6 +279,7 @@ impl CatalogItem {
             CatalogItem::View(_) => "view",
             CatalogItem::Index(_) => "index",
             CatalogItem::Type(_) => "type",
+            CatalogItem::Func(_) => "function",
         }
     }
 
@@ -242,6 +291,20 @@ impl CatalogItem {
             CatalogItem::View(view) => Ok(&view.desc),
             CatalogItem::Index(_) => Err(SqlCatalogError::InvalidIndexDependency(name.to_string())),
             CatalogItem::Type(_) => Err(SqlCatalogError::InvalidTypeDependency(name.to_string())),
+            CatalogItem::Func(_) => Err(SqlCatalogError::InvalidFuncDependency(name.to_string())),
+        }
+    }
+
+    /// Returns the SQL statement that created this item.
+    pub fn create_sql(&self) -> &str {
+        match self {
+            CatalogItem::Table(Table { create_sql, .. }) => create_sql,
+            CatalogItem::Source(Source { create_sql, .. }) => create_sql,
+            CatalogItem::Sink(Sink { create_sql, .. }) => create_sql,
+            CatalogItem::View(View { create_sql, .. }) => create_sql,
+            CatalogItem::Index(Index { create_sql, .. }) => create_sql,
+            CatalogItem::Type(Type { create_sql, .. }) => create_sql,
+            CatalogItem::Func(Func { create_sql, .. }) => create_sql,
         }
     }
 
@@ -249,17 +312,26 @@ impl CatalogItem {
     /// upon.
     pub fn uses(&self) -> Vec<GlobalId> {
         match self {
-            CatalogItem::Table(_) => vec![],
+            CatalogItem::Table(table) => table.func_uses.clone(),
             CatalogItem::Source(_) => vec![],
             CatalogItem::Sink(sink) => vec![sink.from],
-            CatalogItem::View(view) => view.optimized_expr.as_ref().global_uses(),
-            CatalogItem::Index(idx) => vec![idx.on],
+            CatalogItem::View(view) => {
+                let mut uses = view.optimized_expr.as_ref().global_uses();
+                uses.extend(&view.func_uses);
+                uses
+            }
+            CatalogItem::Index(idx) => {
+                let mut uses = vec![idx.on];
+                uses.extend(&idx.func_uses);
+                uses
+            }
             CatalogItem::Type(typ) => match &typ.inner {
                 TypeInner::Array { element_id } => vec![*element_id],
                 TypeInner::Base { .. } => vec![],
                 TypeInner::List { element_id } => vec![*element_id],
                 TypeInner::Map { key_id, value_id } => vec![*key_id, *value_id],
             },
+            CatalogItem::Func(func) => func.func_uses.clone(),
         }
     }
 
@@ -271,7 +343,8 @@ impl CatalogItem {
             | CatalogItem::Source(_)
             | CatalogItem::View(_)
             | CatalogItem::Index(_)
-            | CatalogItem::Type(_) => false,
+            | CatalogItem::Type(_)
+            | CatalogItem::Func(_) => false,
             CatalogItem::Sink(s) => match s.connector {
                 SinkConnectorState::Pending(_) => true,
                 SinkConnectorState::Ready(_) => false,
@@ -283,7 +356,9 @@ impl CatalogItem {
     /// temporary.
     pub fn conn_id(&self) -> Option<u32> {
         match self {
+            CatalogItem::Table(table) => table.conn_id,
             CatalogItem::View(view) => view.conn_id,
+            CatalogItem::Index(index) => index.conn_id,
             _ => None,
         }
     }
@@ -339,6 +414,50 @@ impl CatalogItem {
                 Ok(CatalogItem::Index(i))
             }
             CatalogItem::Type(_) => unreachable!("types cannot be renamed"),
+            CatalogItem::Func(_) => unreachable!("functions cannot be renamed"),
+        }
+    }
+
+    /// Returns a clone of `self` with all references to the column `from` of
+    /// the view described by `desc` renamed to `to`.
+    ///
+    /// Only the view itself and the indexes on it refer to its columns by
+    /// name in a way that can be rewritten.
+    fn rename_column_refs(&self, desc: &RelationDesc, from: &str, to: &str) -> CatalogItem {
+        let columns: Vec<_> = desc
+            .iter_names()
+            .map(|name| Ident::new(name.map(|n| n.as_str()).unwrap_or("?column?")))
+            .collect();
+        let do_rewrite = |create_sql: &str| -> String {
+            let mut create_stmt = sql::parse::parse(create_sql).unwrap().into_element();
+            sql::ast::transform::create_stmt_rename_column(
+                &mut create_stmt,
+                &columns,
+                &Ident::new(from),
+                &Ident::new(to),
+            );
+            create_stmt.to_ast_string_stable()
+        };
+
+        match self {
+            CatalogItem::View(i) => {
+                let mut i = i.clone();
+                i.create_sql = do_rewrite(&i.create_sql);
+                i.desc = RelationDesc::new(
+                    i.desc.typ().clone(),
+                    i.desc.iter_names().map(|name| match name {
+                        Some(name) if name.as_str() == from => Some(ColumnName::from(to)),
+                        name => name.cloned(),
+                    }),
+                );
+                CatalogItem::View(i)
+            }
+            CatalogItem::Index(i) => {
+                let mut i = i.clone();
+                i.create_sql = do_rewrite(&i.create_sql);
+                CatalogItem::Index(i)
+            }
+            _ => unreachable!("only views and indexes refer to renamable columns"),
         }
     }
 }
@@ -384,6 +503,12 @@ impl CatalogEntry {
     pub fn used_by(&self) -> &[GlobalId] {
         &self.used_by
     }
+
+    /// Returns the comments attached to the item, under `None`, and to its
+    /// columns, under their 0-indexed positions.
+    pub fn comments(&self) -> &BTreeMap<Option<usize>, String> {
+        &self.comments
+    }
 }
 
 impl Catalog {
@@ -403,11 +528,13 @@ impl Catalog {
             temporary_schemas: HashMap::new(),
             storage: Arc::new(Mutex::new(storage)),
             oid_counter: FIRST_USER_OID,
+            generation: 0,
             config: sql::catalog::CatalogConfig {
                 startup_time: SystemTime::now(),
                 nonce: rand::random(),
                 experimental_mode,
                 cluster_id,
+                default_source_materialization: config.default_source_materialization,
                 cache_directory: config.cache_directory.clone(),
                 build_info: config.build_info,
             },
@@ -508,6 +635,8 @@ impl Catalog {
                                     &log.variant.index_by(),
                                 ),
                                 plan_cx: PlanContext::default(),
+                                conn_id: None,
+                                func_uses: vec![],
                             }),
                         ),
                     );
@@ -532,6 +661,8 @@ impl Catalog {
                             plan_cx: PlanContext::default(),
                             desc: table.desc.clone(),
                             defaults: vec![Expr::null(); table.desc.arity()],
+                            conn_id: None,
+                            func_uses: vec![],
                         }),
                     ));
                     let oid = catalog.allocate_oid()?;
@@ -552,6 +683,8 @@ impl Catalog {
                                     .collect(),
                                 create_sql: index_sql,
                                 plan_cx: PlanContext::default(),
+                                conn_id: None,
+                                func_uses: vec![],
                             }),
                         ),
                     );
@@ -601,9 +734,51 @@ impl Catalog {
             }
         }
 
+        // Refuse to open a catalog that a newer version of materialized has
+        // opened, as its contents may have been migrated to a format that this
+        // version does not understand. Builds without a valid version, like
+        // those used in tests, skip the check.
+        let build_version = semver::Version::parse(config.build_info.version).ok();
+        if let Some(build_version) = &build_version {
+            if let Some(catalog_version) = catalog.storage().get_catalog_build_version()? {
+                match semver::Version::parse(&catalog_version) {
+                    Ok(catalog_version) if catalog_version > *build_version => {
+                        return Err(Error::new(ErrorKind::NewerCatalog {
+                            catalog_version: catalog_version.to_string(),
+                            build_version: build_version.to_string(),
+                        }));
+                    }
+                    Ok(_) => (),
+                    Err(_) => {
+                        return Err(Error::new(ErrorKind::Corruption {
+                            detail: format!(
+                                "catalog has invalid build version {}",
+                                catalog_version
+                            ),
+                        }))
+                    }
+                }
+            }
+        }
+
         let mut catalog_content_version = catalog.storage().get_catalog_content_version()?;
+        if catalog_content_version > CONTENT_MIGRATIONS.len() {
+            return Err(Error::new(ErrorKind::Corruption {
+                detail: format!(
+                    "catalog content version {} is newer than the newest version \
+                     known to this build ({})",
+                    catalog_content_version,
+                    CONTENT_MIGRATIONS.len()
+                ),
+            }));
+        }
 
         while CONTENT_MIGRATIONS.len() > catalog_content_version {
+            info!(
+                "migrating catalog content from version {} to {}",
+                catalog_content_version,
+                catalog_content_version + 1
+            );
             if let Err(e) = CONTENT_MIGRATIONS[catalog_content_version](&mut catalog) {
                 return Err(Error::new(ErrorKind::FailedMigration {
                     last_version: catalog_content_version,
@@ -616,6 +791,12 @@ impl Catalog {
                 .set_catalog_content_version(catalog_content_version)?;
         }
 
+        if let Some(build_version) = &build_version {
+            catalog
+                .storage()
+                .set_catalog_build_version(&build_version.to_string())?;
+        }
+
         let items = catalog.storage().load_items()?;
         for (id, name, def) in items {
             // TODO(benesch): a better way of detecting when a view has depended
@@ -642,6 +823,26 @@ impl Catalog {
             let oid = catalog.allocate_oid()?;
             events.push(catalog.insert_item(id, oid, name, item));
         }
+
+        let comments = catalog.storage().load_comments()?;
+        for (id, column_pos, comment) in comments {
+            let entry = match catalog.by_id.get_mut(&id) {
+                Some(entry) => entry,
+                None => {
+                    return Err(Error::new(ErrorKind::Corruption {
+                        detail: format!("comment attached to unknown item {}", id),
+                    }))
+                }
+            };
+            entry.comments.insert(column_pos, comment.clone());
+            events.push(Event::UpdatedComment {
+                id,
+                column_pos,
+                from: None,
+                to: Some(comment),
+            });
+        }
+
         Ok((catalog, events))
     }
 
@@ -656,6 +857,7 @@ impl Catalog {
             path,
             enable_logging: true,
             experimental_mode: None,
+            default_source_materialization: false,
             cache_directory: None,
             build_info: &DUMMY_BUILD_INFO,
         })?;
@@ -667,7 +869,14 @@ impl Catalog {
             catalog: self,
             conn_id: session.conn_id(),
             database: session.vars().database().into(),
-            search_path: session.vars().search_path(),
+            search_path: session
+                .vars()
+                .search_path()
+                .into_iter()
+                .map(|s| s.into())
+                .collect(),
+            session_user: session.vars().user().into(),
+            current_user: session.vars().current_user().into(),
         }
     }
 
@@ -678,7 +887,9 @@ impl Catalog {
             catalog: self,
             conn_id: SYSTEM_CONN_ID,
             database: "materialize".into(),
-            search_path: &[],
+            search_path: vec![],
+            session_user: "materialize".into(),
+            current_user: "materialize".into(),
         }
     }
 
@@ -690,12 +901,34 @@ impl Catalog {
         self.storage().allocate_id()
     }
 
+    /// Returns the IDs of the placeholder items that were committed to the
+    /// on-disk catalog but never replaced, because the process that created
+    /// them exited first.
+    pub fn load_pending_items(&self) -> Result<Vec<GlobalId>, Error> {
+        self.storage().load_pending_items()
+    }
+
+    /// Allocates a new OID.
+    ///
+    /// OIDs are not persisted, but reassigned to every object whenever the
+    /// catalog is opened, so the OIDs of dropped objects are reclaimed by a
+    /// restart. They are not otherwise reused, as clients may cache OIDs and
+    /// would be confused to see one refer to a different object.
     pub fn allocate_oid(&mut self) -> Result<u32, Error> {
         let oid = self.oid_counter;
         if oid == u32::max_value() {
             return Err(Error::new(ErrorKind::OidExhaustion));
         }
+        if oid == OID_HIGH_WATER_MARK {
+            warn!(
+                "{} of {} OIDs have been allocated; restart materialized to reclaim \
+                 the OIDs of dropped objects before the OID space is exhausted",
+                oid - FIRST_USER_OID,
+                u32::max_value() - FIRST_USER_OID,
+            );
+        }
         self.oid_counter += 1;
+        OIDS_ALLOCATED.set(i64::from(self.oid_counter - FIRST_USER_OID));
         Ok(oid)
     }
 
@@ -820,6 +1053,14 @@ impl Catalog {
             .contains_key(item_name)
     }
 
+    /// Reports whether the connection `conn_id` has created any temporary
+    /// items that still exist.
+    pub fn has_temporary_items(&self, conn_id: u32) -> bool {
+        self.temporary_schemas
+            .get(&conn_id)
+            .map_or(false, |schema| !schema.items.is_empty())
+    }
+
     pub fn drop_temp_item_ops(&mut self, conn_id: u32) -> Vec<Op> {
         self.temporary_schemas[&conn_id]
             .items
@@ -828,6 +1069,14 @@ impl Catalog {
             .collect()
     }
 
+    /// Returns the number of items in the temporary schema of the connection
+    /// `conn_id`, or zero if the connection has no temporary schema.
+    pub fn temporary_item_count(&self, conn_id: u32) -> usize {
+        self.temporary_schemas
+            .get(&conn_id)
+            .map_or(0, |schema| schema.items.len())
+    }
+
     pub fn drop_temporary_schema(&mut self, conn_id: u32) -> Result<(), Error> {
         if !self.temporary_schemas[&conn_id].items.is_empty() {
             return Err(Error::new(ErrorKind::SchemaNotEmpty(MZ_TEMP_SCHEMA.into())));
@@ -894,6 +1143,7 @@ impl Catalog {
             id,
             oid,
             used_by: Vec::new(),
+            comments: BTreeMap::new(),
         };
         for u in entry.uses() {
             match self.by_id.get_mut(&u) {
@@ -910,12 +1160,17 @@ impl Catalog {
                 self.indexes.insert(id, vec![]);
             }
             CatalogItem::Index(index) => {
-                self.indexes
-                    .get_mut(&index.on)
-                    .unwrap()
-                    .push((id, index.keys.clone()));
+                // Keep the indexes on each object sorted by ID, so that the
+                // choice of index does not depend on the order in which the
+                // indexes were installed, which changes when an index is
+                // renamed or the catalog is reloaded.
+                let indexes = self.indexes.get_mut(&index.on).unwrap();
+                let i = match indexes.binary_search_by_key(&id, |(idx_id, _keys)| *idx_id) {
+                    Ok(i) | Err(i) => i,
+                };
+                indexes.insert(i, (id, index.keys.clone()));
             }
-            CatalogItem::Sink(_) | CatalogItem::Type(_) => (),
+            CatalogItem::Sink(_) | CatalogItem::Type(_) | CatalogItem::Func(_) => (),
         }
 
         let conn_id = entry.item().conn_id().unwrap_or(SYSTEM_CONN_ID);
@@ -1013,20 +1268,18 @@ impl Catalog {
                 id,
                 oid: _,
                 name,
-                item:
-                    CatalogItem::View(View {
-                        conn_id: Some(conn_id),
-                        ..
-                    }),
+                item,
             } = op
             {
-                if self.item_exists_in_temp_schemas(*conn_id, &name.item)
-                    || creating.contains(&(conn_id, &name.item))
-                {
-                    return Err(Error::new(ErrorKind::ItemAlreadyExists(name.item.clone())));
-                } else {
-                    creating.insert((conn_id, &name.item));
-                    temporary_ids.push(id.clone());
+                if let Some(conn_id) = item.conn_id() {
+                    if self.item_exists_in_temp_schemas(conn_id, &name.item)
+                        || creating.contains(&(conn_id, &name.item))
+                    {
+                        return Err(Error::new(ErrorKind::ItemAlreadyExists(name.item.clone())));
+                    } else {
+                        creating.insert((conn_id, &name.item));
+                        temporary_ids.push(id.clone());
+                    }
                 }
             }
         }
@@ -1035,6 +1288,7 @@ impl Catalog {
 
     pub fn transact(&mut self, ops: Vec<Op>) -> Result<Vec<Event>, Error> {
         trace!("transact: {:?}", ops);
+        self.generation += 1;
 
         #[derive(Debug, Clone)]
         enum Action {
@@ -1069,6 +1323,15 @@ impl Catalog {
                 to_name: FullName,
                 item: CatalogItem,
             },
+            UpdateIndexKeys {
+                id: GlobalId,
+                index: Index,
+            },
+            UpdateComment {
+                id: GlobalId,
+                column_pos: Option<usize>,
+                comment: Option<String>,
+            },
         }
 
         let temporary_ids = self.temporary_ids(&ops)?;
@@ -1150,6 +1413,9 @@ impl Catalog {
                         let schema_id = tx.load_schema_id(database_id, &name.schema)?;
                         let serialized_item = self.serialize_item(&item);
                         tx.insert_item(id, schema_id, &name.item, &serialized_item)?;
+                        if item.is_placeholder() {
+                            tx.insert_pending_item(id)?;
+                        }
                     }
 
                     vec![Action::CreateItem {
@@ -1222,6 +1488,9 @@ impl Catalog {
                         })?;
                     let serialized_item = self.serialize_item(&item);
 
+                    // Rewrite the references in every dependent item within
+                    // the same transaction, so that the stored definitions
+                    // never refer to a name that no longer exists.
                     for id in entry.used_by() {
                         let dependent_item = self.by_id.get(&id).unwrap();
                         let updated_item = dependent_item
@@ -1235,9 +1504,16 @@ impl Catalog {
                                 })
                             })?;
 
-                        let serialized_item = self.serialize_item(&updated_item);
-
-                        tx.update_item(id.clone(), &dependent_item.name.item, &serialized_item)?;
+                        // Temporary items are not persisted, so only their
+                        // in-memory definition needs rewriting.
+                        if !dependent_item.item().is_temporary() {
+                            let serialized_item = self.serialize_item(&updated_item);
+                            tx.update_item(
+                                id.clone(),
+                                &dependent_item.name.item,
+                                &serialized_item,
+                            )?;
+                        }
                         actions.push(Action::UpdateItem {
                             id: id.clone(),
                             from_name: None,
@@ -1245,7 +1521,9 @@ impl Catalog {
                             item: updated_item,
                         });
                     }
-                    tx.update_item(id.clone(), &to_full_name.item, &serialized_item)?;
+                    if !entry.item().is_temporary() {
+                        tx.update_item(id.clone(), &to_full_name.item, &serialized_item)?;
+                    }
                     actions.push(Action::UpdateItem {
                         id,
                         from_name: Some(entry.name.clone()),
@@ -1254,6 +1532,114 @@ impl Catalog {
                     });
                     actions
                 }
+                Op::RenameColumn { id, from, to } => {
+                    let mut actions = Vec::new();
+
+                    let entry = self.by_id.get(&id).unwrap();
+                    let desc = match entry.item() {
+                        CatalogItem::View(view) => &view.desc,
+                        _ => unreachable!("only the columns of views can be renamed"),
+                    };
+
+                    for id in entry.used_by() {
+                        let dependent_item = self.by_id.get(&id).unwrap();
+                        let updated_item = match dependent_item.item() {
+                            CatalogItem::Index(_) => {
+                                dependent_item.item.rename_column_refs(desc, &from, &to)
+                            }
+                            item => {
+                                let create_stmt =
+                                    sql::parse::parse(item.create_sql()).unwrap().into_element();
+                                if sql::ast::transform::create_stmt_may_reference_column(
+                                    &create_stmt,
+                                    &Ident::new(from.clone()),
+                                ) {
+                                    return Err(Error::new(ErrorKind::ColumnRenameDependency {
+                                        column: from,
+                                        relation: entry.name.to_string(),
+                                        depender: dependent_item.name.to_string(),
+                                    }));
+                                }
+                                continue;
+                            }
+                        };
+
+                        // Temporary items are not persisted, so only their
+                        // in-memory definition needs rewriting.
+                        if !dependent_item.item().is_temporary() {
+                            let serialized_item = self.serialize_item(&updated_item);
+                            tx.update_item(
+                                id.clone(),
+                                &dependent_item.name.item,
+                                &serialized_item,
+                            )?;
+                        }
+                        actions.push(Action::UpdateItem {
+                            id: id.clone(),
+                            from_name: None,
+                            to_name: dependent_item.name.clone(),
+                            item: updated_item,
+                        });
+                    }
+
+                    let item = entry.item.rename_column_refs(desc, &from, &to);
+                    if !item.is_temporary() {
+                        let serialized_item = self.serialize_item(&item);
+                        tx.update_item(id.clone(), &entry.name.item, &serialized_item)?;
+                    }
+                    actions.push(Action::UpdateItem {
+                        id,
+                        from_name: None,
+                        to_name: entry.name.clone(),
+                        item,
+                    });
+                    actions
+                }
+                Op::AlterIndexKeys {
+                    id,
+                    keys,
+                    create_sql,
+                    plan_cx,
+                    func_uses,
+                } => {
+                    let entry = self.get_by_id(&id);
+                    let index = match entry.item() {
+                        CatalogItem::Index(index) => Index {
+                            create_sql,
+                            plan_cx,
+                            keys,
+                            func_uses,
+                            ..index.clone()
+                        },
+                        _ => unreachable!("only the keys of indexes can be altered"),
+                    };
+                    if index.conn_id.is_none() {
+                        let serialized_item =
+                            self.serialize_item(&CatalogItem::Index(index.clone()));
+                        tx.update_item(id, &entry.name.item, &serialized_item)?;
+                    }
+                    vec![Action::UpdateIndexKeys { id, index }]
+                }
+                Op::Comment {
+                    id,
+                    column_pos,
+                    comment,
+                } => {
+                    let entry = self.get_by_id(&id);
+                    if id.is_system() {
+                        return Err(Error::new(ErrorKind::ReadOnlyItem(
+                            entry.name().to_string(),
+                        )));
+                    }
+                    if !entry.item().is_temporary() {
+                        tx.set_comment(id, column_pos, comment.as_deref())?;
+                    }
+                    vec![Action::UpdateComment {
+                        id,
+                        column_pos,
+                        comment,
+                    }]
+                }
             });
         }
         tx.commit()?;
@@ -1430,6 +1816,65 @@ impl Catalog {
                         None => Event::NoOp, // If name didn't change, don't update system tables.
                     }
                 }
+
+                Action::UpdateIndexKeys { id, index } => {
+                    let entry = self.by_id.get_mut(&id).unwrap();
+                    info!("update keys of index {} ({})", entry.name, id);
+                    let old_uses = entry.uses();
+                    let from = match std::mem::replace(&mut entry.item, CatalogItem::Index(index)) {
+                        CatalogItem::Index(from) => from,
+                        _ => unreachable!("only the keys of indexes can be altered"),
+                    };
+                    let new_uses = entry.uses();
+                    let oid = entry.oid();
+                    let name = entry.name.clone();
+
+                    // The new keys may call different functions than the old
+                    // ones did.
+                    for u in old_uses.iter().filter(|u| !new_uses.contains(u)) {
+                        let dep = self.by_id.get_mut(u).unwrap();
+                        dep.used_by.retain(|u| *u != id);
+                    }
+                    for u in new_uses.iter().filter(|u| !old_uses.contains(u)) {
+                        let dep = self.by_id.get_mut(u).unwrap();
+                        dep.used_by.push(id);
+                    }
+
+                    let to = match &self.by_id[&id].item {
+                        CatalogItem::Index(to) => to.clone(),
+                        _ => unreachable!(),
+                    };
+                    for (idx_id, keys) in self.indexes.get_mut(&to.on).unwrap() {
+                        if *idx_id == id {
+                            *keys = to.keys.clone();
+                        }
+                    }
+                    Event::UpdatedIndexKeys {
+                        id,
+                        oid,
+                        name,
+                        from,
+                        to,
+                    }
+                }
+
+                Action::UpdateComment {
+                    id,
+                    column_pos,
+                    comment,
+                } => {
+                    let entry = self.by_id.get_mut(&id).unwrap();
+                    let from = match &comment {
+                        Some(comment) => entry.comments.insert(column_pos, comment.clone()),
+                        None => entry.comments.remove(&column_pos),
+                    };
+                    Event::UpdatedComment {
+                        id,
+                        column_pos,
+                        from,
+                        to: comment,
+                    }
+                }
             })
             .collect())
     }
@@ -1460,6 +1905,10 @@ impl Catalog {
                 create_sql: typ.create_sql.clone(),
                 eval_env: Some(typ.plan_cx.clone().into()),
             },
+            CatalogItem::Func(func) => SerializedCatalogItem::V1 {
+                create_sql: func.create_sql.clone(),
+                eval_env: Some(func.plan_cx.clone().into()),
+            },
         };
         serde_json::to_vec(&item).expect("catalog serialization cannot fail")
     }
@@ -1492,6 +1941,8 @@ impl Catalog {
                 plan_cx: pcx,
                 desc: table.desc,
                 defaults: table.defaults,
+                conn_id: None,
+                func_uses: table.func_uses,
             }),
             Plan::CreateSource { source, .. } => CatalogItem::Source(Source {
                 create_sql: source.create_sql,
@@ -1509,6 +1960,7 @@ impl Catalog {
                     optimized_expr,
                     desc,
                     conn_id: None,
+                    func_uses: view.func_uses,
                 })
             }
             Plan::CreateIndex { index, .. } => CatalogItem::Index(Index {
@@ -1516,6 +1968,8 @@ impl Catalog {
                 plan_cx: pcx,
                 on: index.on,
                 keys: index.keys,
+                conn_id: None,
+                func_uses: index.func_uses,
             }),
             Plan::CreateSink {
                 sink,
@@ -1535,12 +1989,20 @@ impl Catalog {
                 plan_cx: pcx,
                 inner: typ.inner.into(),
             }),
+            Plan::CreateFunction { func, .. } => CatalogItem::Func(Func {
+                create_sql: func.create_sql,
+                plan_cx: pcx,
+                params: func.params,
+                return_type: func.return_type,
+                body: func.body,
+                func_uses: func.func_uses,
+            }),
             _ => bail!("catalog entry generated inappropriate plan"),
         })
     }
 
     /// Returns a mapping that indicates all indices that are available for
-    /// each item in the catalog.
+    /// each item in the catalog. The indices for each item are sorted by ID.
     pub fn indexes(&self) -> &HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>> {
         &self.indexes
     }
@@ -1550,8 +2012,8 @@ impl Catalog {
     /// Panics if `id` does not exist, or if `id` is not an object on which
     /// indexes can be built.
     pub fn default_index_for(&self, id: GlobalId) -> Option<GlobalId> {
-        // The default index is just whatever index happens to appear first in
-        // self.indexes.
+        // The default index is the index with the smallest ID, which appears
+        // first in self.indexes.
         self.indexes[&id].first().map(|(id, _keys)| *id)
     }
 
@@ -1569,6 +2031,12 @@ impl Catalog {
             indexes: &mut Vec<GlobalId>,
             complete: &mut bool,
         ) {
+            // Functions are inlined into the views that call them, so they
+            // have no indexes of their own to find.
+            if let CatalogItem::Func(_) = catalog.get_by_id(&id).item() {
+                return;
+            }
+
             // If an index exists for `id`, record it in the output set and stop
             // searching.
             if let Some((index_id, _)) = catalog.indexes[&id].first() {
@@ -1591,8 +2059,13 @@ impl Catalog {
                 CatalogItem::Table(_) => {
                     unreachable!("tables always have at least one index");
                 }
-                CatalogItem::Sink(_) | CatalogItem::Index(_) | CatalogItem::Type(_) => {
-                    unreachable!("sinks, indexes, and user-defined types cannot be depended upon");
+                CatalogItem::Sink(_)
+                | CatalogItem::Index(_)
+                | CatalogItem::Type(_)
+                | CatalogItem::Func(_) => {
+                    unreachable!(
+                        "sinks, indexes, user-defined types, and functions cannot be depended upon"
+                    );
                 }
             }
         }
@@ -1610,7 +2083,7 @@ impl Catalog {
     pub fn uses_tables(&self, id: GlobalId) -> bool {
         match self.get_by_id(&id).item() {
             CatalogItem::Table(_) => true,
-            CatalogItem::Source(_) => false,
+            CatalogItem::Source(_) | CatalogItem::Func(_) => false,
             item @ CatalogItem::View(_) => item.uses().into_iter().any(|id| self.uses_tables(id)),
             CatalogItem::Sink(_) | CatalogItem::Index(_) | CatalogItem::Type(_) => {
                 unreachable!("sinks, indexes, and user-defined types cannot be depended upon");
@@ -1627,9 +2100,32 @@ impl Catalog {
         serde_json::to_string(&self.by_name).expect("serialization cannot fail")
     }
 
+    /// Like [`Catalog::dump`], but returns the serialized state as a JSON
+    /// value, for embedding in larger documents.
+    pub fn dump_value(&self) -> serde_json::Value {
+        serde_json::to_value(&self.by_name).expect("serialization cannot fail")
+    }
+
+    /// Returns the IDs of the sink placeholders whose connectors are still
+    /// being built, in ascending order.
+    pub fn pending_sinks(&self) -> Vec<GlobalId> {
+        self.by_id
+            .values()
+            .filter(|entry| entry.item().is_placeholder())
+            .map(|entry| entry.id())
+            .collect()
+    }
+
     pub fn config(&self) -> &sql::catalog::CatalogConfig {
         &self.config
     }
+
+    /// Returns the number of times the catalog has been changed by
+    /// [`Catalog::transact`]. Information derived from the catalog is only
+    /// valid while its generation is unchanged.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1664,6 +2160,30 @@ pub enum Op {
         id: GlobalId,
         to_name: String,
     },
+    /// Renames the column `from` of the view `id` to `to`. Fails if any view
+    /// or sink that depends on the view might refer to the column.
+    RenameColumn {
+        id: GlobalId,
+        from: String,
+        to: String,
+    },
+    /// Replaces the keys of the index `id`, along with the `CREATE INDEX`
+    /// statement that describes them. The index keeps its ID and name.
+    AlterIndexKeys {
+        id: GlobalId,
+        keys: Vec<ScalarExpr>,
+        create_sql: String,
+        plan_cx: PlanContext,
+        func_uses: Vec<GlobalId>,
+    },
+    /// Sets the comment on the item `id`, or on its column at the 0-indexed
+    /// `column_pos`, if specified. A `comment` of `None` removes the existing
+    /// comment.
+    Comment {
+        id: GlobalId,
+        column_pos: Option<usize>,
+        comment: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -1713,6 +2233,19 @@ pub enum Event {
         to_name: FullName,
         item: CatalogItem,
     },
+    UpdatedIndexKeys {
+        id: GlobalId,
+        oid: u32,
+        name: FullName,
+        from: Index,
+        to: Index,
+    },
+    UpdatedComment {
+        id: GlobalId,
+        column_pos: Option<usize>,
+        from: Option<String>,
+        to: Option<String>,
+    },
     NoOp,
 }
 
@@ -1816,12 +2349,13 @@ impl ExprHumanizer for ConnCatalog<'_> {
                     .map(|f| format!("{}: {}", f.0, self.humanize_column_type(&f.1)))
                     .join(",")
             ),
+            Void => "void".into(),
             ty => {
                 let pgrepr_type = pgrepr::Type::from(ty);
                 let res = if self
                     .search_path
                     .iter()
-                    .any(|schema| schema == &PG_CATALOG_SCHEMA)
+                    .any(|schema| schema == PG_CATALOG_SCHEMA)
                 {
                     pgrepr_type.name().to_string()
                 } else {
@@ -1850,16 +2384,14 @@ impl ExprHumanizer for ConnCatalog<'_> {
 impl SqlCatalog for ConnCatalog<'_> {
     fn search_path(&self, include_system_schemas: bool) -> Vec<&str> {
         if include_system_schemas {
-            self.search_path.to_vec()
+            self.search_path.iter().map(|s| s.as_str()).collect()
         } else {
             self.search_path
                 .iter()
+                .map(|s| s.as_str())
                 .filter(|s| {
-                    (**s != PG_CATALOG_SCHEMA)
-                        && (**s != MZ_CATALOG_SCHEMA)
-                        && (**s != MZ_TEMP_SCHEMA)
+                    (*s != PG_CATALOG_SCHEMA) && (*s != MZ_CATALOG_SCHEMA) && (*s != MZ_TEMP_SCHEMA)
                 })
-                .cloned()
                 .collect()
         }
     }
@@ -1868,6 +2400,14 @@ impl SqlCatalog for ConnCatalog<'_> {
         &self.database
     }
 
+    fn session_user(&self) -> &str {
+        &self.session_user
+    }
+
+    fn current_user(&self) -> &str {
+        &self.current_user
+    }
+
     fn resolve_database(
         &self,
         database_name: &str,
@@ -1894,7 +2434,7 @@ impl SqlCatalog for ConnCatalog<'_> {
     ) -> Result<&dyn sql::catalog::CatalogItem, SqlCatalogError> {
         Ok(self
             .catalog
-            .resolve(&self.database, self.search_path, name, self.conn_id)?)
+            .resolve(&self.database, &self.search_path(true), name, self.conn_id)?)
     }
 
     fn list_items<'a>(
@@ -2010,14 +2550,7 @@ impl sql::catalog::CatalogItem for CatalogEntry {
     }
 
     fn create_sql(&self) -> &str {
-        match self.item() {
-            CatalogItem::Table(Table { create_sql, .. }) => create_sql,
-            CatalogItem::Source(Source { create_sql, .. }) => create_sql,
-            CatalogItem::Sink(Sink { create_sql, .. }) => create_sql,
-            CatalogItem::View(View { create_sql, .. }) => create_sql,
-            CatalogItem::Index(Index { create_sql, .. }) => create_sql,
-            CatalogItem::Type(Type { create_sql, .. }) => create_sql,
-        }
+        self.item().create_sql()
     }
 
     fn plan_cx(&self) -> &PlanContext {
@@ -2028,6 +2561,7 @@ impl sql::catalog::CatalogItem for CatalogEntry {
             CatalogItem::View(View { plan_cx, .. }) => plan_cx,
             CatalogItem::Index(Index { plan_cx, .. }) => plan_cx,
             CatalogItem::Type(Type { plan_cx, .. }) => plan_cx,
+            CatalogItem::Func(Func { plan_cx, .. }) => plan_cx,
         }
     }
 
@@ -2039,6 +2573,7 @@ impl sql::catalog::CatalogItem for CatalogEntry {
             CatalogItem::View(_) => sql::catalog::CatalogItemType::View,
             CatalogItem::Index(_) => sql::catalog::CatalogItemType::Index,
             CatalogItem::Type(_) => sql::catalog::CatalogItemType::Type,
+            CatalogItem::Func(_) => sql::catalog::CatalogItemType::Func,
         }
     }
 
@@ -2058,6 +2593,20 @@ impl sql::catalog::CatalogItem for CatalogEntry {
         }
     }
 
+    fn func_details(&self) -> Option<(&RelationDesc, &ScalarType, &Expr)> {
+        if let CatalogItem::Func(Func {
+            params,
+            return_type,
+            body,
+            ..
+        }) = self.item()
+        {
+            Some((params, return_type, body))
+        } else {
+            None
+        }
+    }
+
     fn uses(&self) -> Vec<GlobalId> {
         self.uses()
     }
@@ -2145,4 +2694,15 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_oid_exhaustion() -> Result<(), anyhow::Error> {
+        let catalog_file = NamedTempFile::new()?;
+        let mut catalog = Catalog::open_debug(catalog_file.path())?;
+        catalog.oid_counter = u32::max_value() - 1;
+        assert_eq!(catalog.allocate_oid()?, u32::max_value() - 1);
+        let err = catalog.allocate_oid().unwrap_err();
+        assert_eq!(err.to_string(), "OID space exhausted; restart required");
+        Ok(())
+    }
 }
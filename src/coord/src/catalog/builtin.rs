@@ -515,7 +515,8 @@ lazy_static! {
         desc: RelationDesc::empty()
             .with_column("sink_id", ScalarType::String.nullable(false))
             .with_column("path", ScalarType::Bytes.nullable(false))
-            .with_key(vec![0]),
+            .with_column("active", ScalarType::Bool.nullable(false))
+            .with_key(vec![1]),
         id: GlobalId::System(3007),
         index_id: GlobalId::System(3008),
     };
@@ -666,6 +667,138 @@ lazy_static! {
             id: GlobalId::System(3035),
             index_id: GlobalId::System(3036),
     };
+    pub static ref MZ_KAFKA_SOURCE_PARTITIONS: BuiltinTable = BuiltinTable {
+        name: "mz_kafka_source_partitions",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("source_id", ScalarType::String.nullable(false))
+            .with_column("dataflow_id", ScalarType::Int64.nullable(false))
+            .with_column("topic", ScalarType::String.nullable(false))
+            .with_column("partition_id", ScalarType::Int32.nullable(false))
+            .with_column("offset", ScalarType::Int64.nullable(false))
+            .with_column("timestamp", ScalarType::Int64.nullable(false))
+            .with_key(vec![0, 1, 3]),
+        id: GlobalId::System(3037),
+        index_id: GlobalId::System(3038),
+    };
+    /// Carries the payloads of `NOTIFY` statements to the sessions that
+    /// `LISTEN` on their channels. Each notification is inserted and then
+    /// immediately retracted, so the table is empty except in passing.
+    pub static ref MZ_NOTIFICATIONS: BuiltinTable = BuiltinTable {
+        name: "mz_notifications",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("channel", ScalarType::String.nullable(false))
+            .with_column("payload", ScalarType::String.nullable(false))
+            .with_column("conn_id", ScalarType::Int32.nullable(false)),
+        id: GlobalId::System(3039),
+        index_id: GlobalId::System(3040),
+    };
+    /// Counts the peeks that had to build a temporary dataflow to read an
+    /// unindexed relation, grouped by the columns that the peek constrained to
+    /// literal values.
+    pub static ref MZ_SLOW_PATH_PEEKS: BuiltinTable = BuiltinTable {
+        name: "mz_slow_path_peeks",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("on_id", ScalarType::String.nullable(false))
+            .with_column("key", ScalarType::String.nullable(false))
+            .with_column("peeks", ScalarType::Int64.nullable(false))
+            .with_column("create_sql", ScalarType::String.nullable(false))
+            .with_key(vec![0, 1]),
+        id: GlobalId::System(3041),
+        index_id: GlobalId::System(3042),
+    };
+    /// Reports whether each running sink is healthy, and if not, the error
+    /// that it most recently encountered.
+    pub static ref MZ_SINK_STATUS: BuiltinTable = BuiltinTable {
+        name: "mz_sink_status",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("sink_id", ScalarType::String.nullable(false))
+            .with_column("status", ScalarType::String.nullable(false))
+            .with_column("last_error", ScalarType::String.nullable(true))
+            .with_key(vec![0]),
+        id: GlobalId::System(3043),
+        index_id: GlobalId::System(3044),
+    };
+    /// Reports the progress of building the connector of each sink, which
+    /// may involve arbitrarily slow external operations, like creating a
+    /// Kafka topic.
+    pub static ref MZ_SINK_BUILDS: BuiltinTable = BuiltinTable {
+        name: "mz_sink_builds",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("sink_id", ScalarType::String.nullable(false))
+            .with_column("state", ScalarType::String.nullable(false))
+            .with_column("started_at", ScalarType::TimestampTz.nullable(false))
+            .with_key(vec![0]),
+        id: GlobalId::System(3045),
+        index_id: GlobalId::System(3046),
+    };
+    /// Records the creation, drop, and rename of each object, if enabled by
+    /// `--object-history`. Rows are never retracted.
+    pub static ref MZ_OBJECT_HISTORY: BuiltinTable = BuiltinTable {
+        name: "mz_object_history",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("id", ScalarType::String.nullable(false))
+            .with_column("object_type", ScalarType::String.nullable(false))
+            .with_column("event", ScalarType::String.nullable(false))
+            .with_column("name", ScalarType::String.nullable(false))
+            .with_column("previous_name", ScalarType::String.nullable(true))
+            .with_column("occurred_at", ScalarType::TimestampTz.nullable(false)),
+        id: GlobalId::System(3047),
+        index_id: GlobalId::System(3048),
+    };
+    /// Reports each client connection, along with the `application_name` that
+    /// the client most recently set.
+    pub static ref MZ_SESSIONS: BuiltinTable = BuiltinTable {
+        name: "mz_sessions",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("conn_id", ScalarType::Int32.nullable(false))
+            .with_column("application_name", ScalarType::String.nullable(false))
+            .with_column("connected_at", ScalarType::TimestampTz.nullable(false))
+            .with_key(vec![0]),
+        id: GlobalId::System(3049),
+        index_id: GlobalId::System(3050),
+    };
+    /// Reports the signature of each user-defined function, which the
+    /// `mz_functions` view exposes.
+    pub static ref MZ_FUNCTION_SIGNATURES: BuiltinTable = BuiltinTable {
+        name: "mz_function_signatures",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("id", ScalarType::String.nullable(false))
+            .with_column("oid", ScalarType::Oid.nullable(false))
+            .with_column("schema_id", ScalarType::Int64.nullable(false))
+            .with_column("name", ScalarType::String.nullable(false))
+            .with_column("arguments", ScalarType::String.nullable(false))
+            .with_column("return_type", ScalarType::String.nullable(false)),
+        id: GlobalId::System(3051),
+        index_id: GlobalId::System(3052),
+    };
+    /// Reports a single row describing the running coordinator.
+    pub static ref MZ_COORDINATOR_STATE: BuiltinTable = BuiltinTable {
+        name: "mz_coordinator_state",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("started_at", ScalarType::TimestampTz.nullable(false))
+            .with_column("workers", ScalarType::Int64.nullable(false)),
+        id: GlobalId::System(3053),
+        index_id: GlobalId::System(3054),
+    };
+    pub static ref MZ_COMMENTS: BuiltinTable = BuiltinTable {
+        name: "mz_comments",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("id", ScalarType::String.nullable(false))
+            .with_column("column_position", ScalarType::Int64.nullable(true))
+            .with_column("comment", ScalarType::String.nullable(false)),
+        id: GlobalId::System(3055),
+        index_id: GlobalId::System(3056),
+    };
 }
 
 pub const MZ_RELATIONS: BuiltinView = BuiltinView {
@@ -925,6 +1058,27 @@ JOIN mz_catalog.mz_catalog_names mcn_source ON mcn_source.global_id = source_inf
     needs_logs: true,
 };
 
+pub const MZ_INDEX_RECOMMENDATIONS: BuiltinView = BuiltinView {
+    name: "mz_index_recommendations",
+    schema: MZ_CATALOG_SCHEMA,
+    sql: "CREATE VIEW mz_index_recommendations AS SELECT
+    on_id, key, peeks, create_sql
+FROM mz_catalog.mz_slow_path_peeks
+WHERE peeks >= 10",
+    id: GlobalId::System(4025),
+    needs_logs: false,
+};
+
+pub const MZ_FUNCTIONS: BuiltinView = BuiltinView {
+    name: "mz_functions",
+    schema: MZ_CATALOG_SCHEMA,
+    sql: "CREATE VIEW mz_functions AS SELECT
+    id, oid, schema_id, name, arguments, return_type
+FROM mz_catalog.mz_function_signatures",
+    id: GlobalId::System(4026),
+    needs_logs: false,
+};
+
 pub const PG_NAMESPACE: BuiltinView = BuiltinView {
     name: "pg_namespace",
     schema: PG_CATALOG_SCHEMA,
@@ -990,11 +1144,12 @@ pub const PG_DESCRIPTION: BuiltinView = BuiltinView {
     name: "pg_description",
     schema: PG_CATALOG_SCHEMA,
     sql: "CREATE VIEW pg_description AS SELECT
-    oid as objoid,
+    mz_objects.oid as objoid,
     NULL::pg_catalog.oid as classoid,
-    0::pg_catalog.int4 as objsubid,
-    NULL::pg_catalog.text as description
-FROM pg_catalog.pg_class",
+    coalesce(mz_comments.column_position, 0)::pg_catalog.int4 as objsubid,
+    mz_comments.comment as description
+FROM mz_catalog.mz_comments
+JOIN mz_catalog.mz_objects ON mz_comments.id = mz_objects.id",
     id: GlobalId::System(4019),
     needs_logs: false,
 };
@@ -1155,6 +1310,16 @@ lazy_static! {
             Builtin::Table(&MZ_BASE_TYPES),
             Builtin::Table(&MZ_LIST_TYPES),
             Builtin::Table(&MZ_MAP_TYPES),
+            Builtin::Table(&MZ_KAFKA_SOURCE_PARTITIONS),
+            Builtin::Table(&MZ_NOTIFICATIONS),
+            Builtin::Table(&MZ_SLOW_PATH_PEEKS),
+            Builtin::Table(&MZ_SINK_STATUS),
+            Builtin::Table(&MZ_SINK_BUILDS),
+            Builtin::Table(&MZ_OBJECT_HISTORY),
+            Builtin::Table(&MZ_SESSIONS),
+            Builtin::Table(&MZ_FUNCTION_SIGNATURES),
+            Builtin::Table(&MZ_COORDINATOR_STATE),
+            Builtin::Table(&MZ_COMMENTS),
             Builtin::View(&MZ_RELATIONS),
             Builtin::View(&MZ_OBJECTS),
             Builtin::View(&MZ_CATALOG_NAMES),
@@ -1170,6 +1335,8 @@ lazy_static! {
             Builtin::View(&MZ_PERF_PEEK_DURATIONS_AGGREGATES),
             Builtin::View(&MZ_MATERIALIZATION_FRONTIERS),
             Builtin::View(&MZ_PERF_DEPENDENCY_FRONTIERS),
+            Builtin::View(&MZ_INDEX_RECOMMENDATIONS),
+            Builtin::View(&MZ_FUNCTIONS),
             Builtin::View(&PG_NAMESPACE),
             Builtin::View(&PG_CLASS),
             Builtin::View(&PG_DATABASE),
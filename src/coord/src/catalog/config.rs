@@ -18,6 +18,8 @@ pub struct Config<'a> {
     pub path: &'a Path,
     /// Whether to enable experimental mode.
     pub experimental_mode: Option<bool>,
+    /// Whether sources are materialized by default.
+    pub default_source_materialization: bool,
     /// Whether to enable logging sources and the views that depend upon them.
     pub enable_logging: bool,
     /// Path to cache source data to disk.
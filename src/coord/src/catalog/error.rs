@@ -47,12 +47,21 @@ pub(crate) enum ErrorKind {
         message: String,
     },
     TypeRename(String),
+    ColumnRenameDependency {
+        column: String,
+        relation: String,
+        depender: String,
+    },
     ExperimentalModeRequired,
     ExperimentalModeUnavailable,
     FailedMigration {
         last_version: usize,
         cause: String,
     },
+    NewerCatalog {
+        catalog_version: String,
+        build_version: String,
+    },
 }
 
 impl Error {
@@ -95,9 +104,11 @@ impl std::error::Error for Error {
             | ErrorKind::UnsatisfiableLoggingDependency { .. }
             | ErrorKind::AmbiguousRename { .. }
             | ErrorKind::TypeRename(_)
+            | ErrorKind::ColumnRenameDependency { .. }
             | ErrorKind::ExperimentalModeRequired
             | ErrorKind::ExperimentalModeUnavailable
-            | ErrorKind::FailedMigration { .. } => None,
+            | ErrorKind::FailedMigration { .. }
+            | ErrorKind::NewerCatalog { .. } => None,
             ErrorKind::Sql(e) => Some(e),
             ErrorKind::Storage(e) => Some(e),
         }
@@ -109,7 +120,7 @@ impl fmt::Display for Error {
         match &self.kind {
             ErrorKind::Corruption { detail } => write!(f, "corrupt catalog: {}", detail),
             ErrorKind::IdExhaustion => write!(f, "id counter overflows i64"),
-            ErrorKind::OidExhaustion => write!(f, "oid counter overflows u32"),
+            ErrorKind::OidExhaustion => write!(f, "OID space exhausted; restart required"),
             ErrorKind::Sql(e) => write!(f, "{}", e),
             ErrorKind::DatabaseAlreadyExists(name) => {
                 write!(f, "database '{}' already exists", name)
@@ -161,6 +172,15 @@ impl fmt::Display for Error {
                 }
             }
             ErrorKind::TypeRename(typ) => write!(f, "cannot rename type: {}", typ),
+            ErrorKind::ColumnRenameDependency {
+                column,
+                relation,
+                depender,
+            } => write!(
+                f,
+                "cannot rename column \"{}\" of {}: {} may depend on it",
+                column, relation, depender
+            ),
             ErrorKind::ExperimentalModeRequired => write!(
                 f,
                 r#"Materialize previously started with --experimental to
@@ -181,6 +201,15 @@ more details, see https://materialize.com/docs/cli#experimental-mode"#
                 "migration from catalog content version {} failed: {}",
                 last_version, cause,
             ),
+            ErrorKind::NewerCatalog {
+                catalog_version,
+                build_version,
+            } => write!(
+                f,
+                "catalog was last opened by materialized v{}, which is newer than \
+                 this version (v{}); downgrading is not supported",
+                catalog_version, build_version,
+            ),
         }
     }
 }
@@ -62,6 +62,7 @@ pub const CONTENT_MIGRATIONS: &[fn(&mut Catalog) -> Result<(), anyhow::Error>] =
                     constraints: _,
                     with_options: _,
                     if_not_exists: _,
+                    temporary: _,
                 }) => {
                     for c in columns {
                         TypeNormalizer.visit_column_def_mut(c);
@@ -135,4 +136,8 @@ pub const CONTENT_MIGRATIONS: &[fn(&mut Catalog) -> Result<(), anyhow::Error>] =
     // of materialized. Migrations can be edited up until they ship in a
     // release, after which they must never be removed, only patched by future
     // migrations.
+    //
+    // Migrations must be idempotent. The catalog content version is bumped
+    // only after a migration's transaction commits, so a crash in between
+    // will cause the migration to run again on the next startup.
 ];
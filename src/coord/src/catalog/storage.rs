@@ -92,6 +92,26 @@ const MIGRATIONS: &[&str] = &[
         name TEXT PRIMARY KEY,
         value TEXT
     );",
+    // Tracks placeholder items, like sinks whose connectors are still being
+    // created, so that placeholders orphaned by a crash can be removed on the
+    // next boot.
+    //
+    // Introduced in v0.6.1.
+    "CREATE TABLE pending_items (
+        gid blob PRIMARY KEY
+    );",
+    // Stores the comments attached to items and their columns by `COMMENT
+    // ON`. As in PostgreSQL's `pg_description`, a `sub_id` of zero refers to
+    // the item itself, while a positive `sub_id` refers to the column at that
+    // 1-indexed position.
+    //
+    // Introduced in v0.6.1.
+    "CREATE TABLE comments (
+        gid blob NOT NULL,
+        sub_id integer NOT NULL,
+        comment text NOT NULL,
+        PRIMARY KEY (gid, sub_id)
+    );",
     // Add new migrations here.
     //
     // Migrations should be preceded with a comment of the following form:
@@ -282,6 +302,27 @@ impl Connection {
         Ok(())
     }
 
+    /// Gets the version of the newest `materialized` binary to have opened the
+    /// catalog, if one has been recorded.
+    pub fn get_catalog_build_version(&mut self) -> Result<Option<String>, Error> {
+        Ok(self
+            .inner
+            .query_row(
+                "SELECT value FROM settings WHERE name = 'build_version';",
+                params![],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_catalog_build_version(&mut self, version: &str) -> Result<(), Error> {
+        self.inner.execute(
+            "INSERT OR REPLACE INTO settings (name, value) VALUES ('build_version', ?);",
+            params![version],
+        )?;
+        Ok(())
+    }
+
     pub fn load_databases(&self) -> Result<Vec<(i64, String)>, Error> {
         self.inner
             .prepare("SELECT id, name FROM databases")?
@@ -338,6 +379,38 @@ impl Connection {
             .collect()
     }
 
+    /// Loads the IDs of placeholder items that were committed to the catalog
+    /// but never replaced by their final definition.
+    pub fn load_pending_items(&self) -> Result<Vec<GlobalId>, Error> {
+        self.inner
+            .prepare("SELECT gid FROM pending_items")?
+            .query_and_then(params![], |row| -> Result<_, Error> {
+                let id: SqlVal<GlobalId> = row.get(0)?;
+                Ok(id.0)
+            })?
+            .collect()
+    }
+
+    /// Loads the comments on items and their columns. Each comment is
+    /// returned alongside the ID of its item and the 0-indexed position of
+    /// its column, if it is attached to a column.
+    pub fn load_comments(&self) -> Result<Vec<(GlobalId, Option<usize>, String)>, Error> {
+        self.inner
+            .prepare("SELECT gid, sub_id, comment FROM comments")?
+            .query_and_then(params![], |row| -> Result<_, Error> {
+                let id: SqlVal<GlobalId> = row.get(0)?;
+                let sub_id: i64 = row.get(1)?;
+                let comment: String = row.get(2)?;
+                let column_pos = if sub_id == 0 {
+                    None
+                } else {
+                    Some(usize::cast_from(sub_id as u64 - 1))
+                };
+                Ok((id.0, column_pos, comment))
+            })?
+            .collect()
+    }
+
     pub fn allocate_id(&mut self) -> Result<GlobalId, Error> {
         let tx = self.inner.transaction()?;
         // SQLite doesn't support u64s, so we constrain ourselves to the more
@@ -443,6 +516,15 @@ impl Transaction<'_> {
         }
     }
 
+    /// Marks the item `id` as a placeholder. The mark is cleared when the item
+    /// is removed.
+    pub fn insert_pending_item(&self, id: GlobalId) -> Result<(), Error> {
+        self.inner
+            .prepare_cached("INSERT INTO pending_items (gid) VALUES (?)")?
+            .execute(params![SqlVal(&id)])?;
+        Ok(())
+    }
+
     pub fn remove_database(&self, name: &str) -> Result<(), Error> {
         let n = self
             .inner
@@ -475,6 +557,12 @@ impl Transaction<'_> {
             .prepare_cached("DELETE FROM items WHERE gid = ?")?
             .execute(params![SqlVal(id)])?;
         assert!(n <= 1);
+        self.inner
+            .prepare_cached("DELETE FROM pending_items WHERE gid = ?")?
+            .execute(params![SqlVal(id)])?;
+        self.inner
+            .prepare_cached("DELETE FROM comments WHERE gid = ?")?
+            .execute(params![SqlVal(id)])?;
         if n == 1 {
             Ok(())
         } else {
@@ -495,6 +583,34 @@ impl Transaction<'_> {
         }
     }
 
+    /// Sets the comment on the item `id`, or on its column at the 0-indexed
+    /// `column_pos`, if specified. A `comment` of `None` removes the existing
+    /// comment, if any.
+    pub fn set_comment(
+        &self,
+        id: GlobalId,
+        column_pos: Option<usize>,
+        comment: Option<&str>,
+    ) -> Result<(), Error> {
+        let sub_id = match column_pos {
+            None => 0,
+            Some(pos) => i64::try_from(pos + 1).expect("column position fits in i64"),
+        };
+        match comment {
+            Some(comment) => self
+                .inner
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO comments (gid, sub_id, comment) VALUES (?, ?, ?)",
+                )?
+                .execute(params![SqlVal(&id), sub_id, comment])?,
+            None => self
+                .inner
+                .prepare_cached("DELETE FROM comments WHERE gid = ? AND sub_id = ?")?
+                .execute(params![SqlVal(&id), sub_id])?,
+        };
+        Ok(())
+    }
+
     pub fn commit(self) -> Result<(), rusqlite::Error> {
         self.inner.commit()
     }
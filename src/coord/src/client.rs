@@ -13,7 +13,8 @@ use sql::ast::Statement;
 use sql::plan::Params;
 
 use crate::command::{
-    Command, ExecuteResponse, NoSessionExecuteResponse, Response, StartupMessage,
+    Command, ConnectionResources, ExecuteResponse, NoSessionExecuteResponse, Response,
+    StartupMessage,
 };
 use crate::session::Session;
 
@@ -45,6 +46,24 @@ impl Client {
         self.send(|tx| Command::DumpCatalog { tx }).await
     }
 
+    /// Snapshots the catalog and the coordinator's index frontiers and pending
+    /// sinks, all as of the same moment, to a JSON string.
+    pub async fn snapshot_catalog(&mut self) -> String {
+        self.send(|tx| Command::SnapshotCatalog { tx }).await
+    }
+
+    /// Pauses the ingestion of new data by all sources, returning the number
+    /// of sources that were paused.
+    pub async fn pause_sources(&mut self) -> usize {
+        self.send(|tx| Command::PauseSources { tx }).await
+    }
+
+    /// Resumes the ingestion of new data by all sources, returning the number
+    /// of sources that were resumed.
+    pub async fn resume_sources(&mut self) -> usize {
+        self.send(|tx| Command::ResumeSources { tx }).await
+    }
+
     /// Executes a statement as the system user that is not tied to a session.
     ///
     /// This will execute in a pseudo session that is not able to create any
@@ -58,6 +77,18 @@ impl Client {
             .await
     }
 
+    /// Executes several `SELECT` statements as the system user, like
+    /// [`Client::execute`], all at the same timestamp.
+    ///
+    /// Fails if there is no timestamp at which every statement's inputs are
+    /// complete and not yet compacted.
+    pub async fn execute_batch(
+        &mut self,
+        stmts: Vec<Statement>,
+    ) -> Result<Vec<NoSessionExecuteResponse>, anyhow::Error> {
+        self.send(|tx| Command::ExecuteBatch { stmts, tx }).await
+    }
+
     /// Cancel the query currently running on another connection.
     pub async fn cancel_request(&mut self, conn_id: u32) {
         self.cmd_tx
@@ -66,6 +97,27 @@ impl Client {
             .expect("coordinator unexpectedly canceled request")
     }
 
+    /// Forcibly cancels the query currently running on the specified
+    /// connection.
+    ///
+    /// Unlike [`Client::cancel_request`], this reports whether the connection
+    /// had a query to cancel.
+    pub async fn cancel_connection(&mut self, conn_id: u32) -> bool {
+        self.send(|tx| Command::CancelConnection { conn_id, tx })
+            .await
+    }
+
+    /// Lists the IDs of the connections that are currently running a query.
+    pub async fn list_active_queries(&mut self) -> Vec<u32> {
+        self.send(|tx| Command::ListActiveQueries { tx }).await
+    }
+
+    /// Lists the admitted connections and the resources held on behalf of
+    /// each, in ascending order of connection ID.
+    pub async fn list_connections(&mut self) -> Vec<ConnectionResources> {
+        self.send(|tx| Command::ListConnections { tx }).await
+    }
+
     async fn send<T, F>(&mut self, f: F) -> T
     where
         F: FnOnce(futures::channel::oneshot::Sender<T>) -> Command,
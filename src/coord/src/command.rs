@@ -7,10 +7,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 
 use derivative::Derivative;
+use futures::Stream;
 
 use dataflow_types::PeekResponse;
 use repr::Row;
@@ -54,10 +56,48 @@ pub enum Command {
         conn_id: u32,
     },
 
+    /// Cancel the query running on the specified connection, if any. Reports
+    /// whether a query was running.
+    CancelConnection {
+        conn_id: u32,
+        tx: futures::channel::oneshot::Sender<bool>,
+    },
+
+    /// List the connections that are running a query.
+    ListActiveQueries {
+        tx: futures::channel::oneshot::Sender<Vec<u32>>,
+    },
+
+    /// List the admitted connections, along with the resources that the
+    /// coordinator holds on behalf of each.
+    ListConnections {
+        tx: futures::channel::oneshot::Sender<Vec<ConnectionResources>>,
+    },
+
     DumpCatalog {
         tx: futures::channel::oneshot::Sender<String>,
     },
 
+    /// Serializes a point-in-time snapshot of the catalog together with the
+    /// frontiers of every index and the IDs of any sinks whose creation is
+    /// still in progress.
+    SnapshotCatalog {
+        tx: futures::channel::oneshot::Sender<String>,
+    },
+
+    /// Stops assigning timestamps to all sources, so that no new data is
+    /// ingested, while existing dataflows continue to serve reads. Reports
+    /// the number of sources that were paused.
+    PauseSources {
+        tx: futures::channel::oneshot::Sender<usize>,
+    },
+
+    /// Undoes `PauseSources`. Reports the number of sources that were
+    /// resumed.
+    ResumeSources {
+        tx: futures::channel::oneshot::Sender<usize>,
+    },
+
     Terminate {
         session: Session,
     },
@@ -67,6 +107,14 @@ pub enum Command {
         params: sql::plan::Params,
         tx: futures::channel::oneshot::Sender<anyhow::Result<NoSessionExecuteResponse>>,
     },
+
+    /// Like `NoSessionExecute`, but executes several `SELECT` statements, all
+    /// at a single timestamp that is valid for every index they read, so that
+    /// their results reflect a consistent snapshot.
+    ExecuteBatch {
+        stmts: Vec<Statement>,
+        tx: futures::channel::oneshot::Sender<anyhow::Result<Vec<NoSessionExecuteResponse>>>,
+    },
 }
 
 #[derive(Debug)]
@@ -83,6 +131,10 @@ pub struct NoSessionExecuteResponse {
 
 pub type RowsFuture = Pin<Box<dyn Future<Output = Result<PeekResponse, comm::Error>> + Send>>;
 
+/// A stream of peek responses, one per worker, that together make up the
+/// results of a peek.
+pub type RowsStream = Pin<Box<dyn Stream<Item = Result<PeekResponse, comm::Error>> + Send>>;
+
 /// Notifications that may be generated in response to [`Command::Startup`].
 #[derive(Debug)]
 pub enum StartupMessage {
@@ -90,6 +142,69 @@ pub enum StartupMessage {
     UnknownSessionDatabase,
 }
 
+/// The resources that the coordinator holds on behalf of a connection, as
+/// reported by [`Command::ListConnections`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionResources {
+    /// The ID of the connection.
+    pub conn_id: u32,
+    /// The number of items in the connection's temporary schema. Each holds a
+    /// catalog ID until the connection drops it or terminates.
+    pub temporary_items: usize,
+    /// The number of peeks that the connection has issued whose results have
+    /// not yet been delivered, including any peek awaiting admission.
+    pub active_peeks: usize,
+    /// The number of dataflows servicing a `TAIL` on behalf of the
+    /// connection, including the one that delivers its notifications.
+    pub active_tails: usize,
+}
+
+/// The error returned by [`Command::Startup`] when the coordinator is already
+/// serving the maximum number of connections.
+#[derive(Debug)]
+pub struct TooManyConnections {
+    /// The configured connection limit.
+    pub limit: usize,
+}
+
+impl fmt::Display for TooManyConnections {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "too many connections: the limit of {} connections has been reached",
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for TooManyConnections {}
+
+/// The error returned by [`Command::Execute`] when the statement was canceled
+/// before it began executing.
+#[derive(Debug)]
+pub struct QueryCanceled;
+
+impl fmt::Display for QueryCanceled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("canceling statement due to user request")
+    }
+}
+
+impl std::error::Error for QueryCanceled {}
+
+/// The error returned in response to any command from a connection that the
+/// coordinator terminated for sitting idle too long.
+#[derive(Debug)]
+pub struct IdleSessionTimeout;
+
+impl fmt::Display for IdleSessionTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("terminating connection due to idle-session timeout")
+    }
+}
+
+impl std::error::Error for IdleSessionTimeout {}
+
 /// The response to [`Command::Execute]`.
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -103,8 +218,13 @@ pub enum ExecuteResponse {
     AlteredObject(ObjectType),
     // The index was altered.
     AlteredIndexLogicalCompaction,
+    /// The compaction window of all indexes was altered, and this many
+    /// indexes were affected.
+    AlteredAllIndexesLogicalCompaction(usize),
     /// The requested cursor was closed.
     ClosedCursor,
+    /// The comment on the requested object was set.
+    Commented,
     CopyTo {
         format: sql::plan::CopyFormat,
         #[derivative(Debug = "ignore")]
@@ -140,12 +260,19 @@ pub enum ExecuteResponse {
     },
     /// The requested type was created.
     CreatedType,
+    /// The requested function was created.
+    CreatedFunction,
     /// The requested cursor was declared.
     DeclaredCursor,
     /// The specified number of rows were deleted from the requested table.
     Deleted(usize),
     /// The temporary objects associated with the session have been discarded.
     DiscardedTemp,
+    /// The prepared statements and portals associated with the session have
+    /// been discarded.
+    DiscardedPlans,
+    /// The sequence state associated with the session has been discarded.
+    DiscardedSequences,
     /// All state associated with the session has been discarded.
     DiscardedAll,
     /// The requested database was dropped.
@@ -164,6 +291,8 @@ pub enum ExecuteResponse {
     DroppedSink,
     /// The requested type was dropped.
     DroppedType,
+    /// The requested function was dropped.
+    DroppedFunction,
     /// The provided query was empty.
     EmptyQuery,
     /// Fetch results from a cursor.
@@ -177,13 +306,28 @@ pub enum ExecuteResponse {
     },
     /// The specified number of rows were inserted into the requested table.
     Inserted(usize),
+    /// The session started listening on the requested channel.
+    Listened,
+    /// A notification was sent on the requested channel.
+    Notified,
     /// A SQL error occurred.
     PgError {
         code: SqlState,
         message: String,
     },
+    /// The requested view's indexes are being rebuilt, and this many indexes
+    /// were affected.
+    RematerializedView(usize),
+    /// The requested materialized view was refreshed, which has no effect.
+    RefreshedMaterializedView,
+    /// The specified number of rows were selected into a newly created table.
+    SelectedInto(usize),
     /// Rows will be delivered via the specified future.
     SendingRows(#[derivative(Debug = "ignore")] RowsFuture),
+    /// Rows will be delivered in batches via the specified stream, as they
+    /// arrive from the workers. Only produced as the inner response of
+    /// [`ExecuteResponse::CopyTo`].
+    StreamingRows(#[derivative(Debug = "ignore")] RowsStream),
     /// The specified variable was set to a new value.
     SetVariable {
         name: String,
@@ -195,6 +339,8 @@ pub enum ExecuteResponse {
     Tailing {
         rx: comm::mpsc::Receiver<Vec<Row>>,
     },
+    /// The session stopped listening on the requested channels.
+    Unlistened,
     /// The specified number of rows were updated in the requested table.
     Updated(usize),
 }
@@ -17,22 +17,29 @@
 //! must accumulate to the same value as would an un-compacted trace.
 
 use std::cmp;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
+use std::fmt;
 use std::iter;
-use std::os::unix::ffi::OsStringExt;
-use std::path::Path;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use differential_dataflow::lattice::Lattice;
-use futures::future::{self, TryFutureExt};
+use futures::future::{self, FutureExt, TryFutureExt};
 use futures::sink::SinkExt;
 use futures::stream::{self, StreamExt, TryStreamExt};
+use lazy_static::lazy_static;
+use prometheus::{register_histogram_vec, HistogramVec};
+use serde::Serialize;
 use timely::progress::{Antichain, ChangeBatch, Timestamp as _};
 use tokio::runtime::{Handle, Runtime};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_postgres::error::SqlState;
 use uuid::Uuid;
 
@@ -41,16 +48,17 @@ use dataflow::source::cache::CacheSender;
 use dataflow::{CacheMessage, SequencedCommand, WorkerFeedback, WorkerFeedbackWithMeta};
 use dataflow_types::logging::LoggingConfig as DataflowLoggingConfig;
 use dataflow_types::{
-    AvroOcfSinkConnector, DataflowDesc, IndexDesc, KafkaSinkConnector, PeekResponse, SinkConnector,
-    SourceConnector, TailSinkConnector, TimestampSourceUpdate, Update,
+    AvroOcfSinkConnector, DataflowDesc, IndexDesc, KafkaSinkConnector, MzOffset, PeekResponse,
+    SinkConnector, SourceConnector, TailBackpressure, TailLimit, TailSinkConnector,
+    TimestampSourceUpdate, Update,
 };
 use expr::{
     ExprHumanizer, GlobalId, Id, NullaryFunc, OptimizedRelationExpr, RelationExpr, RowSetFinishing,
     ScalarExpr, SourceInstanceId,
 };
-use ore::collections::CollectionExt;
 use ore::thread::JoinHandleExt;
-use repr::{ColumnName, Datum, RelationDesc, RelationType, Row, RowPacker, Timestamp};
+use repr::adt::interval::Interval;
+use repr::{ColumnName, Datum, RelationDesc, RelationType, Row, RowArena, RowPacker, Timestamp};
 use sql::ast::display::AstDisplay;
 use sql::ast::{
     CreateIndexStatement, CreateTableStatement, DropObjectsStatement, ExplainOptions, ExplainStage,
@@ -60,36 +68,73 @@ use sql::catalog::Catalog as _;
 use sql::names::{DatabaseSpecifier, FullName, SchemaName};
 use sql::plan::StatementDesc;
 use sql::plan::{
-    AlterIndexLogicalCompactionWindow, CopyFormat, LogicalCompactionWindow, MutationKind, Params,
-    PeekWhen, Plan, PlanContext,
+    AlterIndexKeys, AlterIndexLogicalCompactionWindow,
+    AlterMaterializedViewLogicalCompactionWindow, ArrangementsExplainee, CopyFormat, CopyTo,
+    CopyToTarget, LogicalCompactionWindow, MutationKind, Params, PeekWhen, Plan, PlanContext,
 };
 use transform::Optimizer;
 
 use self::arrangement_state::{ArrangementFrontiers, Frontiers};
+use self::feedback_batch::FrontierUppersBatch;
+use self::parse_cache::{ParseCache, PARSE_CACHE_CAPACITY};
+use self::plan_cache::{CachedPeek, PlanCache, PlanCacheKey, PLAN_CACHE_CAPACITY};
+use self::since_updates::SinceUpdates;
+use self::source_registrations::SourceRegistrations;
 use crate::cache::{CacheConfig, Cacher};
 use crate::catalog::builtin::{
-    BUILTINS, MZ_ARRAY_TYPES, MZ_AVRO_OCF_SINKS, MZ_BASE_TYPES, MZ_COLUMNS, MZ_DATABASES,
-    MZ_INDEXES, MZ_INDEX_COLUMNS, MZ_KAFKA_SINKS, MZ_LIST_TYPES, MZ_MAP_TYPES, MZ_SCHEMAS,
-    MZ_SINKS, MZ_SOURCES, MZ_TABLES, MZ_TYPES, MZ_VIEWS, MZ_VIEW_FOREIGN_KEYS, MZ_VIEW_KEYS,
+    BUILTINS, MZ_ARRAY_TYPES, MZ_AVRO_OCF_SINKS, MZ_BASE_TYPES, MZ_COLUMNS, MZ_COMMENTS,
+    MZ_COORDINATOR_STATE, MZ_DATABASES, MZ_FUNCTION_SIGNATURES, MZ_INDEXES, MZ_INDEX_COLUMNS,
+    MZ_KAFKA_SINKS, MZ_KAFKA_SOURCE_PARTITIONS, MZ_LIST_TYPES, MZ_MAP_TYPES, MZ_NOTIFICATIONS,
+    MZ_OBJECT_HISTORY, MZ_SCHEMAS, MZ_SESSIONS, MZ_SINKS, MZ_SINK_BUILDS, MZ_SINK_STATUS,
+    MZ_SLOW_PATH_PEEKS, MZ_SOURCES, MZ_TABLES, MZ_TYPES, MZ_VIEWS, MZ_VIEW_FOREIGN_KEYS,
+    MZ_VIEW_KEYS,
+};
+use crate::catalog::{
+    self, Catalog, CatalogEntry, CatalogItem, Func, Index, SinkConnectorState, Type, TypeInner,
 };
-use crate::catalog::{self, Catalog, CatalogItem, Index, SinkConnectorState, Type, TypeInner};
 use crate::command::{
-    Command, ExecuteResponse, NoSessionExecuteResponse, Response, StartupMessage,
+    Command, ConnectionResources, ExecuteResponse, IdleSessionTimeout, NoSessionExecuteResponse,
+    QueryCanceled, Response, StartupMessage, TooManyConnections,
 };
-use crate::session::{PreparedStatement, Session, TransactionStatus};
+use crate::copy;
+use crate::session::{Notice, PreparedStatement, Session, TransactionSnapshot, TransactionStatus};
 use crate::sink_connector;
 use crate::timestamp::{TimestampConfig, TimestampMessage, Timestamper};
 use crate::util::ClientTransmitter;
 
 mod arrangement_state;
 mod dataflow_builder;
+mod feedback_batch;
+mod parse_cache;
+mod plan_cache;
+mod since_updates;
+mod source_registrations;
+
+lazy_static! {
+    /// How long each phase of a catalog transaction took. The `mutation` phase
+    /// applies the transaction to the catalog and its durable storage, and the
+    /// `broadcast` phase updates the system tables and the dataflow layer to
+    /// reflect the changes.
+    static ref CATALOG_TRANSACTION_DURATIONS: HistogramVec = register_histogram_vec!(
+        "mz_catalog_transaction_duration_seconds",
+        "How long each phase of a catalog transaction took",
+        &["phase"],
+        prometheus::exponential_buckets(0.001, 2.0, 16).unwrap()
+    )
+    .unwrap();
+}
 
 pub enum Message {
     Command(Command),
     Worker(WorkerFeedbackWithMeta),
     AdvanceSourceTimestamp(AdvanceSourceTimestamp),
+    KafkaPartitionWatermark(KafkaPartitionWatermark),
     StatementReady(StatementReady),
     SinkConnectorReady(SinkConnectorReady),
+    PeekAdmissionReady(PeekAdmissionReady),
+    PeekComplete { conn_id: u32 },
+    ScanIdleSessions,
+    FlushFrontierUppers,
     Shutdown,
 }
 
@@ -98,11 +143,24 @@ pub struct AdvanceSourceTimestamp {
     pub update: TimestampSourceUpdate,
 }
 
+/// The timestamper's current offset-to-timestamp binding for one partition of
+/// a Kafka source instance.
+pub struct KafkaPartitionWatermark {
+    pub id: SourceInstanceId,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: MzOffset,
+    pub timestamp: Timestamp,
+}
+
 pub struct StatementReady {
     pub session: Session,
     pub tx: ClientTransmitter<ExecuteResponse>,
     pub result: Result<sql::ast::Statement, anyhow::Error>,
     pub params: Params,
+    /// The session's cancel epoch when purification began. See
+    /// `Coordinator::active_conns`.
+    pub cancel_epoch: u64,
 }
 
 pub struct SinkConnectorReady {
@@ -113,12 +171,90 @@ pub struct SinkConnectorReady {
     pub result: Result<SinkConnector, anyhow::Error>,
 }
 
+/// A slow-path peek that was waiting to be admitted, along with the outcome of
+/// the wait.
+pub struct PeekAdmissionReady {
+    pub session: Session,
+    pub tx: ClientTransmitter<ExecuteResponse>,
+    pub source: RelationExpr,
+    pub when: PeekWhen,
+    pub finishing: RowSetFinishing,
+    pub copy_to: Option<CopyTo>,
+    pub index_hint: Option<GlobalId>,
+    pub source_optimized: bool,
+    pub result: PeekAdmissionResult,
+}
+
+/// The outcome of waiting for a slow-path peek to be admitted.
+pub enum PeekAdmissionResult {
+    /// A permit was acquired. The peek may now ship its dataflow.
+    Admitted(OwnedSemaphorePermit),
+    /// The peek was canceled while it was waiting.
+    Canceled,
+    /// No permit became available within the maximum wait time.
+    TimedOut(Duration),
+}
+
 #[derive(Clone, Debug)]
 pub struct LoggingConfig {
     pub granularity: Duration,
     pub log_logging: bool,
 }
 
+/// Configures admission control for peeks that must build a transient
+/// dataflow.
+#[derive(Clone, Debug)]
+pub struct PeekAdmissionConfig {
+    /// The maximum number of slow-path peeks that may be in flight at once.
+    /// If zero, every slow-path peek fails immediately.
+    pub max_concurrent: usize,
+    /// How long a slow-path peek will wait to be admitted before failing. If
+    /// `None`, peeks wait indefinitely, unless `max_concurrent` is zero. If
+    /// zero, peeks that cannot be admitted immediately fail.
+    pub max_wait: Option<Duration>,
+}
+
+/// Configures `COPY ... TO` statements that write to files on the server.
+#[derive(Clone, Debug)]
+pub struct CopyToFileConfig {
+    /// The directory within which files may be written.
+    pub directory: PathBuf,
+    /// The size, in bytes, at which a new file is started.
+    pub max_file_size: u64,
+}
+
+/// A TAIL that is running on behalf of a connection.
+#[derive(Debug)]
+struct ActiveTail {
+    /// The ID of the dataflow that is servicing the TAIL.
+    sink_id: GlobalId,
+    /// The ID of the object that the TAIL reads from.
+    from: GlobalId,
+    /// For a TAIL whose rows are collected into a single result set, a
+    /// channel that reports that the TAIL was canceled, so that its partial
+    /// result is not mistaken for a complete one.
+    cancel_tx: Option<futures::channel::oneshot::Sender<()>>,
+    /// Whether the TAIL was issued inside an explicit transaction, as a TAIL
+    /// read through a cursor is, in which case the client fetches its rows
+    /// with further commands.
+    in_transaction: bool,
+}
+
+/// The default maximum number of indexes that may exist on a single relation.
+pub const DEFAULT_MAX_INDEXES_PER_RELATION: usize = 32;
+
+/// The default size, in bytes, at which `COPY ... TO` a file starts a new
+/// file.
+pub const DEFAULT_COPY_TO_FILE_MAX_SIZE: u64 = 1 << 30;
+
+/// The length, in bytes, above which the output of `EXPLAIN` is split into one
+/// row per line.
+const EXPLAIN_SPLIT_THRESHOLD: usize = 1 << 20;
+
+/// The longest interval at which the coordinator checks for connections that
+/// have exceeded the idle-session timeout.
+const IDLE_SESSION_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct Config<'a, C>
 where
     C: comm::Connection,
@@ -132,7 +268,19 @@ where
     pub timestamp: TimestampConfig,
     pub cache: Option<CacheConfig>,
     pub logical_compaction_window: Option<Duration>,
+    pub logical_compaction_hysteresis: u64,
+    pub feedback_batch_window: Option<Duration>,
+    pub peek_admission: Option<PeekAdmissionConfig>,
+    pub max_connections: Option<usize>,
+    pub idle_session_timeout: Option<Duration>,
+    pub max_indexes_per_relation: usize,
+    pub copy_to_file: Option<CopyToFileConfig>,
+    pub bootstrap_timeout: Option<Duration>,
+    pub timestamp_quantum: Duration,
     pub experimental_mode: bool,
+    pub default_source_materialization: bool,
+    pub object_history: bool,
+    pub skip_failed_sink_rebuilds: bool,
     pub build_info: &'static BuildInfo,
 }
 
@@ -144,19 +292,143 @@ where
     switchboard: comm::Switchboard<C>,
     broadcast_tx: comm::broadcast::Sender<SequencedCommand>,
     num_timely_workers: usize,
+    /// When the coordinator started, for computing `mz_uptime`.
+    start_time: Instant,
+    /// The wall clock time at which the coordinator started, as reported to
+    /// `mz_coordinator_state`.
+    started_at: DateTime<Utc>,
     optimizer: Optimizer,
     catalog: Catalog,
     symbiosis: Option<symbiosis::Postgres>,
     /// Maps (global Id of arrangement) -> (frontier information)
     indexes: ArrangementFrontiers<Timestamp>,
-    since_updates: Vec<(GlobalId, Antichain<Timestamp>)>,
-    /// For each connection running a TAIL command, the name of the dataflow
-    /// that is servicing the TAIL. A connection can only run one TAIL at a
-    /// time.
-    active_tails: HashMap<u32, GlobalId>,
+    since_updates: SinceUpdates,
+    /// For each connection running a TAIL command, the dataflow that is
+    /// servicing the TAIL. A connection can only run one TAIL at a time.
+    active_tails: HashMap<u32, ActiveTail>,
+    /// For each connection that is listening on at least one channel, the
+    /// name of the dataflow that is servicing its `TAIL` of
+    /// `mz_notifications`. Unlike `active_tails`, these are unaffected by
+    /// query cancellation.
+    listen_tails: HashMap<u32, GlobalId>,
+    /// For each TAIL with an `UNTIL` frontier, the number of workers that
+    /// have reported reaching that frontier. The dataflow is dropped once
+    /// every worker has done so.
+    tail_completions: HashMap<GlobalId, usize>,
+    /// For each running Kafka source instance, the most recent row reported
+    /// to `mz_kafka_source_partitions` for each partition, so that it can be
+    /// retracted when the timestamper reports a new binding or the instance
+    /// is dropped.
+    kafka_partition_watermarks: HashMap<SourceInstanceId, HashMap<i32, Row>>,
+    /// For each unindexed relation that slow-path peeks have read, the number
+    /// of such peeks for each set of columns they constrained to literals,
+    /// along with the row most recently reported to `mz_slow_path_peeks`, so
+    /// that it can be retracted when the count changes or the relation is
+    /// dropped or indexed.
+    slow_path_peeks: HashMap<GlobalId, HashMap<Vec<usize>, (i64, Row)>>,
+    /// For each running Kafka or Avro OCF sink, and each sink that failed to
+    /// rebuild during bootstrap, the row most recently reported to
+    /// `mz_sink_status`, so that it can be retracted when the sink reports an
+    /// error or recovers from one, or when the sink is dropped.
+    sink_statuses: HashMap<GlobalId, Row>,
+    /// For each sink whose connector is being built, or was built or failed
+    /// to build since the coordinator started, the time at which the build
+    /// started, along with the row most recently reported to
+    /// `mz_sink_builds`, so that it can be retracted when the build finishes
+    /// or the sink is dropped.
+    sink_builds: HashMap<GlobalId, (DateTime<Utc>, Row)>,
+    /// For each client connection, the time at which it was established,
+    /// along with the row most recently reported to `mz_sessions`, so that it
+    /// can be retracted when the connection changes its `application_name` or
+    /// terminates.
+    sessions: HashMap<u32, (DateTime<Utc>, Row)>,
+    /// For each running Avro OCF sink, the files it has written to, in the
+    /// order it wrote to them, so that they can be retracted from
+    /// `mz_avro_ocf_sinks` when the sink is dropped. The last file is the one
+    /// the sink is currently writing to.
+    avro_ocf_sink_files: HashMap<GlobalId, Vec<PathBuf>>,
+    /// The statements parsed from the `create_sql` of catalog items, which
+    /// are re-parsed during bootstrap and whenever an index is reported to
+    /// `mz_indexes`.
+    parse_cache: ParseCache,
+    /// The optimized plans of recently executed ad-hoc queries, which are
+    /// cleared whenever the catalog changes.
+    plan_cache: PlanCache,
+    /// Whether timestamping of all sources has been paused by
+    /// `Command::PauseSources`.
+    sources_paused: bool,
+    /// The source instances that have been registered with the timestamper,
+    /// or that will be when sources are resumed.
+    source_registrations: SourceRegistrations,
+    /// Permits for slow-path peeks, if the number of concurrent slow-path
+    /// peeks is limited.
+    peek_semaphore: Option<Arc<Semaphore>>,
+    /// How long a slow-path peek will wait for a permit before failing.
+    peek_max_wait: Option<Duration>,
+    /// For each connection with a peek that is waiting for a permit, a
+    /// channel that cancels the wait.
+    throttled_peeks: HashMap<u32, futures::channel::oneshot::Sender<()>>,
+    /// For each connection, the number of issued peeks whose results have not
+    /// yet been delivered to the client.
+    active_peeks: HashMap<u32, usize>,
+    /// For each connection with an issued or throttled peek, the objects that
+    /// the peek reads from, so that the peek can be canceled if those objects
+    /// are dropped.
+    peek_uses: HashMap<u32, Vec<GlobalId>>,
+    /// For each connection with an issued peek, the arrangements of catalog
+    /// indexes that the peek reads from, so that the peek can be failed if
+    /// those arrangements are dropped before it completes.
+    peek_arrangements: HashMap<u32, Vec<GlobalId>>,
+    /// The maximum number of concurrent connections, if limited.
+    max_connections: Option<usize>,
+    /// How long a connection may go without issuing a command, while it has
+    /// no query in progress, before it is terminated, if ever.
+    idle_session_timeout: Option<Duration>,
+    /// For each connection in `active_conns`, when it last issued a command.
+    conn_activity: HashMap<u32, Instant>,
+    /// The connections that were terminated for exceeding
+    /// `idle_session_timeout` but whose clients have not yet sent
+    /// `Command::Terminate`.
+    idle_terminated_conns: HashSet<u32>,
+    /// The maximum number of indexes that `CREATE INDEX` permits on a single
+    /// relation.
+    max_indexes_per_relation: usize,
+    /// Where `COPY ... TO` may write files, if it may write files at all.
+    copy_to_file: Option<CopyToFileConfig>,
+    /// Whether to record the creation, drop, and rename of each object in
+    /// `mz_object_history`.
+    object_history: bool,
+    /// Whether a sink whose connector fails to rebuild during bootstrap is
+    /// skipped, rather than failing startup.
+    skip_failed_sink_rebuilds: bool,
+    /// The IDs of the connections that have been admitted by
+    /// `Command::Startup` and not yet terminated, each with its cancel epoch:
+    /// the number of cancel requests the connection has received. A statement
+    /// whose purification straddles a cancel request observes a new epoch
+    /// when it is ready, and is abandoned.
+    active_conns: HashMap<u32, u64>,
     timestamp_config: TimestampConfig,
+    /// The granularity, in milliseconds, to which `get_ts` rounds down the
+    /// wall clock.
+    timestamp_quantum_ms: Timestamp,
     /// Delta from leading edge of an arrangement from which we allow compaction.
     logical_compaction_window_ms: Option<Timestamp>,
+    /// How far, as a percentage of an arrangement's compaction window, its
+    /// compaction frontier must advance before compaction is requested.
+    logical_compaction_hysteresis: u64,
+    /// How long to buffer `WorkerFeedback::FrontierUppers` before applying
+    /// it, if at all.
+    feedback_batch_window: Option<Duration>,
+    /// The `WorkerFeedback::FrontierUppers` buffered during the current
+    /// `feedback_batch_window`.
+    frontier_uppers_batch: FrontierUppersBatch,
+    /// The compaction window imposed on all indexes by `ALTER INDEX ALL SET`,
+    /// if any.
+    compaction_window_override: Option<Option<Timestamp>>,
+    /// The compaction windows that indexes had before
+    /// `compaction_window_override` was imposed. `ALTER INDEX ALL RESET`
+    /// restores them.
+    saved_compaction_windows: HashMap<GlobalId, Option<Timestamp>>,
     /// Instance count: number of times sources have been instantiated in views. This is used
     /// to associate each new instance of a source with a unique instance id (iid)
     logging_granularity: Option<u64>,
@@ -173,6 +445,9 @@ where
     /// TODO(justin): this is a hack, and does not work right with TAIL.
     need_advance: bool,
     transient_id_counter: u64,
+    /// The step of `bootstrap` that is in progress, reported if bootstrapping
+    /// exceeds its timeout.
+    bootstrap_phase: BootstrapPhase,
 }
 
 impl<C> Coordinator<C>
@@ -207,12 +482,16 @@ where
         // This is a hack. In a perfect world we would represent time as having a "real" dimension
         // and a "coordinator" dimension so that clients always observed linearizability from
         // things the coordinator did without being related to the real dimension.
-        let ts = SystemTime::now()
+        let ts: Timestamp = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect("failed to get millis since epoch")
             .as_millis()
             .try_into()
             .expect("current time did not fit into u64");
+        // Round down to the configured quantum, so that operations that occur
+        // within the same quantum share a timestamp. Rounding down can move
+        // the timestamp backwards, but the clamp below preserves monotonicity.
+        let ts = ts - ts % self.timestamp_quantum_ms;
 
         if ts < self.read_lower_bound {
             self.read_lower_bound
@@ -225,6 +504,12 @@ where
     /// called after creating the coordinator and before calling the
     /// `Coordinator::serve` method.
     async fn bootstrap(&mut self, events: Vec<catalog::Event>) -> Result<(), anyhow::Error> {
+        // Placeholder items that are still pending were orphaned when the
+        // previous process exited before replacing them, e.g. while it was
+        // building a sink's connector. Their creation never completed, so
+        // don't rebuild them; they are removed below.
+        let orphaned_ids = self.catalog.load_pending_items()?;
+
         let items: Vec<_> = events
             .iter()
             .filter_map(|event| match event {
@@ -234,14 +519,41 @@ where
                     name,
                     item,
                     ..
-                } => Some((id, oid, name, item)),
+                } if !orphaned_ids.contains(id) => Some((id, oid, name, item)),
                 _ => None,
             })
             .collect();
 
+        self.bootstrap_phase = BootstrapPhase::ValidatingCatalog;
+        // The `create_sql` of user items is re-parsed on the next boot, and
+        // that of indexes whenever they are reported to `mz_indexes`. Verify
+        // that it parses now, so that a corrupted catalog fails startup with a
+        // clear error rather than causing a panic later on. The `create_sql`
+        // of other builtin items is a placeholder and is never parsed.
+        for &(id, _, name, item) in &items {
+            if !id.is_user() && !matches!(item, CatalogItem::Index(_)) {
+                continue;
+            }
+            if let Err(e) = self.parse_cache.parse(item.create_sql()) {
+                log::error!(
+                    "catalog item {} ({}) has invalid create_sql {:?}: {}",
+                    name,
+                    id,
+                    item.create_sql(),
+                    e
+                );
+                bail!(
+                    "catalog item {} ({}) has invalid create_sql: {}",
+                    name,
+                    id,
+                    e
+                );
+            }
+        }
+
         // Sources and indexes may be depended upon by other catalog items,
         // insert them first.
-        for &(id, _, _, item) in &items {
+        for &(id, _, name, item) in &items {
             match item {
                 //currently catalog item rebuild assumes that sinks and
                 //indexes are always built individually and does not store information
@@ -249,9 +561,11 @@ where
                 //using a single dataflow, we have to make sure the rebuild process re-runs
                 //the same multiple-build dataflow.
                 CatalogItem::Source(source) => {
+                    self.bootstrap_phase = BootstrapPhase::CachingSource(name.to_string());
                     self.maybe_begin_caching(*id, &source.connector).await;
                 }
                 CatalogItem::Index(_) => {
+                    self.bootstrap_phase = BootstrapPhase::ShippingIndex(name.to_string());
                     if BUILTINS.logs().any(|log| log.index_id == *id) {
                         // Indexes on logging views are special, as they are
                         // already installed in the dataflow plane via
@@ -266,7 +580,7 @@ where
                             .insert(*id, Frontiers::new(self.num_timely_workers, Some(1_000)));
                     } else {
                         self.ship_dataflow(self.dataflow_builder().build_index_dataflow(*id))
-                            .await;
+                            .await?;
                     }
                 }
                 _ => (), // Handled in next loop.
@@ -277,30 +591,67 @@ where
             match item {
                 CatalogItem::Table(_) | CatalogItem::View(_) => (),
                 CatalogItem::Sink(sink) => {
+                    self.bootstrap_phase = BootstrapPhase::RebuildingSink(name.to_string());
                     let builder = match &sink.connector {
                         SinkConnectorState::Pending(builder) => builder,
                         SinkConnectorState::Ready(_) => {
                             panic!("sink already initialized during catalog boot")
                         }
                     };
-                    let connector = sink_connector::build(
-                        builder.clone(),
-                        sink.with_snapshot,
-                        self.determine_frontier(sink.as_of, sink.from)?,
-                        *id,
-                    )
-                    .await
-                    .with_context(|| format!("recreating sink {}", name))?;
-                    self.handle_sink_connector_ready(*id, *oid, connector).await;
+                    let started_at = Utc::now();
+                    let connector = match self.determine_frontier(sink.as_of, sink.from) {
+                        Ok(frontier) => {
+                            sink_connector::build(
+                                builder.clone(),
+                                sink.with_snapshot,
+                                frontier,
+                                *id,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    }
+                    .with_context(|| format!("recreating sink {}", name));
+                    let connector = match connector {
+                        Ok(connector) => connector,
+                        Err(e) if self.skip_failed_sink_rebuilds => {
+                            // Leave the sink in the catalog, without a
+                            // dataflow, so that the user can drop it. The
+                            // rebuild is retried on the next boot.
+                            log::error!("skipping sink {} ({}): {:#}", name, id, e);
+                            self.report_sink_build(*id, "FAILED", started_at).await;
+                            self.report_sink_status(*id, "failed", Some(format!("{:#}", e)))
+                                .await;
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    self.handle_sink_connector_ready(*id, *oid, connector)
+                        .await
+                        .with_context(|| format!("recreating sink {}", name))?;
                 }
                 _ => (), // Handled in prior loop.
             }
         }
 
+        self.bootstrap_phase = BootstrapPhase::ProcessingCatalogEvents;
         self.process_catalog_events(events).await?;
 
+        if !orphaned_ids.is_empty() {
+            self.bootstrap_phase = BootstrapPhase::RemovingOrphanedItems;
+            for id in &orphaned_ids {
+                log::warn!(
+                    "removing placeholder catalog item {} orphaned by a previous crash",
+                    id
+                );
+            }
+            let ops = self.catalog.drop_items_ops(&orphaned_ids);
+            self.catalog_transact(ops).await?;
+        }
+
         // Announce primary and foreign key relationships.
         if self.logging_granularity.is_some() {
+            self.bootstrap_phase = BootstrapPhase::AnnouncingLogKeys;
             for log in BUILTINS.logs() {
                 let log_id = &log.id.to_string();
                 self.update_catalog_view(
@@ -347,6 +698,18 @@ where
             }
         }
 
+        self.update_catalog_view(
+            MZ_COORDINATOR_STATE.id,
+            iter::once((
+                Row::pack_slice(&[
+                    Datum::TimestampTz(self.started_at),
+                    Datum::Int64(self.num_timely_workers as i64),
+                ]),
+                1,
+            )),
+        )
+        .await;
+
         Ok(())
     }
 
@@ -380,28 +743,53 @@ where
         })
         .join_on_drop();
 
+        let idle_scan_stream = match self.idle_session_timeout {
+            Some(timeout) => {
+                let interval = tokio::time::interval(cmp::min(timeout, IDLE_SESSION_SCAN_INTERVAL));
+                stream::unfold(interval, |mut interval| async move {
+                    interval.tick().await;
+                    Some((Message::ScanIdleSessions, interval))
+                })
+                .boxed()
+            }
+            None => stream::pending().boxed(),
+        };
+
         let mut messages = ore::future::select_all_biased(vec![
             // Order matters here. We want to drain internal commands
             // (`internal_cmd_stream` and `feedback_stream`) before processing
             // external commands (`cmd_stream`).
             internal_cmd_stream.boxed(),
             feedback_stream.boxed(),
+            idle_scan_stream,
             cmd_stream.boxed(),
         ]);
 
         while let Some(msg) = messages.next().await {
             match msg {
-                Message::Command(cmd) => self.message_command(cmd, &internal_cmd_tx).await,
-                Message::Worker(worker) => self.message_worker(worker, &ts_tx).await,
+                Message::Command(cmd) => self.message_command(cmd, &internal_cmd_tx, &ts_tx).await,
+                Message::Worker(worker) => {
+                    self.message_worker(worker, &internal_cmd_tx, &ts_tx).await
+                }
                 Message::StatementReady(ready) => {
                     self.message_statement_ready(ready, &internal_cmd_tx).await
                 }
                 Message::SinkConnectorReady(ready) => {
                     self.message_sink_connector_ready(ready).await
                 }
+                Message::PeekAdmissionReady(ready) => {
+                    self.message_peek_admission_ready(ready, &internal_cmd_tx)
+                        .await
+                }
+                Message::PeekComplete { conn_id } => self.message_peek_complete(conn_id),
+                Message::ScanIdleSessions => self.message_scan_idle_sessions().await,
+                Message::FlushFrontierUppers => self.message_flush_frontier_uppers().await,
                 Message::AdvanceSourceTimestamp(advance) => {
                     self.message_advance_source_timestamp(advance).await
                 }
+                Message::KafkaPartitionWatermark(watermark) => {
+                    self.message_kafka_partition_watermark(watermark).await
+                }
                 Message::Shutdown => {
                     self.message_shutdown(&ts_tx).await;
                     break;
@@ -446,27 +834,69 @@ where
             worker_id: _,
             message,
         }: WorkerFeedbackWithMeta,
+        internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
         ts_tx: &std::sync::mpsc::Sender<TimestampMessage>,
     ) {
         match message {
-            WorkerFeedback::FrontierUppers(updates) => {
-                for (name, changes) in updates {
-                    self.update_upper(&name, changes);
+            WorkerFeedback::FrontierUppers(updates) => match self.feedback_batch_window {
+                None => {
+                    for (name, changes) in updates {
+                        self.update_upper(&name, changes);
+                    }
+                    self.maintenance().await;
                 }
-                self.maintenance().await;
-            }
+                Some(window) => {
+                    // Defer the changes, and the maintenance that follows
+                    // them, until the end of the window, so that a burst of
+                    // feedback from many workers results in at most one
+                    // `AllowCompaction` command.
+                    if self.frontier_uppers_batch.push(updates) {
+                        let mut internal_cmd_tx = internal_cmd_tx.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(window).await;
+                            internal_cmd_tx
+                                .send(Message::FlushFrontierUppers)
+                                .await
+                                .expect("sending to internal_cmd_tx cannot fail");
+                        });
+                    }
+                }
+            },
             WorkerFeedback::DroppedSource(source_id) => {
                 // Notify timestamping thread that source has been dropped
                 ts_tx
                     .send(TimestampMessage::DropInstance(source_id))
                     .expect("Failed to send Drop Instance notice to timestamper");
+                self.source_registrations.drop(source_id);
+                if let Some(partitions) = self
+                    .kafka_partition_watermarks
+                    .remove(&source_id)
+                    .filter(|partitions| !partitions.is_empty())
+                {
+                    self.update_catalog_view(
+                        MZ_KAFKA_SOURCE_PARTITIONS.id,
+                        partitions.into_iter().map(|(_, row)| (row, -1)),
+                    )
+                    .await;
+                }
             }
             WorkerFeedback::CreateSource(src_instance_id) => {
                 if let Some(entry) = self.catalog.try_get_by_id(src_instance_id.source_id) {
                     if let CatalogItem::Source(s) = entry.item() {
-                        ts_tx
-                            .send(TimestampMessage::Add(src_instance_id, s.connector.clone()))
-                            .expect("Failed to send CREATE Instance notice to timestamper");
+                        // Every worker may report the creation of the same
+                        // instance, but the timestamper must only learn of it
+                        // once.
+                        if self
+                            .source_registrations
+                            .create(src_instance_id, self.sources_paused)
+                        {
+                            ts_tx
+                                .send(TimestampMessage::Add(src_instance_id, s.connector.clone()))
+                                .expect("Failed to send CREATE Instance notice to timestamper");
+                        }
+                        self.kafka_partition_watermarks
+                            .entry(src_instance_id)
+                            .or_default();
                     } else {
                         panic!("A non-source is re-using the same source ID");
                     }
@@ -474,6 +904,42 @@ where
                     // Someone already dropped the source
                 }
             }
+            WorkerFeedback::SinkError { id, error } => {
+                // Ignore errors from sinks that have already been dropped.
+                if self.sink_statuses.contains_key(&id) {
+                    let status = if error.is_some() { "error" } else { "running" };
+                    self.report_sink_status(id, status, error).await;
+                }
+            }
+            WorkerFeedback::AvroOcfSinkRotated { id, path } => {
+                // Ignore rotations of sinks that have already been dropped.
+                if self.avro_ocf_sink_files.contains_key(&id) {
+                    self.report_avro_ocf_sink_file(id, path).await;
+                }
+            }
+            WorkerFeedback::TailComplete { id, limit_reached } => {
+                // Ignore completions of tails that have already been canceled.
+                let conn_id = self
+                    .active_tails
+                    .iter()
+                    .find(|(_, tail)| tail.sink_id == id)
+                    .map(|(conn_id, _)| *conn_id);
+                if let Some(conn_id) = conn_id {
+                    let completed = self.tail_completions.entry(id).or_insert(0);
+                    *completed += 1;
+                    // Once every worker has closed its end of the channel,
+                    // the client has seen the end of the TAIL. A tail's
+                    // updates are all routed to a single worker, though, so
+                    // once that worker reaches the tail's limit, the other
+                    // workers have nothing left to send, and dropping the
+                    // dataflow closes their ends of the channel.
+                    if limit_reached || *completed == self.num_timely_workers {
+                        self.tail_completions.remove(&id);
+                        self.active_tails.remove(&conn_id);
+                        self.drop_sinks(vec![id]).await;
+                    }
+                }
+            }
         }
     }
 
@@ -484,15 +950,24 @@ where
             tx,
             result,
             params,
+            cancel_epoch,
         }: StatementReady,
         internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
     ) {
+        // The session may have been canceled or terminated while the statement
+        // was being purified, in which case the statement must not run.
+        if self.idle_terminated_conns.contains(&session.conn_id()) {
+            return tx.send(Err(IdleSessionTimeout.into()), session);
+        }
+        if self.active_conns.get(&session.conn_id()) != Some(&cancel_epoch) {
+            return tx.send(Err(QueryCanceled.into()), session);
+        }
         match future::ready(result)
-            .and_then(|stmt| self.handle_statement(&session, stmt, &params))
+            .and_then(|stmt| self.handle_statement_cached(&session, stmt, &params))
             .await
         {
-            Ok((pcx, plan)) => {
-                self.sequence_plan(&internal_cmd_tx, tx, session, pcx, plan)
+            Ok((pcx, plan, source_optimized)) => {
+                self.sequence_plan(&internal_cmd_tx, tx, session, pcx, plan, source_optimized)
                     .await
             }
             Err(e) => tx.send(Err(e), session),
@@ -516,7 +991,18 @@ where
                 // a Kafka topic) that's been created on our behalf. If
                 // we fail now, we'll leak that external state.
                 if self.catalog.try_get_by_id(id).is_some() {
-                    self.handle_sink_connector_ready(id, oid, connector).await;
+                    if let Err(e) = self.handle_sink_connector_ready(id, oid, connector).await {
+                        // The sink's inputs went away while we were creating
+                        // the connector. Remove the sink so that its name is
+                        // not reserved forever, though the external state
+                        // it created is necessarily leaked.
+                        let ops = self.catalog.drop_items_ops(&[id]);
+                        self.catalog_transact(ops)
+                            .await
+                            .expect("deleting sink cannot fail");
+                        tx.send(Err(e), session);
+                        return;
+                    }
                 } else {
                     // Another session dropped the sink while we were
                     // creating the connector. Report to the client that
@@ -527,14 +1013,125 @@ where
                 tx.send(Ok(ExecuteResponse::CreatedSink { existed: false }), session);
             }
             Err(e) => {
-                self.catalog_transact(vec![catalog::Op::DropItem(id)])
-                    .await
-                    .expect("deleting placeholder sink cannot fail");
+                // The placeholder sink may already be gone if its inputs
+                // were dropped with CASCADE while we were creating the
+                // connector.
+                if self.catalog.try_get_by_id(id).is_some() {
+                    self.catalog_transact(vec![catalog::Op::DropItem(id)])
+                        .await
+                        .expect("deleting placeholder sink cannot fail");
+                }
                 tx.send(Err(e), session);
             }
         }
     }
 
+    async fn message_peek_admission_ready(
+        &mut self,
+        PeekAdmissionReady {
+            session,
+            tx,
+            source,
+            when,
+            finishing,
+            copy_to,
+            index_hint,
+            source_optimized,
+            result,
+        }: PeekAdmissionReady,
+        internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
+    ) {
+        self.throttled_peeks.remove(&session.conn_id());
+        self.peek_uses.remove(&session.conn_id());
+        match result {
+            PeekAdmissionResult::Admitted(permit) => {
+                self.sequence_throttled_peek(
+                    internal_cmd_tx,
+                    tx,
+                    session,
+                    source,
+                    when,
+                    finishing,
+                    copy_to,
+                    index_hint,
+                    source_optimized,
+                    Some(permit),
+                )
+                .await
+            }
+            PeekAdmissionResult::Canceled => {
+                let resp =
+                    ExecuteResponse::SendingRows(Box::pin(future::ok(PeekResponse::Canceled)));
+                let resp = match copy_to {
+                    Some(CopyTo {
+                        format,
+                        target: CopyToTarget::Stdout,
+                    }) => ExecuteResponse::CopyTo {
+                        format,
+                        resp: Box::new(resp),
+                    },
+                    _ => resp,
+                };
+                tx.send(Ok(resp), session)
+            }
+            PeekAdmissionResult::TimedOut(max_wait) => tx.send(
+                Err(anyhow!(
+                    "query was not admitted within {:?}: too many concurrent queries \
+                     require temporary dataflows",
+                    max_wait
+                )),
+                session,
+            ),
+        }
+    }
+
+    /// Terminates the connections that have gone longer than the idle-session
+    /// timeout without issuing a command. A connection with a query in
+    /// progress is not idle, and its idle time starts over when the query
+    /// finishes.
+    async fn message_scan_idle_sessions(&mut self) {
+        let timeout = match self.idle_session_timeout {
+            Some(timeout) => timeout,
+            None => return,
+        };
+        let now = Instant::now();
+        let mut expired = vec![];
+        for (conn_id, last_activity) in &mut self.conn_activity {
+            let busy = self.active_peeks.contains_key(conn_id)
+                || self.throttled_peeks.contains_key(conn_id)
+                || self
+                    .active_tails
+                    .get(conn_id)
+                    .map_or(false, |tail| !tail.in_transaction);
+            if busy {
+                *last_activity = now;
+            } else if now.duration_since(*last_activity) >= timeout {
+                expired.push(*conn_id);
+            }
+        }
+        expired.sort_unstable();
+        for conn_id in expired {
+            log::info!(
+                "terminating connection {} after {:?} of inactivity",
+                conn_id,
+                timeout
+            );
+            self.terminate_conn(conn_id).await;
+            self.idle_terminated_conns.insert(conn_id);
+        }
+    }
+
+    fn message_peek_complete(&mut self, conn_id: u32) {
+        if let Some(count) = self.active_peeks.get_mut(&conn_id) {
+            *count -= 1;
+            if *count == 0 {
+                self.active_peeks.remove(&conn_id);
+                self.peek_uses.remove(&conn_id);
+                self.peek_arrangements.remove(&conn_id);
+            }
+        }
+    }
+
     async fn message_shutdown(&mut self, ts_tx: &std::sync::mpsc::Sender<TimestampMessage>) {
         ts_tx.send(TimestampMessage::Shutdown).unwrap();
 
@@ -558,13 +1155,65 @@ where
         .await;
     }
 
+    async fn message_kafka_partition_watermark(
+        &mut self,
+        KafkaPartitionWatermark {
+            id,
+            topic,
+            partition,
+            offset,
+            timestamp,
+        }: KafkaPartitionWatermark,
+    ) {
+        // Ignore reports for instances that have already been dropped, as
+        // nothing would ever retract them.
+        let partitions = match self.kafka_partition_watermarks.get_mut(&id) {
+            Some(partitions) => partitions,
+            None => return,
+        };
+        let row = Row::pack_slice(&[
+            Datum::String(&id.source_id.to_string()),
+            Datum::Int64(id.dataflow_id as i64),
+            Datum::String(&topic),
+            Datum::Int32(partition),
+            Datum::Int64(offset.offset),
+            Datum::Int64(timestamp as i64),
+        ]);
+        let mut updates = vec![];
+        if let Some(old_row) = partitions.insert(partition, row.clone()) {
+            if old_row == row {
+                return;
+            }
+            updates.push((old_row, -1));
+        }
+        updates.push((row, 1));
+        self.update_catalog_view(MZ_KAFKA_SOURCE_PARTITIONS.id, updates)
+            .await;
+    }
+
     async fn message_command(
         &mut self,
         cmd: Command,
         internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
+        ts_tx: &std::sync::mpsc::Sender<TimestampMessage>,
     ) {
+        let cmd = match self.note_conn_activity(cmd) {
+            Some(cmd) => cmd,
+            None => return,
+        };
         match cmd {
             Command::Startup { session, tx } => {
+                // Reject the connection before allocating any resources on its
+                // behalf, like its temporary schema.
+                if let Some(limit) = self.max_connections {
+                    if self.active_conns.len() >= limit {
+                        let _ = tx.send(Response {
+                            result: Err(TooManyConnections { limit }.into()),
+                            session,
+                        });
+                        return;
+                    }
+                }
                 let mut messages = vec![];
                 let catalog = self.catalog.for_session(&session);
                 if catalog
@@ -580,6 +1229,9 @@ where
                     });
                     return;
                 }
+                self.active_conns.insert(session.conn_id(), 0);
+                self.conn_activity.insert(session.conn_id(), Instant::now());
+                self.report_session(&session).await;
                 ClientTransmitter::new(tx).send(Ok(messages), session)
             }
 
@@ -606,6 +1258,7 @@ where
                         let mut internal_cmd_tx = internal_cmd_tx.clone();
                         let stmt = stmt.clone();
                         let params = portal.parameters.clone();
+                        let cancel_epoch = self.active_conns[&session.conn_id()];
                         tokio::spawn(async move {
                             let result = sql::pure::purify(stmt).await;
                             internal_cmd_tx
@@ -614,6 +1267,7 @@ where
                                     tx: ClientTransmitter::new(tx),
                                     result,
                                     params,
+                                    cancel_epoch,
                                 }))
                                 .await
                                 .expect("sending to internal_cmd_tx cannot fail");
@@ -649,9 +1303,41 @@ where
                             when,
                             finishing,
                             copy_to,
+                            index_hint,
+                            locking: _,
                         } => {
-                            self.sequence_peek(conn_id, source, when, finishing, copy_to)
+                            // Queries without a session have no way to be
+                            // resumed later, so fail immediately rather than
+                            // waiting for admission.
+                            let stream_rows = matches!(
+                                copy_to,
+                                Some(CopyTo {
+                                    target: CopyToTarget::Stdout,
+                                    ..
+                                })
+                            );
+                            match self
+                                .sequence_peek(
+                                    conn_id,
+                                    source,
+                                    when,
+                                    finishing,
+                                    copy_to,
+                                    stream_rows,
+                                    index_hint,
+                                    false,
+                                    None,
+                                    None,
+                                    None,
+                                    Tz::UTC,
+                                )
                                 .await?
+                            {
+                                PeekOutcome::Issued(resp, _, _, _) => resp,
+                                PeekOutcome::Throttled => {
+                                    bail!("too many concurrent queries require temporary dataflows")
+                                }
+                            }
                         }
 
                         Plan::SendRows(rows) => send_immediate_rows(rows),
@@ -667,6 +1353,10 @@ where
                 let _ = tx.send(res);
             }
 
+            Command::ExecuteBatch { stmts, tx } => {
+                let _ = tx.send(self.sequence_execute_batch(stmts).await);
+            }
+
             Command::Declare {
                 name,
                 stmt,
@@ -693,10 +1383,38 @@ where
                 self.handle_cancel(conn_id).await;
             }
 
+            Command::CancelConnection { conn_id, tx } => {
+                let active = self.active_query_conn_ids().contains(&conn_id);
+                if active {
+                    self.handle_cancel(conn_id).await;
+                }
+                let _ = tx.send(active);
+            }
+
+            Command::ListActiveQueries { tx } => {
+                let _ = tx.send(self.active_query_conn_ids());
+            }
+
+            Command::ListConnections { tx } => {
+                let _ = tx.send(self.connection_resources());
+            }
+
             Command::DumpCatalog { tx } => {
                 let _ = tx.send(self.catalog.dump());
             }
 
+            Command::SnapshotCatalog { tx } => {
+                let _ = tx.send(self.snapshot_catalog());
+            }
+
+            Command::PauseSources { tx } => {
+                let _ = tx.send(self.pause_sources(ts_tx).await);
+            }
+
+            Command::ResumeSources { tx } => {
+                let _ = tx.send(self.resume_sources(ts_tx).await);
+            }
+
             Command::Terminate { mut session } => {
                 self.handle_terminate(&mut session).await;
             }
@@ -715,32 +1433,26 @@ where
                 // the case of a constant collection, this compaction is actively
                 // harmful. We should reconsider compaction policy with an eye
                 // towards minimizing unexpected screw-ups.
-                if let Some(compaction_window_ms) = index_state.compaction_window_ms {
-                    // Decline to compact complete collections. This would have the
-                    // effect of making the collection unusable. Instead, we would
-                    // prefer to compact collections only when we believe it would
-                    // reduce the volume of the collection, but we don't have that
-                    // information here.
-                    if !index_state.upper.frontier().is_empty() {
-                        let mut compaction_frontier = Antichain::new();
-                        for time in index_state.upper.frontier().iter() {
-                            compaction_frontier.insert(
-                                compaction_window_ms
-                                    * (time.saturating_sub(compaction_window_ms)
-                                        / compaction_window_ms),
-                            );
-                        }
-                        if index_state.since != compaction_frontier {
-                            index_state.advance_since(&compaction_frontier);
-                            self.since_updates
-                                .push((name.clone(), index_state.since.clone()));
-                        }
-                    }
+                if let Some(compaction_frontier) =
+                    index_state.compaction_frontier(self.logical_compaction_hysteresis)
+                {
+                    index_state.advance_since(&compaction_frontier);
+                    self.since_updates
+                        .push(name.clone(), index_state.since.clone());
                 }
             }
         }
     }
 
+    /// Applies the `WorkerFeedback::FrontierUppers` buffered during the
+    /// feedback batch window that just ended.
+    async fn message_flush_frontier_uppers(&mut self) {
+        for (name, changes) in self.frontier_uppers_batch.drain() {
+            self.update_upper(&name, changes);
+        }
+        self.maintenance().await;
+    }
+
     /// Perform maintenance work associated with the coordinator.
     ///
     /// Primarily, this involves sequencing compaction commands, which should be
@@ -750,20 +1462,103 @@ where
         // Don't try to compact to an empty frontier. There may be a good reason to do this
         // in principle, but not in any current Mz use case.
         // (For background, see: https://github.com/MaterializeInc/materialize/pull/1113#issuecomment-559281990)
-        self.since_updates
-            .retain(|(_, frontier)| frontier != &Antichain::new());
-        if !self.since_updates.is_empty() {
+        let since_updates = self.since_updates.drain();
+        if !since_updates.is_empty() {
             broadcast(
                 &mut self.broadcast_tx,
-                SequencedCommand::AllowCompaction(std::mem::replace(
-                    &mut self.since_updates,
-                    Vec::new(),
-                )),
+                SequencedCommand::AllowCompaction(since_updates),
             )
             .await;
         }
     }
 
+    /// Stops the timestamper from assigning timestamps to any source
+    /// instance, and the dataflow workers from ingesting new data from any
+    /// source, including those created while sources are paused. Returns
+    /// the number of sources that were paused, which is zero if sources were
+    /// already paused.
+    async fn pause_sources(&mut self, ts_tx: &std::sync::mpsc::Sender<TimestampMessage>) -> usize {
+        if self.sources_paused {
+            return 0;
+        }
+        self.sources_paused = true;
+        ts_tx
+            .send(TimestampMessage::Pause)
+            .expect("Failed to send Pause notice to timestamper");
+        // Real-time sources assign their own timestamps, so the timestamper
+        // alone cannot hold them still.
+        broadcast(
+            &mut self.broadcast_tx,
+            SequencedCommand::SetSourcesPaused(true),
+        )
+        .await;
+        self.running_source_count()
+    }
+
+    /// Undoes `pause_sources`, informing the timestamper of any source
+    /// instances that were created in the meantime. Returns the number of
+    /// sources that were resumed, which is zero if sources were not paused.
+    async fn resume_sources(&mut self, ts_tx: &std::sync::mpsc::Sender<TimestampMessage>) -> usize {
+        if !self.sources_paused {
+            return 0;
+        }
+        self.sources_paused = false;
+        broadcast(
+            &mut self.broadcast_tx,
+            SequencedCommand::SetSourcesPaused(false),
+        )
+        .await;
+        ts_tx
+            .send(TimestampMessage::Resume)
+            .expect("Failed to send Resume notice to timestamper");
+        for id in self.source_registrations.resume() {
+            if let Some(CatalogItem::Source(s)) =
+                self.catalog.try_get_by_id(id.source_id).map(|e| e.item())
+            {
+                ts_tx
+                    .send(TimestampMessage::Add(id, s.connector.clone()))
+                    .expect("Failed to send CREATE Instance notice to timestamper");
+            }
+        }
+        self.running_source_count()
+    }
+
+    /// Returns the number of sources with at least one running instance.
+    fn running_source_count(&self) -> usize {
+        // Despite its name, `kafka_partition_watermarks` has an entry for
+        // every running source instance, not just those of Kafka sources.
+        self.kafka_partition_watermarks
+            .keys()
+            .map(|id| id.source_id)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Serializes the catalog together with the runtime state needed to
+    /// restore the compaction state of its indexes.
+    ///
+    /// The coordinator handles one message at a time, so no DDL or frontier
+    /// update can interleave with the snapshot.
+    fn snapshot_catalog(&self) -> String {
+        let mut indexes: Vec<_> = self
+            .indexes
+            .iter()
+            .map(|(id, frontiers)| IndexSnapshot {
+                id: *id,
+                since: frontiers.since.elements().to_vec(),
+                upper: frontiers.upper.frontier().iter().cloned().collect(),
+                compaction_window_ms: frontiers.compaction_window_ms,
+            })
+            .collect();
+        indexes.sort_by_key(|index| index.id);
+        let snapshot = CatalogSnapshot {
+            catalog: self.catalog.dump_value(),
+            indexes,
+            pending_sinks: self.catalog.pending_sinks(),
+        };
+        serde_json::to_string(&snapshot).expect("serialization cannot fail")
+    }
+
     async fn handle_statement(
         &mut self,
         session: &Session,
@@ -813,6 +1608,96 @@ where
         }
     }
 
+    /// Like `handle_statement`, but consults the plan cache before planning
+    /// ad-hoc queries, and caches their optimized plans afterwards. Only
+    /// timestamp determination remains to be done for each execution.
+    ///
+    /// Also reports whether the plan is a peek whose source is already
+    /// prepared and optimized, and so must not be prepared again.
+    async fn handle_statement_cached(
+        &mut self,
+        session: &Session,
+        stmt: sql::ast::Statement,
+        params: &sql::plan::Params,
+    ) -> Result<(PlanContext, sql::plan::Plan, bool), anyhow::Error> {
+        // Planning in symbiosis mode has side effects, and parameters are
+        // baked into plans, so neither can be cached. Nor can plans for
+        // sessions with temporary items, which shadow other items of the same
+        // name for that session only, or for sessions that limit the
+        // optimizer's fuel, as cached plans are fully optimized.
+        if self.symbiosis.is_some()
+            || !params.types.is_empty()
+            || self.catalog.has_temporary_items(session.conn_id())
+            || session.vars().optimizer_fuel().is_some()
+            || !plan_cache::is_cacheable(&stmt)
+        {
+            let (pcx, plan) = self.handle_statement(session, stmt, params).await?;
+            return Ok((pcx, plan, false));
+        }
+        let key = PlanCacheKey {
+            sql: stmt.to_ast_string_stable(),
+            catalog_generation: self.catalog.generation(),
+            database: session.vars().database().into(),
+            search_path: session
+                .vars()
+                .search_path()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            user: session.vars().user().into(),
+        };
+        if let Some(peek) = self.plan_cache.get(&key) {
+            return Ok((PlanContext::default(), peek.into(), true));
+        }
+
+        let (pcx, plan) = self.handle_statement(session, stmt, params).await?;
+        match plan {
+            Plan::Peek {
+                source,
+                when,
+                finishing,
+                copy_to,
+                index_hint,
+                locking,
+            } => {
+                // Only plans that preparation would leave untouched are
+                // cached, as cached plans are not prepared again. Preparing a
+                // static plan fails if preparation would replace any part of
+                // it.
+                let mut prepared = source.clone();
+                if prepared
+                    .try_visit_scalars_mut(&mut |s| self.prep_scalar_expr(s, ExprPrepStyle::Static))
+                    .is_err()
+                {
+                    let plan = Plan::Peek {
+                        source,
+                        when,
+                        finishing,
+                        copy_to,
+                        index_hint,
+                        locking,
+                    };
+                    return Ok((pcx, plan, false));
+                }
+                let source = self
+                    .optimizer
+                    .optimize(source, self.catalog.indexes())?
+                    .into_inner();
+                let peek = CachedPeek {
+                    source,
+                    when,
+                    finishing,
+                    copy_to,
+                    index_hint,
+                    locking,
+                };
+                self.plan_cache.insert(key, peek.clone());
+                Ok((pcx, peek.into(), true))
+            }
+            plan => Ok((pcx, plan, false)),
+        }
+    }
+
     fn handle_declare(
         &self,
         session: &mut Session,
@@ -873,43 +1758,204 @@ where
     /// only have one active query at a time. This is true today, but will not
     /// be true once we have full support for portals.
     async fn handle_cancel(&mut self, conn_id: u32) {
-        if let Some(name) = self.active_tails.remove(&conn_id) {
+        // Abandon any statement that is being purified.
+        if let Some(epoch) = self.active_conns.get_mut(&conn_id) {
+            *epoch += 1;
+        }
+        if let Some(cancel_tx) = self.throttled_peeks.remove(&conn_id) {
+            // A PEEK is waiting to be admitted, so it has not yet shipped any
+            // work to the dataflow layer. Abandoning the wait is sufficient.
+            let _ = cancel_tx.send(());
+        } else if let Some(tail) = self.active_tails.remove(&conn_id) {
             // A TAIL is known to be active, so drop the dataflow that is
             // servicing it. No need to try to cancel PEEKs in this case,
             // because if a TAIL is active, a PEEK cannot be.
-            self.drop_sinks(vec![name]).await;
+            self.tail_completions.remove(&tail.sink_id);
+            if let Some(cancel_tx) = tail.cancel_tx {
+                let _ = cancel_tx.send(());
+            }
+            self.drop_sinks(vec![tail.sink_id]).await;
         } else {
             // No TAIL is known to be active, so drop the PEEK that may be
             // active on this connection. This is a no-op if no PEEKs are
             // active.
             broadcast(
                 &mut self.broadcast_tx,
-                SequencedCommand::CancelPeek { conn_id },
+                SequencedCommand::CancelPeek {
+                    conn_id,
+                    response: PeekResponse::Canceled,
+                },
             )
             .await;
         }
     }
 
-    /// Handle termination of a client session.
-    ///
-    // This cleans up any state in the coordinator associated with the session.
-    async fn handle_terminate(&mut self, session: &mut Session) {
-        if let Some(name) = self.active_tails.remove(&session.conn_id()) {
-            self.drop_sinks(vec![name]).await;
-        }
-        self.drop_temp_items(session.conn_id()).await;
-        self.catalog
-            .drop_temporary_schema(session.conn_id())
-            .expect("unable to drop temporary schema");
+    /// Returns the IDs of the connections that have a PEEK or TAIL in
+    /// progress, in ascending order.
+    fn active_query_conn_ids(&self) -> Vec<u32> {
+        let mut conn_ids: Vec<_> = self
+            .active_peeks
+            .keys()
+            .chain(self.throttled_peeks.keys())
+            .chain(self.active_tails.keys())
+            .copied()
+            .collect();
+        conn_ids.sort_unstable();
+        conn_ids.dedup();
+        conn_ids
     }
 
-    // Removes all temporary items created by the specified connection, though
+    /// Returns the resources held on behalf of each admitted connection, in
+    /// ascending order of connection ID.
+    fn connection_resources(&self) -> Vec<ConnectionResources> {
+        let mut conns: Vec<_> = self
+            .active_conns
+            .keys()
+            .map(|conn_id| ConnectionResources {
+                conn_id: *conn_id,
+                temporary_items: self.catalog.temporary_item_count(*conn_id),
+                active_peeks: self.active_peeks.get(conn_id).copied().unwrap_or(0)
+                    + usize::from(self.throttled_peeks.contains_key(conn_id)),
+                active_tails: usize::from(self.active_tails.contains_key(conn_id))
+                    + usize::from(self.listen_tails.contains_key(conn_id)),
+            })
+            .collect();
+        conns.sort_unstable_by_key(|conn| conn.conn_id);
+        conns
+    }
+
+    /// Returns the IDs of the connections that have a PEEK or TAIL in
+    /// progress that reads from any of the items dropped by `ops`, in
+    /// ascending order.
+    fn query_conn_ids_reading_dropped_items(&self, ops: &[catalog::Op]) -> Vec<u32> {
+        let dropped: HashSet<_> = ops
+            .iter()
+            .filter_map(|op| match op {
+                catalog::Op::DropItem(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        self.active_query_conn_ids()
+            .into_iter()
+            .filter(|conn_id| {
+                let tail_affected = self
+                    .active_tails
+                    .get(conn_id)
+                    .map_or(false, |tail| dropped.contains(&tail.from));
+                let peek_affected = self
+                    .peek_uses
+                    .get(conn_id)
+                    .map_or(false, |uses| uses.iter().any(|id| dropped.contains(id)));
+                tail_affected || peek_affected
+            })
+            .collect()
+    }
+
+    /// Records that the connection that issued `cmd`, if any, is active. If
+    /// the connection was already terminated for sitting idle, instead
+    /// responds to `cmd` with an error and returns `None`.
+    fn note_conn_activity(&mut self, cmd: Command) -> Option<Command> {
+        let conn_id = match &cmd {
+            Command::Declare { session, .. }
+            | Command::Describe { session, .. }
+            | Command::Execute { session, .. } => session.conn_id(),
+            _ => return Some(cmd),
+        };
+        if !self.idle_terminated_conns.contains(&conn_id) {
+            if let Some(last_activity) = self.conn_activity.get_mut(&conn_id) {
+                *last_activity = Instant::now();
+            }
+            return Some(cmd);
+        }
+        match cmd {
+            Command::Declare { session, tx, .. } | Command::Describe { session, tx, .. } => {
+                let _ = tx.send(Response {
+                    result: Err(IdleSessionTimeout.into()),
+                    session,
+                });
+            }
+            Command::Execute { session, tx, .. } => {
+                let _ = tx.send(Response {
+                    result: Err(IdleSessionTimeout.into()),
+                    session,
+                });
+            }
+            _ => unreachable!(),
+        }
+        None
+    }
+
+    /// Handle termination of a client session.
+    async fn handle_terminate(&mut self, session: &mut Session) {
+        if self.idle_terminated_conns.remove(&session.conn_id()) {
+            // The connection was already cleaned up when it timed out.
+            return;
+        }
+        self.terminate_conn(session.conn_id()).await;
+    }
+
+    // Cleans up any state in the coordinator associated with the specified
+    // connection.
+    async fn terminate_conn(&mut self, conn_id: u32) {
+        if self.active_conns.remove(&conn_id).is_none() {
+            // The connection was rejected at startup, so there is nothing to
+            // clean up.
+            return;
+        }
+        self.conn_activity.remove(&conn_id);
+        if let Some(cancel_tx) = self.throttled_peeks.remove(&conn_id) {
+            let _ = cancel_tx.send(());
+        }
+        self.peek_uses.remove(&conn_id);
+        self.peek_arrangements.remove(&conn_id);
+        if let Some(tail) = self.active_tails.remove(&conn_id) {
+            self.tail_completions.remove(&tail.sink_id);
+            self.drop_sinks(vec![tail.sink_id]).await;
+        }
+        if let Some(name) = self.listen_tails.remove(&conn_id) {
+            self.drop_sinks(vec![name]).await;
+        }
+        self.forget_session(conn_id).await;
+        self.drop_temp_items(conn_id).await;
+        self.catalog
+            .drop_temporary_schema(conn_id)
+            .expect("unable to drop temporary schema");
+    }
+
+    // Removes all temporary items created by the specified connection, though
     // not the temporary schema itself.
     async fn drop_temp_items(&mut self, conn_id: u32) {
         let ops = self.catalog.drop_temp_item_ops(conn_id);
+        // Note the arrangements of the temporary items, and of the indexes
+        // built on them, so that we can verify that they are dropped too.
+        let mut arrangement_ids = vec![];
+        for op in &ops {
+            if let catalog::Op::DropItem(id) = op {
+                arrangement_ids.push(*id);
+                if let Some(indexes) = self.catalog.indexes().get(id) {
+                    arrangement_ids.extend(indexes.iter().map(|(index_id, _)| *index_id));
+                }
+            }
+        }
         self.catalog_transact(ops)
             .await
             .expect("unable to drop temporary items for conn_id");
+
+        // Any arrangement that survived the drop would otherwise leak, as no
+        // catalog item refers to it anymore.
+        let stragglers: Vec<_> = arrangement_ids
+            .into_iter()
+            .filter(|id| self.indexes.get(id).is_some())
+            .collect();
+        if !stragglers.is_empty() {
+            log::error!(
+                "arrangements {:?} for temporary items of connection {} were not dropped; \
+                 dropping them now",
+                stragglers,
+                conn_id
+            );
+            self.drop_indexes(stragglers).await;
+        }
     }
 
     async fn handle_sink_connector_ready(
@@ -917,7 +1963,11 @@ where
         id: GlobalId,
         oid: u32,
         connector: SinkConnector,
-    ) {
+    ) -> Result<(), anyhow::Error> {
+        // Replacing the catalog entry retracts the sink's build from
+        // `mz_sink_builds`, so remember when the build started.
+        let started_at = self.sink_build_started_at(id);
+
         // Update catalog entry with sink connector.
         let entry = self.catalog.get_by_id(&id);
         let name = entry.name().clone();
@@ -945,7 +1995,191 @@ where
             sink.from,
             connector,
         ))
-        .await
+        .await?;
+        self.report_sink_build(id, "READY", started_at).await;
+        Ok(())
+    }
+
+    /// Counts a slow-path peek of the relation `id` that constrained the
+    /// columns in `key` to literals, updating `mz_slow_path_peeks`.
+    async fn report_slow_path_peek(&mut self, id: GlobalId, key: Vec<usize>) {
+        let entry = self.catalog.get_by_id(&id);
+        let desc = match entry.desc() {
+            Ok(desc) => desc,
+            Err(_) => return,
+        };
+        let column_names: Vec<_> = key
+            .iter()
+            .map(|c| match desc.get_unambiguous_name(*c) {
+                Some(name) => name.to_string(),
+                None => (c + 1).to_string(),
+            })
+            .collect();
+        // Name the index as `CREATE INDEX` would if no name were given.
+        let index_name = format!("{}_{}_idx", entry.name().item, column_names.join("_"));
+        let create_sql = index_sql(index_name, entry.name().clone(), desc, &key);
+
+        let (peeks, row) = self
+            .slow_path_peeks
+            .entry(id)
+            .or_default()
+            .entry(key)
+            .or_insert_with(|| (0, Row::default()));
+        let mut updates = vec![];
+        if *peeks > 0 {
+            updates.push((row.clone(), -1));
+        }
+        *peeks += 1;
+        *row = Row::pack_slice(&[
+            Datum::String(&id.to_string()),
+            Datum::String(&column_names.join(", ")),
+            Datum::Int64(*peeks),
+            Datum::String(&create_sql),
+        ]);
+        updates.push((row.clone(), 1));
+        self.update_catalog_view(MZ_SLOW_PATH_PEEKS.id, updates)
+            .await;
+    }
+
+    /// Retracts the rows of `mz_slow_path_peeks` for the relation `id`, which
+    /// has been dropped or indexed.
+    async fn forget_slow_path_peeks(&mut self, id: GlobalId) {
+        if let Some(peeks) = self.slow_path_peeks.remove(&id) {
+            self.update_catalog_view(
+                MZ_SLOW_PATH_PEEKS.id,
+                peeks.into_iter().map(|(_key, (_peeks, row))| (row, -1)),
+            )
+            .await;
+        }
+    }
+
+    /// Reports the status of the sink `id` to `mz_sink_status`, along with
+    /// the error that it most recently encountered, if any.
+    ///
+    /// A sink's status is `running` if it is healthy, `error` if its dataflow
+    /// has encountered an error, and `failed` if its connector could not be
+    /// rebuilt during bootstrap, in which case it has no dataflow at all.
+    async fn report_sink_status(&mut self, id: GlobalId, status: &str, error: Option<String>) {
+        let mut updates = vec![];
+        if let Some(row) = self.sink_statuses.remove(&id) {
+            updates.push((row, -1));
+        }
+        let row = Row::pack_slice(&[
+            Datum::String(&id.to_string()),
+            Datum::String(status),
+            match &error {
+                Some(error) => Datum::String(error),
+                None => Datum::Null,
+            },
+        ]);
+        updates.push((row.clone(), 1));
+        self.sink_statuses.insert(id, row);
+        self.update_catalog_view(MZ_SINK_STATUS.id, updates).await;
+    }
+
+    /// Retracts the row of `mz_sink_status` for the sink `id`, which has been
+    /// dropped.
+    async fn forget_sink_status(&mut self, id: GlobalId) {
+        if let Some(row) = self.sink_statuses.remove(&id) {
+            self.update_catalog_view(MZ_SINK_STATUS.id, iter::once((row, -1)))
+                .await;
+        }
+    }
+
+    /// Returns the time at which the build of the connector for the sink `id`
+    /// started, or the current time if no build has been reported.
+    fn sink_build_started_at(&self, id: GlobalId) -> DateTime<Utc> {
+        match self.sink_builds.get(&id) {
+            Some((started_at, _row)) => *started_at,
+            None => Utc::now(),
+        }
+    }
+
+    /// Reports to `mz_sink_builds` that the build of the connector for the
+    /// sink `id`, which started at `started_at`, is in `state`.
+    async fn report_sink_build(&mut self, id: GlobalId, state: &str, started_at: DateTime<Utc>) {
+        let mut updates = vec![];
+        if let Some((_started_at, row)) = self.sink_builds.remove(&id) {
+            updates.push((row, -1));
+        }
+        let row = Row::pack_slice(&[
+            Datum::String(&id.to_string()),
+            Datum::String(state),
+            Datum::TimestampTz(started_at),
+        ]);
+        updates.push((row.clone(), 1));
+        self.sink_builds.insert(id, (started_at, row));
+        self.update_catalog_view(MZ_SINK_BUILDS.id, updates).await;
+    }
+
+    /// Retracts the row of `mz_sink_builds` for the sink `id`, which has been
+    /// dropped.
+    async fn forget_sink_build(&mut self, id: GlobalId) {
+        if let Some((_started_at, row)) = self.sink_builds.remove(&id) {
+            self.update_catalog_view(MZ_SINK_BUILDS.id, iter::once((row, -1)))
+                .await;
+        }
+    }
+
+    /// Reports the state of `session` to `mz_sessions`. The connection is
+    /// considered to have been established the first time it is reported.
+    async fn report_session(&mut self, session: &Session) {
+        let conn_id = session.conn_id();
+        let connected_at = match self.sessions.get(&conn_id) {
+            Some((connected_at, _row)) => *connected_at,
+            None => Utc::now(),
+        };
+        let row = Row::pack_slice(&[
+            Datum::Int32(conn_id as i32),
+            Datum::String(session.vars().application_name()),
+            Datum::TimestampTz(connected_at),
+        ]);
+        let mut updates = vec![];
+        match self.sessions.insert(conn_id, (connected_at, row.clone())) {
+            Some((_connected_at, prev)) if prev == row => return,
+            Some((_connected_at, prev)) => updates.push((prev, -1)),
+            None => (),
+        }
+        updates.push((row, 1));
+        self.update_catalog_view(MZ_SESSIONS.id, updates).await;
+    }
+
+    /// Retracts the row of `mz_sessions` for the connection `conn_id`, which
+    /// has terminated.
+    async fn forget_session(&mut self, conn_id: u32) {
+        if let Some((_connected_at, row)) = self.sessions.remove(&conn_id) {
+            self.update_catalog_view(MZ_SESSIONS.id, iter::once((row, -1)))
+                .await;
+        }
+    }
+
+    /// Records in `mz_avro_ocf_sinks` that the Avro OCF sink `id` is now
+    /// writing to `path`, and is no longer writing to its previous file.
+    async fn report_avro_ocf_sink_file(&mut self, id: GlobalId, path: PathBuf) {
+        let files = self.avro_ocf_sink_files.entry(id).or_default();
+        let mut updates = vec![];
+        if let Some(prev) = files.last() {
+            updates.push((avro_ocf_sink_file_row(id, prev, true), -1));
+            updates.push((avro_ocf_sink_file_row(id, prev, false), 1));
+        }
+        updates.push((avro_ocf_sink_file_row(id, &path, true), 1));
+        files.push(path);
+        self.update_catalog_view(MZ_AVRO_OCF_SINKS.id, updates)
+            .await;
+    }
+
+    /// Retracts the rows of `mz_avro_ocf_sinks` for the Avro OCF sink `id`,
+    /// which has been dropped.
+    async fn forget_avro_ocf_sink_files(&mut self, id: GlobalId) {
+        if let Some(files) = self.avro_ocf_sink_files.remove(&id) {
+            let active = files.len() - 1;
+            let updates = files
+                .iter()
+                .enumerate()
+                .map(|(i, path)| (avro_ocf_sink_file_row(id, path, i == active), -1));
+            self.update_catalog_view(MZ_AVRO_OCF_SINKS.id, updates)
+                .await;
+        }
     }
 
     /// Insert a single row into a given catalog view.
@@ -1086,9 +2320,10 @@ where
         nullable: Vec<bool>,
         diff: isize,
     ) {
-        let key_sqls = match sql::parse::parse(&index.create_sql)
+        let key_sqls = match self
+            .parse_cache
+            .parse(&index.create_sql)
             .expect("create_sql cannot be invalid")
-            .into_element()
         {
             Statement::CreateIndex(CreateIndexStatement { key_parts, .. }) => key_parts.unwrap(),
             _ => unreachable!(),
@@ -1209,6 +2444,16 @@ where
         .await
     }
 
+    /// Reports whether the sink `id` is reported to `mz_sinks`: sinks are
+    /// reported once their connectors are ready, or if their connectors failed
+    /// to rebuild during bootstrap, so that they can be found and dropped.
+    fn is_reported_sink(&self, id: GlobalId, sink: &catalog::Sink) -> bool {
+        match sink.connector {
+            SinkConnectorState::Ready(_) => true,
+            SinkConnectorState::Pending(_) => self.sink_statuses.contains_key(&id),
+        }
+    }
+
     async fn report_sink_update(
         &mut self,
         global_id: GlobalId,
@@ -1280,6 +2525,81 @@ where
         .await
     }
 
+    async fn report_function_update(
+        &mut self,
+        id: GlobalId,
+        oid: u32,
+        schema_id: i64,
+        name: &str,
+        func: &Func,
+        diff: isize,
+    ) {
+        let humanizer = self.catalog.for_system_session();
+        let arguments = func
+            .params
+            .iter()
+            .map(|(name, typ)| {
+                format!(
+                    "{} {}",
+                    name.expect("function parameters are always named"),
+                    humanizer.humanize_scalar_type(&typ.scalar_type)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let return_type = humanizer.humanize_scalar_type(&func.return_type);
+        self.update_catalog_view(
+            MZ_FUNCTION_SIGNATURES.id,
+            iter::once((
+                Row::pack_slice(&[
+                    Datum::String(&id.to_string()),
+                    Datum::Int32(oid as i32),
+                    Datum::Int64(schema_id),
+                    Datum::String(name),
+                    Datum::String(&arguments),
+                    Datum::String(&return_type),
+                ]),
+                diff,
+            )),
+        )
+        .await
+    }
+
+    /// Reports the comment on the item `id`, or on its column at the 0-indexed
+    /// `column_pos`, if specified, to `mz_comments`.
+    async fn report_comment_update(
+        &mut self,
+        id: GlobalId,
+        column_pos: Option<usize>,
+        comment: &str,
+        diff: isize,
+    ) {
+        self.update_catalog_view(
+            MZ_COMMENTS.id,
+            iter::once((
+                Row::pack_slice(&[
+                    Datum::String(&id.to_string()),
+                    match column_pos {
+                        Some(pos) => Datum::Int64(pos as i64 + 1),
+                        None => Datum::Null,
+                    },
+                    Datum::String(comment),
+                ]),
+                diff,
+            )),
+        )
+        .await
+    }
+
+    /// Reports every comment on the item `entry` and its columns to
+    /// `mz_comments`.
+    async fn report_comment_updates(&mut self, entry: &CatalogEntry, diff: isize) {
+        for (column_pos, comment) in entry.comments() {
+            self.report_comment_update(entry.id(), *column_pos, comment, diff)
+                .await;
+        }
+    }
+
     async fn sequence_plan(
         &mut self,
         internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
@@ -1287,7 +2607,46 @@ where
         mut session: Session,
         pcx: PlanContext,
         plan: Plan,
+        source_optimized: bool,
     ) {
+        // Transactions that read from a consistent snapshot are read only.
+        if session.transaction_snapshot().is_some()
+            && matches!(
+                plan,
+                Plan::CreateDatabase { .. }
+                    | Plan::CreateSchema { .. }
+                    | Plan::CreateSource { .. }
+                    | Plan::CreateSink { .. }
+                    | Plan::CreateTable { .. }
+                    | Plan::CreateView { .. }
+                    | Plan::CreateIndex { .. }
+                    | Plan::CreateType { .. }
+                    | Plan::CreateFunction { .. }
+                    | Plan::DropDatabase { .. }
+                    | Plan::DropSchema { .. }
+                    | Plan::DropItems { .. }
+                    | Plan::SendDiffs { .. }
+                    | Plan::Insert { .. }
+                    | Plan::SelectInto { .. }
+                    | Plan::AlterItemRename { .. }
+                    | Plan::AlterViewRenameColumn { .. }
+                    | Plan::AlterIndexLogicalCompactionWindow(_)
+                    | Plan::AlterIndexKeys(_)
+                    | Plan::AlterMaterializedViewLogicalCompactionWindow(_)
+                    | Plan::RematerializeView { .. }
+                    | Plan::AlterAllIndexesCompaction { .. }
+                    | Plan::Comment { .. }
+                    | Plan::Notify { .. }
+            )
+        {
+            return tx.send(
+                Err(anyhow!(
+                    "cannot modify data or the catalog in a read-only transaction"
+                )),
+                session,
+            );
+        }
+
         match plan {
             Plan::CreateDatabase {
                 name,
@@ -1312,7 +2671,7 @@ where
                 table,
                 if_not_exists,
             } => tx.send(
-                self.sequence_create_table(pcx, name, table, if_not_exists)
+                self.sequence_create_table(pcx, name, table, session.conn_id(), if_not_exists)
                     .await,
                 session,
             ),
@@ -1374,7 +2733,7 @@ where
                 index,
                 if_not_exists,
             } => tx.send(
-                self.sequence_create_index(pcx, name, index, if_not_exists)
+                self.sequence_create_index(pcx, name, index, session.conn_id(), if_not_exists)
                     .await,
                 session,
             ),
@@ -1383,14 +2742,19 @@ where
                 tx.send(self.sequence_create_type(pcx, name, typ).await, session)
             }
 
+            Plan::CreateFunction { name, func } => tx.send(
+                self.sequence_create_function(pcx, name, func).await,
+                session,
+            ),
+
             Plan::DropDatabase { name } => {
                 tx.send(self.sequence_drop_database(name).await, session)
             }
 
             Plan::DropSchema { name } => tx.send(self.sequence_drop_schema(name).await, session),
 
-            Plan::DropItems { items, ty } => {
-                tx.send(self.sequence_drop_items(items, ty).await, session)
+            Plan::DropItems { items, ty, cascade } => {
+                tx.send(self.sequence_drop_items(items, ty, cascade).await, session)
             }
 
             Plan::EmptyQuery => tx.send(Ok(ExecuteResponse::EmptyQuery), session),
@@ -1408,8 +2772,10 @@ where
                 session,
             ),
 
-            Plan::StartTransaction => {
-                session.start_transaction();
+            Plan::StartTransaction {
+                consistent_snapshot,
+            } => {
+                session.start_transaction(consistent_snapshot);
                 tx.send(Ok(ExecuteResponse::StartedTransaction), session)
             }
 
@@ -1436,28 +2802,57 @@ where
                 when,
                 finishing,
                 copy_to,
-            } => tx.send(
-                self.sequence_peek(session.conn_id(), source, when, finishing, copy_to)
-                    .await,
-                session,
-            ),
+                index_hint,
+                locking,
+            } => {
+                // Rows are never locked, as there are no concurrent writers to
+                // exclude. Clients that require locking semantics can opt in
+                // to rejecting the clause instead of silently ignoring it.
+                if let Some(locking) = locking {
+                    if session.vars().strict_row_locking() {
+                        return tx.send(Err(anyhow!("{} is not supported", locking)), session);
+                    }
+                    session.add_notice(Notice::RowLockingIgnored(locking));
+                }
+                self.sequence_throttled_peek(
+                    internal_cmd_tx,
+                    tx,
+                    session,
+                    source,
+                    when,
+                    finishing,
+                    copy_to,
+                    index_hint,
+                    source_optimized,
+                    None,
+                )
+                .await
+            }
 
             Plan::Tail {
                 id,
                 ts,
+                until,
                 with_snapshot,
                 copy_to,
                 emit_progress,
                 object_columns,
+                on_backpressure,
+                limit,
+                collect,
             } => tx.send(
                 self.sequence_tail(
                     &session,
                     id,
                     with_snapshot,
                     ts,
+                    until,
                     copy_to,
                     emit_progress,
                     object_columns,
+                    on_backpressure,
+                    limit,
+                    collect,
                 )
                 .await,
                 session,
@@ -1471,14 +2866,43 @@ where
                 row_set_finishing,
                 stage,
                 options,
+                is_insert,
             } => tx.send(
                 self.sequence_explain_plan(
-                    &session,
+                    &mut session,
                     raw_plan,
                     decorrelated_plan,
                     row_set_finishing,
                     stage,
                     options,
+                    is_insert,
+                ),
+                session,
+            ),
+
+            Plan::ExplainAnalyze(plan) => {
+                self.sequence_explain_analyze(internal_cmd_tx, tx, session, *plan)
+                    .await
+            }
+
+            Plan::ExplainArrangements(explainee) => {
+                tx.send(self.sequence_explain_arrangements(explainee), session)
+            }
+
+            Plan::ExplainSink {
+                name,
+                sink,
+                with_snapshot,
+                as_of,
+                if_not_exists,
+            } => tx.send(
+                self.sequence_explain_sink(
+                    &session,
+                    name,
+                    sink,
+                    with_snapshot,
+                    as_of,
+                    if_not_exists,
                 ),
                 session,
             ),
@@ -1494,7 +2918,21 @@ where
                 session,
             ),
 
-            Plan::Insert { id, values } => tx.send(self.sequence_insert(id, values).await, session),
+            Plan::Insert {
+                id,
+                values,
+                returning,
+            } => tx.send(self.sequence_insert(id, values, returning).await, session),
+
+            Plan::SelectInto {
+                name,
+                table,
+                values,
+            } => tx.send(
+                self.sequence_select_into(pcx, name, table, values, session.conn_id())
+                    .await,
+                session,
+            ),
 
             Plan::AlterItemRename {
                 id,
@@ -1506,16 +2944,70 @@ where
                 session,
             ),
 
+            Plan::AlterViewRenameColumn { id, old, new } => tx.send(
+                self.sequence_alter_view_rename_column(id, old, new).await,
+                session,
+            ),
+
             Plan::AlterIndexLogicalCompactionWindow(alter_index) => tx.send(
                 self.sequence_alter_index_logical_compaction_window(alter_index),
                 session,
             ),
 
+            Plan::AlterMaterializedViewLogicalCompactionWindow(alter_view) => tx.send(
+                self.sequence_alter_materialized_view_logical_compaction_window(alter_view),
+                session,
+            ),
+
+            Plan::AlterIndexKeys(alter) => {
+                tx.send(self.sequence_alter_index_keys(pcx, alter).await, session)
+            }
+
+            Plan::RematerializeView { id } => {
+                tx.send(self.sequence_rematerialize_view(id).await, session)
+            }
+
+            Plan::RefreshMaterializedView { name } => {
+                session.add_notice(Notice::RefreshIgnored(name));
+                tx.send(Ok(ExecuteResponse::RefreshedMaterializedView), session)
+            }
+
+            Plan::AlterAllIndexesCompaction { window } => tx.send(
+                Ok(self.sequence_alter_all_indexes_compaction(window)),
+                session,
+            ),
+
+            Plan::Comment {
+                object_id,
+                column_pos,
+                comment,
+            } => tx.send(
+                self.sequence_comment(object_id, column_pos, comment).await,
+                session,
+            ),
+
             Plan::DiscardTemp => {
                 self.drop_temp_items(session.conn_id()).await;
                 tx.send(Ok(ExecuteResponse::DiscardedTemp), session);
             }
 
+            Plan::DiscardPlans => {
+                let ret = if session.transaction() != &TransactionStatus::Idle {
+                    ExecuteResponse::PgError {
+                        code: SqlState::ACTIVE_SQL_TRANSACTION,
+                        message: "DISCARD PLANS cannot run inside a transaction block".to_string(),
+                    }
+                } else {
+                    session.remove_all_prepared_statements();
+                    ExecuteResponse::DiscardedPlans
+                };
+                tx.send(Ok(ret), session);
+            }
+
+            // Materialize does not support sequences, so there is no sequence
+            // state to discard.
+            Plan::DiscardSequences => tx.send(Ok(ExecuteResponse::DiscardedSequences), session),
+
             Plan::DiscardAll => {
                 let ret = if session.transaction() != &TransactionStatus::Idle {
                     ExecuteResponse::PgError {
@@ -1524,7 +3016,9 @@ where
                     }
                 } else {
                     self.drop_temp_items(session.conn_id()).await;
+                    self.sequence_unlisten(&mut session, None).await;
                     session.reset();
+                    self.report_session(&session).await;
                     ExecuteResponse::DiscardedAll
                 };
                 tx.send(Ok(ret), session);
@@ -1542,14 +3036,19 @@ where
                 name,
                 count,
                 timeout,
-            } => tx.send(
-                Ok(ExecuteResponse::Fetch {
-                    name,
-                    count,
-                    timeout,
-                }),
-                session,
-            ),
+            } => {
+                // An explicit timeout takes precedence over the session's
+                // default.
+                let timeout = timeout.unwrap_or_else(|| session.vars().fetch_timeout());
+                tx.send(
+                    Ok(ExecuteResponse::Fetch {
+                        name,
+                        count,
+                        timeout,
+                    }),
+                    session,
+                )
+            }
 
             Plan::Close { name } => {
                 if session.remove_portal(&name) {
@@ -1558,14 +3057,30 @@ where
                     tx.send(Err(anyhow!("cursor \"{}\" does not exist", name)), session)
                 }
             }
-        }
-    }
 
-    async fn sequence_create_database(
-        &mut self,
-        name: String,
-        if_not_exists: bool,
-    ) -> Result<ExecuteResponse, anyhow::Error> {
+            Plan::Listen { channel } => {
+                let res = self.sequence_listen(&mut session, channel).await;
+                tx.send(res, session);
+            }
+
+            Plan::Unlisten { channel } => {
+                self.sequence_unlisten(&mut session, channel.as_deref())
+                    .await;
+                tx.send(Ok(ExecuteResponse::Unlistened), session);
+            }
+
+            Plan::Notify { channel, payload } => {
+                self.sequence_notify(&session, channel, payload).await;
+                tx.send(Ok(ExecuteResponse::Notified), session);
+            }
+        }
+    }
+
+    async fn sequence_create_database(
+        &mut self,
+        name: String,
+        if_not_exists: bool,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
         let db_oid = self.catalog.allocate_oid()?;
         let schema_oid = self.catalog.allocate_oid()?;
         let ops = vec![
@@ -1610,47 +3125,103 @@ where
         pcx: PlanContext,
         name: FullName,
         table: sql::plan::Table,
+        conn_id: u32,
         if_not_exists: bool,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         let table_id = self.catalog.allocate_id()?;
+        let (ops, index_id) = self.create_table_ops(pcx, table_id, name, table, conn_id)?;
+        match self.catalog_transact(ops).await {
+            Ok(_) => {
+                self.ship_index_dataflow(index_id, &[table_id]).await?;
+                Ok(ExecuteResponse::CreatedTable { existed: false })
+            }
+            Err(_) if if_not_exists => Ok(ExecuteResponse::CreatedTable { existed: true }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the catalog operations that create the table `table_id` and its
+    /// primary index, along with the ID of that index.
+    fn create_table_ops(
+        &mut self,
+        pcx: PlanContext,
+        table_id: GlobalId,
+        name: FullName,
+        table: sql::plan::Table,
+        conn_id: u32,
+    ) -> Result<(Vec<catalog::Op>, GlobalId), anyhow::Error> {
+        let conn_id = if table.temporary { Some(conn_id) } else { None };
         let table = catalog::Table {
             create_sql: table.create_sql,
             plan_cx: pcx,
             desc: table.desc,
             defaults: table.defaults,
+            conn_id,
+            func_uses: table.func_uses,
         };
+        // The primary index is not optional. A table's contents exist only in
+        // the arrangement that backs this index: the dataflow layer installs
+        // the table's local input only when a dataflow imports the table, and
+        // inserts into a table without a local input are fatal. Each importing
+        // dataflow installs its own, initially empty, input.
         let index_id = self.catalog.allocate_id()?;
         let mut index_name = name.clone();
         index_name.item += "_primary_idx";
-        let index =
-            auto_generate_primary_idx(index_name.item.clone(), name.clone(), table_id, &table.desc);
+        let index = auto_generate_primary_idx(
+            index_name.item.clone(),
+            name.clone(),
+            table_id,
+            &table.desc,
+            conn_id,
+        );
         let table_oid = self.catalog.allocate_oid()?;
         let index_oid = self.catalog.allocate_oid()?;
-        match self
-            .catalog_transact(vec![
-                catalog::Op::CreateItem {
-                    id: table_id,
-                    oid: table_oid,
-                    name,
-                    item: CatalogItem::Table(table),
-                },
-                catalog::Op::CreateItem {
-                    id: index_id,
-                    oid: index_oid,
-                    name: index_name,
-                    item: CatalogItem::Index(index),
-                },
-            ])
-            .await
-        {
-            Ok(_) => {
-                self.ship_dataflow(self.dataflow_builder().build_index_dataflow(index_id))
-                    .await;
-                Ok(ExecuteResponse::CreatedTable { existed: false })
-            }
-            Err(_) if if_not_exists => Ok(ExecuteResponse::CreatedTable { existed: true }),
-            Err(err) => Err(err),
-        }
+        let ops = vec![
+            catalog::Op::CreateItem {
+                id: table_id,
+                oid: table_oid,
+                name,
+                item: CatalogItem::Table(table),
+            },
+            catalog::Op::CreateItem {
+                id: index_id,
+                oid: index_oid,
+                name: index_name,
+                item: CatalogItem::Index(index),
+            },
+        ];
+        Ok((ops, index_id))
+    }
+
+    /// Creates a table and populates it with the result of `values`, which,
+    /// as for `INSERT`, must optimize to a constant.
+    async fn sequence_select_into(
+        &mut self,
+        pcx: PlanContext,
+        name: FullName,
+        table: sql::plan::Table,
+        values: RelationExpr,
+        conn_id: u32,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        // Evaluate the query before creating the table, so that a query we
+        // cannot handle does not leave an empty table behind.
+        let prep_style = ExprPrepStyle::OneShot {
+            logical_time: self.get_write_ts(),
+        };
+        let rows = match self.prep_relation_expr(values, prep_style)?.into_inner() {
+            RelationExpr::Constant { rows, typ: _ } => rows,
+            _ => bail!("SELECT ... INTO statements cannot reference other relations"),
+        };
+
+        let table_id = self.catalog.allocate_id()?;
+        let (ops, index_id) = self.create_table_ops(pcx, table_id, name, table, conn_id)?;
+        self.catalog_transact(ops).await?;
+        self.ship_index_dataflow(index_id, &[table_id]).await?;
+
+        let affected_rows = rows.len();
+        self.sequence_send_diffs(table_id, rows, affected_rows, MutationKind::Insert)
+            .await?;
+        Ok(ExecuteResponse::SelectedInto(affected_rows))
     }
 
     async fn sequence_create_source(
@@ -1678,8 +3249,13 @@ where
         let index_id = if materialized {
             let mut index_name = name.clone();
             index_name.item += "_primary_idx";
-            let index =
-                auto_generate_primary_idx(index_name.item.clone(), name, source_id, &source.desc);
+            let index = auto_generate_primary_idx(
+                index_name.item.clone(),
+                name,
+                source_id,
+                &source.desc,
+                None,
+            );
             let index_id = self.catalog.allocate_id()?;
             let index_oid = self.catalog.allocate_oid()?;
             ops.push(catalog::Op::CreateItem {
@@ -1695,8 +3271,7 @@ where
         match self.catalog_transact(ops).await {
             Ok(()) => {
                 if let Some(index_id) = index_id {
-                    self.ship_dataflow(self.dataflow_builder().build_index_dataflow(index_id))
-                        .await;
+                    self.ship_index_dataflow(index_id, &[source_id]).await?;
                 }
 
                 self.maybe_begin_caching(source_id, &source.connector).await;
@@ -1736,7 +3311,7 @@ where
             }
         };
 
-        let frontier = match self.determine_frontier(as_of, sink.from) {
+        let frontier = match self.validate_sink(&sink, as_of) {
             Ok(frontier) => frontier,
             Err(e) => {
                 tx.send(Err(e), session);
@@ -1750,6 +3325,11 @@ where
         //
         // This placeholder catalog item reserves the name while we create
         // the sink connector, which could take an arbitrarily long time.
+        // Other statements may be sequenced in the meantime, but the sink's
+        // ID and name are already committed, so they cannot be reused. The
+        // placeholder is later replaced or removed in a single catalog
+        // transaction by `message_sink_connector_ready`, and if we crash
+        // before then, it is removed during the next `bootstrap`.
         let op = catalog::Op::CreateItem {
             id,
             oid,
@@ -1774,6 +3354,7 @@ where
                 return;
             }
         }
+        self.report_sink_build(id, "PENDING", Utc::now()).await;
 
         // Now we're ready to create the sink connector. Arrange to notify the
         // main coordinator thread when the future completes.
@@ -1793,6 +3374,78 @@ where
         });
     }
 
+    /// Validates `sink` before anything is created for it, and determines the
+    /// frontier at which it will begin.
+    fn validate_sink(
+        &mut self,
+        sink: &sql::plan::Sink,
+        as_of: Option<u64>,
+    ) -> Result<Antichain<Timestamp>, anyhow::Error> {
+        let from = self.catalog.get_by_id(&sink.from);
+        if from.item().is_temporary() {
+            bail!(
+                "non-temporary items cannot depend on temporary item '{}'",
+                from.name()
+            );
+        }
+        self.check_system_object_export("CREATE SINK", sink.from)?;
+        self.determine_frontier(as_of, sink.from)
+    }
+
+    /// Rejects `statement`, which streams the contents of the object `id` out
+    /// of Materialize, if `id` is a system object, unless experimental mode is
+    /// enabled. System objects describe Materialize's internal state, which is
+    /// rarely intended to leave it, but can be useful when debugging.
+    fn check_system_object_export(
+        &self,
+        statement: &str,
+        id: GlobalId,
+    ) -> Result<(), anyhow::Error> {
+        if BUILTINS.contains_key(&id) && !self.catalog.config().experimental_mode {
+            bail!(
+                "{} from system object '{}' requires experimental mode; see \
+                 https://materialize.com/docs/cli/#experimental-mode",
+                statement,
+                self.catalog.get_by_id(&id).name()
+            );
+        }
+        Ok(())
+    }
+
+    /// Validates a sink as `sequence_create_sink` would, and reports the
+    /// external state that the sink would create, without reserving its name
+    /// or building its connector.
+    fn sequence_explain_sink(
+        &mut self,
+        session: &Session,
+        name: FullName,
+        sink: sql::plan::Sink,
+        with_snapshot: bool,
+        as_of: Option<u64>,
+        if_not_exists: bool,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let action = match self.catalog.try_get(&name, session.conn_id()) {
+            None => "create",
+            Some(_) if if_not_exists => "none (already exists)",
+            Some(_) => bail!("catalog item '{}' already exists", name.item),
+        };
+        let frontier = self.validate_sink(&sink, as_of)?;
+        let mut properties = vec![
+            ("action", action.to_string()),
+            ("name", name.to_string()),
+            (
+                "from",
+                self.catalog.get_by_id(&sink.from).name().to_string(),
+            ),
+        ];
+        properties.extend(sink_connector::describe(&sink.connector_builder, "<id>")?);
+        properties.push(("with_snapshot", with_snapshot.to_string()));
+        if let Some(ts) = frontier.elements().first() {
+            properties.push(("as_of", ts.to_string()));
+        }
+        Ok(send_immediate_rows(pack_properties(properties)))
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn sequence_create_view(
         &mut self,
@@ -1819,6 +3472,7 @@ where
             optimized_expr,
             desc,
             conn_id: if view.temporary { Some(conn_id) } else { None },
+            func_uses: view.func_uses,
         };
         ops.push(catalog::Op::CreateItem {
             id: view_id,
@@ -1829,8 +3483,13 @@ where
         let index_id = if materialize {
             let mut index_name = name.clone();
             index_name.item += "_primary_idx";
-            let index =
-                auto_generate_primary_idx(index_name.item.clone(), name, view_id, &view.desc);
+            let index = auto_generate_primary_idx(
+                index_name.item.clone(),
+                name,
+                view_id,
+                &view.desc,
+                view.conn_id,
+            );
             let index_id = self.catalog.allocate_id()?;
             let index_oid = self.catalog.allocate_oid()?;
             ops.push(catalog::Op::CreateItem {
@@ -1846,8 +3505,7 @@ where
         match self.catalog_transact(ops).await {
             Ok(()) => {
                 if let Some(index_id) = index_id {
-                    self.ship_dataflow(self.dataflow_builder().build_index_dataflow(index_id))
-                        .await;
+                    self.ship_index_dataflow(index_id, &[view_id]).await?;
                 }
                 Ok(ExecuteResponse::CreatedView { existed: false })
             }
@@ -1861,16 +3519,46 @@ where
         pcx: PlanContext,
         name: FullName,
         mut index: sql::plan::Index,
+        session_conn_id: u32,
         if_not_exists: bool,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         for key in &mut index.keys {
-            Self::prep_scalar_expr(key, ExprPrepStyle::Static)?;
+            self.prep_scalar_expr(key, ExprPrepStyle::Static)?;
+        }
+        // Refuse to build more than the configured number of arrangements on
+        // any one relation. An existing index of the same name still satisfies
+        // `IF NOT EXISTS`, even at the limit.
+        let existing = self
+            .catalog
+            .indexes()
+            .get(&index.on)
+            .map(|indexes| indexes.iter().map(|(id, _)| *id).collect::<Vec<_>>())
+            .unwrap_or_default();
+        if existing.len() >= self.max_indexes_per_relation {
+            if if_not_exists && self.catalog.try_get(&name, session_conn_id).is_some() {
+                return Ok(ExecuteResponse::CreatedIndex { existed: true });
+            }
+            bail!(
+                "cannot create index on {}: it already has {} indexes ({}), \
+                 the maximum permitted per relation",
+                self.catalog.get_by_id(&index.on).name(),
+                existing.len(),
+                existing
+                    .iter()
+                    .map(|id| self.catalog.get_by_id(id).name().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
         }
+        // Indexes on temporary items are themselves temporary.
+        let conn_id = self.catalog.get_by_id(&index.on).item().conn_id();
         let index = catalog::Index {
             create_sql: index.create_sql,
             plan_cx: pcx,
             keys: index.keys,
             on: index.on,
+            conn_id,
+            func_uses: index.func_uses,
         };
         let id = self.catalog.allocate_id()?;
         let oid = self.catalog.allocate_oid()?;
@@ -1882,8 +3570,7 @@ where
         };
         match self.catalog_transact(vec![op]).await {
             Ok(()) => {
-                self.ship_dataflow(self.dataflow_builder().build_index_dataflow(id))
-                    .await;
+                self.ship_index_dataflow(id, &[id]).await?;
                 Ok(ExecuteResponse::CreatedIndex { existed: false })
             }
             Err(_) if if_not_exists => Ok(ExecuteResponse::CreatedIndex { existed: true }),
@@ -1916,11 +3603,47 @@ where
         }
     }
 
+    async fn sequence_create_function(
+        &mut self,
+        pcx: PlanContext,
+        name: FullName,
+        func: sql::plan::Function,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let func = catalog::Func {
+            create_sql: func.create_sql,
+            plan_cx: pcx,
+            params: func.params,
+            return_type: func.return_type,
+            body: func.body,
+            func_uses: func.func_uses,
+        };
+        let id = self.catalog.allocate_id()?;
+        let oid = self.catalog.allocate_oid()?;
+        let op = catalog::Op::CreateItem {
+            id,
+            oid,
+            name,
+            item: CatalogItem::Func(func),
+        };
+        match self.catalog_transact(vec![op]).await {
+            Ok(()) => Ok(ExecuteResponse::CreatedFunction),
+            Err(err) => Err(err),
+        }
+    }
+
     async fn sequence_drop_database(
         &mut self,
         name: String,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         let ops = self.catalog.drop_database_ops(name);
+
+        // Cancel any PEEKs or TAILs that read from objects in the database
+        // up front, so that they end cleanly rather than failing midway
+        // through with errors about missing objects.
+        for conn_id in self.query_conn_ids_reading_dropped_items(&ops) {
+            self.handle_cancel(conn_id).await;
+        }
+
         self.catalog_transact(ops).await?;
         Ok(ExecuteResponse::DroppedDatabase)
     }
@@ -1938,9 +3661,46 @@ where
         &mut self,
         items: Vec<GlobalId>,
         ty: ObjectType,
+        cascade: bool,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         let ops = self.catalog.drop_items_ops(&items);
+
+        // A TAIL holds no catalog dependency on the object it reads from, so
+        // check for TAILs that would be left reading from a dropped object.
+        // Without CASCADE, refuse the drop; with CASCADE, cancel the TAILs.
+        let dropped: HashSet<_> = ops
+            .iter()
+            .filter_map(|op| match op {
+                catalog::Op::DropItem(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let mut affected_tails: Vec<_> = self
+            .active_tails
+            .iter()
+            .filter(|(_, tail)| dropped.contains(&tail.from))
+            .map(|(conn_id, tail)| (*conn_id, tail.from))
+            .collect();
+        affected_tails.sort_unstable();
+        if let Some((_, from)) = affected_tails.first() {
+            if !cascade {
+                bail!(
+                    "cannot drop {}: still being read by an active TAIL",
+                    self.catalog.get_by_id(from).name()
+                );
+            }
+        }
+
         self.catalog_transact(ops).await?;
+        for (conn_id, _) in affected_tails {
+            if let Some(tail) = self.active_tails.remove(&conn_id) {
+                self.tail_completions.remove(&tail.sink_id);
+                if let Some(cancel_tx) = tail.cancel_tx {
+                    let _ = cancel_tx.send(());
+                }
+                self.drop_sinks(vec![tail.sink_id]).await;
+            }
+        }
         Ok(match ty {
             ObjectType::Schema => unreachable!(),
             ObjectType::Source => {
@@ -1959,6 +3719,7 @@ where
             ObjectType::Sink => ExecuteResponse::DroppedSink,
             ObjectType::Index => ExecuteResponse::DroppedIndex,
             ObjectType::Type => ExecuteResponse::DroppedType,
+            ObjectType::Function => ExecuteResponse::DroppedFunction,
             ObjectType::Object => unreachable!("generic OBJECT cannot be dropped"),
         })
     }
@@ -1994,117 +3755,583 @@ where
     }
 
     async fn sequence_set_variable(
-        &self,
+        &mut self,
         session: &mut Session,
         name: String,
         value: String,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         session.vars_mut().set(&name, &value)?;
+        self.report_session(session).await;
         Ok(ExecuteResponse::SetVariable { name })
     }
 
-    async fn sequence_peek(
+    /// Sequences a peek on behalf of a session, subject to admission control.
+    ///
+    /// If the peek requires a transient dataflow and no permit is available,
+    /// the peek is parked until one is, and resequenced via a
+    /// `Message::PeekAdmissionReady`.
+    #[allow(clippy::too_many_arguments)]
+    async fn sequence_throttled_peek(
         &mut self,
-        conn_id: u32,
+        internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
+        tx: ClientTransmitter<ExecuteResponse>,
+        mut session: Session,
         source: RelationExpr,
         when: PeekWhen,
         finishing: RowSetFinishing,
-        copy_to: Option<CopyFormat>,
-    ) -> Result<ExecuteResponse, anyhow::Error> {
-        let timestamp = self.determine_timestamp(&source, when)?;
-
-        let source = self.prep_relation_expr(
-            source,
-            ExprPrepStyle::OneShot {
-                logical_time: timestamp,
-            },
-        )?;
-
-        // If this optimizes to a constant expression, we can immediately return the result.
-        let resp = if let RelationExpr::Constant { rows, typ: _ } = source.as_ref() {
-            let mut results = Vec::new();
-            for &(ref row, count) in rows {
-                assert!(
-                    count >= 0,
-                    "Negative multiplicity in constant result: {}",
-                    count
-                );
-                for _ in 0..count {
-                    results.push(row.clone());
-                }
+        copy_to: Option<CopyTo>,
+        index_hint: Option<GlobalId>,
+        source_optimized: bool,
+        permit: Option<OwnedSemaphorePermit>,
+    ) {
+        let when = match self.resolve_peek_when(&session, &source, when) {
+            Ok(when) => when,
+            Err(e) => return tx.send(Err(e), session),
+        };
+        let pin_snapshot = matches!(when, PeekWhen::Immediately);
+
+        // Stash a copy of the peek in case it needs to be resequenced. This is
+        // only necessary if admission control is enabled.
+        let retry = match self.peek_semaphore {
+            Some(_) if permit.is_none() => Some((
+                source.clone(),
+                when.clone(),
+                finishing.clone(),
+                copy_to.clone(),
+                index_hint,
+            )),
+            _ => None,
+        };
+        let conn_id = session.conn_id();
+        let uses = source.global_uses();
+        let max_timestamp_age_ms = session.vars().max_timestamp_age_ms();
+        let optimizer_fuel = session.vars().optimizer_fuel();
+        // `COPY ... TO STDOUT` streams each worker's rows to the client as
+        // they arrive, rather than buffering the entire result.
+        let stream_rows = matches!(
+            copy_to,
+            Some(CopyTo {
+                target: CopyToTarget::Stdout,
+                ..
+            })
+        );
+        let outcome = self
+            .sequence_peek(
+                conn_id,
+                source,
+                when,
+                finishing,
+                copy_to,
+                stream_rows,
+                index_hint,
+                source_optimized,
+                permit,
+                max_timestamp_age_ms,
+                optimizer_fuel,
+                session.vars().timezone(),
+            )
+            .await;
+        let semaphore = match (outcome, &self.peek_semaphore) {
+            (Ok(PeekOutcome::Issued(resp, timestamp, fuel_exhausted, _path)), _) => {
+                note_issued_peek(&mut session, timestamp, fuel_exhausted, pin_snapshot);
+                let resp = self.track_peek(conn_id, resp, internal_cmd_tx);
+                self.peek_uses.insert(conn_id, uses);
+                return tx.send(Ok(resp), session);
             }
-            finishing.finish(&mut results);
-            send_immediate_rows(results)
-        } else {
-            // Peeks describe a source of data and a timestamp at which to view its contents.
-            //
-            // We need to determine both an appropriate timestamp from the description, and
-            // also to ensure that there is a view in place to query, if the source of data
-            // for the peek is not a base relation.
-
-            // Choose a timestamp for all workers to use in the peek.
-            // We minimize over all participating views, to ensure that the query will not
-            // need to block on the arrival of further input data.
-            let (rows_tx, rows_rx) = self.switchboard.mpsc_limited(self.num_timely_workers);
-
-            // Extract any surrounding linear operators to determine if we can simply read
-            // out the contents from an existing arrangement.
-            let (mut map_filter_project, inner) =
-                expr::MapFilterProject::extract_from_expression(source.as_ref());
+            (Ok(PeekOutcome::Throttled), Some(semaphore)) => Arc::clone(semaphore),
+            (Ok(PeekOutcome::Throttled), None) => unreachable!("peek throttled without a limit"),
+            (Err(e), _) => return tx.send(Err(e), session),
+        };
+        let (source, when, finishing, copy_to, index_hint) =
+            retry.expect("throttled peek was stashed");
+
+        // A peek that is not permitted to wait fails immediately, rather than
+        // racing a zero-length timeout against the semaphore.
+        if self.peek_max_wait == Some(Duration::from_secs(0)) {
+            return tx.send(
+                Err(anyhow!(
+                    "query was not admitted: too many concurrent queries require \
+                     temporary dataflows"
+                )),
+                session,
+            );
+        }
 
-            // We can use a fast path approach if our query corresponds to a read out of
-            // an existing materialization. This is the case if the expression is now a
-            // `RelationExpr::Get` and its target is something we have materialized.
-            // Otherwise, we will need to build a new dataflow.
-            let mut fast_path: Option<(_, Option<Row>)> = None;
-            if let RelationExpr::Get {
-                id: Id::Global(id),
-                typ: _,
-            } = inner
-            {
-                // Here we should check for an index whose keys are constrained to literal
-                // values by predicate constraints in `map_filter_project`. If we find such
-                // an index, we can use it with the literal to perform look-ups at workers,
-                // and in principle avoid even contacting all but one worker (future work).
-                if let Some(indexes) = self.catalog.indexes().get(id) {
-                    // Determine for each index identifier, an optional row literal as key.
-                    // We want to extract the "best" option, where we prefer indexes with
-                    // literals and long keys, then indexes at all, then exit correctly.
-                    fast_path = indexes
-                        .iter()
-                        .map(|(id, exprs)| {
-                            let literal_row = map_filter_project.literal_constraints(exprs);
-                            // Prefer non-trivial literal rows foremost, then long expressions,
-                            // then we don't really care at that point.
-                            (literal_row.is_some(), exprs.len(), literal_row, *id)
-                        })
-                        .max()
-                        .map(|(_some, _len, literal, id)| (id, literal));
+        let (cancel_tx, cancel_rx) = futures::channel::oneshot::channel();
+        self.throttled_peeks.insert(conn_id, cancel_tx);
+        self.peek_uses.insert(conn_id, uses);
+        let max_wait = self.peek_max_wait;
+        let mut internal_cmd_tx = internal_cmd_tx.clone();
+        tokio::spawn(async move {
+            let timeout = async {
+                match max_wait {
+                    Some(max_wait) => {
+                        tokio::time::sleep(max_wait).await;
+                        max_wait
+                    }
+                    None => future::pending().await,
                 }
-            }
-
-            // Unpack what we have learned with default values if we found nothing.
-            let (fast_path, index_id, literal_row) = if let Some((id, row)) = fast_path {
-                (true, id, row)
-            } else {
-                (false, self.allocate_transient_id()?, None)
             };
+            let result = tokio::select! {
+                permit = semaphore.acquire_owned() => PeekAdmissionResult::Admitted(
+                    permit.expect("peek semaphore is never closed"),
+                ),
+                _ = cancel_rx => PeekAdmissionResult::Canceled,
+                max_wait = timeout => PeekAdmissionResult::TimedOut(max_wait),
+            };
+            internal_cmd_tx
+                .send(Message::PeekAdmissionReady(PeekAdmissionReady {
+                    session,
+                    tx,
+                    source,
+                    when,
+                    finishing,
+                    copy_to,
+                    index_hint,
+                    source_optimized,
+                    result,
+                }))
+                .await
+                .expect("sending to internal_cmd_tx cannot fail");
+        });
+    }
 
-            if !fast_path {
-                // Slow path. We need to perform some computation, so build
-                // a new transient dataflow that will be dropped after the
-                // peek completes.
-                let typ = source.as_ref().typ();
-                map_filter_project = expr::MapFilterProject::new(typ.arity());
-                let key: Vec<_> = (0..typ.arity()).map(ScalarExpr::Column).collect();
-                let view_id = self.allocate_transient_id()?;
-                let mut dataflow = DataflowDesc::new(format!("temp-view-{}", view_id));
-                dataflow.set_as_of(Antichain::from_elem(timestamp));
+    /// Determines when a peek of `source` on behalf of `session` reads.
+    ///
+    /// Transactions that read from a consistent snapshot read every query at
+    /// the timestamp chosen for their first. Queries with an explicit `AS OF`
+    /// are left alone.
+    fn resolve_peek_when(
+        &self,
+        session: &Session,
+        source: &RelationExpr,
+        when: PeekWhen,
+    ) -> Result<PeekWhen, anyhow::Error> {
+        match (session.transaction_snapshot(), when) {
+            (Some(TransactionSnapshot::Pinned(timestamp)), PeekWhen::Immediately) => {
+                self.check_snapshot_valid(source, timestamp)?;
+                Ok(PeekWhen::AtTimestamp(timestamp))
+            }
+            (_, when) => Ok(when),
+        }
+    }
+
+    /// Sequences `EXPLAIN ANALYZE`, which executes `plan` and reports its
+    /// timing and outcome in place of its results.
+    ///
+    /// Queries are peeked as by `sequence_throttled_peek`, except that, like
+    /// queries in a batch, they fail immediately rather than wait for
+    /// admission.
+    async fn sequence_explain_analyze(
+        &mut self,
+        internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
+        tx: ClientTransmitter<ExecuteResponse>,
+        mut session: Session,
+        plan: Plan,
+    ) {
+        let start = Instant::now();
+        match plan {
+            Plan::Peek {
+                source,
+                when,
+                finishing,
+                copy_to: _,
+                index_hint,
+                locking: _,
+            } => {
+                let when = match self.resolve_peek_when(&session, &source, when) {
+                    Ok(when) => when,
+                    Err(e) => return tx.send(Err(e), session),
+                };
+                let pin_snapshot = matches!(when, PeekWhen::Immediately);
+                let conn_id = session.conn_id();
+                let uses = source.global_uses();
+                // Rows are streamed so that the arrival of the first can be
+                // distinguished from that of the last.
+                let outcome = self
+                    .sequence_peek(
+                        conn_id,
+                        source,
+                        when,
+                        finishing,
+                        None,
+                        true,
+                        index_hint,
+                        false,
+                        None,
+                        session.vars().max_timestamp_age_ms(),
+                        session.vars().optimizer_fuel(),
+                        session.vars().timezone(),
+                    )
+                    .await;
+                match outcome {
+                    Ok(PeekOutcome::Issued(resp, timestamp, fuel_exhausted, path)) => {
+                        note_issued_peek(&mut session, timestamp, fuel_exhausted, pin_snapshot);
+                        let resp = self.track_peek(conn_id, resp, internal_cmd_tx);
+                        self.peek_uses.insert(conn_id, uses);
+                        let mut properties = vec![("path", path.to_string())];
+                        if let Some(timestamp) = timestamp {
+                            properties.push(("timestamp", timestamp.to_string()));
+                        }
+                        let resp = explain_analyze_rows(start, resp, properties);
+                        tx.send(Ok(resp), session)
+                    }
+                    Ok(PeekOutcome::Throttled) => tx.send(
+                        Err(anyhow!(
+                            "too many concurrent queries require temporary dataflows"
+                        )),
+                        session,
+                    ),
+                    Err(e) => tx.send(Err(e), session),
+                }
+            }
+            Plan::Insert {
+                id,
+                values,
+                returning,
+            } => {
+                if session.transaction_snapshot().is_some() {
+                    return tx.send(
+                        Err(anyhow!(
+                            "cannot modify data or the catalog in a read-only transaction"
+                        )),
+                        session,
+                    );
+                }
+                let resp = match self.sequence_insert(id, values, returning).await {
+                    Ok(ExecuteResponse::Inserted(affected_rows)) => {
+                        Ok(send_immediate_rows(pack_properties(vec![
+                            ("affected_rows", affected_rows.to_string()),
+                            ("total_time_ms", format_ms(start.elapsed())),
+                        ])))
+                    }
+                    Ok(resp) => unreachable!("unexpected response to INSERT: {:?}", resp),
+                    Err(e) => Err(e),
+                };
+                tx.send(resp, session)
+            }
+            _ => unreachable!("EXPLAIN ANALYZE planned an unsupported statement"),
+        }
+    }
+
+    /// Records that `conn_id` has an active peek until the rows future or
+    /// stream in `resp` completes or is dropped.
+    fn track_peek(
+        &mut self,
+        conn_id: u32,
+        resp: ExecuteResponse,
+        internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
+    ) -> ExecuteResponse {
+        match resp {
+            ExecuteResponse::SendingRows(rows) => {
+                *self.active_peeks.entry(conn_id).or_default() += 1;
+                let guard = ActivePeekGuard {
+                    conn_id,
+                    internal_cmd_tx: internal_cmd_tx.clone(),
+                };
+                ExecuteResponse::SendingRows(Box::pin(async move {
+                    let _guard = guard;
+                    rows.await
+                }))
+            }
+            ExecuteResponse::StreamingRows(rows) => {
+                *self.active_peeks.entry(conn_id).or_default() += 1;
+                let guard = ActivePeekGuard {
+                    conn_id,
+                    internal_cmd_tx: internal_cmd_tx.clone(),
+                };
+                ExecuteResponse::StreamingRows(Box::pin(rows.map(move |resp| {
+                    let _guard = &guard;
+                    resp
+                })))
+            }
+            ExecuteResponse::CopyTo { format, resp } => ExecuteResponse::CopyTo {
+                format,
+                resp: Box::new(self.track_peek(conn_id, *resp, internal_cmd_tx)),
+            },
+            resp => resp,
+        }
+    }
+
+    /// Verifies that every object that `source` transitively depends upon
+    /// still exists in the catalog.
+    fn check_peek_dependencies(&self, source: &RelationExpr) -> Result<(), anyhow::Error> {
+        let mut seen = HashSet::new();
+        let mut todo: Vec<_> = source
+            .global_uses()
+            .into_iter()
+            .map(|id| (None, id))
+            .collect();
+        while let Some((user, id)) = todo.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            match self.catalog.try_get_by_id(id) {
+                Some(entry) => todo.extend(entry.uses().into_iter().map(|dep| (Some(id), dep))),
+                None => match user {
+                    Some(user) => bail!(
+                        "view {} references dropped object {}",
+                        self.catalog.get_by_id(&user).name(),
+                        id
+                    ),
+                    None => bail!("query references dropped object {}", id),
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Sequences a peek.
+    ///
+    /// If the peek requires a transient dataflow and admission control is
+    /// enabled, the peek is only issued if `permit` is provided or a permit
+    /// can be acquired immediately. Otherwise `PeekOutcome::Throttled` is
+    /// returned and no work is shipped to the dataflow layer. The permit is
+    /// released when the peek's response is dropped, which happens when the
+    /// peek completes or is canceled.
+    ///
+    /// If `max_timestamp_age_ms` is provided, the peek fails if the chosen
+    /// timestamp lags the current time by more than that many milliseconds.
+    ///
+    /// Timestamps with time zones written by `COPY ... TO` a file are
+    /// displayed in `timezone`.
+    ///
+    /// If `stream_rows` is set, each worker's rows are delivered as they
+    /// arrive, rather than buffered, unless the query has an `ORDER BY`,
+    /// `LIMIT`, or `OFFSET`, which can only be applied to the entire result at
+    /// once.
+    ///
+    /// If `source_optimized` is set, `source` is a cached plan that was
+    /// prepared and optimized when it was cached, and is not prepared again.
+    #[allow(clippy::too_many_arguments)]
+    async fn sequence_peek(
+        &mut self,
+        conn_id: u32,
+        mut source: RelationExpr,
+        when: PeekWhen,
+        finishing: RowSetFinishing,
+        mut copy_to: Option<CopyTo>,
+        stream_rows: bool,
+        index_hint: Option<GlobalId>,
+        source_optimized: bool,
+        permit: Option<OwnedSemaphorePermit>,
+        max_timestamp_age_ms: Option<u64>,
+        optimizer_fuel: Option<usize>,
+        timezone: Tz,
+    ) -> Result<PeekOutcome, anyhow::Error> {
+        // Check for dropped dependencies up front, as they otherwise surface
+        // as an opaque failure while determining the timestamp.
+        self.check_peek_dependencies(&source)?;
+
+        // Likewise validate the destination of `COPY ... TO` a file before
+        // any work is shipped to the dataflow layer.
+        if let Some(CopyTo {
+            target: CopyToTarget::File { prefix, .. },
+            ..
+        }) = &mut copy_to
+        {
+            let config = match &self.copy_to_file {
+                Some(config) => config,
+                None => bail!(
+                    "COPY to a file is not enabled; restart materialized with \
+                     --copy-to-file-directory"
+                ),
+            };
+            *prefix = copy::resolve_file_prefix(&config.directory, prefix)?;
+        }
+
+        let timestamp = self.determine_timestamp(&[&source], when)?;
+
+        if let Some(max_age) = max_timestamp_age_ms {
+            let now = self.get_read_ts();
+            let age = now.saturating_sub(timestamp);
+            if age > max_age {
+                bail!(
+                    "query timestamp {} is {}ms behind the current time {}, \
+                     which exceeds max_timestamp_age_ms ({}ms)",
+                    timestamp,
+                    age,
+                    now,
+                    max_age
+                );
+            }
+        }
+
+        // Calls to `mz_sleep` and `pg_sleep` delay the delivery of the peek's
+        // results, below. They are replaced with `NULL` during preparation.
+        let sleep_ms = Self::mz_sleep_duration_ms(&mut source);
+
+        // Cached plans were prepared and optimized when they were cached.
+        let (source, fuel_exhausted) = if source_optimized {
+            (OptimizedRelationExpr::declare_optimized(source), false)
+        } else {
+            self.prep_relation_expr_with_fuel(
+                source,
+                ExprPrepStyle::OneShot {
+                    logical_time: timestamp,
+                },
+                optimizer_fuel,
+            )?
+        };
+
+        // If this optimizes to a constant expression, we can immediately return the result.
+        let (resp, timestamp, path) = if let RelationExpr::Constant { rows, typ: _ } =
+            source.as_ref()
+        {
+            if let Some(index_id) = index_hint {
+                bail!(
+                    "index {} cannot be used: the query does not read from any relation",
+                    self.catalog.get_by_id(&index_id).name()
+                );
+            }
+            let mut results = Vec::new();
+            for &(ref row, count) in rows {
+                assert!(
+                    count >= 0,
+                    "Negative multiplicity in constant result: {}",
+                    count
+                );
+                for _ in 0..count {
+                    results.push(row.clone());
+                }
+            }
+            finishing.finish(&mut results);
+            (send_immediate_rows(results), None, PeekPath::Constant)
+        } else {
+            // Peeks describe a source of data and a timestamp at which to view its contents.
+            //
+            // We need to determine both an appropriate timestamp from the description, and
+            // also to ensure that there is a view in place to query, if the source of data
+            // for the peek is not a base relation.
+
+            // Choose a timestamp for all workers to use in the peek.
+            // We minimize over all participating views, to ensure that the query will not
+            // need to block on the arrival of further input data.
+            let (rows_tx, rows_rx) = self.switchboard.mpsc_limited(self.num_timely_workers);
+
+            // Extract any surrounding linear operators to determine if we can simply read
+            // out the contents from an existing arrangement.
+            let (mut map_filter_project, inner) =
+                expr::MapFilterProject::extract_from_expression(source.as_ref());
+
+            // We can use a fast path approach if our query corresponds to a read out of
+            // an existing materialization. This is the case if the expression is now a
+            // `RelationExpr::Get` and its target is something we have materialized.
+            // Otherwise, we will need to build a new dataflow.
+            let mut fast_path: Option<(_, Option<Row>)> = None;
+            if let RelationExpr::Get {
+                id: Id::Global(id),
+                typ: _,
+            } = inner
+            {
+                // Here we should check for an index whose keys are constrained to literal
+                // values by predicate constraints in `map_filter_project`. If we find such
+                // an index, we can use it with the literal to perform look-ups at workers,
+                // and in principle avoid even contacting all but one worker (future work).
+                if let Some(indexes) = self.catalog.indexes().get(id) {
+                    fast_path = choose_fast_path_index(indexes, &map_filter_project);
+                }
+            }
+
+            // An index hint overrides the automatic choice of index, but only
+            // if the query can be served by reading from that index alone.
+            if let Some(index_id) = index_hint {
+                let exprs = match inner {
+                    RelationExpr::Get {
+                        id: Id::Global(id),
+                        typ: _,
+                    } => self
+                        .catalog
+                        .indexes()
+                        .get(id)
+                        .and_then(|indexes| indexes.iter().find(|(id, _)| *id == index_id))
+                        .map(|(_, exprs)| exprs),
+                    _ => None,
+                };
+                match exprs {
+                    Some(exprs) => {
+                        let literal_row = map_filter_project.literal_constraints(exprs);
+                        fast_path = Some((index_id, literal_row));
+                    }
+                    None => bail!(
+                        "index {} cannot be used: the query cannot be served by reading \
+                         from the index alone",
+                        self.catalog.get_by_id(&index_id).name()
+                    ),
+                }
+            }
+
+            // If the peek reads an unindexed relation, note which of its
+            // columns the peek constrains to literals: an index on those
+            // columns would have allowed a fast-path peek.
+            let slow_path_key = match (inner, &fast_path) {
+                (
+                    RelationExpr::Get {
+                        id: Id::Global(id),
+                        typ: _,
+                    },
+                    None,
+                ) => {
+                    let key: Vec<_> = (0..inner.arity())
+                        .filter(|c| {
+                            map_filter_project
+                                .literal_constraints(&[ScalarExpr::Column(*c)])
+                                .is_some()
+                        })
+                        .collect();
+                    if key.is_empty() {
+                        None
+                    } else {
+                        Some((*id, key))
+                    }
+                }
+                _ => None,
+            };
+
+            // Slow-path peeks are subject to admission control, as each one
+            // builds a new dataflow with its own arrangements.
+            let permit = match (&fast_path, permit, &self.peek_semaphore) {
+                (Some(_), _, _) | (None, _, None) => None,
+                (None, Some(permit), _) => Some(permit),
+                (None, None, Some(semaphore)) => match Arc::clone(semaphore).try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    Err(_) => return Ok(PeekOutcome::Throttled),
+                },
+            };
+
+            // Unpack what we have learned with default values if we found nothing.
+            let (fast_path, index_id, literal_row) = if let Some((id, row)) = fast_path {
+                (true, id, row)
+            } else {
+                (false, self.allocate_transient_id()?, None)
+            };
+
+            // The arrangements of catalog indexes that the peek reads from,
+            // either directly or via the transient dataflow built below.
+            let mut arrangement_ids = vec![];
+            if fast_path {
+                arrangement_ids.push(index_id);
+            }
+
+            if !fast_path {
+                // Slow path. We need to perform some computation, so build
+                // a new transient dataflow that will be dropped after the
+                // peek completes.
+                if let Some((id, key)) = slow_path_key {
+                    self.report_slow_path_peek(id, key).await;
+                }
+                let typ = source.as_ref().typ();
+                map_filter_project = expr::MapFilterProject::new(typ.arity());
+                let key: Vec<_> = (0..typ.arity()).map(ScalarExpr::Column).collect();
+                let view_id = self.allocate_transient_id()?;
+                let mut dataflow = DataflowDesc::new(format!("temp-view-{}", view_id));
+                dataflow.set_as_of(Antichain::from_elem(timestamp));
                 self.dataflow_builder()
                     .import_view_into_dataflow(&view_id, &source, &mut dataflow);
                 dataflow.add_index_to_build(index_id, view_id, typ.clone(), key.clone());
                 dataflow.add_index_export(index_id, view_id, typ, key);
-                self.ship_dataflow(dataflow).await;
+                arrangement_ids.extend(dataflow.index_imports.keys().copied());
+                self.ship_dataflow(dataflow).await?;
             }
 
             broadcast(
@@ -2124,31 +4351,174 @@ where
             if !fast_path {
                 self.drop_indexes(vec![index_id]).await;
             }
-
-            let rows_rx = rows_rx
-                .try_fold(PeekResponse::Rows(vec![]), |memo, resp| {
-                    match (memo, resp) {
-                        (PeekResponse::Rows(mut memo), PeekResponse::Rows(rows)) => {
-                            memo.extend(rows);
-                            future::ok(PeekResponse::Rows(memo))
+            self.peek_arrangements.insert(conn_id, arrangement_ids);
+
+            let stream_rows = stream_rows
+                && finishing.order_by.is_empty()
+                && finishing.limit.is_none()
+                && finishing.offset == 0;
+
+            let resp = if stream_rows {
+                let rows_rx = rows_rx
+                    .map_ok(move |mut resp| {
+                        // The permit is held until the stream is dropped.
+                        let _permit = &permit;
+                        if let PeekResponse::Rows(rows) = &mut resp {
+                            finishing.finish(rows)
                         }
-                        (PeekResponse::Error(e), _) | (_, PeekResponse::Error(e)) => {
-                            future::ok(PeekResponse::Error(e))
+                        resp
+                    })
+                    .err_into();
+                ExecuteResponse::StreamingRows(Box::pin(rows_rx))
+            } else {
+                let rows_rx = rows_rx
+                    .try_fold(PeekResponse::Rows(vec![]), |memo, resp| {
+                        match (memo, resp) {
+                            (PeekResponse::Rows(mut memo), PeekResponse::Rows(rows)) => {
+                                memo.extend(rows);
+                                future::ok(PeekResponse::Rows(memo))
+                            }
+                            // A canceled peek is reported as such even if some
+                            // workers responded before the cancellation, so that
+                            // the client can distinguish it from an error or a
+                            // complete result.
+                            (PeekResponse::Canceled, _) | (_, PeekResponse::Canceled) => {
+                                future::ok(PeekResponse::Canceled)
+                            }
+                            (PeekResponse::Error(e), _) | (_, PeekResponse::Error(e)) => {
+                                future::ok(PeekResponse::Error(e))
+                            }
                         }
-                        (PeekResponse::Canceled, _) | (_, PeekResponse::Canceled) => {
-                            future::ok(PeekResponse::Canceled)
+                    })
+                    .map_ok(move |mut resp| {
+                        drop(permit);
+                        if let PeekResponse::Rows(rows) = &mut resp {
+                            finishing.finish(rows)
                         }
-                    }
-                })
-                .map_ok(move |mut resp| {
-                    if let PeekResponse::Rows(rows) = &mut resp {
-                        finishing.finish(rows)
-                    }
-                    resp
-                })
-                .err_into();
+                        resp
+                    })
+                    .err_into();
+                ExecuteResponse::SendingRows(Box::pin(rows_rx))
+            };
+
+            let path = if fast_path {
+                PeekPath::Fast
+            } else {
+                PeekPath::Slow
+            };
+            (resp, Some(timestamp), path)
+        };
+
+        // The delay is applied to the future that delivers the results, rather
+        // than awaited here, so that the coordinator can continue to serve
+        // other clients while it elapses.
+        let resp = match resp {
+            ExecuteResponse::SendingRows(rows) if sleep_ms > 0 => {
+                let delay = tokio::time::sleep(Duration::from_millis(sleep_ms));
+                ExecuteResponse::SendingRows(Box::pin(delay.then(|()| rows)))
+            }
+            ExecuteResponse::StreamingRows(rows) if sleep_ms > 0 => {
+                let delay = tokio::time::sleep(Duration::from_millis(sleep_ms));
+                ExecuteResponse::StreamingRows(Box::pin(delay.map(|()| rows).flatten_stream()))
+            }
+            resp => resp,
+        };
+
+        let resp = match copy_to {
+            None => resp,
+            Some(CopyTo {
+                format,
+                target: CopyToTarget::Stdout,
+            }) => ExecuteResponse::CopyTo {
+                format,
+                resp: Box::new(resp),
+            },
+            Some(CopyTo {
+                format,
+                target: CopyToTarget::File { prefix, desc },
+            }) => match resp {
+                ExecuteResponse::SendingRows(rows) => {
+                    let max_file_size = self
+                        .copy_to_file
+                        .as_ref()
+                        .expect("COPY to a file validated above")
+                        .max_file_size;
+                    ExecuteResponse::SendingRows(copy::copy_to_files(
+                        rows,
+                        format,
+                        desc,
+                        prefix,
+                        max_file_size,
+                        timezone,
+                    ))
+                }
+                _ => unreachable!("peeks always respond with SendingRows"),
+            },
+        };
+        Ok(PeekOutcome::Issued(resp, timestamp, fuel_exhausted, path))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn sequence_tail(
+        &mut self,
+        session: &Session,
+        source_id: GlobalId,
+        with_snapshot: bool,
+        ts: Option<Timestamp>,
+        until: Option<Timestamp>,
+        copy_to: Option<CopyFormat>,
+        emit_progress: bool,
+        object_columns: usize,
+        on_backpressure: TailBackpressure,
+        limit: Option<TailLimit>,
+        collect: bool,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        self.check_system_object_export("TAIL", source_id)?;
+        let (sink_id, rx) = self
+            .ship_tail(
+                session,
+                source_id,
+                with_snapshot,
+                ts,
+                until,
+                emit_progress,
+                object_columns,
+                on_backpressure,
+                limit,
+            )
+            .await?;
+        let (cancel_tx, cancel_rx) = if collect {
+            let (cancel_tx, cancel_rx) = futures::channel::oneshot::channel();
+            (Some(cancel_tx), Some(cancel_rx))
+        } else {
+            (None, None)
+        };
+        self.active_tails.insert(
+            session.conn_id(),
+            ActiveTail {
+                sink_id,
+                from: source_id,
+                cancel_tx,
+                in_transaction: matches!(session.transaction(), TransactionStatus::InTransaction),
+            },
+        );
 
-            ExecuteResponse::SendingRows(Box::pin(rows_rx))
+        let resp = match cancel_rx {
+            Some(mut cancel_rx) => {
+                // The tail is bounded by `until`, so the receiver is closed
+                // once all of its rows have been produced. Cancellation drops
+                // the dataflow, which closes the receiver early, but only
+                // after reporting the cancellation.
+                let rows = rx.try_concat().map_ok(move |rows| {
+                    if let Ok(Some(())) = cancel_rx.try_recv() {
+                        PeekResponse::Canceled
+                    } else {
+                        PeekResponse::Rows(rows)
+                    }
+                });
+                ExecuteResponse::SendingRows(Box::pin(rows))
+            }
+            None => ExecuteResponse::Tailing { rx },
         };
 
         match copy_to {
@@ -2160,20 +4530,42 @@ where
         }
     }
 
+    /// Ships a dataflow that tails `source_id`, returning the ID of the
+    /// dataflow and the receiver for the rows it produces. If `until` is
+    /// specified, the receiver is closed once all updates before `until` have
+    /// been produced. If `limit` is specified, the receiver is closed once
+    /// that many updates have been produced.
     #[allow(clippy::too_many_arguments)]
-    async fn sequence_tail(
+    async fn ship_tail(
         &mut self,
         session: &Session,
         source_id: GlobalId,
         with_snapshot: bool,
         ts: Option<Timestamp>,
-        copy_to: Option<CopyFormat>,
+        until: Option<Timestamp>,
         emit_progress: bool,
         object_columns: usize,
-    ) -> Result<ExecuteResponse, anyhow::Error> {
+        on_backpressure: TailBackpressure,
+        limit: Option<TailLimit>,
+    ) -> Result<(GlobalId, comm::mpsc::Receiver<Vec<Row>>), anyhow::Error> {
         // Determine the frontier of updates to tail *from*.
         // Updates greater or equal to this frontier will be produced.
         let frontier = self.determine_frontier(ts, source_id)?;
+        // Determine the frontier of updates to tail *until*.
+        // Updates greater or equal to this frontier will not be produced.
+        let until = match until {
+            Some(until) => {
+                if !frontier.less_equal(&until) {
+                    bail!(
+                        "TAIL UNTIL timestamp {} is before the starting timestamp {}",
+                        until,
+                        frontier.elements()[0],
+                    );
+                }
+                Antichain::from_elem(until)
+            }
+            None => Antichain::new(),
+        };
         let sink_name = format!(
             "tail-source-{}",
             self.catalog
@@ -2181,8 +4573,15 @@ where
                 .humanize_id(source_id)
                 .expect("Source id is known to exist in catalog")
         );
+        let key = self
+            .catalog
+            .get_by_id(&source_id)
+            .desc()?
+            .typ()
+            .keys
+            .first()
+            .cloned();
         let sink_id = self.catalog.allocate_id()?;
-        self.active_tails.insert(session.conn_id(), sink_id);
         let (tx, rx) = self.switchboard.mpsc_limited(self.num_timely_workers);
 
         self.ship_dataflow(self.dataflow_builder().build_sink_dataflow(
@@ -2192,33 +4591,102 @@ where
             SinkConnector::Tail(TailSinkConnector {
                 tx,
                 frontier,
+                until,
                 strict: !with_snapshot,
                 emit_progress,
                 object_columns,
+                on_backpressure,
+                key,
+                limit,
             }),
         ))
-        .await;
+        .await?;
+        Ok((sink_id, rx))
+    }
 
-        let resp = ExecuteResponse::Tailing { rx };
+    async fn sequence_listen(
+        &mut self,
+        session: &mut Session,
+        channel: String,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        if !session.is_listening() {
+            // A single `TAIL` of `mz_notifications` serves all of a session's
+            // channels; the session filters out notifications on the others.
+            let (sink_id, rx) = self
+                .ship_tail(
+                    session,
+                    MZ_NOTIFICATIONS.id,
+                    false,
+                    None,
+                    None,
+                    false,
+                    MZ_NOTIFICATIONS.desc.arity(),
+                    TailBackpressure::Block,
+                    None,
+                )
+                .await?;
+            self.listen_tails.insert(session.conn_id(), sink_id);
+            session.start_listening(rx);
+        }
+        session.listen(channel);
+        Ok(ExecuteResponse::Listened)
+    }
 
-        match copy_to {
-            None => Ok(resp),
-            Some(format) => Ok(ExecuteResponse::CopyTo {
-                format,
-                resp: Box::new(resp),
-            }),
+    async fn sequence_unlisten(&mut self, session: &mut Session, channel: Option<&str>) {
+        if !session.unlisten(channel) {
+            if let Some(name) = self.listen_tails.remove(&session.conn_id()) {
+                self.drop_sinks(vec![name]).await;
+            }
         }
     }
 
+    async fn sequence_notify(&mut self, session: &Session, channel: String, payload: String) {
+        let row = Row::pack_slice(&[
+            Datum::String(&channel),
+            Datum::String(&payload),
+            Datum::Int32(session.conn_id() as i32),
+        ]);
+        // Retract the notification at a strictly later timestamp than it was
+        // inserted, so that listeners observe the insertion rather than the
+        // two updates consolidating away, and so that `mz_notifications` does
+        // not grow. Consecutive writes may be assigned the same timestamp, so
+        // the retraction cannot simply be written separately. Local inputs
+        // are never closed beyond the write timestamp, so the later timestamp
+        // is still open.
+        let timestamp = self.get_write_ts();
+        broadcast(
+            &mut self.broadcast_tx,
+            SequencedCommand::Insert {
+                id: MZ_NOTIFICATIONS.id,
+                updates: vec![
+                    Update {
+                        row: row.clone(),
+                        diff: 1,
+                        timestamp,
+                    },
+                    Update {
+                        row,
+                        diff: -1,
+                        timestamp: timestamp + 1,
+                    },
+                ],
+            },
+        )
+        .await;
+    }
+
     /// A policy for determining the timestamp for a peek.
     ///
     /// The result may be `None` in the case that the `when` policy cannot be satisfied,
     /// which is possible due to the restricted validity of traces (each has a `since`
     /// and `upper` frontier, and are only valid after `since` and sure to be available
     /// not after `upper`).
+    ///
+    /// If multiple `sources` are provided, the timestamp is valid for all of
+    /// them, so that they can be read at the same time.
     fn determine_timestamp(
         &mut self,
-        source: &RelationExpr,
+        sources: &[&RelationExpr],
         when: PeekWhen,
     ) -> Result<Timestamp, anyhow::Error> {
         // Each involved trace has a validity interval `[since, upper)`.
@@ -2232,7 +4700,10 @@ where
         // the compacted arrangements we have at hand. It remains unresolved
         // what to do if it cannot be satisfied (perhaps the query should use
         // a larger timestamp and block, perhaps the user should intervene).
-        let uses_ids = &source.global_uses();
+        let uses_ids = &sources
+            .iter()
+            .flat_map(|source| source.global_uses())
+            .collect::<Vec<_>>();
         let (index_ids, indexes_complete) = self.catalog.nearest_indexes(&uses_ids);
 
         // Determine the valid lower bound of times that can produce correct outputs.
@@ -2332,6 +4803,156 @@ where
         }
     }
 
+    /// Checks that the arrangements that `source` would read from can still
+    /// produce correct results at `timestamp`, the pinned timestamp of a
+    /// consistent-snapshot transaction.
+    fn check_snapshot_valid(
+        &self,
+        source: &RelationExpr,
+        timestamp: Timestamp,
+    ) -> Result<(), anyhow::Error> {
+        let (index_ids, _) = self.catalog.nearest_indexes(&source.global_uses());
+        let since = self.indexes.least_valid_since(index_ids.iter().cloned());
+        if !since.less_equal(&timestamp) {
+            bail!(
+                "the transaction's snapshot at timestamp {} is no longer available, \
+                 as its inputs have been compacted past it; \
+                 restart the transaction to read from a newer snapshot",
+                timestamp
+            );
+        }
+        Ok(())
+    }
+
+    /// Executes the `SELECT` statements in `stmts` as the system user, like
+    /// `Command::NoSessionExecute`, all at a single timestamp that is valid for
+    /// every index they read.
+    async fn sequence_execute_batch(
+        &mut self,
+        stmts: Vec<Statement>,
+    ) -> Result<Vec<NoSessionExecuteResponse>, anyhow::Error> {
+        let mut purified = vec![];
+        for stmt in stmts {
+            purified.push(sql::pure::purify(stmt).await?);
+        }
+
+        let catalog = self.catalog.for_system_session();
+        let conn_id = catalog.conn_id();
+        let pcx = PlanContext::default();
+        let mut plans = vec![];
+        for stmt in purified {
+            let desc = describe(&catalog, stmt.clone(), &[], None)?;
+            let plan = sql::plan::plan(&pcx, &catalog, stmt, &Params::empty())?;
+            match &plan {
+                Plan::Peek {
+                    when: PeekWhen::Immediately,
+                    copy_to: None,
+                    ..
+                } => (),
+                Plan::Peek { .. } => {
+                    bail!("queries in a batch cannot use AS OF or COPY ... TO")
+                }
+                Plan::SendRows(_) => (),
+                _ => bail!("unsupported plan"),
+            }
+            plans.push((desc.relation_desc, plan));
+        }
+
+        let sources: Vec<_> = plans
+            .iter()
+            .filter_map(|(_, plan)| match plan {
+                Plan::Peek { source, .. } => Some(source),
+                _ => None,
+            })
+            .collect();
+        let timestamp = self.determine_timestamp(&sources, PeekWhen::Immediately)?;
+        self.check_batch_timestamp(&sources, timestamp)?;
+
+        let mut responses = vec![];
+        for (desc, plan) in plans {
+            let response = match plan {
+                Plan::Peek {
+                    source,
+                    finishing,
+                    index_hint,
+                    ..
+                } => {
+                    // Like `NoSessionExecute`, fail immediately rather than
+                    // waiting for admission.
+                    match self
+                        .sequence_peek(
+                            conn_id,
+                            source,
+                            PeekWhen::AtTimestamp(timestamp),
+                            finishing,
+                            None,
+                            false,
+                            index_hint,
+                            false,
+                            None,
+                            None,
+                            None,
+                            Tz::UTC,
+                        )
+                        .await?
+                    {
+                        PeekOutcome::Issued(resp, _, _, _) => resp,
+                        PeekOutcome::Throttled => {
+                            bail!("too many concurrent queries require temporary dataflows")
+                        }
+                    }
+                }
+                Plan::SendRows(rows) => send_immediate_rows(rows),
+                _ => unreachable!(),
+            };
+            responses.push(NoSessionExecuteResponse { desc, response });
+        }
+        Ok(responses)
+    }
+
+    /// Checks that every index that `sources` read is complete at `timestamp`,
+    /// which `determine_timestamp` chose for all of them at once.
+    ///
+    /// A single query waits for its inputs to catch up to its timestamp, but a
+    /// batch whose indexes have been compacted past the point that some other
+    /// index has completed has no common timestamp that is immediately
+    /// available, so it fails instead.
+    fn check_batch_timestamp(
+        &self,
+        sources: &[&RelationExpr],
+        timestamp: Timestamp,
+    ) -> Result<(), anyhow::Error> {
+        let uses_ids: Vec<_> = sources
+            .iter()
+            .flat_map(|source| source.global_uses())
+            .collect();
+        if uses_ids.iter().any(|id| self.catalog.uses_tables(*id)) {
+            // Reads of tables wait for the latest input time to close, as
+            // they do outside of a batch.
+            return Ok(());
+        }
+        let (index_ids, _) = self.catalog.nearest_indexes(&uses_ids);
+        let incomplete = index_ids
+            .iter()
+            .filter(|id| {
+                self.indexes
+                    .upper_of(id)
+                    .expect("id not found")
+                    .less_equal(&timestamp)
+            })
+            .collect::<Vec<_>>();
+        if !incomplete.is_empty() {
+            bail!(
+                "no timestamp is valid for every query in the batch: the inputs \
+                 are compacted up to timestamp {}, but {:?} are not yet complete \
+                 at that timestamp",
+                timestamp,
+                incomplete
+            );
+        }
+        Ok(())
+    }
+
     /// Determine the frontier of updates to start *from*.
     /// Updates greater or equal to this frontier will be produced.
     fn determine_frontier(
@@ -2342,11 +4963,11 @@ where
         let frontier = if let Some(ts) = as_of {
             // If a timestamp was explicitly requested, use that.
             Antichain::from_elem(self.determine_timestamp(
-                &RelationExpr::Get {
+                &[&RelationExpr::Get {
                     id: Id::Global(source_id),
                     // TODO(justin): find a way to avoid synthesizing an arbitrary relation type.
                     typ: RelationType::empty(),
-                },
+                }],
                 PeekWhen::AtTimestamp(ts),
             )?)
         }
@@ -2372,17 +4993,23 @@ where
         Ok(frontier)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn sequence_explain_plan(
         &mut self,
-        session: &Session,
+        session: &mut Session,
         raw_plan: sql::plan::RelationExpr,
         decorrelated_plan: expr::RelationExpr,
         row_set_finishing: Option<RowSetFinishing>,
         stage: ExplainStage,
         options: ExplainOptions,
+        is_insert: bool,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         let explanation_string = match stage {
             ExplainStage::RawPlan => {
+                let mut raw_plan = raw_plan;
+                if options.redacted {
+                    raw_plan.redact_literals();
+                }
                 let catalog = self.catalog.for_session(session);
                 let mut explanation = sql::plan::Explanation::new(&raw_plan, &catalog);
                 if let Some(row_set_finishing) = row_set_finishing {
@@ -2394,6 +5021,10 @@ where
                 explanation.to_string()
             }
             ExplainStage::DecorrelatedPlan => {
+                let mut decorrelated_plan = decorrelated_plan;
+                if options.redacted {
+                    decorrelated_plan.redact_literals();
+                }
                 let catalog = self.catalog.for_session(session);
                 let mut explanation = expr::explain::Explanation::new(&decorrelated_plan, &catalog);
                 if let Some(row_set_finishing) = row_set_finishing {
@@ -2405,9 +5036,21 @@ where
                 explanation.to_string()
             }
             ExplainStage::OptimizedPlan => {
-                let optimized_plan = self
-                    .prep_relation_expr(decorrelated_plan, ExprPrepStyle::Explain)?
-                    .into_inner();
+                let optimizer_fuel = session.vars().optimizer_fuel();
+                let (optimized_plan, fuel_exhausted) = self.prep_relation_expr_with_fuel(
+                    decorrelated_plan,
+                    ExprPrepStyle::Explain,
+                    optimizer_fuel,
+                )?;
+                if let (Some(fuel), true) = (optimizer_fuel, fuel_exhausted) {
+                    session.add_notice(Notice::OptimizerFuelExhausted(fuel));
+                }
+                let mut optimized_plan = optimized_plan.into_inner();
+                // Literals are only redacted after optimization, so that the
+                // optimizer sees the same plan as it would without redaction.
+                if options.redacted {
+                    optimized_plan.redact_literals();
+                }
                 let catalog = self.catalog.for_session(session);
                 let mut explanation = expr::explain::Explanation::new(&optimized_plan, &catalog);
                 if let Some(row_set_finishing) = row_set_finishing {
@@ -2416,10 +5059,112 @@ where
                 if options.typed {
                     explanation.explain_types();
                 }
-                explanation.to_string()
+                let mut explanation_string = explanation.to_string();
+                // Mirror the check in `sequence_insert`, so that users can see
+                // why an INSERT that references other relations is rejected.
+                if is_insert && !matches!(optimized_plan, RelationExpr::Constant { .. }) {
+                    explanation_string.push_str(
+                        "\nNot constant: INSERT statements cannot reference other relations\n",
+                    );
+                }
+                explanation_string
             }
+            ExplainStage::Arrangements => {
+                unreachable!("EXPLAIN (ARRANGEMENTS) is sequenced separately")
+            }
+        };
+        // Very large explanations are returned as one row per line, so that
+        // they are not packed into a single enormous datum.
+        let rows = if explanation_string.len() > EXPLAIN_SPLIT_THRESHOLD {
+            explanation_string
+                .lines()
+                .map(|line| Row::pack_slice(&[Datum::from(line)]))
+                .collect()
+        } else {
+            vec![Row::pack_slice(&[Datum::from(&*explanation_string)])]
         };
-        let rows = vec![Row::pack_slice(&[Datum::from(&*explanation_string)])];
+        Ok(send_immediate_rows(rows))
+    }
+
+    /// Reports the indexes that the dataflow for `explainee` would import
+    /// and export, without shipping the dataflow.
+    fn sequence_explain_arrangements(
+        &mut self,
+        explainee: ArrangementsExplainee,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        // Names for the objects that the explainee would create, which are
+        // assigned transient IDs, as they are not in the catalog.
+        let mut new_names = HashMap::new();
+        let dataflow = match explainee {
+            ArrangementsExplainee::View {
+                expr,
+                materialized_name,
+            } => {
+                let view_id = self.allocate_transient_id()?;
+                let optimized_expr = self.prep_relation_expr(expr, ExprPrepStyle::Explain)?;
+                let typ = optimized_expr.as_ref().typ();
+                let mut dataflow = DataflowDesc::new("explain".into());
+                self.dataflow_builder().import_view_into_dataflow(
+                    &view_id,
+                    &optimized_expr,
+                    &mut dataflow,
+                );
+                if let Some(name) = materialized_name {
+                    let index_id = self.allocate_transient_id()?;
+                    let keys: Vec<_> = typ
+                        .default_key()
+                        .iter()
+                        .map(|k| ScalarExpr::Column(*k))
+                        .collect();
+                    dataflow.add_index_to_build(index_id, view_id, typ.clone(), keys.clone());
+                    dataflow.add_index_export(index_id, view_id, typ, keys);
+                    let mut index_name = name.clone();
+                    index_name.item += "_primary_idx";
+                    new_names.insert(view_id, name);
+                    new_names.insert(index_id, index_name);
+                }
+                dataflow
+            }
+            ArrangementsExplainee::Index { name, index } => {
+                let index_id = self.allocate_transient_id()?;
+                new_names.insert(index_id, name.clone());
+                self.dataflow_builder().build_index_dataflow_for(
+                    name.to_string(),
+                    index_id,
+                    index.on,
+                    index.keys,
+                )
+            }
+        };
+
+        let name_of = |id: &GlobalId| match new_names.get(id) {
+            Some(name) => name.to_string(),
+            None => self.catalog.get_by_id(id).name().to_string(),
+        };
+        let imports = dataflow
+            .index_imports
+            .iter()
+            .map(|(id, (desc, _))| ("import", id, desc.on_id));
+        let exports = dataflow
+            .index_exports
+            .iter()
+            .map(|(id, desc, _)| ("export", id, desc.on_id));
+        let rows = imports
+            .chain(exports)
+            .map(|(kind, id, on_id)| {
+                let id_string = id.to_string();
+                Row::pack_slice(&[
+                    Datum::String(kind),
+                    if new_names.contains_key(id) {
+                        Datum::Null
+                    } else {
+                        Datum::String(&id_string)
+                    },
+                    Datum::String(&name_of(id)),
+                    Datum::String(&name_of(&on_id)),
+                ])
+            })
+            .collect();
         Ok(send_immediate_rows(rows))
     }
 
@@ -2457,10 +5202,14 @@ where
         &mut self,
         id: GlobalId,
         values: RelationExpr,
+        mut returning: Vec<ScalarExpr>,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         let prep_style = ExprPrepStyle::OneShot {
             logical_time: self.get_write_ts(),
         };
+        for expr in &mut returning {
+            self.prep_scalar_expr(expr, prep_style)?;
+        }
         match self.prep_relation_expr(values, prep_style)?.into_inner() {
             RelationExpr::Constant { rows, typ: _ } => {
                 let desc = self.catalog.get_by_id(&id).desc()?;
@@ -2475,9 +5224,37 @@ where
                     }
                 }
 
+                // Evaluate the RETURNING clause before sending the diffs, so
+                // that an evaluation error does not leave the rows inserted.
+                // The values have already had their defaults filled in.
+                let returned_rows = if returning.is_empty() {
+                    None
+                } else {
+                    let temp_storage = RowArena::new();
+                    let mut returned_rows = vec![];
+                    for (row, diff) in &rows {
+                        let datums = row.unpack();
+                        let returned_row = Row::pack(
+                            returning
+                                .iter()
+                                .map(|expr| expr.eval(&datums, &temp_storage))
+                                .collect::<Result<Vec<_>, _>>()?,
+                        );
+                        for _ in 0..*diff {
+                            returned_rows.push(returned_row.clone());
+                        }
+                    }
+                    Some(returned_rows)
+                };
+
                 let affected_rows = rows.len();
-                self.sequence_send_diffs(id, rows, affected_rows, MutationKind::Insert)
-                    .await
+                let response = self
+                    .sequence_send_diffs(id, rows, affected_rows, MutationKind::Insert)
+                    .await?;
+                Ok(match returned_rows {
+                    None => response,
+                    Some(rows) => send_immediate_rows(rows),
+                })
             }
             // If we couldn't optimize the INSERT statement to a constant, it
             // must depend on another relation. We're not yet sophisticated
@@ -2504,6 +5281,76 @@ where
         }
     }
 
+    async fn sequence_alter_view_rename_column(
+        &mut self,
+        id: Option<GlobalId>,
+        old: String,
+        new: String,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let id = match id {
+            Some(id) => id,
+            // None is generated by `IF EXISTS`
+            None => return Ok(ExecuteResponse::AlteredObject(ObjectType::View)),
+        };
+        let entry = self.catalog.get_by_id(&id);
+        let old_desc = entry.desc()?.clone();
+        let old_indexes: Vec<_> = entry
+            .used_by()
+            .iter()
+            .filter_map(|index_id| {
+                let index_entry = self.catalog.get_by_id(index_id);
+                match index_entry.item() {
+                    CatalogItem::Index(index) => Some((
+                        *index_id,
+                        index_entry.oid(),
+                        index_entry.name().item.clone(),
+                        index.clone(),
+                    )),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        let op = catalog::Op::RenameColumn {
+            id,
+            from: old,
+            to: new,
+        };
+        self.catalog_transact(vec![op]).await?;
+
+        // The catalog does not report updates that leave an item's name
+        // unchanged, so refresh the affected system tables here.
+        let new_desc = self.catalog.get_by_id(&id).desc()?.clone();
+        self.report_column_updates(&old_desc, id, -1).await?;
+        self.report_column_updates(&new_desc, id, 1).await?;
+        for (index_id, oid, name, old_index) in old_indexes {
+            let new_index = match self.catalog.get_by_id(&index_id).item() {
+                CatalogItem::Index(index) => index.clone(),
+                _ => unreachable!(),
+            };
+            self.report_index_update(index_id, oid, &old_index, &name, -1)
+                .await;
+            self.report_index_update(index_id, oid, &new_index, &name, 1)
+                .await;
+        }
+        Ok(ExecuteResponse::AlteredObject(ObjectType::View))
+    }
+
+    async fn sequence_comment(
+        &mut self,
+        id: GlobalId,
+        column_pos: Option<usize>,
+        comment: Option<String>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let op = catalog::Op::Comment {
+            id,
+            column_pos,
+            comment,
+        };
+        self.catalog_transact(vec![op]).await?;
+        Ok(ExecuteResponse::Commented)
+    }
+
     fn sequence_alter_index_logical_compaction_window(
         &mut self,
         alter_index: Option<AlterIndexLogicalCompactionWindow>,
@@ -2524,19 +5371,338 @@ where
             LogicalCompactionWindow::Custom(window) => Some(duration_to_timestamp_millis(window)),
         };
 
-        if let Some(index) = self.indexes.get_mut(&index) {
-            index.set_compaction_window_ms(logical_compaction_window);
-            Ok(ExecuteResponse::AlteredIndexLogicalCompaction)
-        } else {
-            // This can potentially happen if tries to delete the index and also
-            // alter the index concurrently
-            bail!("index {} not found", index.to_string())
+        if let Some(index_state) = self.indexes.get_mut(&index) {
+            index_state.set_compaction_window_ms(logical_compaction_window);
+            // Remember the new window so that it survives the removal of any
+            // override imposed by `ALTER INDEX ALL`.
+            if self.compaction_window_override.is_some() {
+                self.saved_compaction_windows
+                    .insert(index, logical_compaction_window);
+            }
+            Ok(ExecuteResponse::AlteredIndexLogicalCompaction)
+        } else {
+            // This can potentially happen if tries to delete the index and also
+            // alter the index concurrently
+            bail!("index {} not found", index.to_string())
+        }
+    }
+
+    fn sequence_alter_materialized_view_logical_compaction_window(
+        &mut self,
+        alter_view: Option<AlterMaterializedViewLogicalCompactionWindow>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let (view, logical_compaction_window) = match alter_view {
+            Some(AlterMaterializedViewLogicalCompactionWindow {
+                view,
+                logical_compaction_window,
+            }) => (view, logical_compaction_window),
+            // None is generated by `IF EXISTS` or if `retain_history` was not
+            // found in ALTER MATERIALIZED VIEW ... RESET
+            None => return Ok(ExecuteResponse::AlteredObject(ObjectType::View)),
+        };
+        let index = match self.catalog.default_index_for(view) {
+            Some(index) => index,
+            None => bail!(
+                "{} is not materialized",
+                self.catalog.get_by_id(&view).name()
+            ),
+        };
+        self.sequence_alter_index_logical_compaction_window(Some(
+            AlterIndexLogicalCompactionWindow {
+                index,
+                logical_compaction_window,
+            },
+        ))?;
+        Ok(ExecuteResponse::AlteredObject(ObjectType::View))
+    }
+
+    /// Rebuilds every index on the view `id` from scratch, by dropping each
+    /// index's arrangement and shipping a new dataflow that recomputes it from
+    /// the view's inputs. The indexes' catalog entries are untouched.
+    async fn sequence_rematerialize_view(
+        &mut self,
+        id: Option<GlobalId>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        // None is generated by `IF EXISTS`.
+        let id = match id {
+            Some(id) => id,
+            None => return Ok(ExecuteResponse::RematerializedView(0)),
+        };
+        let entry = self.catalog.get_by_id(&id);
+        let indexes: Vec<_> = self.catalog.indexes()[&id]
+            .iter()
+            .map(|(id, _keys)| *id)
+            .collect();
+        if indexes.is_empty() {
+            bail!("{} is not materialized", entry.name());
+        }
+        // Dataflows that import the view's arrangements would not observe the
+        // rebuilt ones.
+        if let Some(dependent) = entry.used_by().iter().find(|id| !indexes.contains(id)) {
+            bail!(
+                "cannot rematerialize {}: {} depends on it",
+                entry.name(),
+                self.catalog.get_by_id(dependent).name()
+            );
+        }
+
+        self.rebuild_indexes(&indexes).await?;
+        Ok(ExecuteResponse::RematerializedView(indexes.len()))
+    }
+
+    /// Replaces the keys of an index, then rebuilds its arrangement from
+    /// scratch with the new keys. The index keeps its ID, name, and
+    /// compaction window.
+    async fn sequence_alter_index_keys(
+        &mut self,
+        pcx: PlanContext,
+        alter: Option<AlterIndexKeys>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        // None is generated by `IF EXISTS`.
+        let AlterIndexKeys {
+            id,
+            mut new_keys,
+            create_sql,
+            func_uses,
+        } = match alter {
+            Some(alter) => alter,
+            None => return Ok(ExecuteResponse::AlteredObject(ObjectType::Index)),
+        };
+        for key in &mut new_keys {
+            self.prep_scalar_expr(key, ExprPrepStyle::Static)?;
+        }
+        let on = match self.catalog.get_by_id(&id).item() {
+            CatalogItem::Index(index) => index.on,
+            _ => unreachable!("planner only alters the keys of indexes"),
+        };
+        // Dataflows that import the relation's arrangements would not observe
+        // the rebuilt one.
+        let on_entry = self.catalog.get_by_id(&on);
+        let indexes: Vec<_> = self.catalog.indexes()[&on]
+            .iter()
+            .map(|(id, _keys)| *id)
+            .collect();
+        if let Some(dependent) = on_entry.used_by().iter().find(|id| !indexes.contains(id)) {
+            bail!(
+                "cannot alter the keys of {}: {} depends on {}",
+                self.catalog.get_by_id(&id).name(),
+                self.catalog.get_by_id(dependent).name(),
+                on_entry.name()
+            );
+        }
+
+        let op = catalog::Op::AlterIndexKeys {
+            id,
+            keys: new_keys,
+            create_sql,
+            plan_cx: pcx,
+            func_uses,
+        };
+        self.catalog_transact(vec![op]).await?;
+        self.rebuild_indexes(&[id]).await?;
+        Ok(ExecuteResponse::AlteredObject(ObjectType::Index))
+    }
+
+    /// Rebuilds the arrangements of `indexes` from scratch, by dropping each
+    /// arrangement and shipping a new dataflow that recomputes it from the
+    /// indexed relation's inputs. The rebuilt arrangements retain the
+    /// compaction windows of the ones they replace.
+    async fn rebuild_indexes(&mut self, indexes: &[GlobalId]) -> Result<(), anyhow::Error> {
+        let windows: Vec<_> = indexes
+            .iter()
+            .map(|id| {
+                (
+                    self.indexes.get(id).map(|state| state.compaction_window_ms),
+                    self.saved_compaction_windows.get(id).copied(),
+                )
+            })
+            .collect();
+        self.drop_indexes(indexes.to_vec()).await;
+        for (id, (window, saved_window)) in indexes.iter().zip(windows) {
+            let dataflow = self.dataflow_builder().build_index_dataflow(*id);
+            self.ship_dataflow(dataflow).await?;
+            if let (Some(window), Some(state)) = (window, self.indexes.get_mut(id)) {
+                state.set_compaction_window_ms(window);
+            }
+            match saved_window {
+                Some(saved_window) => self.saved_compaction_windows.insert(*id, saved_window),
+                None => self.saved_compaction_windows.remove(id),
+            };
+        }
+        Ok(())
+    }
+
+    fn sequence_alter_all_indexes_compaction(
+        &mut self,
+        window: Option<LogicalCompactionWindow>,
+    ) -> ExecuteResponse {
+        let window = match window {
+            Some(LogicalCompactionWindow::Default) => {
+                // Remove the override and restore each index's own window.
+                self.compaction_window_override = None;
+                let mut affected = 0;
+                for (id, window) in self.saved_compaction_windows.drain() {
+                    if let Some(index_state) = self.indexes.get_mut(&id) {
+                        index_state.set_compaction_window_ms(window);
+                        affected += 1;
+                    }
+                }
+                return ExecuteResponse::AlteredAllIndexesLogicalCompaction(affected);
+            }
+            Some(LogicalCompactionWindow::Off) => None,
+            Some(LogicalCompactionWindow::Custom(window)) => {
+                Some(duration_to_timestamp_millis(window))
+            }
+            // None is generated if `logical_compaction_window` was not found
+            // in ALTER INDEX ALL ... RESET.
+            None => return ExecuteResponse::AlteredAllIndexesLogicalCompaction(0),
+        };
+
+        self.compaction_window_override = Some(window);
+        let mut affected = 0;
+        for (id, index_state) in self.indexes.iter_mut() {
+            // Indexes on logging views are installed with their own
+            // compaction window, which the override does not apply to.
+            if BUILTINS.logs().any(|log| log.index_id == *id) {
+                continue;
+            }
+            // Only the window from before the first override is worth
+            // restoring.
+            self.saved_compaction_windows
+                .entry(*id)
+                .or_insert(index_state.compaction_window_ms);
+            index_state.set_compaction_window_ms(window);
+            affected += 1;
         }
+        ExecuteResponse::AlteredAllIndexesLogicalCompaction(affected)
     }
 
     async fn catalog_transact(&mut self, ops: Vec<catalog::Op>) -> Result<(), anyhow::Error> {
-        let events = self.catalog.transact(ops)?;
-        self.process_catalog_events(events).await
+        self.plan_cache.clear();
+        let start = Instant::now();
+        let events = self.catalog.transact(ops);
+        CATALOG_TRANSACTION_DURATIONS
+            .with_label_values(&["mutation"])
+            .observe(start.elapsed().as_secs_f64());
+        let events = events?;
+
+        let start = Instant::now();
+        if self.object_history {
+            self.report_object_history(&events).await;
+        }
+        let result = self.process_catalog_events(events).await;
+        CATALOG_TRANSACTION_DURATIONS
+            .with_label_values(&["broadcast"])
+            .observe(start.elapsed().as_secs_f64());
+        result
+    }
+
+    /// Appends a row to `mz_object_history` for each object that `events`
+    /// create, drop, or rename.
+    ///
+    /// Items that are dropped and recreated with the same ID by a single
+    /// transaction, like sinks whose connectors have been built, are merely
+    /// being replaced, and are not reported.
+    async fn report_object_history(&mut self, events: &[catalog::Event]) {
+        let mut created = HashSet::new();
+        let mut dropped = HashSet::new();
+        for event in events {
+            match event {
+                catalog::Event::CreatedItem { id, .. } => {
+                    created.insert(*id);
+                }
+                catalog::Event::DroppedIndex { entry, .. }
+                | catalog::Event::DroppedItem { entry, .. } => {
+                    dropped.insert(entry.id());
+                }
+                _ => (),
+            }
+        }
+        let replaced: HashSet<_> = created.intersection(&dropped).collect();
+
+        let occurred_at = Utc::now();
+        let mut updates = vec![];
+        for event in events {
+            let (id, object_type, event, name, previous_name) = match event {
+                catalog::Event::CreatedDatabase { name, id, .. } => {
+                    (id.to_string(), "database", "create", name.clone(), None)
+                }
+                catalog::Event::CreatedSchema {
+                    schema_id,
+                    schema_name,
+                    ..
+                } => (
+                    schema_id.to_string(),
+                    "schema",
+                    "create",
+                    schema_name.clone(),
+                    None,
+                ),
+                catalog::Event::CreatedItem { id, name, item, .. } if !replaced.contains(id) => (
+                    id.to_string(),
+                    item.type_string(),
+                    "create",
+                    name.to_string(),
+                    None,
+                ),
+                catalog::Event::DroppedDatabase { name, id, .. } => {
+                    (id.to_string(), "database", "drop", name.clone(), None)
+                }
+                catalog::Event::DroppedSchema {
+                    schema_id,
+                    schema_name,
+                    ..
+                } => (
+                    schema_id.to_string(),
+                    "schema",
+                    "drop",
+                    schema_name.clone(),
+                    None,
+                ),
+                catalog::Event::DroppedIndex { entry, .. }
+                | catalog::Event::DroppedItem { entry, .. }
+                    if !replaced.contains(&entry.id()) =>
+                {
+                    (
+                        entry.id().to_string(),
+                        entry.item().type_string(),
+                        "drop",
+                        entry.name().to_string(),
+                        None,
+                    )
+                }
+                catalog::Event::UpdatedItem {
+                    id,
+                    from_name,
+                    to_name,
+                    item,
+                    ..
+                } => (
+                    id.to_string(),
+                    item.type_string(),
+                    "rename",
+                    to_name.to_string(),
+                    Some(from_name.to_string()),
+                ),
+                _ => continue,
+            };
+            let row = Row::pack_slice(&[
+                Datum::String(&id),
+                Datum::String(object_type),
+                Datum::String(event),
+                Datum::String(&name),
+                match &previous_name {
+                    Some(previous_name) => Datum::String(previous_name),
+                    None => Datum::Null,
+                },
+                Datum::TimestampTz(occurred_at),
+            ]);
+            updates.push((row, 1));
+        }
+        if !updates.is_empty() {
+            self.update_catalog_view(MZ_OBJECT_HISTORY.id, updates)
+                .await;
+        }
     }
 
     async fn process_catalog_events(
@@ -2574,7 +5740,8 @@ where
                     match item {
                         CatalogItem::Index(index) => {
                             self.report_index_update(*id, *oid, &index, &name.item, 1)
-                                .await
+                                .await;
+                            self.forget_slow_path_peeks(index.on).await;
                         }
                         CatalogItem::Table(_) => {
                             self.report_table_update(*id, *oid, *schema_id, &name.item, 1)
@@ -2589,11 +5756,7 @@ where
                                 .await;
                         }
                         CatalogItem::Sink(sink) => {
-                            if let catalog::Sink {
-                                connector: SinkConnectorState::Ready(_),
-                                ..
-                            } = sink
-                            {
+                            if self.is_reported_sink(*id, sink) {
                                 self.report_sink_update(*id, *oid, *schema_id, &name.item, 1)
                                     .await;
                             }
@@ -2602,6 +5765,10 @@ where
                             self.report_type_update(*id, *oid, *schema_id, &name.item, ty, 1)
                                 .await;
                         }
+                        CatalogItem::Func(func) => {
+                            self.report_function_update(*id, *oid, *schema_id, &name.item, func, 1)
+                                .await;
+                        }
                     }
                 }
                 catalog::Event::UpdatedItem {
@@ -2627,11 +5794,7 @@ where
                                 .await;
                         }
                         CatalogItem::Sink(sink) => {
-                            if let catalog::Sink {
-                                connector: SinkConnectorState::Ready(_),
-                                ..
-                            } = sink
-                            {
+                            if self.is_reported_sink(*id, sink) {
                                 self.report_sink_update(*id, *oid, *schema_id, &from_name.item, -1)
                                     .await;
                                 self.report_sink_update(*id, *oid, *schema_id, &to_name.item, 1)
@@ -2663,6 +5826,7 @@ where
                             self.report_type_update(*id, *oid, *schema_id, &to_name.item, &typ, 1)
                                 .await;
                         }
+                        CatalogItem::Func(_) => unreachable!("functions cannot be renamed"),
                     }
                 }
                 catalog::Event::DroppedDatabase { id, oid, name } => {
@@ -2686,6 +5850,7 @@ where
                 catalog::Event::DroppedIndex { entry, nullable } => match entry.item() {
                     CatalogItem::Index(index) => {
                         indexes_to_drop.push(entry.id());
+                        self.report_comment_updates(entry, -1).await;
                         self.report_index_update_inner(
                             entry.id(),
                             entry.oid(),
@@ -2699,6 +5864,8 @@ where
                     _ => unreachable!("DroppedIndex for non-index item"),
                 },
                 catalog::Event::DroppedItem { schema_id, entry } => {
+                    self.forget_slow_path_peeks(entry.id()).await;
+                    self.report_comment_updates(entry, -1).await;
                     match entry.item() {
                         CatalogItem::Table(_) => {
                             sources_to_drop.push(entry.id());
@@ -2737,6 +5904,8 @@ where
                             ..
                         }) => {
                             sinks_to_drop.push(entry.id());
+                            self.forget_sink_status(entry.id()).await;
+                            self.forget_sink_build(entry.id()).await;
                             self.report_sink_update(
                                 entry.id(),
                                 entry.oid(),
@@ -2757,16 +5926,8 @@ where
                                     )
                                     .await;
                                 }
-                                SinkConnector::AvroOcf(AvroOcfSinkConnector { path, .. }) => {
-                                    let row = Row::pack_slice(&[
-                                        Datum::String(entry.id().to_string().as_str()),
-                                        Datum::Bytes(&path.clone().into_os_string().into_vec()),
-                                    ]);
-                                    self.update_catalog_view(
-                                        MZ_AVRO_OCF_SINKS.id,
-                                        iter::once((row, -1)),
-                                    )
-                                    .await;
+                                SinkConnector::AvroOcf(_) => {
+                                    self.forget_avro_ocf_sink_files(entry.id()).await;
                                 }
                                 _ => (),
                             }
@@ -2777,6 +5938,20 @@ where
                         }) => {
                             // If the sink connector state is pending, the sink
                             // dataflow was never created, so nothing to drop.
+                            // The sink may have failed to rebuild during
+                            // bootstrap, in which case it was reported.
+                            if self.sink_statuses.contains_key(&entry.id()) {
+                                self.report_sink_update(
+                                    entry.id(),
+                                    entry.oid(),
+                                    *schema_id,
+                                    &entry.name().item,
+                                    -1,
+                                )
+                                .await;
+                                self.forget_sink_status(entry.id()).await;
+                            }
+                            self.forget_sink_build(entry.id()).await;
                         }
                         CatalogItem::Type(typ) => {
                             self.report_type_update(
@@ -2789,6 +5964,17 @@ where
                             )
                             .await;
                         }
+                        CatalogItem::Func(func) => {
+                            self.report_function_update(
+                                entry.id(),
+                                entry.oid(),
+                                *schema_id,
+                                &entry.name().item,
+                                func,
+                                -1,
+                            )
+                            .await;
+                        }
                         CatalogItem::Index(_) => {
                             unreachable!("dropped indexes should be handled by DroppedIndex");
                         }
@@ -2797,6 +5983,30 @@ where
                         self.report_column_updates(desc, entry.id(), -1).await?;
                     }
                 }
+                catalog::Event::UpdatedIndexKeys {
+                    id,
+                    oid,
+                    name,
+                    from,
+                    to,
+                } => {
+                    self.report_index_update(*id, *oid, from, &name.item, -1)
+                        .await;
+                    self.report_index_update(*id, *oid, to, &name.item, 1).await;
+                }
+                catalog::Event::UpdatedComment {
+                    id,
+                    column_pos,
+                    from,
+                    to,
+                } => {
+                    if let Some(from) = from {
+                        self.report_comment_update(*id, *column_pos, from, -1).await;
+                    }
+                    if let Some(to) = to {
+                        self.report_comment_update(*id, *column_pos, to, 1).await;
+                    }
+                }
                 _ => (),
             }
         }
@@ -2834,9 +6044,36 @@ where
         let mut trace_keys = Vec::new();
         for id in indexes {
             if self.indexes.remove(&id).is_some() {
+                self.saved_compaction_windows.remove(&id);
                 trace_keys.push(id);
             }
         }
+        // Buffered feedback about a dropped arrangement would otherwise be
+        // applied to any arrangement rebuilt under the same ID, as by
+        // `REMATERIALIZE` or `ALTER INDEX ... SET (key = ...)`.
+        self.frontier_uppers_batch.discard(&trace_keys);
+
+        // Peeks that read from a dropped arrangement would otherwise wait
+        // forever for it to reach their timestamps, so fail them first.
+        let mut failed_conn_ids: Vec<_> = self
+            .peek_arrangements
+            .iter()
+            .filter(|(_conn_id, ids)| ids.iter().any(|id| trace_keys.contains(id)))
+            .map(|(conn_id, _ids)| *conn_id)
+            .collect();
+        failed_conn_ids.sort_unstable();
+        for conn_id in failed_conn_ids {
+            self.peek_arrangements.remove(&conn_id);
+            broadcast(
+                &mut self.broadcast_tx,
+                SequencedCommand::CancelPeek {
+                    conn_id,
+                    response: PeekResponse::Error("arrangement dropped during query".into()),
+                },
+            )
+            .await;
+        }
+
         if !trace_keys.is_empty() {
             broadcast(
                 &mut self.broadcast_tx,
@@ -2854,7 +6091,7 @@ where
         mut expr: RelationExpr,
         style: ExprPrepStyle,
     ) -> Result<OptimizedRelationExpr, anyhow::Error> {
-        expr.try_visit_scalars_mut(&mut |s| Self::prep_scalar_expr(s, style))?;
+        expr.try_visit_scalars_mut(&mut |s| self.prep_scalar_expr(s, style))?;
 
         // TODO (wangandi): Is there anything that optimizes to a
         // constant expression that originally contains a global get? Is
@@ -2863,6 +6100,25 @@ where
         Ok(self.optimizer.optimize(expr, self.catalog.indexes())?)
     }
 
+    /// Like `prep_relation_expr`, but applies at most `fuel` optimizer
+    /// transforms, if limited. Also returns whether the fuel ran out.
+    fn prep_relation_expr_with_fuel(
+        &mut self,
+        mut expr: RelationExpr,
+        style: ExprPrepStyle,
+        fuel: Option<usize>,
+    ) -> Result<(OptimizedRelationExpr, bool), anyhow::Error> {
+        match fuel {
+            None => Ok((self.prep_relation_expr(expr, style)?, false)),
+            Some(fuel) => {
+                expr.try_visit_scalars_mut(&mut |s| self.prep_scalar_expr(s, style))?;
+                Ok(self
+                    .optimizer
+                    .optimize_with_fuel(expr, self.catalog.indexes(), fuel)?)
+            }
+        }
+    }
+
     /// Prepares a scalar expression for execution by replacing any placeholders
     /// with their correct values.
     ///
@@ -2874,25 +6130,116 @@ where
     ///   * if `Explain`, calls are replaced with a dummy time.
     ///   * if `Static`, calls trigger an error indicating that static queries
     ///     are not permitted to observe their own timestamps.
-    fn prep_scalar_expr(expr: &mut ScalarExpr, style: ExprPrepStyle) -> Result<(), anyhow::Error> {
-        // Replace calls to `MzLogicalTimestamp` as described above.
+    ///
+    /// Calls to `MzSleep` are replaced with `NULL`, as the sleeping itself is
+    /// arranged by `sequence_peek`. They are not permitted in `Static`
+    /// expressions.
+    ///
+    /// Calls to `MzIndexSince` and `MzIndexUpper` are replaced with the
+    /// current frontiers of the named index. They are not permitted in
+    /// `Static` expressions.
+    ///
+    /// Calls to `MzUptime` are replaced with the time elapsed since the
+    /// coordinator started. They are not permitted in `Static` expressions.
+    fn prep_scalar_expr(
+        &self,
+        expr: &mut ScalarExpr,
+        style: ExprPrepStyle,
+    ) -> Result<(), anyhow::Error> {
+        // Replace calls to `MzLogicalTimestamp` and `MzSleep` as described
+        // above.
         let ts = match style {
             ExprPrepStyle::Explain | ExprPrepStyle::Static => 0, // dummy timestamp
             ExprPrepStyle::OneShot { logical_time } => logical_time,
         };
         let mut observes_ts = false;
-        expr.visit_mut(&mut |e| {
-            if let ScalarExpr::CallNullary(f @ NullaryFunc::MzLogicalTimestamp) = e {
+        let mut sleeps = false;
+        let mut observes_frontiers = false;
+        let mut observes_uptime = false;
+        let mut unknown_index = None;
+        expr.visit_mut(&mut |e| match e {
+            ScalarExpr::CallNullary(f @ NullaryFunc::MzLogicalTimestamp) => {
                 observes_ts = true;
                 *e = ScalarExpr::literal_ok(Datum::from(i128::from(ts)), f.output_type());
             }
+            ScalarExpr::CallNullary(f @ NullaryFunc::MzSleep { .. }) => {
+                sleeps = true;
+                *e = ScalarExpr::literal_null(f.output_type());
+            }
+            ScalarExpr::CallNullary(f @ NullaryFunc::MzUptime) => {
+                observes_uptime = true;
+                let uptime = Interval {
+                    months: 0,
+                    duration: self.start_time.elapsed().as_nanos() as i128,
+                };
+                *e = ScalarExpr::literal_ok(Datum::Interval(uptime), f.output_type());
+            }
+            ScalarExpr::CallNullary(f)
+                if matches!(
+                    f,
+                    NullaryFunc::MzIndexSince { .. } | NullaryFunc::MzIndexUpper { .. }
+                ) =>
+            {
+                observes_frontiers = true;
+                // Timestamps are totally ordered, so a frontier has at most
+                // one element.
+                let frontier = match f {
+                    NullaryFunc::MzIndexSince { id } => (
+                        *id,
+                        self.indexes
+                            .since_of(id)
+                            .map(|since| since.elements().get(0).copied()),
+                    ),
+                    NullaryFunc::MzIndexUpper { id } => (
+                        *id,
+                        self.indexes.upper_of(id).map(|upper| upper.get(0).copied()),
+                    ),
+                    _ => unreachable!(),
+                };
+                let datum = match frontier {
+                    (id, None) => {
+                        unknown_index = Some(id);
+                        Datum::Null
+                    }
+                    (_, Some(None)) => Datum::Null,
+                    (_, Some(Some(ts))) => Datum::from(i128::from(ts)),
+                };
+                *e = ScalarExpr::literal_ok(datum, f.output_type());
+            }
+            _ => (),
         });
         if observes_ts && matches!(style, ExprPrepStyle::Static) {
             bail!("mz_logical_timestamp cannot be used in static queries");
         }
+        if sleeps && matches!(style, ExprPrepStyle::Static) {
+            bail!("mz_sleep and pg_sleep cannot be used in static queries");
+        }
+        if observes_frontiers && matches!(style, ExprPrepStyle::Static) {
+            bail!("mz_index_since and mz_index_upper cannot be used in static queries");
+        }
+        if observes_uptime && matches!(style, ExprPrepStyle::Static) {
+            bail!("mz_uptime cannot be used in static queries");
+        }
+        if let Some(id) = unknown_index {
+            bail!("{} is not the ID of an index", id);
+        }
         Ok(())
     }
 
+    /// Returns the total number of milliseconds that the calls to `MzSleep` in
+    /// `expr` request. Each call sleeps once per query, not once per row.
+    fn mz_sleep_duration_ms(expr: &mut RelationExpr) -> u64 {
+        let mut duration_ms = 0u64;
+        expr.visit_scalars_mut(&mut |s| {
+            s.visit(&mut |e| {
+                if let ScalarExpr::CallNullary(NullaryFunc::MzSleep { duration_ms: d }) = e {
+                    duration_ms = duration_ms.saturating_add(*d);
+                }
+            })
+        });
+        duration_ms
+    }
+
     /// Finalizes a dataflow and then broadcasts it to all workers.
     ///
     /// Finalization includes optimization, but also validation of various
@@ -2902,7 +6249,11 @@ where
     /// In particular, there are requirement on the `as_of` field for the dataflow
     /// and the `since` frontiers of created arrangements, as a function of the `since`
     /// frontiers of dataflow inputs (sources and imported arrangements).
-    async fn ship_dataflow(&mut self, mut dataflow: DataflowDesc) {
+    ///
+    /// Returns an error, without shipping the dataflow or otherwise modifying
+    /// any state, if any of the arrangements the dataflow imports no longer
+    /// exist.
+    async fn ship_dataflow(&mut self, mut dataflow: DataflowDesc) -> Result<(), anyhow::Error> {
         // The identity for `join` is the minimum element.
         let mut since = Antichain::from_elem(Timestamp::minimum());
 
@@ -2915,11 +6266,14 @@ where
 
         // For each imported arrangement, lower bound `since` by its own frontier.
         for (global_id, (_description, _typ)) in dataflow.index_imports.iter() {
-            since.join_assign(
-                self.indexes
-                    .since_of(global_id)
-                    .expect("global id missing at coordinator"),
-            );
+            match self.indexes.since_of(global_id) {
+                Some(index_since) => since.join_assign(index_since),
+                None => bail!(
+                    "cannot build dataflow {}: index {} no longer exists",
+                    dataflow.debug_name,
+                    global_id
+                ),
+            }
         }
 
         // For each produced arrangement, start tracking the arrangement with
@@ -2927,6 +6281,14 @@ where
         for (global_id, _description, _typ) in dataflow.index_exports.iter() {
             let mut frontiers =
                 Frontiers::new(self.num_timely_workers, self.logical_compaction_window_ms);
+            // New indexes are subject to any override imposed by
+            // `ALTER INDEX ALL`, and revert to the default window when it is
+            // removed.
+            if let Some(window) = self.compaction_window_override {
+                self.saved_compaction_windows
+                    .insert(*global_id, frontiers.compaction_window_ms);
+                frontiers.set_compaction_window_ms(window);
+            }
             frontiers.advance_since(&since);
             self.indexes.insert(*global_id, frontiers);
         }
@@ -2940,14 +6302,11 @@ where
                     ]);
                     self.update_catalog_view(MZ_KAFKA_SINKS.id, iter::once((row, 1)))
                         .await;
+                    self.report_sink_status(*id, "running", None).await;
                 }
                 SinkConnector::AvroOcf(AvroOcfSinkConnector { path, .. }) => {
-                    let row = Row::pack_slice(&[
-                        Datum::String(&id.to_string()),
-                        Datum::Bytes(&path.clone().into_os_string().into_vec()),
-                    ]);
-                    self.update_catalog_view(MZ_AVRO_OCF_SINKS.id, iter::once((row, 1)))
-                        .await;
+                    self.report_avro_ocf_sink_file(*id, path.clone()).await;
+                    self.report_sink_status(*id, "running", None).await;
                 }
                 _ => (),
             }
@@ -2990,6 +6349,28 @@ where
             SequencedCommand::CreateDataflows(vec![dataflow]),
         )
         .await;
+        Ok(())
+    }
+
+    /// Ships the dataflow that builds the index `index_id`.
+    ///
+    /// If the dataflow cannot be shipped, the items in `created` are dropped
+    /// from the catalog again, so that the statement that created them has no
+    /// effect.
+    async fn ship_index_dataflow(
+        &mut self,
+        index_id: GlobalId,
+        created: &[GlobalId],
+    ) -> Result<(), anyhow::Error> {
+        let dataflow = self.dataflow_builder().build_index_dataflow(index_id);
+        if let Err(e) = self.ship_dataflow(dataflow).await {
+            let ops = self.catalog.drop_items_ops(created);
+            self.catalog_transact(ops)
+                .await
+                .expect("dropping newly created items cannot fail");
+            return Err(e);
+        }
+        Ok(())
     }
 
     // Tell the cacher to start caching data for `id` if that source
@@ -3044,7 +6425,19 @@ pub async fn serve<C>(
         timestamp: timestamp_config,
         cache: cache_config,
         logical_compaction_window,
+        logical_compaction_hysteresis,
+        feedback_batch_window,
+        peek_admission,
+        max_connections,
+        idle_session_timeout,
+        max_indexes_per_relation,
+        copy_to_file,
+        bootstrap_timeout,
+        timestamp_quantum,
         experimental_mode,
+        default_source_materialization,
+        object_history,
+        skip_failed_sink_rebuilds,
         build_info,
     }: Config<'_, C>,
     // TODO(benesch): Don't pass runtime explicitly when
@@ -3055,6 +6448,9 @@ pub async fn serve<C>(
 where
     C: comm::Connection,
 {
+    let start_time = Instant::now();
+    let started_at = Utc::now();
+
     let mut broadcast_tx = switchboard.broadcast_tx(dataflow::BroadcastToken);
 
     // First, configure the dataflow workers as directed by our configuration.
@@ -3115,6 +6511,7 @@ where
         let (catalog, initial_catalog_events) = Catalog::open(&catalog::Config {
             path: &path,
             experimental_mode: Some(experimental_mode),
+            default_source_materialization,
             enable_logging: logging.is_some(),
             cache_directory: cache_config.map(|c| c.path),
             build_info,
@@ -3125,24 +6522,85 @@ where
             broadcast_tx: switchboard.broadcast_tx(dataflow::BroadcastToken),
             switchboard: switchboard.clone(),
             num_timely_workers,
+            start_time,
+            started_at,
             optimizer: Default::default(),
             catalog,
             symbiosis,
             indexes: ArrangementFrontiers::default(),
-            since_updates: Vec::new(),
+            since_updates: SinceUpdates::default(),
             active_tails: HashMap::new(),
+            listen_tails: HashMap::new(),
+            tail_completions: HashMap::new(),
+            kafka_partition_watermarks: HashMap::new(),
+            slow_path_peeks: HashMap::new(),
+            sink_statuses: HashMap::new(),
+            sink_builds: HashMap::new(),
+            sessions: HashMap::new(),
+            avro_ocf_sink_files: HashMap::new(),
+            parse_cache: ParseCache::new(PARSE_CACHE_CAPACITY),
+            plan_cache: PlanCache::new(PLAN_CACHE_CAPACITY),
+            sources_paused: false,
+            source_registrations: SourceRegistrations::default(),
+            peek_semaphore: peek_admission
+                .as_ref()
+                .map(|config| Arc::new(Semaphore::new(config.max_concurrent))),
+            // If no temporary dataflows are permitted at all, a throttled peek
+            // could never be admitted, so it must not wait.
+            peek_max_wait: peek_admission.and_then(|config| match config.max_concurrent {
+                0 => Some(Duration::from_secs(0)),
+                _ => config.max_wait,
+            }),
+            throttled_peeks: HashMap::new(),
+            peek_uses: HashMap::new(),
+            peek_arrangements: HashMap::new(),
+            active_peeks: HashMap::new(),
+            max_connections,
+            idle_session_timeout,
+            conn_activity: HashMap::new(),
+            idle_terminated_conns: HashSet::new(),
+            max_indexes_per_relation,
+            copy_to_file,
+            object_history,
+            skip_failed_sink_rebuilds,
+            active_conns: HashMap::new(),
             logging_granularity: logging.and_then(|c| c.granularity.as_millis().try_into().ok()),
             timestamp_config,
+            timestamp_quantum_ms: cmp::max(duration_to_timestamp_millis(timestamp_quantum), 1),
             logical_compaction_window_ms: logical_compaction_window
                 .map(duration_to_timestamp_millis),
+            logical_compaction_hysteresis,
+            feedback_batch_window,
+            frontier_uppers_batch: FrontierUppersBatch::default(),
+            compaction_window_override: None,
+            saved_compaction_windows: HashMap::new(),
             cache_tx,
             closed_up_to: 1,
             read_lower_bound: 1,
             last_op_was_read: false,
             need_advance: true,
             transient_id_counter: 1,
+            bootstrap_phase: BootstrapPhase::LoadingCatalog,
         };
-        coord.bootstrap(initial_catalog_events).await?;
+        let bootstrap = coord.bootstrap(initial_catalog_events);
+        match bootstrap_timeout {
+            None => bootstrap.await?,
+            Some(timeout) => match tokio::time::timeout(timeout, bootstrap).await {
+                Ok(res) => res?,
+                Err(_) => {
+                    log::error!(
+                        "bootstrap did not complete within {:?}; stuck while {}",
+                        timeout,
+                        coord.bootstrap_phase
+                    );
+                    bail!(
+                        "bootstrap timed out after {:?} while {}",
+                        timeout,
+                        coord.bootstrap_phase
+                    );
+                }
+            },
+        }
         Ok((coord, cluster_id))
     };
     let (coord, cluster_id) = match coord.await {
@@ -3166,6 +6624,109 @@ where
     ))
 }
 
+/// A step of `Coordinator::bootstrap`.
+#[derive(Debug)]
+enum BootstrapPhase {
+    LoadingCatalog,
+    ValidatingCatalog,
+    /// Beginning to cache the named source.
+    CachingSource(String),
+    /// Shipping the dataflow for the named index.
+    ShippingIndex(String),
+    /// Rebuilding the connector for the named sink.
+    RebuildingSink(String),
+    ProcessingCatalogEvents,
+    RemovingOrphanedItems,
+    AnnouncingLogKeys,
+}
+
+impl fmt::Display for BootstrapPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BootstrapPhase::LoadingCatalog => f.write_str("loading the catalog"),
+            BootstrapPhase::ValidatingCatalog => f.write_str("validating the catalog"),
+            BootstrapPhase::CachingSource(name) => write!(f, "caching source {}", name),
+            BootstrapPhase::ShippingIndex(name) => write!(f, "shipping index {}", name),
+            BootstrapPhase::RebuildingSink(name) => write!(f, "rebuilding sink {}", name),
+            BootstrapPhase::ProcessingCatalogEvents => f.write_str("processing catalog events"),
+            BootstrapPhase::RemovingOrphanedItems => {
+                f.write_str("removing orphaned placeholder items")
+            }
+            BootstrapPhase::AnnouncingLogKeys => f.write_str("announcing log view keys"),
+        }
+    }
+}
+
+/// A point-in-time snapshot of the catalog and of the coordinator state that
+/// accompanies it. See `Coordinator::snapshot_catalog`.
+#[derive(Serialize)]
+struct CatalogSnapshot {
+    /// The catalog, as serialized by [`Catalog::dump`].
+    catalog: serde_json::Value,
+    /// The frontiers of every maintained index, in ascending ID order.
+    indexes: Vec<IndexSnapshot>,
+    /// The IDs of sinks whose connectors are still being built.
+    pending_sinks: Vec<GlobalId>,
+}
+
+/// The frontiers of an index, as recorded in a [`CatalogSnapshot`].
+#[derive(Serialize)]
+struct IndexSnapshot {
+    id: GlobalId,
+    since: Vec<Timestamp>,
+    upper: Vec<Timestamp>,
+    compaction_window_ms: Option<Timestamp>,
+}
+
+/// Notifies the coordinator that a peek's results have been delivered (or
+/// abandoned) when dropped.
+struct ActivePeekGuard {
+    conn_id: u32,
+    internal_cmd_tx: futures::channel::mpsc::UnboundedSender<Message>,
+}
+
+impl Drop for ActivePeekGuard {
+    fn drop(&mut self) {
+        // The coordinator may have shut down already, in which case there is
+        // nobody left to notify.
+        let _ = self.internal_cmd_tx.unbounded_send(Message::PeekComplete {
+            conn_id: self.conn_id,
+        });
+    }
+}
+
+/// The outcome of sequencing a peek.
+enum PeekOutcome {
+    /// The peek was issued, at the given timestamp if the peek was not
+    /// answered by constant folding. Also reports whether the optimizer ran
+    /// out of fuel while planning the peek, and how the peek was answered.
+    Issued(ExecuteResponse, Option<Timestamp>, bool, PeekPath),
+    /// The peek requires a transient dataflow, but the limit on concurrent
+    /// slow-path peeks has been reached.
+    Throttled,
+}
+
+/// How a peek was answered.
+#[derive(Clone, Copy, Debug)]
+enum PeekPath {
+    /// The query optimized to a constant, so no dataflow was consulted.
+    Constant,
+    /// The query was answered by reading from an existing index.
+    Fast,
+    /// The query required a transient dataflow.
+    Slow,
+}
+
+impl fmt::Display for PeekPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PeekPath::Constant => f.write_str("constant"),
+            PeekPath::Fast => f.write_str("fast"),
+            PeekPath::Slow => f.write_str("slow"),
+        }
+    }
+}
+
 /// The styles in which an expression can be prepared.
 #[derive(Clone, Copy, Debug)]
 enum ExprPrepStyle {
@@ -3185,6 +6746,16 @@ async fn broadcast(tx: &mut comm::broadcast::Sender<SequencedCommand>, cmd: Sequ
     tx.send(cmd).await.unwrap();
 }
 
+/// Constructs the row of `mz_avro_ocf_sinks` that records that the Avro OCF
+/// sink `id` has written to `path`.
+fn avro_ocf_sink_file_row(id: GlobalId, path: &Path, active: bool) -> Row {
+    Row::pack_slice(&[
+        Datum::String(&id.to_string()),
+        Datum::Bytes(path.as_os_str().as_bytes()),
+        Datum::from(active),
+    ])
+}
+
 /// Constructs an [`ExecuteResponse`] that that will send some rows to the
 /// client immediately, as opposed to asking the dataflow layer to send along
 /// the rows after some computation.
@@ -3194,11 +6765,113 @@ fn send_immediate_rows(rows: Vec<Row>) -> ExecuteResponse {
     ExecuteResponse::SendingRows(Box::pin(rx.err_into()))
 }
 
+/// Packs the `properties` reported by an `EXPLAIN` into rows of property names
+/// and values.
+fn pack_properties(properties: Vec<(&str, String)>) -> Vec<Row> {
+    properties
+        .into_iter()
+        .map(|(property, value)| Row::pack_slice(&[Datum::String(property), Datum::String(&value)]))
+        .collect()
+}
+
+/// Formats `duration` as a number of milliseconds.
+fn format_ms(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+/// Reports the outcome of an issued peek to `session`, and, if `pin_snapshot`
+/// is set, pins the session's transaction snapshot to the peek's `timestamp`
+/// if the snapshot has yet to be chosen.
+///
+/// Peeks with an explicit `AS OF` must not pin the snapshot, as they do not
+/// read at the timestamp the transaction would otherwise have chosen.
+fn note_issued_peek(
+    session: &mut Session,
+    timestamp: Option<Timestamp>,
+    fuel_exhausted: bool,
+    pin_snapshot: bool,
+) {
+    if let (Some(fuel), true) = (session.vars().optimizer_fuel(), fuel_exhausted) {
+        session.add_notice(Notice::OptimizerFuelExhausted(fuel));
+    }
+    if let Some(timestamp) = timestamp {
+        if session.vars().emit_timestamp_notices() {
+            session.add_notice(Notice::QueryTimestamp(timestamp));
+        }
+        if pin_snapshot && session.transaction_snapshot() == Some(TransactionSnapshot::Unpinned) {
+            session.pin_transaction_snapshot(timestamp);
+        }
+    }
+}
+
+/// Consumes the rows delivered by the peek response `resp` and delivers, in
+/// their place, `properties` extended with the number of rows and how long
+/// after `start` the first and last of them arrived.
+fn explain_analyze_rows(
+    start: Instant,
+    resp: ExecuteResponse,
+    mut properties: Vec<(&'static str, String)>,
+) -> ExecuteResponse {
+    let mut rows = match resp {
+        ExecuteResponse::SendingRows(rows) => rows.into_stream().boxed(),
+        ExecuteResponse::StreamingRows(rows) => rows,
+        _ => unreachable!("peeks always respond with rows"),
+    };
+    ExecuteResponse::SendingRows(Box::pin(async move {
+        let mut count = 0;
+        let mut first_row = None;
+        while let Some(resp) = rows.next().await {
+            match resp? {
+                PeekResponse::Rows(rows) => {
+                    if first_row.is_none() && !rows.is_empty() {
+                        first_row = Some(start.elapsed());
+                    }
+                    count += rows.len();
+                }
+                // Cancellations and errors are reported as they would be
+                // for the query itself.
+                resp => return Ok(resp),
+            }
+        }
+        let total = start.elapsed();
+        properties.push(("rows", count.to_string()));
+        if let Some(first_row) = first_row {
+            properties.push(("time_to_first_row_ms", format_ms(first_row)));
+        }
+        properties.push(("total_time_ms", format_ms(total)));
+        Ok(PeekResponse::Rows(pack_properties(properties)))
+    }))
+}
+
+/// Chooses the index from which a peek that applies `map_filter_project` to
+/// an indexed relation reads, among the relation's `indexes`.
+///
+/// Indexes whose keys are constrained to literals by `map_filter_project` are
+/// preferred, as the literals can be looked up rather than scanning the whole
+/// index, followed by indexes with longer keys. Remaining ties are broken in
+/// favor of the index with the smallest ID, so that the choice is stable
+/// across executions and restarts. Returns the chosen index along with the
+/// literal key to look up, if any.
+fn choose_fast_path_index(
+    indexes: &[(GlobalId, Vec<ScalarExpr>)],
+    map_filter_project: &expr::MapFilterProject,
+) -> Option<(GlobalId, Option<Row>)> {
+    indexes
+        .iter()
+        .map(|(id, exprs)| {
+            let literal_row = map_filter_project.literal_constraints(exprs);
+            (*id, exprs.len(), literal_row)
+        })
+        .max_by_key(|(id, len, literal_row)| (literal_row.is_some(), *len, cmp::Reverse(*id)))
+        .map(|(id, _len, literal_row)| (id, literal_row))
+}
+
 fn auto_generate_primary_idx(
     index_name: String,
     on_name: FullName,
     on_id: GlobalId,
     on_desc: &RelationDesc,
+    conn_id: Option<u32>,
 ) -> catalog::Index {
     let default_key = on_desc.typ().default_key();
 
@@ -3207,6 +6880,8 @@ fn auto_generate_primary_idx(
         plan_cx: PlanContext::default(),
         on: on_id,
         keys: default_key.iter().map(|k| ScalarExpr::Column(*k)).collect(),
+        conn_id,
+        func_uses: vec![],
     }
 }
 
@@ -3277,3 +6952,225 @@ pub fn describe(
         _ => sql::plan::describe(catalog, stmt, param_types),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::time::{Duration, Instant};
+
+    use chrono::Utc;
+    use futures::stream::{StreamExt, TryStreamExt};
+    use tempfile::NamedTempFile;
+    use timely::progress::{Antichain, ChangeBatch};
+    use tokio::net::UnixStream;
+
+    use comm::Switchboard;
+    use dataflow::{SequencedCommand, WorkerFeedback, WorkerFeedbackWithMeta};
+    use expr::{BinaryFunc, GlobalId, MapFilterProject, ScalarExpr};
+    use repr::{Datum, Row, ScalarType, Timestamp};
+
+    use super::arrangement_state::{ArrangementFrontiers, Frontiers};
+    use super::feedback_batch::FrontierUppersBatch;
+    use super::parse_cache::{ParseCache, PARSE_CACHE_CAPACITY};
+    use super::plan_cache::{PlanCache, PLAN_CACHE_CAPACITY};
+    use super::since_updates::SinceUpdates;
+    use super::source_registrations::SourceRegistrations;
+    use super::{broadcast, choose_fast_path_index, BootstrapPhase, Coordinator, Message};
+    use crate::catalog::Catalog;
+    use crate::timestamp::TimestampConfig;
+
+    const WORKERS: usize = 4;
+
+    /// Constructs a coordinator that has no dataflow workers and whose catalog
+    /// is stored in `catalog_file`.
+    fn test_coordinator(
+        switchboard: Switchboard<UnixStream>,
+        catalog_file: &NamedTempFile,
+        feedback_batch_window: Option<Duration>,
+    ) -> Result<Coordinator<UnixStream>, anyhow::Error> {
+        Ok(Coordinator {
+            broadcast_tx: switchboard.broadcast_tx(dataflow::BroadcastToken),
+            switchboard,
+            num_timely_workers: WORKERS,
+            start_time: Instant::now(),
+            started_at: Utc::now(),
+            optimizer: Default::default(),
+            catalog: Catalog::open_debug(catalog_file.path())?,
+            symbiosis: None,
+            indexes: ArrangementFrontiers::default(),
+            since_updates: SinceUpdates::default(),
+            active_tails: HashMap::new(),
+            listen_tails: HashMap::new(),
+            tail_completions: HashMap::new(),
+            kafka_partition_watermarks: HashMap::new(),
+            slow_path_peeks: HashMap::new(),
+            sink_statuses: HashMap::new(),
+            sink_builds: HashMap::new(),
+            sessions: HashMap::new(),
+            avro_ocf_sink_files: HashMap::new(),
+            parse_cache: ParseCache::new(PARSE_CACHE_CAPACITY),
+            plan_cache: PlanCache::new(PLAN_CACHE_CAPACITY),
+            sources_paused: false,
+            source_registrations: SourceRegistrations::default(),
+            peek_semaphore: None,
+            peek_max_wait: None,
+            throttled_peeks: HashMap::new(),
+            peek_uses: HashMap::new(),
+            peek_arrangements: HashMap::new(),
+            active_peeks: HashMap::new(),
+            max_connections: None,
+            idle_session_timeout: None,
+            conn_activity: HashMap::new(),
+            idle_terminated_conns: HashSet::new(),
+            max_indexes_per_relation: usize::max_value(),
+            copy_to_file: None,
+            object_history: false,
+            skip_failed_sink_rebuilds: false,
+            active_conns: HashMap::new(),
+            logging_granularity: None,
+            timestamp_config: TimestampConfig {
+                frequency: Duration::from_millis(10),
+            },
+            timestamp_quantum_ms: 1,
+            logical_compaction_window_ms: Some(1),
+            logical_compaction_hysteresis: 0,
+            feedback_batch_window,
+            frontier_uppers_batch: FrontierUppersBatch::default(),
+            compaction_window_override: None,
+            saved_compaction_windows: HashMap::new(),
+            cache_tx: None,
+            closed_up_to: 1,
+            read_lower_bound: 1,
+            last_op_was_read: false,
+            need_advance: true,
+            transient_id_counter: 1,
+            bootstrap_phase: BootstrapPhase::LoadingCatalog,
+        })
+    }
+
+    /// Generates `rounds` rounds of feedback in which each worker in turn
+    /// reports that its upper frontier for `id` has advanced by one.
+    fn bursty_feedback(id: GlobalId, rounds: u64) -> Vec<WorkerFeedbackWithMeta> {
+        let mut feedback = vec![];
+        for time in 0..rounds {
+            for worker_id in 0..WORKERS {
+                let mut changes = ChangeBatch::new();
+                changes.update(time + 1, 1);
+                changes.update(time, -1);
+                feedback.push(WorkerFeedbackWithMeta {
+                    worker_id,
+                    message: WorkerFeedback::FrontierUppers(vec![(id, changes)]),
+                });
+            }
+        }
+        feedback
+    }
+
+    /// Feeds 100 rounds of feedback for one index to a coordinator, in bursts
+    /// of ten rounds, and returns the number of `AllowCompaction` commands
+    /// that the coordinator broadcast along with the index's final `since`.
+    async fn count_allow_compactions(
+        feedback_batch_window: Option<Duration>,
+    ) -> Result<(usize, Antichain<Timestamp>), anyhow::Error> {
+        let id = GlobalId::User(1);
+        let catalog_file = NamedTempFile::new()?;
+        let switchboard = Switchboard::local()?;
+        let mut broadcast_rx = switchboard.broadcast_rx(dataflow::BroadcastToken);
+        let mut coord = test_coordinator(switchboard, &catalog_file, feedback_batch_window)?;
+        coord.indexes.insert(id, Frontiers::new(WORKERS, Some(1)));
+
+        let (internal_cmd_tx, mut internal_cmd_rx) = futures::channel::mpsc::unbounded();
+        let (ts_tx, _ts_rx) = std::sync::mpsc::channel();
+        for burst in bursty_feedback(id, 100).chunks(10 * WORKERS) {
+            for feedback in burst {
+                coord
+                    .message_worker(feedback.clone(), &internal_cmd_tx, &ts_tx)
+                    .await;
+            }
+            if feedback_batch_window.is_some() {
+                match internal_cmd_rx.next().await {
+                    Some(Message::FlushFrontierUppers) => {
+                        coord.message_flush_frontier_uppers().await
+                    }
+                    _ => panic!("expected the feedback batch window to close"),
+                }
+            }
+        }
+
+        // Mark the end of the coordinator's broadcasts, so that we know when
+        // to stop counting.
+        broadcast(&mut coord.broadcast_tx, SequencedCommand::Shutdown).await;
+        let mut allow_compactions = 0;
+        loop {
+            match broadcast_rx.try_next().await? {
+                Some(SequencedCommand::AllowCompaction(_)) => allow_compactions += 1,
+                Some(SequencedCommand::Shutdown) | None => break,
+                Some(_) => (),
+            }
+        }
+        let since = coord.indexes.get(&id).unwrap().since.clone();
+        Ok((allow_compactions, since))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_feedback_batch_window() -> Result<(), anyhow::Error> {
+        // Without batching, each piece of feedback is applied immediately and
+        // is followed by maintenance, which requests compaction whenever
+        // `since` has advanced: once per round but the first.
+        let (unbatched, unbatched_since) = count_allow_compactions(None).await?;
+        assert_eq!(unbatched, 99);
+
+        // With batching, each burst of feedback is applied at once and
+        // followed by a single maintenance.
+        let (batched, batched_since) =
+            count_allow_compactions(Some(Duration::from_millis(1))).await?;
+        assert_eq!(batched, 10);
+
+        // Batching does not hold back compaction once the batch is applied.
+        assert_eq!(batched_since, unbatched_since);
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_fast_path_index_ties() {
+        let key = vec![ScalarExpr::column(0)];
+        let indexes = vec![
+            (GlobalId::User(1), key.clone()),
+            (GlobalId::User(2), key.clone()),
+        ];
+        let mut reversed = indexes.clone();
+        reversed.reverse();
+
+        // Without any literal constraints, the identical indexes tie, and the
+        // index with the smallest ID is chosen regardless of order.
+        let mfp = MapFilterProject::new(2);
+        for indexes in &[&indexes, &reversed] {
+            assert_eq!(
+                choose_fast_path_index(indexes, &mfp),
+                Some((GlobalId::User(1), None))
+            );
+        }
+
+        // Likewise if both indexes can look up the same literal.
+        let mfp = MapFilterProject::new(2).filter(vec![ScalarExpr::column(0).call_binary(
+            ScalarExpr::literal_ok(Datum::Int32(5), ScalarType::Int32.nullable(false)),
+            BinaryFunc::Eq,
+        )]);
+        for indexes in &[&indexes, &reversed] {
+            assert_eq!(
+                choose_fast_path_index(indexes, &mfp),
+                Some((GlobalId::User(1), Some(Row::pack_slice(&[Datum::Int32(5)]))))
+            );
+        }
+
+        // An index that can look up a literal still beats a lower ID.
+        let indexes = vec![
+            (GlobalId::User(1), vec![ScalarExpr::column(1)]),
+            (GlobalId::User(2), key),
+        ];
+        assert_eq!(
+            choose_fast_path_index(&indexes, &mfp),
+            Some((GlobalId::User(2), Some(Row::pack_slice(&[Datum::Int32(5)]))))
+        );
+    }
+}
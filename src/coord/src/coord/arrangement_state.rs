@@ -12,6 +12,7 @@
 use std::collections::HashMap;
 
 use differential_dataflow::lattice::Lattice;
+use timely::order::PartialOrder;
 use timely::progress::frontier::{Antichain, AntichainRef, MutableAntichain};
 use timely::progress::Timestamp;
 
@@ -46,6 +47,12 @@ impl<T: Timestamp> ArrangementFrontiers<T> {
     pub fn remove(&mut self, id: &GlobalId) -> Option<Frontiers<T>> {
         self.index.remove(id)
     }
+    pub fn iter(&self) -> impl Iterator<Item = (&GlobalId, &Frontiers<T>)> {
+        self.index.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&GlobalId, &mut Frontiers<T>)> {
+        self.index.iter_mut()
+    }
 
     /// The upper frontier of a maintained index, if it exists.
     pub fn upper_of(&self, name: &GlobalId) -> Option<AntichainRef<T>> {
@@ -139,3 +146,90 @@ impl<T: Timestamp> Frontiers<T> {
         self.since.join_assign(frontier);
     }
 }
+
+impl Frontiers<repr::Timestamp> {
+    /// Reports the frontier to which `since` should advance so that it trails
+    /// `upper` by the compaction window, or `None` if `since` should stay put.
+    ///
+    /// To avoid requesting compaction on every movement of a rapidly
+    /// advancing `upper`, `since` is held back until the new frontier leads it
+    /// by at least `hysteresis_pct` percent of the compaction window.
+    pub fn compaction_frontier(&self, hysteresis_pct: u64) -> Option<Antichain<repr::Timestamp>> {
+        let compaction_window_ms = self.compaction_window_ms?;
+        // Decline to compact complete collections. This would have the
+        // effect of making the collection unusable. Instead, we would
+        // prefer to compact collections only when we believe it would
+        // reduce the volume of the collection, but we don't have that
+        // information here.
+        if self.upper.frontier().is_empty() {
+            return None;
+        }
+        let mut compaction_frontier = Antichain::new();
+        for time in self.upper.frontier().iter() {
+            compaction_frontier.insert(
+                compaction_window_ms
+                    * (time.saturating_sub(compaction_window_ms) / compaction_window_ms),
+            );
+        }
+        let min_advance = compaction_window_ms.saturating_mul(hysteresis_pct) / 100;
+        let mut threshold = Antichain::new();
+        for time in self.since.iter() {
+            threshold.insert(time.saturating_add(min_advance));
+        }
+        if self.since == compaction_frontier
+            || !<_ as PartialOrder>::less_equal(&threshold, &compaction_frontier)
+        {
+            return None;
+        }
+        Some(compaction_frontier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frontiers;
+
+    /// Advances the upper frontier of `frontiers` from 0 to `until` one
+    /// millisecond at a time, and counts how often `since` advances.
+    fn count_compactions(
+        frontiers: &mut Frontiers<repr::Timestamp>,
+        until: u64,
+        pct: u64,
+    ) -> usize {
+        let mut compactions = 0;
+        for time in 0..until {
+            frontiers.upper.update_iter(vec![(time + 1, 1), (time, -1)]);
+            if let Some(since) = frontiers.compaction_frontier(pct) {
+                frontiers.advance_since(&since);
+                compactions += 1;
+            }
+        }
+        compactions
+    }
+
+    #[test]
+    fn test_compaction_hysteresis() {
+        let mut frontiers = Frontiers::new(1, Some(1));
+        assert_eq!(count_compactions(&mut frontiers, 1000, 0), 999);
+        assert_eq!(frontiers.since.elements(), &[999]);
+
+        // Holding compaction back until `since` can advance by ten windows
+        // requests compaction a tenth as often.
+        let mut frontiers = Frontiers::new(1, Some(1));
+        assert_eq!(count_compactions(&mut frontiers, 1000, 1000), 99);
+        assert_eq!(frontiers.since.elements(), &[990]);
+
+        // The compaction frontier only ever advances by whole windows, so
+        // hysteresis of less than one window has no effect.
+        let mut frontiers = Frontiers::new(1, Some(10));
+        assert_eq!(count_compactions(&mut frontiers, 1000, 0), 99);
+        let mut frontiers = Frontiers::new(1, Some(10));
+        assert_eq!(count_compactions(&mut frontiers, 1000, 50), 99);
+    }
+
+    #[test]
+    fn test_compaction_disabled() {
+        let mut frontiers = Frontiers::new(1, None);
+        assert_eq!(count_compactions(&mut frontiers, 100, 0), 0);
+    }
+}
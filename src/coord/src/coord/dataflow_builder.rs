@@ -152,12 +152,31 @@ impl<'a> DataflowBuilder<'a> {
             CatalogItem::Index(index) => index,
             _ => unreachable!("cannot create index dataflow on non-index"),
         };
-        let on_entry = self.catalog.get_by_id(&index.on);
+        self.build_index_dataflow_for(
+            index_entry.name().to_string(),
+            id,
+            index.on,
+            index.keys.clone(),
+        )
+    }
+
+    /// Builds a dataflow description for an index with the specified name,
+    /// ID, and keys on the item with ID `on`. Unlike
+    /// [`DataflowBuilder::build_index_dataflow`], the index need not exist in
+    /// the catalog.
+    pub fn build_index_dataflow_for(
+        &self,
+        name: String,
+        id: GlobalId,
+        on: GlobalId,
+        keys: Vec<ScalarExpr>,
+    ) -> DataflowDesc {
+        let on_entry = self.catalog.get_by_id(&on);
         let on_type = on_entry.desc().unwrap().typ().clone();
-        let mut dataflow = DataflowDesc::new(index_entry.name().to_string());
-        self.import_into_dataflow(&index.on, &mut dataflow);
-        dataflow.add_index_to_build(id, index.on.clone(), on_type.clone(), index.keys.clone());
-        dataflow.add_index_export(id, index.on, on_type, index.keys.clone());
+        let mut dataflow = DataflowDesc::new(name);
+        self.import_into_dataflow(&on, &mut dataflow);
+        dataflow.add_index_to_build(id, on, on_type.clone(), keys.clone());
+        dataflow.add_index_export(id, on, on_type, keys);
         dataflow
     }
 
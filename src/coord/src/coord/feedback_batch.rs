@@ -0,0 +1,125 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Batching of frontier feedback from dataflow workers.
+
+use std::collections::BTreeMap;
+use std::mem;
+
+use timely::progress::ChangeBatch;
+
+use expr::GlobalId;
+use repr::Timestamp;
+
+/// Changes to the upper frontiers of arrangements, as reported by
+/// `WorkerFeedback::FrontierUppers`, that have not yet been applied.
+///
+/// Changes to the same arrangement are consolidated as they arrive, so a burst
+/// of feedback from many workers is applied, and any resulting compaction is
+/// requested, all at once.
+#[derive(Debug, Default)]
+pub struct FrontierUppersBatch {
+    changes: BTreeMap<GlobalId, ChangeBatch<Timestamp>>,
+    flush_scheduled: bool,
+}
+
+impl FrontierUppersBatch {
+    /// Buffers the changes in `updates`.
+    ///
+    /// Returns `true` if the batch was not already awaiting a flush, in which
+    /// case the caller is responsible for scheduling one.
+    pub fn push(&mut self, updates: Vec<(GlobalId, ChangeBatch<Timestamp>)>) -> bool {
+        for (id, mut changes) in updates {
+            self.changes
+                .entry(id)
+                .or_insert_with(ChangeBatch::new)
+                .extend(changes.drain());
+        }
+        !mem::replace(&mut self.flush_scheduled, true)
+    }
+
+    /// Discards the buffered changes to the arrangements in `ids`.
+    ///
+    /// Changes to a dropped arrangement must not outlive it, as a new
+    /// arrangement may later be built under the same ID.
+    pub fn discard(&mut self, ids: &[GlobalId]) {
+        for id in ids {
+            self.changes.remove(id);
+        }
+    }
+
+    /// Removes and returns the buffered changes, ordered by arrangement ID.
+    ///
+    /// The next call to `push` will request that a new flush be scheduled.
+    pub fn drain(&mut self) -> Vec<(GlobalId, ChangeBatch<Timestamp>)> {
+        self.flush_scheduled = false;
+        mem::take(&mut self.changes).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use timely::progress::ChangeBatch;
+
+    use expr::GlobalId;
+
+    use super::FrontierUppersBatch;
+
+    const WORKERS: usize = 4;
+
+    /// Generates `rounds` rounds of feedback in which each worker in turn
+    /// reports that its upper frontier has advanced by one.
+    fn bursty_feedback(rounds: u64) -> Vec<Vec<(GlobalId, ChangeBatch<repr::Timestamp>)>> {
+        let mut feedback = vec![];
+        for time in 0..rounds {
+            for _ in 0..WORKERS {
+                let mut changes = ChangeBatch::new();
+                changes.update(time + 1, 1);
+                changes.update(time, -1);
+                feedback.push(vec![(GlobalId::User(1), changes)]);
+            }
+        }
+        feedback
+    }
+
+    #[test]
+    fn test_coalesced_changes() {
+        let mut batch = FrontierUppersBatch::default();
+        let mut feedback = bursty_feedback(2).into_iter();
+        assert!(batch.push(feedback.next().unwrap()));
+        for updates in feedback {
+            assert!(!batch.push(updates));
+        }
+        let mut drained = batch.drain();
+        assert_eq!(drained.len(), 1);
+        let (id, changes) = &mut drained[0];
+        assert_eq!(*id, GlobalId::User(1));
+        assert_eq!(
+            changes.drain().collect::<Vec<_>>(),
+            vec![(0, -(WORKERS as i64)), (2, WORKERS as i64)]
+        );
+        assert!(batch.drain().is_empty());
+        assert!(batch.push(vec![]));
+    }
+
+    #[test]
+    fn test_discard() {
+        let mut batch = FrontierUppersBatch::default();
+        let mut changes = ChangeBatch::new();
+        changes.update(1, 1);
+        batch.push(vec![
+            (GlobalId::User(1), changes.clone()),
+            (GlobalId::User(2), changes),
+        ]);
+        batch.discard(&[GlobalId::User(1)]);
+        let drained = batch.drain();
+        assert_eq!(drained.len(), 1);
+        assert_eq!(drained[0].0, GlobalId::User(2));
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A bounded cache of parsed `create_sql` statements.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::bail;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+
+use sql::ast::Statement;
+
+lazy_static! {
+    static ref PARSE_CACHE_HITS: IntCounter = register_int_counter!(
+        "mz_parse_cache_hits_total",
+        "Count of create_sql statements that were found in the parse cache"
+    )
+    .unwrap();
+    static ref PARSE_CACHE_MISSES: IntCounter = register_int_counter!(
+        "mz_parse_cache_misses_total",
+        "Count of create_sql statements that were parsed because they were not in the parse cache"
+    )
+    .unwrap();
+}
+
+/// The number of statements that the coordinator's [`ParseCache`] retains.
+pub const PARSE_CACHE_CAPACITY: usize = 1024;
+
+/// A least-recently-used cache of the statements parsed from catalog items'
+/// `create_sql`.
+///
+/// Entries are keyed by the `create_sql` text itself, so they never need to be
+/// invalidated, even when an item's `create_sql` is rewritten; the cache is
+/// bounded only to limit its memory usage.
+pub struct ParseCache {
+    capacity: usize,
+    /// The parsed statement for each cached `create_sql`, along with the tick
+    /// at which it was last used.
+    entries: HashMap<String, (u64, Statement)>,
+    /// The cached `create_sql` strings, keyed by the tick at which they were
+    /// last used.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl ParseCache {
+    /// Constructs a cache that retains at most `capacity` statements.
+    pub fn new(capacity: usize) -> ParseCache {
+        assert!(capacity > 0, "parse cache capacity must be positive");
+        ParseCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the single statement in `create_sql`, parsing it only if it is
+    /// not already cached.
+    pub fn parse(&mut self, create_sql: &str) -> Result<Statement, anyhow::Error> {
+        self.tick += 1;
+        if let Some((last_used, stmt)) = self.entries.get_mut(create_sql) {
+            let key = self
+                .recency
+                .remove(&*last_used)
+                .expect("recency out of sync");
+            self.recency.insert(self.tick, key);
+            *last_used = self.tick;
+            PARSE_CACHE_HITS.inc();
+            return Ok(stmt.clone());
+        }
+
+        PARSE_CACHE_MISSES.inc();
+        let mut stmts = sql::parse::parse(create_sql)?;
+        if stmts.len() != 1 {
+            bail!("expected exactly one statement, but got {}", stmts.len());
+        }
+        let stmt = stmts.remove(0);
+
+        if self.entries.len() >= self.capacity {
+            let lru = *self
+                .recency
+                .keys()
+                .next()
+                .expect("full cache has a least recently used entry");
+            let evicted = self.recency.remove(&lru).unwrap();
+            self.entries.remove(&evicted);
+        }
+        self.recency.insert(self.tick, create_sql.into());
+        self.entries
+            .insert(create_sql.into(), (self.tick, stmt.clone()));
+        Ok(stmt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParseCache;
+
+    #[test]
+    fn test_parse_cache_many_indexes() -> Result<(), anyhow::Error> {
+        let create_sqls: Vec<_> = (0..100)
+            .map(|i| format!("CREATE INDEX idx{} ON t (a + {})", i, i))
+            .collect();
+
+        // Bootstrap parses every item once, and then each index is parsed
+        // again when it is reported to `mz_indexes`.
+        let mut cache = ParseCache::new(128);
+        for _ in 0..2 {
+            for create_sql in &create_sqls {
+                cache.parse(create_sql)?;
+            }
+        }
+        assert_eq!(cache.entries.len(), 100);
+        assert_eq!(cache.recency.len(), 100);
+
+        // Once the catalog outgrows the cache, the least recently used
+        // statements are evicted.
+        let mut cache = ParseCache::new(50);
+        for create_sql in &create_sqls {
+            cache.parse(create_sql)?;
+        }
+        assert!(!cache.entries.contains_key(&create_sqls[49]));
+        cache.parse(&create_sqls[50])?;
+        cache.parse(&create_sqls[0])?;
+        assert!(cache.entries.contains_key(&create_sqls[0]));
+        assert!(cache.entries.contains_key(&create_sqls[50]));
+        assert!(!cache.entries.contains_key(&create_sqls[51]));
+        assert_eq!(cache.entries.len(), 50);
+        assert_eq!(cache.recency.len(), 50);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_cache_errors() {
+        // Statements that fail to parse are not cached.
+        let mut cache = ParseCache::new(1);
+        assert!(cache.parse("CREATE INDEX").is_err());
+        assert!(cache.parse("SELECT 1; SELECT 2").is_err());
+        assert!(cache.parse("CREATE INDEX").is_err());
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A bounded cache of the plans of ad-hoc queries.
+
+use std::collections::{BTreeMap, HashMap};
+
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+
+use expr::{GlobalId, RelationExpr, RowSetFinishing};
+use sql::ast::visit::Visit;
+use sql::ast::{Ident, LockingClause, Statement};
+use sql::plan::{CopyTo, PeekWhen, Plan};
+
+lazy_static! {
+    static ref PLAN_CACHE_HITS: IntCounter = register_int_counter!(
+        "mz_plan_cache_hits_total",
+        "Count of ad-hoc queries whose plan was found in the plan cache"
+    )
+    .unwrap();
+    static ref PLAN_CACHE_MISSES: IntCounter = register_int_counter!(
+        "mz_plan_cache_misses_total",
+        "Count of cacheable ad-hoc queries whose plan was not in the plan cache"
+    )
+    .unwrap();
+}
+
+/// The number of plans that the coordinator's [`PlanCache`] retains.
+pub const PLAN_CACHE_CAPACITY: usize = 1024;
+
+/// Identifies a statement whose plan may be cached.
+///
+/// Planning depends on the session's view of the catalog, and not just on the
+/// catalog itself, so the session's database, search path, and user are part
+/// of the key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PlanCacheKey {
+    /// The normalized text of the statement.
+    pub sql: String,
+    /// The generation of the catalog against which the statement was planned.
+    pub catalog_generation: u64,
+    pub database: String,
+    pub search_path: Vec<String>,
+    pub user: String,
+}
+
+/// The cached plan of a query, whose `source` has already been prepared and
+/// optimized.
+#[derive(Clone, Debug)]
+pub struct CachedPeek {
+    pub source: RelationExpr,
+    pub when: PeekWhen,
+    pub finishing: RowSetFinishing,
+    pub copy_to: Option<CopyTo>,
+    pub index_hint: Option<GlobalId>,
+    pub locking: Option<LockingClause>,
+}
+
+impl From<CachedPeek> for Plan {
+    fn from(peek: CachedPeek) -> Plan {
+        Plan::Peek {
+            source: peek.source,
+            when: peek.when,
+            finishing: peek.finishing,
+            copy_to: peek.copy_to,
+            index_hint: peek.index_hint,
+            locking: peek.locking,
+        }
+    }
+}
+
+/// A least-recently-used cache of the plans of ad-hoc queries.
+///
+/// Entries are keyed by the catalog generation, so they are never valid after
+/// the catalog changes; the coordinator additionally clears the cache whenever
+/// it changes the catalog, so that stale plans do not linger.
+pub struct PlanCache {
+    capacity: usize,
+    /// The cached plan for each key, along with the tick at which it was last
+    /// used.
+    entries: HashMap<PlanCacheKey, (u64, CachedPeek)>,
+    /// The cached keys, keyed by the tick at which they were last used.
+    recency: BTreeMap<u64, PlanCacheKey>,
+    tick: u64,
+}
+
+impl PlanCache {
+    /// Constructs a cache that retains at most `capacity` plans.
+    pub fn new(capacity: usize) -> PlanCache {
+        assert!(capacity > 0, "plan cache capacity must be positive");
+        PlanCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the cached plan for `key`, if any.
+    pub fn get(&mut self, key: &PlanCacheKey) -> Option<CachedPeek> {
+        self.tick += 1;
+        match self.entries.get_mut(key) {
+            Some((last_used, peek)) => {
+                let key = self
+                    .recency
+                    .remove(&*last_used)
+                    .expect("recency out of sync");
+                self.recency.insert(self.tick, key);
+                *last_used = self.tick;
+                PLAN_CACHE_HITS.inc();
+                Some(peek.clone())
+            }
+            None => {
+                PLAN_CACHE_MISSES.inc();
+                None
+            }
+        }
+    }
+
+    /// Caches `peek` as the plan for `key`, evicting the least recently used
+    /// plan if the cache is full.
+    pub fn insert(&mut self, key: PlanCacheKey, peek: CachedPeek) {
+        self.tick += 1;
+        if let Some((last_used, _)) = self.entries.remove(&key) {
+            self.recency.remove(&last_used);
+        } else if self.entries.len() >= self.capacity {
+            let lru = *self
+                .recency
+                .keys()
+                .next()
+                .expect("full cache has a least recently used entry");
+            let evicted = self.recency.remove(&lru).unwrap();
+            self.entries.remove(&evicted);
+        }
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(key, (self.tick, peek));
+    }
+
+    /// Removes all plans from the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+/// Reports whether the plan of `stmt` may be cached.
+///
+/// Only queries are cached. Queries that observe the wall clock are not, as
+/// their plans embed the time at which they were planned. To catch both
+/// `now()` and the bare `current_timestamp`, any identifier with one of those
+/// names disqualifies a query, even if it names a column.
+pub fn is_cacheable(stmt: &Statement) -> bool {
+    struct WallClockFinder {
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for WallClockFinder {
+        fn visit_ident(&mut self, ident: &'ast Ident) {
+            if let "now" | "current_timestamp" = ident.as_str() {
+                self.found = true;
+            }
+        }
+    }
+
+    match stmt {
+        Statement::Select(select) => {
+            let mut finder = WallClockFinder { found: false };
+            finder.visit_select_statement(select);
+            !finder.found
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expr::{RelationExpr, RowSetFinishing};
+    use repr::RelationType;
+    use sql::plan::PeekWhen;
+
+    use super::{is_cacheable, CachedPeek, PlanCache, PlanCacheKey};
+
+    fn key(sql: &str, catalog_generation: u64) -> PlanCacheKey {
+        PlanCacheKey {
+            sql: sql.into(),
+            catalog_generation,
+            database: "materialize".into(),
+            search_path: vec!["public".into()],
+            user: "materialize".into(),
+        }
+    }
+
+    fn peek() -> CachedPeek {
+        CachedPeek {
+            source: RelationExpr::constant(vec![], RelationType::empty()),
+            when: PeekWhen::Immediately,
+            finishing: RowSetFinishing {
+                order_by: vec![],
+                limit: None,
+                offset: 0,
+                project: vec![],
+                after: None,
+            },
+            copy_to: None,
+            index_hint: None,
+            locking: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_cache() {
+        let mut cache = PlanCache::new(2);
+        assert!(cache.get(&key("SELECT 1", 1)).is_none());
+        cache.insert(key("SELECT 1", 1), peek());
+        assert!(cache.get(&key("SELECT 1", 1)).is_some());
+        // Plans are not valid against other generations of the catalog.
+        assert!(cache.get(&key("SELECT 1", 2)).is_none());
+
+        // Once the cache is full, the least recently used plan is evicted.
+        cache.insert(key("SELECT 2", 1), peek());
+        assert!(cache.get(&key("SELECT 1", 1)).is_some());
+        cache.insert(key("SELECT 3", 1), peek());
+        assert!(cache.get(&key("SELECT 1", 1)).is_some());
+        assert!(cache.get(&key("SELECT 2", 1)).is_none());
+        assert!(cache.get(&key("SELECT 3", 1)).is_some());
+
+        cache.clear();
+        assert!(cache.get(&key("SELECT 1", 1)).is_none());
+    }
+
+    #[test]
+    fn test_is_cacheable() -> Result<(), anyhow::Error> {
+        for (sql, cacheable) in &[
+            ("SELECT a FROM t WHERE b = 1", true),
+            ("SELECT now()", false),
+            ("SELECT * FROM t WHERE ts > current_timestamp()", false),
+            ("SELECT * FROM t WHERE ts > current_timestamp", false),
+            ("SELECT mz_logical_timestamp()", true),
+            ("CREATE VIEW v AS SELECT 1", false),
+            ("INSERT INTO t VALUES (1)", false),
+        ] {
+            let stmt = sql::parse::parse(sql)?.remove(0);
+            assert_eq!(is_cacheable(&stmt), *cacheable, "{}", sql);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Pending compaction frontiers for arrangements.
+
+use std::collections::BTreeMap;
+
+use timely::progress::frontier::Antichain;
+
+use expr::GlobalId;
+use repr::Timestamp;
+
+/// The compaction frontiers that have not yet been sent to the dataflow
+/// layer.
+///
+/// Only the most recent frontier for each arrangement is retained, so an
+/// arrangement whose frontier advances many times between drains is only
+/// reported once.
+#[derive(Debug, Default)]
+pub struct SinceUpdates {
+    updates: BTreeMap<GlobalId, Antichain<Timestamp>>,
+}
+
+impl SinceUpdates {
+    /// Records that the arrangement `id` may be compacted up to `since`,
+    /// replacing any pending frontier for the arrangement.
+    pub fn push(&mut self, id: GlobalId, since: Antichain<Timestamp>) {
+        self.updates.insert(id, since);
+    }
+
+    /// Removes and returns the pending frontiers, ordered by arrangement ID.
+    ///
+    /// Empty frontiers are discarded rather than returned.
+    pub fn drain(&mut self) -> Vec<(GlobalId, Antichain<Timestamp>)> {
+        std::mem::take(&mut self.updates)
+            .into_iter()
+            .filter(|(_, since)| since != &Antichain::new())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use timely::progress::frontier::Antichain;
+
+    use expr::GlobalId;
+
+    use super::SinceUpdates;
+
+    #[test]
+    fn test_latest_frontier_per_id() {
+        let mut updates = SinceUpdates::default();
+        updates.push(GlobalId::User(1), Antichain::from_elem(1));
+        updates.push(GlobalId::User(2), Antichain::from_elem(5));
+        updates.push(GlobalId::User(1), Antichain::from_elem(2));
+        updates.push(GlobalId::User(1), Antichain::from_elem(3));
+        assert_eq!(
+            updates.drain(),
+            vec![
+                (GlobalId::User(1), Antichain::from_elem(3)),
+                (GlobalId::User(2), Antichain::from_elem(5)),
+            ]
+        );
+        assert!(updates.drain().is_empty());
+    }
+
+    #[test]
+    fn test_empty_frontier_discarded() {
+        let mut updates = SinceUpdates::default();
+        updates.push(GlobalId::User(1), Antichain::from_elem(1));
+        updates.push(GlobalId::User(1), Antichain::new());
+        updates.push(GlobalId::User(2), Antichain::new());
+        assert!(updates.drain().is_empty());
+    }
+}
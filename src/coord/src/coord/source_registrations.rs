@@ -0,0 +1,108 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Bookkeeping for the source instances registered with the timestamper.
+
+use std::collections::HashSet;
+
+use expr::SourceInstanceId;
+
+/// Tracks which source instances have been registered with the timestamper,
+/// so that each instance is registered at most once, no matter how many
+/// times its creation is reported.
+#[derive(Debug, Default)]
+pub struct SourceRegistrations {
+    /// The source instances that have been registered with the timestamper.
+    registered: HashSet<SourceInstanceId>,
+    /// The source instances that were created while sources were paused.
+    /// They are registered with the timestamper when sources are resumed.
+    deferred: HashSet<SourceInstanceId>,
+}
+
+impl SourceRegistrations {
+    /// Records the creation of the source instance `id`. Returns whether the
+    /// instance should be registered with the timestamper now, which is only
+    /// the case if sources are not `paused` and the instance is not already
+    /// registered or deferred.
+    pub fn create(&mut self, id: SourceInstanceId, paused: bool) -> bool {
+        if self.registered.contains(&id) || self.deferred.contains(&id) {
+            false
+        } else if paused {
+            self.deferred.insert(id);
+            false
+        } else {
+            self.registered.insert(id);
+            true
+        }
+    }
+
+    /// Records that the source instance `id` was dropped. If the instance is
+    /// created again, it will be registered again.
+    pub fn drop(&mut self, id: SourceInstanceId) {
+        self.registered.remove(&id);
+        self.deferred.remove(&id);
+    }
+
+    /// Marks the instances that were deferred while sources were paused as
+    /// registered, returning them so that they can be registered with the
+    /// timestamper.
+    pub fn resume(&mut self) -> Vec<SourceInstanceId> {
+        let ids: Vec<_> = self.deferred.drain().collect();
+        self.registered.extend(ids.iter().copied());
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expr::{GlobalId, SourceInstanceId};
+
+    use super::SourceRegistrations;
+
+    fn instance(dataflow_id: usize) -> SourceInstanceId {
+        SourceInstanceId {
+            source_id: GlobalId::User(1),
+            dataflow_id,
+        }
+    }
+
+    #[test]
+    fn test_duplicate_create() {
+        // Every worker reports the creation of the instance, but the
+        // timestamper must only learn of it once.
+        let mut registrations = SourceRegistrations::default();
+        let registered = (0..4)
+            .filter(|_| registrations.create(instance(1), false))
+            .count();
+        assert_eq!(registered, 1);
+
+        // Once the instance is dropped, it can be registered again.
+        registrations.drop(instance(1));
+        assert!(registrations.create(instance(1), false));
+        assert!(!registrations.create(instance(1), false));
+
+        // Distinct instances of the same source are registered separately.
+        assert!(registrations.create(instance(2), false));
+    }
+
+    #[test]
+    fn test_paused_create() {
+        let mut registrations = SourceRegistrations::default();
+        assert!(!registrations.create(instance(1), true));
+        assert!(!registrations.create(instance(1), true));
+        assert!(!registrations.create(instance(2), true));
+        registrations.drop(instance(2));
+
+        // Only the instances that still exist are registered on resume, and
+        // only once.
+        assert_eq!(registrations.resume(), vec![instance(1)]);
+        assert!(registrations.resume().is_empty());
+        assert!(!registrations.create(instance(1), false));
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Encoding of rows for `COPY ... TO`.
+//!
+//! The encoders in this module are shared by the pgwire layer, which streams
+//! the encoded rows to the client, and by the coordinator, which writes them
+//! to files on the server.
+
+use std::convert::TryFrom;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+use bytes::BytesMut;
+use chrono_tz::Tz;
+use futures::FutureExt;
+
+use dataflow_types::PeekResponse;
+use repr::{Datum, RelationDesc, RelationType, Row};
+use sql::plan::{CopyCsvFormatParams, CopyFormat, CopyNonfiniteFloats};
+
+use crate::command::RowsFuture;
+
+/// Encodes the header that precedes the rows of a `COPY` in `format`, if
+/// any.
+pub fn encode_copy_header(format: &CopyFormat, desc: &RelationDesc, out: &mut Vec<u8>) {
+    match format {
+        CopyFormat::Text => (),
+        CopyFormat::Csv(params) => {
+            if params.header {
+                encode_copy_csv_header(params, desc, out);
+            }
+        }
+        CopyFormat::Binary => {
+            // 11-byte signature.
+            out.extend(b"PGCOPY\n\xFF\r\n\0");
+            // 32-bit flags field.
+            out.extend(&[0, 0, 0, 0]);
+            // 32-bit header extension length field.
+            out.extend(&[0, 0, 0, 0]);
+        }
+    }
+}
+
+/// Encodes the trailer that follows the rows of a `COPY` in `format`, if any.
+pub fn encode_copy_trailer(format: &CopyFormat, out: &mut Vec<u8>) {
+    if let CopyFormat::Binary = format {
+        let trailer: i16 = -1;
+        out.extend(&trailer.to_be_bytes());
+    }
+}
+
+/// Encodes `row` in `format`.
+///
+/// The text-based formats display timestamps with time zones in `timezone`.
+pub fn encode_copy_row(
+    format: &CopyFormat,
+    row: Row,
+    typ: &RelationType,
+    timezone: Tz,
+    out: &mut Vec<u8>,
+) -> Result<(), io::Error> {
+    match format {
+        CopyFormat::Text => encode_copy_row_text(row, typ, timezone, out),
+        CopyFormat::Csv(params) => encode_copy_row_csv(params, row, typ, timezone, out),
+        CopyFormat::Binary => encode_copy_row_binary(row, typ, out),
+    }
+}
+
+pub fn encode_copy_row_binary(
+    row: Row,
+    typ: &RelationType,
+    out: &mut Vec<u8>,
+) -> Result<(), io::Error> {
+    const NULL_BYTES: [u8; 4] = (-1i32).to_be_bytes();
+
+    // 16-bit int of number of tuples.
+    let count = i16::try_from(typ.column_types.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            "column count does not fit into an i16",
+        )
+    })?;
+
+    out.extend(&count.to_be_bytes());
+    let mut buf = BytesMut::new();
+    for (field, typ) in row
+        .iter()
+        .zip(&typ.column_types)
+        .map(|(datum, typ)| (pgrepr::Value::from_datum(datum, &typ.scalar_type), typ))
+    {
+        match field {
+            None => out.extend(&NULL_BYTES),
+            Some(field) => {
+                buf.clear();
+                field.encode_binary(&pgrepr::Type::from(&typ.scalar_type), &mut buf)?;
+                out.extend(
+                    &i32::try_from(buf.len())
+                        .map_err(|_| {
+                            io::Error::new(
+                                io::ErrorKind::Other,
+                                "field length does not fit into an i32",
+                            )
+                        })?
+                        .to_be_bytes(),
+                );
+                out.extend(&buf);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn encode_copy_row_text(
+    row: Row,
+    typ: &RelationType,
+    timezone: Tz,
+    out: &mut Vec<u8>,
+) -> Result<(), io::Error> {
+    let delim = b'\t';
+    let null = b"\\N";
+    let mut buf = BytesMut::new();
+    for (idx, field) in pgrepr::values_from_row(row, typ).into_iter().enumerate() {
+        if idx > 0 {
+            out.push(delim);
+        }
+        match field {
+            None => out.extend(null),
+            Some(field) => {
+                buf.clear();
+                field.encode_text_in_timezone(&mut buf, timezone);
+                for b in &buf {
+                    match b {
+                        b'\\' => out.extend(b"\\\\"),
+                        b'\n' => out.extend(b"\\n"),
+                        b'\r' => out.extend(b"\\r"),
+                        b'\t' => out.extend(b"\\t"),
+                        _ => out.push(*b),
+                    }
+                }
+            }
+        }
+    }
+    out.push(b'\n');
+    Ok(())
+}
+
+pub fn encode_copy_row_csv(
+    params: &CopyCsvFormatParams,
+    row: Row,
+    typ: &RelationType,
+    timezone: Tz,
+    out: &mut Vec<u8>,
+) -> Result<(), io::Error> {
+    let mut buf = BytesMut::new();
+    for (idx, field) in pgrepr::values_from_row(row, typ).into_iter().enumerate() {
+        if idx > 0 {
+            out.push(params.delimiter);
+        }
+        let field = match field {
+            Some(field) if is_nonfinite_float(&field) => match params.nonfinite_floats {
+                CopyNonfiniteFloats::Text => Some(field),
+                CopyNonfiniteFloats::Null => None,
+                CopyNonfiniteFloats::Error => {
+                    buf.clear();
+                    field.encode_text_in_timezone(&mut buf, timezone);
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "COPY cannot represent {} in CSV output; \
+                             set the NONFINITE_FLOATS option to 'text' or 'null'",
+                            String::from_utf8_lossy(&buf)
+                        ),
+                    ));
+                }
+            },
+            field => field,
+        };
+        match field {
+            None => out.extend(params.null.as_bytes()),
+            Some(field) => {
+                buf.clear();
+                field.encode_text_in_timezone(&mut buf, timezone);
+                encode_copy_csv_field(params, &buf, out);
+            }
+        }
+    }
+    out.push(b'\n');
+    Ok(())
+}
+
+/// Reports whether `value` is a floating-point `NaN`, `Infinity`, or
+/// `-Infinity`.
+fn is_nonfinite_float(value: &pgrepr::Value) -> bool {
+    match value {
+        pgrepr::Value::Float4(f) => !f.is_finite(),
+        pgrepr::Value::Float8(f) => !f.is_finite(),
+        _ => false,
+    }
+}
+
+/// Encodes the names of the columns in `desc` as a CSV header line.
+pub fn encode_copy_csv_header(
+    params: &CopyCsvFormatParams,
+    desc: &RelationDesc,
+    out: &mut Vec<u8>,
+) {
+    for (idx, name) in desc.iter_names().enumerate() {
+        if idx > 0 {
+            out.push(params.delimiter);
+        }
+        let name = name.map(|n| n.as_str()).unwrap_or("?column?");
+        encode_copy_csv_field(params, name.as_bytes(), out);
+    }
+    out.push(b'\n');
+}
+
+fn encode_copy_csv_field(params: &CopyCsvFormatParams, field: &[u8], out: &mut Vec<u8>) {
+    // Like PostgreSQL, quote fields that contain special characters, and
+    // fields that would otherwise be indistinguishable from the null string.
+    let needs_quotes = field == params.null.as_bytes()
+        || field.iter().any(|b| {
+            *b == params.delimiter
+                || *b == params.quote
+                || *b == params.escape
+                || *b == b'\r'
+                || *b == b'\n'
+        });
+    if !needs_quotes {
+        out.extend(field);
+        return;
+    }
+    out.push(params.quote);
+    for b in field {
+        if *b == params.quote || *b == params.escape {
+            out.push(params.escape);
+        }
+        out.push(*b);
+    }
+    out.push(params.quote);
+}
+
+/// Resolves the path prefix of a `COPY ... TO` file target against
+/// `directory`, ensuring that the files it names lie within `directory`.
+///
+/// Relative prefixes are interpreted relative to `directory`. The directory
+/// that contains the files must already exist.
+pub fn resolve_file_prefix(directory: &Path, prefix: &Path) -> Result<PathBuf, anyhow::Error> {
+    let directory = directory.canonicalize().map_err(|e| {
+        anyhow!(
+            "unable to resolve COPY directory {}: {}",
+            directory.display(),
+            e
+        )
+    })?;
+    let prefix = directory.join(prefix);
+    let (parent, file_name) = match (prefix.parent(), prefix.file_name()) {
+        (Some(parent), Some(file_name)) => (parent, file_name),
+        _ => bail!("COPY path {} does not name a file", prefix.display()),
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| anyhow!("unable to resolve COPY path {}: {}", prefix.display(), e))?;
+    if !parent.starts_with(&directory) {
+        bail!(
+            "COPY path {} is not within the permitted directory {}",
+            prefix.display(),
+            directory.display()
+        );
+    }
+    Ok(parent.join(file_name))
+}
+
+/// Writes the rows produced by `rows` to a series of files, as
+/// described by [`write_files`].
+///
+/// The files are written by a task on the calling runtime. The returned
+/// future resolves to one row per file written, or to the error or
+/// cancellation that `rows` produced.
+pub fn copy_to_files(
+    rows: RowsFuture,
+    format: CopyFormat,
+    desc: RelationDesc,
+    prefix: PathBuf,
+    max_file_size: u64,
+    timezone: Tz,
+) -> RowsFuture {
+    let task = tokio::spawn(async move {
+        let rows = match rows.await? {
+            PeekResponse::Rows(rows) => rows,
+            resp => return Ok(resp),
+        };
+        let written = tokio::task::spawn_blocking(move || {
+            write_files(&format, &desc, &prefix, max_file_size, timezone, rows)
+        })
+        .await
+        .expect("COPY file writer panicked");
+        Ok(match written {
+            Ok(files) => PeekResponse::Rows(files),
+            Err(e) => PeekResponse::Error(format!("error writing COPY files: {}", e)),
+        })
+    });
+    Box::pin(task.map(|res| res.expect("COPY task panicked")))
+}
+
+/// Writes `rows` in `format` to a series of new files whose names begin with
+/// `prefix`, e.g., `prefix-0001.csv`, `prefix-0002.csv`.
+///
+/// A new file is started whenever the current file reaches `max_file_size`
+/// bytes. Each file is a complete `COPY` output, with its own header and
+/// trailer, and contains at least one row, unless there are no rows at all,
+/// in which case a single file with no rows is written. Existing files are
+/// never overwritten.
+///
+/// Returns one row per file, containing its path, the number of rows it
+/// contains, and its size in bytes.
+pub fn write_files(
+    format: &CopyFormat,
+    desc: &RelationDesc,
+    prefix: &Path,
+    max_file_size: u64,
+    timezone: Tz,
+    rows: Vec<Row>,
+) -> Result<Vec<Row>, io::Error> {
+    let typ = desc.typ();
+    let mut rows = rows.into_iter().peekable();
+    let mut files = vec![];
+    let mut out = vec![];
+    loop {
+        let path = file_path(format, prefix, files.len() + 1);
+        let mut file = BufWriter::new(
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)?,
+        );
+        let mut file_rows: i64 = 0;
+        let mut file_size = 0;
+
+        encode_copy_header(format, desc, &mut out);
+        while file_rows == 0 || file_size < max_file_size {
+            match rows.next() {
+                None => break,
+                Some(row) => encode_copy_row(format, row, typ, timezone, &mut out)?,
+            }
+            file_rows += 1;
+            file_size += write_out(&mut file, &mut out)?;
+        }
+        encode_copy_trailer(format, &mut out);
+        file_size += write_out(&mut file, &mut out)?;
+        file.into_inner()?.sync_all()?;
+
+        files.push(Row::pack_slice(&[
+            Datum::String(&path.display().to_string()),
+            Datum::Int64(file_rows),
+            Datum::Int64(i64::try_from(file_size).unwrap_or(i64::MAX)),
+        ]));
+        if rows.peek().is_none() {
+            return Ok(files);
+        }
+    }
+}
+
+/// Returns the path of the `n`th file written for `prefix`.
+fn file_path(format: &CopyFormat, prefix: &Path, n: usize) -> PathBuf {
+    let extension = match format {
+        CopyFormat::Text => "txt",
+        CopyFormat::Csv(_) => "csv",
+        CopyFormat::Binary => "bin",
+    };
+    let mut path = prefix.as_os_str().to_owned();
+    path.push(format!("-{:04}.{}", n, extension));
+    PathBuf::from(path)
+}
+
+/// Drains `out` into `file`, returning the number of bytes written.
+fn write_out(file: &mut BufWriter<File>, out: &mut Vec<u8>) -> Result<u64, io::Error> {
+    file.write_all(out)?;
+    let len = out.len();
+    out.clear();
+    Ok(u64::try_from(len).expect("usize fits in u64"))
+}
@@ -29,10 +29,17 @@ mod timestamp;
 mod util;
 
 pub mod catalog;
+pub mod copy;
 pub mod session;
 
 pub use crate::cache::CacheConfig;
 pub use crate::client::{Client, SessionClient};
-pub use crate::command::{ExecuteResponse, NoSessionExecuteResponse, StartupMessage};
-pub use crate::coord::{describe, serve, Config, LoggingConfig};
+pub use crate::command::{
+    ConnectionResources, ExecuteResponse, IdleSessionTimeout, NoSessionExecuteResponse,
+    QueryCanceled, StartupMessage, TooManyConnections,
+};
+pub use crate::coord::{
+    describe, serve, Config, CopyToFileConfig, LoggingConfig, PeekAdmissionConfig,
+    DEFAULT_COPY_TO_FILE_MAX_SIZE, DEFAULT_MAX_INDEXES_PER_RELATION,
+};
 pub use crate::timestamp::TimestampConfig;
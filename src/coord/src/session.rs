@@ -11,13 +11,14 @@
 
 #![forbid(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 
 use derivative::Derivative;
-use futures::Stream;
+use futures::{FutureExt, Stream, StreamExt};
 
-use repr::{Datum, Row, ScalarType};
-use sql::ast::Statement;
+use repr::{Datum, Row, ScalarType, Timestamp};
+use sql::ast::{LockingClause, Statement};
 use sql::plan::{Params, StatementDesc};
 
 mod vars;
@@ -33,7 +34,10 @@ pub struct Session {
     prepared_statements: HashMap<String, PreparedStatement>,
     portals: HashMap<String, Portal>,
     transaction: TransactionStatus,
+    snapshot: Option<TransactionSnapshot>,
     vars: Vars,
+    notices: Vec<Notice>,
+    listener: Option<Listener>,
 }
 
 impl Session {
@@ -55,9 +59,12 @@ impl Session {
         Session {
             conn_id,
             transaction: TransactionStatus::Idle,
+            snapshot: None,
             prepared_statements: HashMap::new(),
             portals: HashMap::new(),
             vars: Vars::default(),
+            notices: Vec::new(),
+            listener: None,
         }
     }
 
@@ -67,7 +74,15 @@ impl Session {
     }
 
     /// Starts a transaction.
-    pub fn start_transaction(&mut self) {
+    ///
+    /// If `consistent_snapshot` is set, the transaction is read only and all of
+    /// its queries read at the timestamp chosen for its first query. Starting a
+    /// transaction while already in an explicit transaction does not change
+    /// the existing transaction's snapshot.
+    pub fn start_transaction(&mut self, consistent_snapshot: bool) {
+        if self.transaction != TransactionStatus::InTransaction && consistent_snapshot {
+            self.snapshot = Some(TransactionSnapshot::Unpinned);
+        }
         self.transaction = TransactionStatus::InTransaction;
     }
 
@@ -84,6 +99,7 @@ impl Session {
     /// > An unnamed portal is destroyed at the end of the transaction
     pub fn end_transaction(&mut self) {
         self.transaction = TransactionStatus::Idle;
+        self.snapshot = None;
         self.portals.clear();
     }
 
@@ -97,6 +113,7 @@ impl Session {
             }
             TransactionStatus::InTransactionImplicit => {
                 self.transaction = TransactionStatus::Idle;
+                self.snapshot = None;
             }
             _ => {}
         }
@@ -107,6 +124,23 @@ impl Session {
         &self.transaction
     }
 
+    /// Returns the snapshot of the current transaction, if the transaction
+    /// reads from a consistent snapshot.
+    pub fn transaction_snapshot(&self) -> Option<TransactionSnapshot> {
+        self.snapshot
+    }
+
+    /// Pins the current transaction's snapshot to `timestamp`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current transaction does not read from a consistent
+    /// snapshot, or if its snapshot is already pinned.
+    pub fn pin_transaction_snapshot(&mut self, timestamp: Timestamp) {
+        assert_eq!(self.snapshot, Some(TransactionSnapshot::Unpinned));
+        self.snapshot = Some(TransactionSnapshot::Pinned(timestamp));
+    }
+
     /// Registers the prepared statement under `name`.
     pub fn set_prepared_statement(&mut self, name: String, statement: PreparedStatement) {
         self.prepared_statements.insert(name, statement);
@@ -177,12 +211,19 @@ impl Session {
         self.portals.get_mut(portal_name)
     }
 
+    /// Removes all prepared statements and portals, leaving the rest of the
+    /// session's state intact.
+    pub fn remove_all_prepared_statements(&mut self) {
+        self.prepared_statements.clear();
+        self.portals.clear();
+    }
+
     /// Resets the session to its initial state.
     pub fn reset(&mut self) {
         self.end_transaction();
-        self.prepared_statements.clear();
-        self.portals.clear();
+        self.remove_all_prepared_statements();
         self.vars = Vars::default();
+        self.listener = None;
     }
 
     /// Returns a reference to the variables in this session.
@@ -194,6 +235,158 @@ impl Session {
     pub fn vars_mut(&mut self) -> &mut Vars {
         &mut self.vars
     }
+
+    /// Queues a notice for delivery to the client.
+    pub fn add_notice(&mut self, notice: Notice) {
+        self.notices.push(notice);
+    }
+
+    /// Removes and returns the notices that have not yet been delivered to the
+    /// client.
+    pub fn drain_notices(&mut self) -> Vec<Notice> {
+        std::mem::take(&mut self.notices)
+    }
+
+    /// Reports whether the session is listening on any channel.
+    pub fn is_listening(&self) -> bool {
+        self.listener.is_some()
+    }
+
+    /// Starts receiving notifications from `rx`, which must produce the rows
+    /// of a `TAIL` of `mz_notifications`. The session does not listen on any
+    /// channel until [`Session::listen`] is called.
+    pub fn start_listening(&mut self, rx: comm::mpsc::Receiver<Vec<Row>>) {
+        self.listener = Some(Listener {
+            channels: HashSet::new(),
+            rx,
+        });
+    }
+
+    /// Starts delivering notifications on `channel` to the session.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Session::start_listening`] has not been called.
+    pub fn listen(&mut self, channel: String) {
+        self.listener
+            .as_mut()
+            .expect("session is not receiving notifications")
+            .channels
+            .insert(channel);
+    }
+
+    /// Stops delivering notifications on `channel`, or on all channels if
+    /// `channel` is `None`, to the session. When the session is no longer
+    /// listening on any channel, it stops receiving notifications altogether.
+    ///
+    /// Returns whether the session is still listening on any channel.
+    pub fn unlisten(&mut self, channel: Option<&str>) -> bool {
+        if let (Some(listener), Some(channel)) = (&mut self.listener, channel) {
+            listener.channels.remove(channel);
+            if !listener.channels.is_empty() {
+                return true;
+            }
+        }
+        self.listener = None;
+        false
+    }
+
+    /// Removes and returns the notifications that have arrived for the
+    /// channels the session is listening on, without waiting for more.
+    pub fn drain_notifications(&mut self) -> Vec<Notification> {
+        let mut notifications = vec![];
+        if let Some(listener) = &mut self.listener {
+            while let Some(Some(Ok(rows))) = listener.rx.next().now_or_never() {
+                listener.decode(rows, &mut notifications);
+            }
+        }
+        notifications
+    }
+
+    /// Waits for the next batch of notifications to arrive, then returns the
+    /// ones for the channels the session is listening on, which may be none.
+    ///
+    /// If the session is not listening on any channel, the returned future
+    /// never completes.
+    pub async fn recv_notifications(&mut self) -> Vec<Notification> {
+        let listener = match &mut self.listener {
+            Some(listener) => listener,
+            None => return futures::future::pending().await,
+        };
+        match listener.rx.next().await {
+            Some(Ok(rows)) => {
+                let mut notifications = vec![];
+                listener.decode(rows, &mut notifications);
+                notifications
+            }
+            // The `TAIL` ended, so no more notifications will arrive.
+            Some(Err(_)) | None => futures::future::pending().await,
+        }
+    }
+}
+
+/// The channels a session is listening on, and the `TAIL` of
+/// `mz_notifications` that feeds them.
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Listener {
+    channels: HashSet<String>,
+    #[derivative(Debug = "ignore")]
+    rx: comm::mpsc::Receiver<Vec<Row>>,
+}
+
+impl Listener {
+    /// Decodes the rows produced by the `TAIL`, which have the form
+    /// `(timestamp, diff, channel, payload, conn_id)`, into notifications.
+    /// Only insertions on channels that are being listened on are kept; the
+    /// retraction that follows each notification is ignored.
+    fn decode(&self, rows: Vec<Row>, notifications: &mut Vec<Notification>) {
+        for row in rows {
+            let datums = row.unpack();
+            let diff = datums[1].unwrap_int64();
+            let channel = datums[2].unwrap_str();
+            if diff <= 0 || !self.channels.contains(channel) {
+                continue;
+            }
+            let notification = Notification {
+                conn_id: u32::try_from(datums[4].unwrap_int32()).unwrap_or_default(),
+                channel: channel.to_owned(),
+                payload: datums[3].unwrap_str().to_owned(),
+            };
+            for _ in 0..diff {
+                notifications.push(notification.clone());
+            }
+        }
+    }
+}
+
+/// A notification sent by `NOTIFY`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// The connection ID of the session that sent the notification.
+    pub conn_id: u32,
+    /// The channel on which the notification was sent.
+    pub channel: String,
+    /// The notification's payload.
+    pub payload: String,
+}
+
+/// A notice generated while executing a statement.
+#[derive(Debug)]
+pub enum Notice {
+    /// A row-locking clause was accepted but had no effect.
+    RowLockingIgnored(LockingClause),
+    /// A query executed at the given timestamp.
+    QueryTimestamp(Timestamp),
+    /// The optimizer ran out of the given amount of fuel, so a query was
+    /// planned with the best plan found so far.
+    OptimizerFuelExhausted(usize),
+    /// A query returned `rows` rows whose order-independent checksum is
+    /// `checksum`.
+    ResultChecksum { checksum: u64, rows: usize },
+    /// A `REFRESH MATERIALIZED VIEW` of the named view was accepted but had
+    /// no effect.
+    RefreshIgnored(String),
 }
 
 /// A prepared statement.
@@ -266,3 +459,12 @@ pub enum TransactionStatus {
     /// Currently in a failed transaction.
     Failed,
 }
+
+/// The snapshot from which a consistent-snapshot transaction reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionSnapshot {
+    /// The transaction has not yet read at any timestamp.
+    Unpinned,
+    /// The transaction reads at the specified timestamp.
+    Pinned(Timestamp),
+}
@@ -10,6 +10,12 @@
 use std::borrow::Borrow;
 
 use anyhow::bail;
+use chrono_tz::{Tz, TZ_VARIANTS};
+
+use repr::strconv;
+use sql::plan::ExecuteTimeout;
+
+use crate::catalog::builtin::{MZ_CATALOG_SCHEMA, MZ_TEMP_SCHEMA, PG_CATALOG_SCHEMA};
 
 const APPLICATION_NAME: ServerVar<str> = ServerVar {
     name: unicase::Ascii::new("application_name"),
@@ -36,21 +42,61 @@ const DATE_STYLE: ServerVar<str> = ServerVar {
     description: "Sets the display format for date and time values (PostgreSQL).",
 };
 
+const EMIT_RESULT_CHECKSUMS: ServerVar<bool> = ServerVar {
+    name: unicase::Ascii::new("emit_result_checksums"),
+    value: &false,
+    description:
+        "Reports an order-independent checksum of each query's results as a notice (Materialize).",
+};
+
+const EMIT_TIMESTAMP_NOTICES: ServerVar<bool> = ServerVar {
+    name: unicase::Ascii::new("emit_timestamp_notices"),
+    value: &false,
+    description: "Reports the timestamp at which each query executes as a notice (Materialize).",
+};
+
 const EXTRA_FLOAT_DIGITS: ServerVar<i32> = ServerVar {
     name: unicase::Ascii::new("extra_float_digits"),
     value: &3,
     description: "Adjusts the number of digits displayed for floating-point values (PostgreSQL).",
 };
 
+const FETCH_TIMEOUT: ServerVar<str> = ServerVar {
+    name: unicase::Ascii::new("fetch_timeout"),
+    value: "",
+    description: "Sets how long FETCH waits for rows when the statement does not specify a TIMEOUT; empty waits until at least one row is available (Materialize).",
+};
+
 const INTEGER_DATETIMES: ServerVar<bool> = ServerVar {
     name: unicase::Ascii::new("integer_datetimes"),
     value: &true,
     description: "Reports whether the server uses 64-bit-integer dates and times (PostgreSQL).",
 };
 
-const SEARCH_PATH: ServerVar<[&str]> = ServerVar {
+const MAX_TIMESTAMP_AGE_MS: ServerVar<i32> = ServerVar {
+    name: unicase::Ascii::new("max_timestamp_age_ms"),
+    value: &0,
+    description: "Rejects queries whose timestamp lags the current time by more than this many milliseconds; zero disables the check (Materialize).",
+};
+
+const OPTIMIZER_FUEL: ServerVar<i32> = ServerVar {
+    name: unicase::Ascii::new("optimizer_fuel"),
+    value: &0,
+    description: "Limits the number of transforms the optimizer applies to each query, after which it stops with the plan so far; zero means unlimited (Materialize).",
+};
+
+/// The name of the session user, if the client does not specify one.
+const DEFAULT_USER: &str = "materialize";
+
+const ROLE: ServerVar<str> = ServerVar {
+    name: unicase::Ascii::new("role"),
+    value: "none",
+    description: "Sets the current role, without enforcing any privileges (PostgreSQL).",
+};
+
+const SEARCH_PATH: ServerVar<str> = ServerVar {
     name: unicase::Ascii::new("search_path"),
-    value: &["mz_catalog", "pg_catalog", "public", "mz_temp"],
+    value: "mz_catalog, pg_catalog, public, mz_temp",
     description:
         "Sets the schema search order for names that are not schema-qualified (PostgreSQL).",
 };
@@ -77,6 +123,13 @@ const STANDARD_CONFORMING_STRINGS: ServerVar<bool> = ServerVar {
     description: "Causes '...' strings to treat backslashes literally (PostgreSQL).",
 };
 
+const STRICT_ROW_LOCKING: ServerVar<bool> = ServerVar {
+    name: unicase::Ascii::new("strict_row_locking"),
+    value: &false,
+    description:
+        "Rejects FOR UPDATE and FOR SHARE clauses rather than ignoring them (Materialize).",
+};
+
 const TIMEZONE: ServerVar<str> = ServerVar {
     // TimeZone has nonstandard capitalization for historical reasons.
     name: unicase::Ascii::new("TimeZone"),
@@ -117,14 +170,24 @@ pub struct Vars {
     client_encoding: ServerVar<str>,
     database: SessionVar<str>,
     date_style: ServerVar<str>,
+    emit_result_checksums: SessionVar<bool>,
+    emit_timestamp_notices: SessionVar<bool>,
     extra_float_digits: SessionVar<i32>,
+    fetch_timeout: SessionVar<str>,
     integer_datetimes: ServerVar<bool>,
-    search_path: ServerVar<[&'static str]>,
+    max_timestamp_age_ms: SessionVar<i32>,
+    optimizer_fuel: SessionVar<i32>,
+    role: SessionVar<str>,
+    search_path: SearchPathVar,
     server_version: ServerVar<str>,
     sql_safe_updates: SessionVar<bool>,
     standard_conforming_strings: ServerVar<bool>,
-    timezone: ServerVar<str>,
+    strict_row_locking: SessionVar<bool>,
+    timezone: SessionVar<str>,
     transaction_isolation: ServerVar<str>,
+    /// The name of the user that established the session. This is not a
+    /// configuration parameter, but `role` is validated against it.
+    user: String,
 }
 
 impl Default for Vars {
@@ -134,14 +197,22 @@ impl Default for Vars {
             client_encoding: CLIENT_ENCODING,
             database: SessionVar::new(&DATABASE),
             date_style: DATE_STYLE,
+            emit_result_checksums: SessionVar::new(&EMIT_RESULT_CHECKSUMS),
+            emit_timestamp_notices: SessionVar::new(&EMIT_TIMESTAMP_NOTICES),
             extra_float_digits: SessionVar::new(&EXTRA_FLOAT_DIGITS),
+            fetch_timeout: SessionVar::new(&FETCH_TIMEOUT),
             integer_datetimes: INTEGER_DATETIMES,
-            search_path: SEARCH_PATH,
+            max_timestamp_age_ms: SessionVar::new(&MAX_TIMESTAMP_AGE_MS),
+            optimizer_fuel: SessionVar::new(&OPTIMIZER_FUEL),
+            role: SessionVar::new(&ROLE),
+            search_path: SearchPathVar::new(&SEARCH_PATH),
             server_version: SERVER_VERSION,
             sql_safe_updates: SessionVar::new(&SQL_SAFE_UPDATES),
             standard_conforming_strings: STANDARD_CONFORMING_STRINGS,
-            timezone: TIMEZONE,
+            strict_row_locking: SessionVar::new(&STRICT_ROW_LOCKING),
+            timezone: SessionVar::new(&TIMEZONE),
             transaction_isolation: TRANSACTION_ISOLATION,
+            user: DEFAULT_USER.into(),
         }
     }
 }
@@ -155,12 +226,19 @@ impl Vars {
             &self.client_encoding,
             &self.database,
             &self.date_style,
+            &self.emit_result_checksums,
+            &self.emit_timestamp_notices,
             &self.extra_float_digits,
+            &self.fetch_timeout,
             &self.integer_datetimes,
+            &self.max_timestamp_age_ms,
+            &self.optimizer_fuel,
+            &self.role,
             &self.search_path,
             &self.server_version,
             &self.sql_safe_updates,
             &self.standard_conforming_strings,
+            &self.strict_row_locking,
             &self.timezone,
             &self.transaction_isolation,
         ]
@@ -178,6 +256,7 @@ impl Vars {
             &self.integer_datetimes,
             &self.server_version,
             &self.standard_conforming_strings,
+            &self.timezone,
         ]
         .into_iter()
     }
@@ -201,10 +280,22 @@ impl Vars {
             Ok(&self.database)
         } else if name == DATE_STYLE.name {
             Ok(&self.date_style)
+        } else if name == EMIT_RESULT_CHECKSUMS.name {
+            Ok(&self.emit_result_checksums)
+        } else if name == EMIT_TIMESTAMP_NOTICES.name {
+            Ok(&self.emit_timestamp_notices)
         } else if name == EXTRA_FLOAT_DIGITS.name {
             Ok(&self.extra_float_digits)
+        } else if name == FETCH_TIMEOUT.name {
+            Ok(&self.fetch_timeout)
         } else if name == INTEGER_DATETIMES.name {
             Ok(&self.integer_datetimes)
+        } else if name == MAX_TIMESTAMP_AGE_MS.name {
+            Ok(&self.max_timestamp_age_ms)
+        } else if name == OPTIMIZER_FUEL.name {
+            Ok(&self.optimizer_fuel)
+        } else if name == ROLE.name {
+            Ok(&self.role)
         } else if name == SEARCH_PATH.name {
             Ok(&self.search_path)
         } else if name == SERVER_VERSION.name {
@@ -213,6 +304,8 @@ impl Vars {
             Ok(&self.sql_safe_updates)
         } else if name == STANDARD_CONFORMING_STRINGS.name {
             Ok(&self.standard_conforming_strings)
+        } else if name == STRICT_ROW_LOCKING.name {
+            Ok(&self.strict_row_locking)
         } else if name == TIMEZONE.name {
             Ok(&self.timezone)
         } else if name == TRANSACTION_ISOLATION.name {
@@ -248,12 +341,53 @@ impl Vars {
                 }
             }
             Ok(())
+        } else if name == EMIT_RESULT_CHECKSUMS.name {
+            self.emit_result_checksums.set(value)
+        } else if name == EMIT_TIMESTAMP_NOTICES.name {
+            self.emit_timestamp_notices.set(value)
         } else if name == EXTRA_FLOAT_DIGITS.name {
             self.extra_float_digits.set(value)
+        } else if name == FETCH_TIMEOUT.name {
+            // Timeouts are stored in their canonical form, so that they can
+            // be parsed again infallibly by `fetch_timeout`.
+            if value.trim().is_empty() {
+                return self.fetch_timeout.set(FETCH_TIMEOUT.value);
+            }
+            let interval = match strconv::parse_interval(value) {
+                Ok(interval) => interval,
+                Err(e) => bail!("invalid value for parameter {}: {}", FETCH_TIMEOUT.name, e),
+            };
+            ExecuteTimeout::from_interval(interval)?;
+            self.fetch_timeout.set(&interval.to_string())
         } else if name == INTEGER_DATETIMES.name {
             bail!("parameter {} is read only", INTEGER_DATETIMES.name);
+        } else if name == MAX_TIMESTAMP_AGE_MS.name {
+            match value.parse::<i32>() {
+                Ok(ms) if ms < 0 => {
+                    bail!("parameter {} cannot be negative", MAX_TIMESTAMP_AGE_MS.name)
+                }
+                _ => self.max_timestamp_age_ms.set(value),
+            }
+        } else if name == OPTIMIZER_FUEL.name {
+            match value.parse::<i32>() {
+                Ok(fuel) if fuel < 0 => {
+                    bail!("parameter {} cannot be negative", OPTIMIZER_FUEL.name)
+                }
+                _ => self.optimizer_fuel.set(value),
+            }
+        } else if name == ROLE.name {
+            // There are no roles besides the session user, so `SET ROLE` only
+            // validates its target. No privileges are enforced either way.
+            let role = unicase::Ascii::new(value);
+            if role == "none" || role == "default" {
+                self.role.set(ROLE.value)
+            } else if value == self.user {
+                self.role.set(value)
+            } else {
+                bail!("role \"{}\" does not exist", value)
+            }
         } else if name == SEARCH_PATH.name {
-            bail!("parameter {} is read only", SEARCH_PATH.name);
+            self.search_path.set(value)
         } else if name == SERVER_VERSION.name {
             bail!("parameter {} is read only", SERVER_VERSION.name);
         } else if name == SQL_SAFE_UPDATES.name {
@@ -263,15 +397,22 @@ impl Vars {
                 "parameter {} is read only",
                 STANDARD_CONFORMING_STRINGS.name
             );
+        } else if name == STRICT_ROW_LOCKING.name {
+            self.strict_row_locking.set(value)
         } else if name == TIMEZONE.name {
-            if unicase::Ascii::new(value) != TIMEZONE.value {
-                bail!(
-                    "parameter {} can only be set to {}",
+            // Time zone names are matched case insensitively, as in
+            // PostgreSQL, but stored with their canonical capitalization.
+            let value = unicase::Ascii::new(value);
+            match TZ_VARIANTS
+                .iter()
+                .find(|tz| unicase::Ascii::new(tz.name()) == value)
+            {
+                Some(tz) => self.timezone.set(tz.name()),
+                None => bail!(
+                    "invalid value for parameter {}: \"{}\"",
                     TIMEZONE.name,
-                    TIMEZONE.value
-                );
-            } else {
-                Ok(())
+                    value
+                ),
             }
         } else if name == TRANSACTION_ISOLATION.name {
             bail!("parameter {} is read only", TRANSACTION_ISOLATION.name);
@@ -280,6 +421,20 @@ impl Vars {
         }
     }
 
+    /// Sets the name of the user that established the session.
+    ///
+    /// The session user can only be set when the session is established, not
+    /// via `SET`.
+    pub fn set_user(&mut self, user: &str) {
+        self.user = user.into();
+    }
+
+    /// Returns the name of the user that established the session, i.e., the
+    /// value of `session_user`.
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
     /// Returns the value of the `application_name` configuration parameter.
     pub fn application_name(&self) -> &str {
         self.application_name.value()
@@ -300,19 +455,96 @@ impl Vars {
         self.database.value()
     }
 
+    /// Returns the value of the `emit_result_checksums` configuration
+    /// parameter.
+    pub fn emit_result_checksums(&self) -> bool {
+        *self.emit_result_checksums.value()
+    }
+
+    /// Returns the value of the `emit_timestamp_notices` configuration
+    /// parameter.
+    pub fn emit_timestamp_notices(&self) -> bool {
+        *self.emit_timestamp_notices.value()
+    }
+
     /// Returns the value of the `extra_float_digits` configuration parameter.
     pub fn extra_float_digits(&self) -> i32 {
         *self.extra_float_digits.value()
     }
 
+    /// Returns the timeout that the `fetch_timeout` configuration parameter
+    /// specifies for `FETCH` statements that do not specify their own.
+    pub fn fetch_timeout(&self) -> ExecuteTimeout {
+        let value = self.fetch_timeout.value();
+        if value.is_empty() {
+            return ExecuteTimeout::WaitOnce;
+        }
+        let interval = strconv::parse_interval(value).expect("validated when set");
+        ExecuteTimeout::from_interval(interval).expect("validated when set")
+    }
+
     /// Returns the value of the `integer_datetimes` configuration parameter.
     pub fn integer_datetimes(&self) -> bool {
         *self.integer_datetimes.value
     }
 
-    /// Returns the value of the `search_path` configuration parameter.
-    pub fn search_path(&self) -> &'static [&'static str] {
-        self.search_path.value
+    /// Returns the value of the `max_timestamp_age_ms` configuration
+    /// parameter, or `None` if the check is disabled.
+    pub fn max_timestamp_age_ms(&self) -> Option<u64> {
+        match *self.max_timestamp_age_ms.value() {
+            ms if ms > 0 => Some(ms as u64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the `optimizer_fuel` configuration parameter, or
+    /// `None` if the optimizer is not limited.
+    pub fn optimizer_fuel(&self) -> Option<usize> {
+        match *self.optimizer_fuel.value() {
+            fuel if fuel > 0 => Some(fuel as usize),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the `role` configuration parameter.
+    pub fn role(&self) -> &str {
+        self.role.value()
+    }
+
+    /// Returns the name of the role whose privileges the session would have,
+    /// i.e., the value of `current_user`. This is the role selected via `SET
+    /// ROLE`, if any, or the session user otherwise.
+    pub fn current_user(&self) -> &str {
+        match self.role() {
+            r if r == ROLE.value => self.user(),
+            r => r,
+        }
+    }
+
+    /// Returns the schemas named by the `search_path` configuration parameter,
+    /// in the order in which they should be searched.
+    ///
+    /// As in PostgreSQL, the system schemas are always searched, even if they
+    /// are not explicitly listed: `mz_catalog` and `pg_catalog` are implicitly
+    /// searched first, and `mz_temp` is implicitly searched last.
+    pub fn search_path(&self) -> Vec<&str> {
+        let explicit: Vec<&str> = self
+            .search_path
+            .schemas
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        let mut search_path = vec![];
+        for schema in &[MZ_CATALOG_SCHEMA, PG_CATALOG_SCHEMA] {
+            if !explicit.contains(schema) {
+                search_path.push(*schema);
+            }
+        }
+        search_path.extend(&explicit);
+        if !explicit.contains(&MZ_TEMP_SCHEMA) {
+            search_path.push(MZ_TEMP_SCHEMA);
+        }
+        search_path
     }
 
     /// Returns the value of the `server_version` configuration parameter.
@@ -331,9 +563,17 @@ impl Vars {
         *self.standard_conforming_strings.value
     }
 
+    /// Returns the value of the `strict_row_locking` configuration parameter.
+    pub fn strict_row_locking(&self) -> bool {
+        *self.strict_row_locking.value()
+    }
+
     /// Returns the value of the `timezone` configuration parameter.
-    pub fn timezone(&self) -> &'static str {
-        self.timezone.value
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+            .value()
+            .parse()
+            .expect("timezone validated when set")
     }
 
     /// Returns the value of the `transaction_isolation` configuration
@@ -446,6 +686,136 @@ where
     }
 }
 
+/// The session value for the `search_path` configuration parameter.
+///
+/// The value is stored as a list of schema names rather than as a string, so
+/// that a quoted schema name containing a comma is not mistaken for two
+/// schemas when the search path is consulted.
+#[derive(Debug)]
+struct SearchPathVar {
+    schemas: Vec<String>,
+    parent: &'static ServerVar<str>,
+}
+
+impl SearchPathVar {
+    fn new(parent: &'static ServerVar<str>) -> SearchPathVar {
+        SearchPathVar {
+            schemas: parse_search_path(parent.value).expect("default search_path is valid"),
+            parent,
+        }
+    }
+
+    fn set(&mut self, s: &str) -> Result<(), anyhow::Error> {
+        self.schemas = parse_search_path(s)?;
+        Ok(())
+    }
+}
+
+impl Var for SearchPathVar {
+    fn name(&self) -> &'static str {
+        &self.parent.name
+    }
+
+    fn value(&self) -> String {
+        self.schemas
+            .iter()
+            .map(|schema| quote_identifier(schema))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn description(&self) -> &'static str {
+        self.parent.description
+    }
+}
+
+/// Parses a comma-separated list of schema names, as accepted by the
+/// `search_path` configuration parameter.
+///
+/// As in PostgreSQL, unquoted names are folded to lowercase and have
+/// surrounding whitespace removed, while names in double quotes are taken
+/// verbatim, with `""` standing for a literal double quote.
+fn parse_search_path(s: &str) -> Result<Vec<String>, anyhow::Error> {
+    let mut schemas = vec![];
+    let mut chars = s.trim().chars().peekable();
+    if chars.peek().is_none() {
+        return Ok(schemas);
+    }
+    loop {
+        while chars.peek().map(|ch| ch.is_whitespace()).unwrap_or(false) {
+            chars.next();
+        }
+        let mut schema = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        schema.push('"');
+                    }
+                    Some('"') => break,
+                    Some(ch) => schema.push(ch),
+                    None => bail!("invalid list syntax in parameter {}", SEARCH_PATH.name),
+                }
+            }
+            while chars.peek().map(|ch| ch.is_whitespace()).unwrap_or(false) {
+                chars.next();
+            }
+            if schema.is_empty() {
+                bail!(
+                    "parameter {} cannot contain an empty schema name",
+                    SEARCH_PATH.name
+                );
+            }
+        } else {
+            while let Some(ch) = chars.peek() {
+                if *ch == ',' || *ch == '"' {
+                    break;
+                }
+                schema.push(*ch);
+                chars.next();
+            }
+            schema = schema.trim_end().to_lowercase();
+            if schema.is_empty() {
+                bail!(
+                    "parameter {} cannot contain an empty schema name",
+                    SEARCH_PATH.name
+                );
+            }
+        }
+        if schemas.contains(&schema) {
+            bail!(
+                "schema {} is specified more than once in parameter {}",
+                quote_identifier(&schema),
+                SEARCH_PATH.name
+            );
+        }
+        schemas.push(schema);
+        match chars.next() {
+            None => return Ok(schemas),
+            Some(',') => (),
+            Some(_) => bail!("invalid list syntax in parameter {}", SEARCH_PATH.name),
+        }
+    }
+}
+
+/// Renders `ident` so that [`parse_search_path`] reads it back unchanged,
+/// quoting it only if necessary.
+fn quote_identifier(ident: &str) -> String {
+    let mut chars = ident.chars();
+    let bare = chars
+        .next()
+        .map(|ch| ch.is_ascii_lowercase() || ch == '_')
+        .unwrap_or(false)
+        && chars.all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_');
+    if bare {
+        ident.into()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
 /// A value that can be stored in a session variable.
 pub trait Value: ToOwned {
     /// The name of the value type.
@@ -498,16 +868,3 @@ impl Value for str {
         self.to_owned()
     }
 }
-
-impl Value for [&str] {
-    const TYPE_NAME: &'static str = "string list";
-
-    fn parse(_: &str) -> Result<Self::Owned, ()> {
-        // Don't know how to parse string lists yet.
-        Err(())
-    }
-
-    fn format(&self) -> String {
-        self.join(", ")
-    }
-}
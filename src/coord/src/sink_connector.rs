@@ -7,7 +7,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::fmt;
 use std::fs::OpenOptions;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, Context};
@@ -17,7 +19,8 @@ use rdkafka::config::ClientConfig;
 
 use dataflow_types::{
     AvroOcfSinkConnector, AvroOcfSinkConnectorBuilder, KafkaSinkConnector,
-    KafkaSinkConnectorBuilder, KafkaSinkConsistencyConnector, SinkConnector, SinkConnectorBuilder,
+    KafkaSinkConnectorBuilder, KafkaSinkConsistencyConnector, KafkaSinkFormat,
+    KafkaSinkFormatBuilder, SinkConnector, SinkConnectorBuilder,
 };
 use expr::GlobalId;
 use ore::collections::CollectionExt;
@@ -36,19 +39,86 @@ pub async fn build(
     }
 }
 
-async fn register_kafka_topic(
+/// Describes the external state that `build` would create for `builder`,
+/// as a list of properties, without creating it.
+///
+/// The names of topics and files include the ID of the sink, which is not
+/// known until the sink is created, so `id` is substituted in its place.
+pub fn describe(
+    builder: &SinkConnectorBuilder,
+    id: impl fmt::Display,
+) -> Result<Vec<(&'static str, String)>, anyhow::Error> {
+    let mut properties = vec![];
+    match builder {
+        SinkConnectorBuilder::Kafka(k) => {
+            let topic = kafka_topic(k, id);
+            properties.push(("connector", "kafka".into()));
+            properties.push(("broker", k.broker_addrs.to_string()));
+            properties.push(("replication_factor", k.replication_factor.to_string()));
+            properties.push(("partition_count", k.partition_count.to_string()));
+            match &k.format {
+                KafkaSinkFormatBuilder::Avro {
+                    schema_registry_url,
+                    consistency_value_schema,
+                    ..
+                } => {
+                    properties.push(("format", "avro".into()));
+                    properties.push(("schema_registry_url", schema_registry_url.to_string()));
+                    if consistency_value_schema.is_some() {
+                        properties.push(("consistency_topic", format!("{}-consistency", topic)));
+                    }
+                }
+                KafkaSinkFormatBuilder::Json => properties.push(("format", "json".into())),
+            }
+            properties.push(("topic", topic));
+        }
+        SinkConnectorBuilder::AvroOcf(a) => {
+            properties.push(("connector", "avro-ocf".into()));
+            properties.push(("path", avro_ocf_path(a, id)?.display().to_string()));
+        }
+    }
+    Ok(properties)
+}
+
+/// Returns the name of the topic that a Kafka sink with ID `id` writes to.
+fn kafka_topic(builder: &KafkaSinkConnectorBuilder, id: impl fmt::Display) -> String {
+    format!("{}-{}-{}", builder.topic_prefix, id, builder.topic_suffix)
+}
+
+/// Returns the path of the file that an Avro OCF sink with ID `id` writes to.
+fn avro_ocf_path(
+    builder: &AvroOcfSinkConnectorBuilder,
+    id: impl fmt::Display,
+) -> Result<PathBuf, anyhow::Error> {
+    let mut name = match builder.path.file_stem() {
+        None => bail!(
+            "unable to read file name from path {}",
+            builder.path.display()
+        ),
+        Some(stem) => stem.to_owned(),
+    };
+    name.push("-");
+    name.push(id.to_string());
+    name.push("-");
+    name.push(&builder.file_name_suffix);
+    if let Some(extension) = builder.path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    Ok(builder.path.with_file_name(name))
+}
+
+async fn create_kafka_topic(
     client: &AdminClient<DefaultClientContext>,
     topic: &str,
+    partition_count: i32,
     replication_factor: i32,
-    ccsr: &ccsr::Client,
-    value_schema: &str,
-    key_schema: Option<&str>,
-) -> Result<(Option<i32>, i32), anyhow::Error> {
+) -> Result<(), anyhow::Error> {
     let res = client
         .create_topics(
             &[NewTopic::new(
                 &topic,
-                1,
+                partition_count,
                 TopicReplication::Fixed(replication_factor),
             )],
             &AdminOptions::new().request_timeout(Some(Duration::from_secs(5))),
@@ -65,6 +135,19 @@ async fn register_kafka_topic(
     }
     res.into_element()
         .map_err(|(_, e)| anyhow!("error creating topic {} for sink: {}", topic, e))?;
+    Ok(())
+}
+
+async fn register_kafka_topic(
+    client: &AdminClient<DefaultClientContext>,
+    topic: &str,
+    partition_count: i32,
+    replication_factor: i32,
+    ccsr: &ccsr::Client,
+    value_schema: &str,
+    key_schema: Option<&str>,
+) -> Result<(Option<i32>, i32), anyhow::Error> {
+    create_kafka_topic(client, topic, partition_count, replication_factor).await?;
 
     // Publish value schema for the topic.
     //
@@ -94,7 +177,7 @@ async fn build_kafka(
     frontier: Antichain<Timestamp>,
     id: GlobalId,
 ) -> Result<SinkConnector, anyhow::Error> {
-    let topic = format!("{}-{}-{}", builder.topic_prefix, id, builder.topic_suffix);
+    let topic = kafka_topic(&builder, id);
 
     // Create Kafka topic with single partition.
     let mut config = ClientConfig::new();
@@ -105,43 +188,76 @@ async fn build_kafka(
     let client = config
         .create::<AdminClient<_>>()
         .expect("creating admin client failed");
-    let ccsr = builder.ccsr_config.build();
-
-    let (key_schema_id, value_schema_id) = register_kafka_topic(
-        &client,
-        &topic,
-        builder.replication_factor as i32,
-        &ccsr,
-        &builder.value_schema,
-        builder.key_schema.as_deref(),
-    )
-    .await
-    .context("error registering kafka topic for sink")?;
-
-    let consistency = if let Some(consistency_value_schema) = builder.consistency_value_schema {
-        let consistency_topic = format!("{}-consistency", topic);
-        let (_, consistency_schema_id) = register_kafka_topic(
-            &client,
-            &consistency_topic,
-            builder.replication_factor as i32,
-            &ccsr,
-            &consistency_value_schema,
-            None,
-        )
-        .await
-        .context("error registering kafka consistency topic for sink")?;
 
-        Some(KafkaSinkConsistencyConnector {
-            topic: consistency_topic,
-            schema_id: consistency_schema_id,
-        })
-    } else {
-        None
+    let (format, consistency) = match builder.format {
+        KafkaSinkFormatBuilder::Avro {
+            key_schema,
+            value_schema,
+            consistency_value_schema,
+            ccsr_config,
+            ..
+        } => {
+            let ccsr = ccsr_config.build();
+
+            let (key_schema_id, value_schema_id) = register_kafka_topic(
+                &client,
+                &topic,
+                builder.partition_count as i32,
+                builder.replication_factor as i32,
+                &ccsr,
+                &value_schema,
+                key_schema.as_deref(),
+            )
+            .await
+            .context("error registering kafka topic for sink")?;
+
+            let consistency = if let Some(consistency_value_schema) = consistency_value_schema {
+                let consistency_topic = format!("{}-consistency", topic);
+                // The consistency topic must have a single partition, so that
+                // consumers observe its transactions in order.
+                let (_, consistency_schema_id) = register_kafka_topic(
+                    &client,
+                    &consistency_topic,
+                    1,
+                    builder.replication_factor as i32,
+                    &ccsr,
+                    &consistency_value_schema,
+                    None,
+                )
+                .await
+                .context("error registering kafka consistency topic for sink")?;
+
+                Some(KafkaSinkConsistencyConnector {
+                    topic: consistency_topic,
+                    schema_id: consistency_schema_id,
+                })
+            } else {
+                None
+            };
+
+            let format = KafkaSinkFormat::Avro {
+                key_schema_id,
+                value_schema_id,
+            };
+            (format, consistency)
+        }
+        KafkaSinkFormatBuilder::Json => {
+            // JSON sinks do not publish schemas anywhere, so there is nothing
+            // to do beyond creating the topic.
+            create_kafka_topic(
+                &client,
+                &topic,
+                builder.partition_count as i32,
+                builder.replication_factor as i32,
+            )
+            .await
+            .context("error creating kafka topic for sink")?;
+            (KafkaSinkFormat::Json, None)
+        }
     };
 
     Ok(SinkConnector::Kafka(KafkaSinkConnector {
-        key_schema_id,
-        value_schema_id,
+        format,
         topic,
         addrs: builder.broker_addrs,
         consistency,
@@ -150,6 +266,10 @@ async fn build_kafka(
         strict: !with_snapshot,
         config_options: builder.config_options,
         key_indices: builder.key_indices,
+        debezium: builder.debezium,
+        topic_by: builder.topic_by,
+        replication_factor: builder.replication_factor,
+        partition_count: builder.partition_count,
     }))
 }
 
@@ -159,23 +279,7 @@ fn build_avro_ocf(
     frontier: Antichain<Timestamp>,
     id: GlobalId,
 ) -> Result<SinkConnector, anyhow::Error> {
-    let mut name = match builder.path.file_stem() {
-        None => bail!(
-            "unable to read file name from path {}",
-            builder.path.display()
-        ),
-        Some(stem) => stem.to_owned(),
-    };
-    name.push("-");
-    name.push(id.to_string());
-    name.push("-");
-    name.push(builder.file_name_suffix);
-    if let Some(extension) = builder.path.extension() {
-        name.push(".");
-        name.push(extension);
-    }
-
-    let path = builder.path.with_file_name(name);
+    let path = avro_ocf_path(&builder, id)?;
 
     // Try to create a new sink file
     let _ = OpenOptions::new()
@@ -193,5 +297,7 @@ fn build_avro_ocf(
         path,
         frontier,
         strict: !with_snapshot,
+        rotate_interval: builder.rotate_interval,
+        max_file_size: builder.max_file_size,
     }))
 }
@@ -19,7 +19,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, TryRecvError};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use anyhow::bail;
 use futures::executor::block_on;
@@ -121,6 +121,10 @@ pub struct TimestampConfig {
 pub enum TimestampMessage {
     Add(SourceInstanceId, SourceConnector),
     DropInstance(SourceInstanceId),
+    /// Stop assigning timestamps to all source instances until `Resume`.
+    Pause,
+    /// Resume assigning timestamps after a `Pause`.
+    Resume,
     Shutdown,
 }
 
@@ -176,6 +180,33 @@ struct ByoTimestampConsumer {
 }
 
 impl ByoTimestampConsumer {
+    /// Reports the offset-to-timestamp binding for a partition of this
+    /// consumer's Kafka topic to the coordinator. Bindings for non-Kafka
+    /// sources are not reported.
+    fn send_kafka_partition_watermark(
+        &self,
+        tx: &futures::channel::mpsc::UnboundedSender<coord::Message>,
+        sid: SourceInstanceId,
+        partition: &PartitionId,
+        timestamp: u64,
+        offset: MzOffset,
+    ) {
+        if let (ByoTimestampConnector::Kafka(_), PartitionId::Kafka(partition)) =
+            (&self.connector, partition)
+        {
+            tx.unbounded_send(coord::Message::KafkaPartitionWatermark(
+                coord::KafkaPartitionWatermark {
+                    id: sid,
+                    topic: self.source_name.clone(),
+                    partition: *partition,
+                    offset,
+                    timestamp,
+                },
+            ))
+            .expect("Failed to send update to coordinator");
+        }
+    }
+
     fn update_and_send(
         &mut self,
         tx: &futures::channel::mpsc::UnboundedSender<coord::Message>,
@@ -212,6 +243,7 @@ impl ByoTimestampConsumer {
         self.current_partition_count = partition_count;
         self.last_ts = timestamp;
         self.last_partition_ts.insert(partition.clone(), timestamp);
+        self.send_kafka_partition_watermark(tx, sid, &partition, timestamp, offset);
         tx.unbounded_send(coord::Message::AdvanceSourceTimestamp(
             coord::AdvanceSourceTimestamp {
                 id: sid,
@@ -258,6 +290,9 @@ struct RtKafkaConnector {
 struct TimestampingState {
     /// Flag is set when timestamping for this source has been dropped
     stop: AtomicBool,
+    /// Flag is set while timestamping for all sources is paused. Shared by
+    /// all sources.
+    paused: Arc<AtomicBool>,
     /// Channel through which messages can be sent to the coordinator
     coordinator_channel: futures::channel::mpsc::UnboundedSender<coord::Message>,
 }
@@ -429,6 +464,16 @@ fn kafka_get_next_message(consumer: &mut BaseConsumer) -> Option<Vec<u8>> {
     }
 }
 
+/// Returns the number of milliseconds since the Unix epoch.
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("failed to get millis since epoch")
+        .as_millis()
+        .try_into()
+        .expect("current time did not fit into u64")
+}
+
 /// Return the list of partition ids associated with a specific topic
 fn get_kafka_partitions(
     consumer: &BaseConsumer,
@@ -467,6 +512,9 @@ pub struct Timestamper {
 
     /// Frequency at which thread should run
     timestamp_frequency: Duration,
+
+    /// Whether timestamping is paused for all sources
+    paused: Arc<AtomicBool>,
 }
 
 /// A byo record contains a single timestamp update for a given source
@@ -546,6 +594,13 @@ fn generate_ts_updates_from_debezium(
                 let parsed_source_name = byo_consumer.source_name.split('.').skip(1).join(".");
                 if byo_consumer.source_name == topic.trim() || parsed_source_name == topic.trim() {
                     byo_consumer.last_offset.offset += count;
+                    byo_consumer.send_kafka_partition_watermark(
+                        tx,
+                        *id,
+                        &PartitionId::Kafka(0),
+                        byo_consumer.last_ts,
+                        byo_consumer.last_offset,
+                    );
                     // Debezium consistency topic should only work for single-partition
                     // topics
                     tx.unbounded_send(coord::Message::AdvanceSourceTimestamp(
@@ -747,6 +802,7 @@ impl Timestamper {
             tx,
             rx,
             timestamp_frequency: config.frequency,
+            paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -759,7 +815,7 @@ impl Timestamper {
             let shutdown = self.update_sources();
             if shutdown {
                 break;
-            } else {
+            } else if !self.paused.load(Ordering::SeqCst) {
                 self.update_byo_timestamp();
             }
         }
@@ -823,6 +879,14 @@ impl Timestamper {
                     }
                     self.byo_sources.remove(&id);
                 }
+                TimestampMessage::Pause => {
+                    info!("Pausing Timestamping for all Sources.");
+                    self.paused.store(true, Ordering::SeqCst);
+                }
+                TimestampMessage::Resume => {
+                    info!("Resuming Timestamping for all Sources.");
+                    self.paused.store(false, Ordering::SeqCst);
+                }
                 TimestampMessage::Shutdown => return true,
             }
         }
@@ -886,6 +950,9 @@ impl Timestamper {
                                     byo_consumer
                                         .last_partition_ts
                                         .insert(partition.clone(), timestamp);
+                                    byo_consumer.send_kafka_partition_watermark(
+                                        &self.tx, *id, &partition, timestamp, offset,
+                                    );
                                     self.tx
                                         .unbounded_send(coord::Message::AdvanceSourceTimestamp(
                                             coord::AdvanceSourceTimestamp {
@@ -1162,6 +1229,7 @@ impl Timestamper {
         let connector = RtKafkaConnector {
             coordination_state: Arc::new(TimestampingState {
                 stop: AtomicBool::new(false),
+                paused: Arc::clone(&self.paused),
                 coordinator_channel: self.tx.clone(),
             }),
             id,
@@ -1397,8 +1465,14 @@ fn rt_kafka_metadata_fetch_loop(c: RtKafkaConnector, consumer: BaseConsumer, wai
     );
 
     let mut current_partition_count = 0;
+    let mut last_high_watermarks = HashMap::new();
 
     while !c.coordination_state.stop.load(Ordering::SeqCst) {
+        if c.coordination_state.paused.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_secs(1));
+            continue;
+        }
+
         match get_kafka_partitions(&consumer, &c.topic, Duration::from_secs(30)) {
             Ok(partitions) => {
                 // There cannot be more than i32 partitions
@@ -1454,6 +1528,24 @@ fn rt_kafka_metadata_fetch_loop(c: RtKafkaConnector, consumer: BaseConsumer, wai
                         &pid.to_string(),
                     ]);
                     max_offset.set(high);
+
+                    // Real-time sources assign timestamps as data arrives, so
+                    // the best binding we can report is the wall-clock time
+                    // at which we observed the high watermark.
+                    if last_high_watermarks.insert(pid, high) != Some(high) {
+                        c.coordination_state
+                            .coordinator_channel
+                            .unbounded_send(coord::Message::KafkaPartitionWatermark(
+                                coord::KafkaPartitionWatermark {
+                                    id: c.id,
+                                    topic: c.topic.clone(),
+                                    partition: pid,
+                                    offset: MzOffset { offset: high },
+                                    timestamp: now_millis(),
+                                },
+                            ))
+                            .expect("Failed to send update to coordinator. This should not happen");
+                    }
                 }
                 Err(e) => {
                     error!(
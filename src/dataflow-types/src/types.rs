@@ -661,8 +661,7 @@ pub struct KafkaSinkConsistencyConnector {
 pub struct KafkaSinkConnector {
     pub addrs: KafkaAddrs,
     pub topic: String,
-    pub key_schema_id: Option<i32>,
-    pub value_schema_id: i32,
+    pub format: KafkaSinkFormat,
     pub consistency: Option<KafkaSinkConsistencyConnector>,
     // Maximum number of records the sink will attempt to send each time it is
     // invoked
@@ -671,6 +670,34 @@ pub struct KafkaSinkConnector {
     pub strict: bool,
     pub config_options: BTreeMap<String, String>,
     pub key_indices: Option<Vec<usize>>,
+    /// Whether to emit Debezium-compatible records, which pair up the
+    /// retractions and insertions of each key into updates and label every
+    /// record with an `op` field. Requires `key_indices`.
+    pub debezium: bool,
+    /// An expression that, if present, routes each record to the topic named
+    /// by `topic` followed by a hyphen and the expression's value for the
+    /// record. Topics are created as records are routed to them.
+    pub topic_by: Option<ScalarExpr>,
+    /// The replication factor with which to create the topics that records
+    /// are routed to.
+    pub replication_factor: u32,
+    /// The number of partitions with which to create the topics that records
+    /// are routed to.
+    pub partition_count: u32,
+}
+
+/// The format in which a Kafka sink writes its records.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KafkaSinkFormat {
+    /// Avro, with the key and value schemas published to a Confluent schema
+    /// registry under the specified IDs.
+    Avro {
+        key_schema_id: Option<i32>,
+        value_schema_id: i32,
+    },
+    /// JSON objects keyed by column name, wrapped in a Debezium-style envelope
+    /// with `before` and `after` fields.
+    Json,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -678,6 +705,10 @@ pub struct AvroOcfSinkConnector {
     pub path: PathBuf,
     pub frontier: Antichain<Timestamp>,
     pub strict: bool,
+    /// How long the sink writes to a file before rolling over to a new one.
+    pub rotate_interval: Option<Duration>,
+    /// The size in bytes past which the sink rolls over to a new file.
+    pub max_file_size: Option<u64>,
 }
 
 impl SinkConnector {
@@ -694,9 +725,46 @@ impl SinkConnector {
 pub struct TailSinkConnector {
     pub tx: comm::mpsc::Sender<Vec<Row>>,
     pub frontier: Antichain<Timestamp>,
+    /// The frontier at which the tail completes. Updates at times not less
+    /// than this frontier are not produced. If empty, the tail never
+    /// completes.
+    pub until: Antichain<Timestamp>,
     pub strict: bool,
     pub emit_progress: bool,
     pub object_columns: usize,
+    pub on_backpressure: TailBackpressure,
+    /// The columns of a unique key of the tailed relation, if it has one.
+    /// Backlogged updates are coalesced per key under
+    /// `TailBackpressure::Latest`.
+    pub key: Option<Vec<usize>>,
+    /// The number of updates after which the tail completes, if any.
+    pub limit: Option<TailLimit>,
+}
+
+/// Bounds the number of updates that a tail produces.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TailLimit {
+    /// The number of updates to produce, where an update with a diff of `n`
+    /// counts as `n` updates.
+    pub count: usize,
+    /// Whether the updates in the tail's initial snapshot count toward
+    /// `count`.
+    pub include_snapshot: bool,
+}
+
+/// What a tail does when its consumer cannot keep up with the updates it
+/// produces.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TailBackpressure {
+    /// Wait for the consumer, stalling the dataflow that feeds the tail.
+    Block,
+    /// Discard the updates that cannot be sent, and send a gap marker in
+    /// their place once the consumer catches up.
+    Drop,
+    /// Buffer the updates that cannot be sent, and once the consumer catches
+    /// up, send only the newest value of each key, along with the retraction
+    /// of the value the consumer last saw.
+    Latest,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -709,22 +777,38 @@ pub enum SinkConnectorBuilder {
 pub struct AvroOcfSinkConnectorBuilder {
     pub path: PathBuf,
     pub file_name_suffix: String,
+    pub rotate_interval: Option<Duration>,
+    pub max_file_size: Option<u64>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct KafkaSinkConnectorBuilder {
     pub broker_addrs: KafkaAddrs,
-    pub schema_registry_url: Url,
-    pub value_schema: String,
+    pub format: KafkaSinkFormatBuilder,
     pub topic_prefix: String,
     pub topic_suffix: String,
     pub replication_factor: u32,
+    pub partition_count: u32,
     pub fuel: usize,
-    pub consistency_value_schema: Option<String>,
     pub config_options: BTreeMap<String, String>,
-    pub ccsr_config: ccsr::ClientConfig,
     pub key_indices: Option<Vec<usize>>,
-    pub key_schema: Option<String>,
+    pub debezium: bool,
+    pub topic_by: Option<ScalarExpr>,
+}
+
+/// The format of a Kafka sink, before any schemas have been published.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KafkaSinkFormatBuilder {
+    /// Avro, with schemas to be published to a Confluent schema registry.
+    Avro {
+        schema_registry_url: Url,
+        key_schema: Option<String>,
+        value_schema: String,
+        consistency_value_schema: Option<String>,
+        ccsr_config: ccsr::ClientConfig,
+    },
+    /// JSON. No schema registry is involved.
+    Json,
 }
 
 /// An index storing processed updates so they can be queried
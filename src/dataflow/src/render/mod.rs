@@ -133,7 +133,10 @@ use repr::{Datum, RelationType, Row, RowArena, Timestamp};
 use crate::decode::{decode_avro_values, decode_values};
 use crate::operator::{CollectionExt, StreamExt};
 use crate::render::context::{ArrangementFlavor, Context};
-use crate::server::{CacheMessage, LocalInput, TimestampDataUpdates, TimestampMetadataUpdates};
+use crate::server::{
+    AvroOcfSinkRotations, CacheMessage, LocalInput, SinkStatusUpdates, SourcesPaused,
+    TailCompletions, TimestampDataUpdates, TimestampMetadataUpdates,
+};
 use crate::sink;
 use crate::source::{self, FileSourceInfo, KafkaSourceInfo, KinesisSourceInfo, S3SourceInfo};
 use crate::source::{SourceConfig, SourceToken};
@@ -165,6 +168,14 @@ pub struct RenderState {
     /// Communication channel for enabling/disabling timestamping on new/dropped
     /// sources.
     pub ts_source_updates: TimestampMetadataUpdates,
+    /// Whether the ingestion of new data by all sources is paused.
+    pub sources_paused: SourcesPaused,
+    /// Communication channel for reporting sink errors.
+    pub sink_status_updates: SinkStatusUpdates,
+    /// Communication channel for reporting completed tails.
+    pub tail_completions: TailCompletions,
+    /// Communication channel for reporting the new files of Avro OCF sinks.
+    pub avro_ocf_sink_rotations: AvroOcfSinkRotations,
     /// Tokens that should be dropped when a dataflow is dropped to clean up
     /// associated state.
     pub dataflow_tokens: HashMap<GlobalId, Box<dyn Any>>,
@@ -319,6 +330,7 @@ where
                     active: active_read_worker,
                     timestamp_histories: render_state.ts_histories.clone(),
                     timestamp_tx: render_state.ts_source_updates.clone(),
+                    paused: render_state.sources_paused.clone(),
                     consistency,
                     timestamp_frequency: ts_frequency,
                     worker_id: scope.index(),
@@ -704,7 +716,13 @@ where
 
         match sink.connector.clone() {
             SinkConnector::Kafka(c) => {
-                let token = sink::kafka(&collection.inner, sink_id, c, sink.from.1.clone());
+                let token = sink::kafka(
+                    &collection.inner,
+                    sink_id,
+                    c,
+                    sink.from.1.clone(),
+                    render_state.sink_status_updates.clone(),
+                );
                 needed_sink_tokens.push(token);
             }
             SinkConnector::Tail(c) => {
@@ -716,10 +734,16 @@ where
                     .map(move |row| (sink_id, row))
                     .arrange_by_key()
                     .stream;
-                sink::tail(stream, sink_id, c);
+                sink::tail(stream, sink_id, c, render_state.tail_completions.clone());
             }
             SinkConnector::AvroOcf(c) => {
-                sink::avro_ocf(&collection.inner, sink_id, c, sink.from.1.clone());
+                sink::avro_ocf(
+                    &collection.inner,
+                    sink_id,
+                    c,
+                    sink.from.1.clone(),
+                    render_state.avro_ocf_sink_rotations.clone(),
+                );
             }
         };
 
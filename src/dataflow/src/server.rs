@@ -9,13 +9,14 @@
 
 //! An interactive dataflow server.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::net::TcpStream;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::{Instant, UNIX_EPOCH};
 
 use differential_dataflow::operators::arrange::arrangement::Arrange;
@@ -113,6 +114,10 @@ pub enum SequencedCommand {
     CancelPeek {
         /// The identifier of the peek request to cancel.
         conn_id: u32,
+        /// The response with which to resolve the peek, e.g.
+        /// `PeekResponse::Canceled` if the user canceled the peek, or an
+        /// error if the arrangement it reads from was dropped.
+        response: PeekResponse,
     },
     /// Insert `updates` into the local input named `id`.
     Insert {
@@ -139,6 +144,9 @@ pub enum SequencedCommand {
         /// The timestamp to advance to.
         advance_to: Timestamp,
     },
+    /// Pause (if `true`) or resume (if `false`) the ingestion of new data by
+    /// all sources, including those created while sources are paused.
+    SetSourcesPaused(bool),
     /// Request that feedback is streamed to the provided channel.
     EnableFeedback(comm::mpsc::Sender<WorkerFeedbackWithMeta>),
     /// Request that cache data is streamed to the provided channel.
@@ -182,6 +190,16 @@ pub enum WorkerFeedback {
     DroppedSource(SourceInstanceId),
     /// The id of a source whose source connector has been created
     CreateSource(SourceInstanceId),
+    /// The id of a sink and the error it most recently encountered, or `None`
+    /// if the sink has since written successfully.
+    SinkError { id: GlobalId, error: Option<String> },
+    /// The id of a tail sink that has produced all updates before its `until`
+    /// frontier, or, if `limit_reached` is set, as many updates as its limit
+    /// allows.
+    TailComplete { id: GlobalId, limit_reached: bool },
+    /// The id of an Avro OCF sink and the path of the file it has rolled over
+    /// to.
+    AvroOcfSinkRotated { id: GlobalId, path: PathBuf },
 }
 
 /// Configures a dataflow server.
@@ -251,6 +269,10 @@ where
                     ts_source_mapping: HashMap::new(),
                     ts_histories: Default::default(),
                     ts_source_updates: Default::default(),
+                    sources_paused: Default::default(),
+                    sink_status_updates: Default::default(),
+                    tail_completions: Default::default(),
+                    avro_ocf_sink_rotations: Default::default(),
                     dataflow_tokens: HashMap::new(),
                     caching_tx: None,
                 },
@@ -288,6 +310,26 @@ pub type TimestampDataUpdates = Rc<RefCell<HashMap<SourceInstanceId, TimestampDa
 /// StopTimestamping request once the operator for the source is dropped.
 pub type TimestampMetadataUpdates = Rc<RefCell<Vec<TimestampMetadataUpdate>>>;
 
+/// Whether the ingestion of new data by all sources is paused. Paused sources
+/// neither read new messages nor close timestamps.
+pub type SourcesPaused = Rc<Cell<bool>>;
+
+/// List of errors encountered by sinks, or `None` once a sink that had
+/// encountered an error writes successfully, that have not yet been reported to
+/// the coordinator.
+/// Sinks may report errors from their producer's background threads, so unlike
+/// `TimestampMetadataUpdates` this list is thread safe.
+pub type SinkStatusUpdates = Arc<Mutex<Vec<(GlobalId, Option<String>)>>>;
+
+/// List of tail sinks that have reached their `until` frontier or their limit
+/// and closed their connection to the coordinator, along with whether it was
+/// the limit that they reached.
+pub type TailCompletions = Rc<RefCell<Vec<(GlobalId, bool)>>>;
+
+/// List of Avro OCF sinks that have rolled over to a new file, along with the
+/// path of that file.
+pub type AvroOcfSinkRotations = Rc<RefCell<Vec<(GlobalId, PathBuf)>>>;
+
 /// Possible timestamping metadata information messages that get sent from workers to coordinator
 pub enum TimestampMetadataUpdate {
     /// Requests to start timestamping a source with given id
@@ -489,6 +531,12 @@ where
 
             self.report_source_modifications();
 
+            self.report_sink_statuses();
+
+            self.report_tail_completions();
+
+            self.report_avro_ocf_sink_rotations();
+
             // Handle any received commands.
             let mut cmds = vec![];
             while let Ok(Some(cmd)) = self.command_rx.try_next() {
@@ -539,6 +587,47 @@ where
         updates.clear();
     }
 
+    /// Report sink errors, and recoveries from them, to the coordinator
+    fn report_sink_statuses(&mut self) {
+        let updates = std::mem::take(&mut *self.render_state.sink_status_updates.lock().unwrap());
+        for (id, error) in updates {
+            let connector = self.feedback_tx.as_mut().unwrap();
+            block_on(connector.send(WorkerFeedbackWithMeta {
+                worker_id: self.timely_worker.index(),
+                message: WorkerFeedback::SinkError { id, error },
+            }))
+            .unwrap();
+        }
+    }
+
+    /// Report tails that have reached their `until` frontier or their limit to
+    /// the coordinator, so that it can drop them.
+    fn report_tail_completions(&mut self) {
+        let completions = std::mem::take(&mut *self.render_state.tail_completions.borrow_mut());
+        for (id, limit_reached) in completions {
+            let connector = self.feedback_tx.as_mut().unwrap();
+            block_on(connector.send(WorkerFeedbackWithMeta {
+                worker_id: self.timely_worker.index(),
+                message: WorkerFeedback::TailComplete { id, limit_reached },
+            }))
+            .unwrap();
+        }
+    }
+
+    /// Report the new files of Avro OCF sinks to the coordinator.
+    fn report_avro_ocf_sink_rotations(&mut self) {
+        let rotations =
+            std::mem::take(&mut *self.render_state.avro_ocf_sink_rotations.borrow_mut());
+        for (id, path) in rotations {
+            let connector = self.feedback_tx.as_mut().unwrap();
+            block_on(connector.send(WorkerFeedbackWithMeta {
+                worker_id: self.timely_worker.index(),
+                message: WorkerFeedback::AvroOcfSinkRotated { id, path },
+            }))
+            .unwrap();
+        }
+    }
+
     /// Send progress information to the coordinator.
     fn report_frontiers(&mut self) {
         if let Some(feedback_tx) = &mut self.feedback_tx {
@@ -686,12 +775,12 @@ where
                 self.metrics.observe_pending_peeks(&self.pending_peeks);
             }
 
-            SequencedCommand::CancelPeek { conn_id } => {
+            SequencedCommand::CancelPeek { conn_id, response } => {
                 let logger = &mut self.materialized_logger;
                 self.pending_peeks.retain(|peek| {
                     if peek.conn_id == conn_id {
                         let mut tx = block_on(peek.tx.connect()).unwrap();
-                        block_on(tx.send(PeekResponse::Canceled)).unwrap();
+                        block_on(tx.send(response.clone())).unwrap();
 
                         if let Some(logger) = logger {
                             logger.log(MaterializedEvent::Peek(peek.as_log_event(), false));
@@ -744,6 +833,9 @@ where
             SequencedCommand::EnableCaching(tx) => {
                 self.render_state.caching_tx = Some(tx);
             }
+            SequencedCommand::SetSourcesPaused(paused) => {
+                self.render_state.sources_paused.set(paused);
+            }
             SequencedCommand::Shutdown => {
                 // this should lead timely to wind down eventually
                 self.render_state.traces.del_all_traces();
@@ -962,6 +1054,19 @@ impl PendingPeek {
                     .evaluate(&mut datums, &arena, &mut row_packer)
                     .map_err(|e| e.to_string())?
                 {
+                    // We cannot `seek_key` to the keyset bound: `Row` orders by
+                    // the length and then the bytes of its packed encoding,
+                    // which stores integers in native byte order and prefixes
+                    // strings with their length, so the arrangement's order
+                    // disagrees with `order_by` even when the index is on the
+                    // `order_by` columns. Seeking would skip rows that sort
+                    // after the bound. We instead discard the rows that precede
+                    // the bound before they are stashed in `results`, which
+                    // lets the limit below bound the work of each page.
+                    if !self.finishing.is_after_key(&result) {
+                        cursor.step_val(&storage);
+                        continue;
+                    }
                     let mut copies = 0;
                     cursor.map_times(&storage, |time, diff| {
                         if time.less_equal(&self.timestamp) {
@@ -117,6 +117,8 @@ struct CommandsProcessedMetrics {
     shutdown: IntCounter,
     advance_all_local_inputs_int: i32,
     advance_all_local_inputs: IntCounter,
+    set_sources_paused_int: i32,
+    set_sources_paused: IntCounter,
 }
 
 impl CommandsProcessedMetrics {
@@ -159,6 +161,9 @@ impl CommandsProcessedMetrics {
             advance_all_local_inputs_int: 0,
             advance_all_local_inputs: COMMANDS_PROCESSED_RAW
                 .with_label_values(&[worker, "advance_all_local_inputs"]),
+            set_sources_paused_int: 0,
+            set_sources_paused: COMMANDS_PROCESSED_RAW
+                .with_label_values(&[worker, "set_sources_paused"]),
         }
     }
 
@@ -182,6 +187,7 @@ impl CommandsProcessedMetrics {
             SequencedCommand::AdvanceAllLocalInputs { .. } => {
                 self.advance_all_local_inputs_int += 1
             }
+            SequencedCommand::SetSourcesPaused(_) => self.set_sources_paused_int += 1,
         }
     }
 
@@ -242,5 +248,10 @@ impl CommandsProcessedMetrics {
                 .inc_by(self.advance_all_local_inputs_int as i64);
             self.advance_all_local_inputs_int = 0;
         }
+        if self.set_sources_paused_int > 0 {
+            self.set_sources_paused
+                .inc_by(self.set_sources_paused_int as i64);
+            self.set_sources_paused_int = 0;
+        }
     }
 }
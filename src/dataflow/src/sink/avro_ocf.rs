@@ -7,7 +7,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use differential_dataflow::hashable::Hashable;
 use log::error;
@@ -20,16 +22,19 @@ use expr::GlobalId;
 use interchange::avro::{DiffPair, Encoder};
 use repr::{Diff, RelationDesc, Row, Timestamp};
 
+use crate::server::AvroOcfSinkRotations;
+
 pub fn avro_ocf<G>(
     stream: &Stream<G, (Row, Timestamp, Diff)>,
     id: GlobalId,
     connector: AvroOcfSinkConnector,
     desc: RelationDesc,
+    rotations: AvroOcfSinkRotations,
 ) where
     G: Scope<Timestamp = Timestamp>,
 {
-    let encoder = Encoder::new(desc, false, None);
-    let schema = encoder.writer_schema();
+    let encoder = Encoder::new(desc, false, None, false);
+    let schema = encoder.writer_schema().clone();
     let sink_hash = id.hashed();
 
     let res = OpenOptions::new().append(true).open(&connector.path);
@@ -40,6 +45,10 @@ pub fn avro_ocf<G>(
             None
         }
     };
+    // When the current file was opened, and how many bytes have been written
+    // to it, for deciding when to roll over to a new file.
+    let mut opened_at = Instant::now();
+    let mut file_size = 0;
 
     stream.sink(
         Exchange::new(move |_| sink_hash),
@@ -52,6 +61,7 @@ pub fn avro_ocf<G>(
             let avro_writer = avro_writer.as_mut().expect("avro writer known to exist");
 
             input.for_each(|_, rows| {
+                let mut checked_rotation = false;
                 for (row, time, diff) in rows.iter() {
                     let should_emit = if connector.strict {
                         connector.frontier.less_than(&time)
@@ -62,6 +72,35 @@ pub fn avro_ocf<G>(
                         continue;
                     }
 
+                    // Roll over to a new file before writing the first record
+                    // of a batch, if the current file has reached its age or
+                    // size limit. Checking only between batches means files
+                    // always end at a flush, and no empty files are created.
+                    if !checked_rotation {
+                        checked_rotation = true;
+                        let expired = match connector.rotate_interval {
+                            Some(interval) => opened_at.elapsed() >= interval,
+                            None => false,
+                        };
+                        let full = match connector.max_file_size {
+                            Some(max_file_size) => file_size >= max_file_size,
+                            None => false,
+                        };
+                        if expired || full {
+                            match create_rotated_file(&connector.path) {
+                                Ok((path, f)) => {
+                                    *avro_writer = mz_avro::Writer::new(schema.clone(), f);
+                                    opened_at = Instant::now();
+                                    file_size = 0;
+                                    rotations.borrow_mut().push((id, path));
+                                }
+                                // Keep writing to the current file, and try
+                                // again with the next batch.
+                                Err(e) => error!("rotating avro ocf file for sink failed: {}", e),
+                            }
+                        }
+                    }
+
                     let diff_pair = if *diff < 0 {
                         DiffPair {
                             before: Some(row),
@@ -78,7 +117,7 @@ pub fn avro_ocf<G>(
                         let res = avro_writer.append(value.clone());
 
                         match res {
-                            Ok(_) => (),
+                            Ok(n) => file_size += n as u64,
                             Err(e) => error!("appending to avro ocf failed: {}", e),
                         }
                     }
@@ -86,10 +125,32 @@ pub fn avro_ocf<G>(
 
                 let res = avro_writer.flush();
                 match res {
-                    Ok(_) => (),
+                    Ok(n) => file_size += n as u64,
                     Err(e) => error!("flushing bytes to avro ocf failed: {}", e),
                 }
             })
         },
     )
 }
+
+/// Creates the file that a sink writing to `path` rolls over to, which is
+/// named after `path` and the current time in milliseconds since the Unix
+/// epoch.
+fn create_rotated_file(path: &Path) -> Result<(PathBuf, File), std::io::Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before Unix epoch")
+        .as_millis();
+    let mut name = path.file_stem().unwrap_or_default().to_owned();
+    name.push(format!("-{}", now));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    let path = path.with_file_name(name);
+    let f = OpenOptions::new()
+        .append(true)
+        .create_new(true)
+        .open(&path)?;
+    Ok((path, f))
+}
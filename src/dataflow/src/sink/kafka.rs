@@ -8,19 +8,22 @@
 // by the Apache License, Version 2.0.
 
 use std::any::Any;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::thread;
 use std::time::Duration;
 
 use differential_dataflow::hashable::Hashable;
+use futures::executor::block_on;
 use lazy_static::lazy_static;
 use log::error;
 use prometheus::{
     register_int_counter_vec, register_uint_gauge_vec, IntCounter, IntCounterVec, UIntGauge,
     UIntGaugeVec,
 };
-use rdkafka::client::ClientContext;
+use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::client::{ClientContext, DefaultClientContext};
 use rdkafka::config::ClientConfig;
 use rdkafka::error::{KafkaError, RDKafkaErrorCode};
 use rdkafka::message::Message;
@@ -29,11 +32,15 @@ use timely::dataflow::channels::pact::Exchange;
 use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
 use timely::dataflow::operators::generic::FrontieredInputHandle;
 use timely::dataflow::{Scope, Stream};
+use timely::scheduling::SyncActivator;
 
-use dataflow_types::KafkaSinkConnector;
-use expr::GlobalId;
-use interchange::avro::{self, DiffPair, Encoder};
-use repr::{Diff, RelationDesc, Row, Timestamp};
+use dataflow_types::{KafkaSinkConnector, KafkaSinkFormat};
+use expr::{GlobalId, ScalarExpr};
+use interchange::avro::{self, DiffPair};
+use interchange::json;
+use repr::{Datum, Diff, RelationDesc, Row, RowArena, Timestamp};
+
+use crate::server::SinkStatusUpdates;
 
 /// Per-Kafka sink metrics.
 #[derive(Clone)]
@@ -91,17 +98,55 @@ impl SinkMetrics {
     }
 }
 
+/// Reports the errors that a sink encounters, and its recovery from them, to
+/// the worker that hosts it.
+#[derive(Clone)]
+pub struct SinkStatusReporter {
+    id: GlobalId,
+    updates: SinkStatusUpdates,
+    errored: Arc<AtomicBool>,
+}
+
+impl SinkStatusReporter {
+    pub fn new(id: GlobalId, updates: SinkStatusUpdates) -> Self {
+        SinkStatusReporter {
+            id,
+            updates,
+            errored: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn error(&self, error: String) {
+        self.errored.store(true, Ordering::SeqCst);
+        self.updates.lock().unwrap().push((self.id, Some(error)));
+    }
+
+    /// Reports that the sink has written successfully, if it had previously
+    /// reported an error.
+    fn healthy(&self) {
+        if self.errored.swap(false, Ordering::SeqCst) {
+            self.updates.lock().unwrap().push((self.id, None));
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct SinkProducerContext {
     metrics: SinkMetrics,
     shutdown_flag: Arc<AtomicBool>,
+    status: SinkStatusReporter,
 }
 
 impl SinkProducerContext {
-    pub fn new(metrics: SinkMetrics, shutdown_flag: Arc<AtomicBool>) -> Self {
+    pub fn new(
+        metrics: SinkMetrics,
+        shutdown_flag: Arc<AtomicBool>,
+        status: SinkStatusReporter,
+    ) -> Self {
         SinkProducerContext {
             metrics,
             shutdown_flag,
+            status,
         }
     }
 }
@@ -115,11 +160,13 @@ impl ProducerContext for SinkProducerContext {
             Ok(_) => (),
             Err((e, msg)) => {
                 self.metrics.message_delivery_errors_counter.inc();
-                error!(
+                let error = format!(
                     "received error while writing to kafka sink topic {}: {}",
                     msg.topic(),
                     e
                 );
+                error!("{}", error);
+                self.status.error(error);
                 self.shutdown_flag.store(true, Ordering::SeqCst);
             }
         }
@@ -136,14 +183,47 @@ impl Drop for KafkaSinkToken {
     }
 }
 
+/// Encodes rows in the format requested by the sink's connector.
+enum SinkEncoder {
+    Avro {
+        encoder: avro::Encoder,
+        key_schema_id: Option<i32>,
+        value_schema_id: i32,
+    },
+    Json(json::Encoder),
+}
+
+impl SinkEncoder {
+    fn encode_unchecked(
+        &self,
+        diff_pair: DiffPair<&Row>,
+        transaction_id: Option<String>,
+    ) -> (Option<Vec<u8>>, Vec<u8>) {
+        match self {
+            SinkEncoder::Avro {
+                encoder,
+                key_schema_id,
+                value_schema_id,
+            } => encoder.encode_unchecked(
+                *key_schema_id,
+                *value_schema_id,
+                diff_pair,
+                transaction_id,
+            ),
+            SinkEncoder::Json(encoder) => encoder.encode_unchecked(diff_pair),
+        }
+    }
+}
+
 struct KafkaSink {
     name: String,
     shutdown_flag: Arc<AtomicBool>,
     metrics: SinkMetrics,
-    encoder: Encoder,
+    encoder: SinkEncoder,
     producer: ThreadedProducer<SinkProducerContext>,
     activator: timely::scheduling::Activator,
     txn_timeout: Duration,
+    status: SinkStatusReporter,
 }
 
 impl KafkaSink {
@@ -157,6 +237,10 @@ impl KafkaSink {
             "encountered error during kafka interaction. {} in state {:?} at time {} : {}",
             &self.name, current_state, ts, e
         );
+        self.status.error(format!(
+            "error during kafka interaction in state {:?}: {}",
+            current_state, e
+        ));
 
         match e {
             KafkaError::Transaction(e) => {
@@ -182,6 +266,8 @@ impl KafkaSink {
                 Err(true)
             } else {
                 // We've received an error that is not transient
+                self.status
+                    .error(format!("unable to produce message: {}", e));
                 self.shutdown_flag.store(true, Ordering::SeqCst);
                 Err(false)
             }
@@ -222,8 +308,252 @@ enum SendState {
     Shutdown,
 }
 
+/// Pairs up the retractions and insertions of each key at a single timestamp,
+/// so that they can be sent as Debezium updates.
+///
+/// Unpaired retractions and insertions become deletes and creates,
+/// respectively, and pairs whose before and after rows are identical are
+/// dropped. Each diff pair is returned alongside the number of times it should
+/// be sent.
+fn pair_updates_by_key(
+    updates: Vec<(Row, Diff)>,
+    key_indices: &[usize],
+) -> Vec<(DiffPair<Row>, usize)> {
+    let mut by_key: BTreeMap<Row, (Vec<(Row, usize)>, Vec<(Row, usize)>)> = BTreeMap::new();
+    for (row, diff) in updates {
+        let datums = row.unpack();
+        let key = Row::pack(key_indices.iter().map(|&idx| datums[idx]));
+        let (retractions, insertions) = by_key.entry(key).or_default();
+        if diff < 0 {
+            retractions.push((row, diff.abs() as usize));
+        } else {
+            insertions.push((row, diff as usize));
+        }
+    }
+
+    let mut diff_pairs = Vec::new();
+    for (_key, (mut retractions, mut insertions)) in by_key {
+        retractions.reverse();
+        insertions.reverse();
+        while let (Some((before, before_count)), Some((after, after_count))) =
+            (retractions.last_mut(), insertions.last_mut())
+        {
+            let count = (*before_count).min(*after_count);
+            if before != after {
+                let diff_pair = DiffPair {
+                    before: Some(before.clone()),
+                    after: Some(after.clone()),
+                };
+                diff_pairs.push((diff_pair, count));
+            }
+            *before_count -= count;
+            *after_count -= count;
+            if *before_count == 0 {
+                retractions.pop();
+            }
+            if *after_count == 0 {
+                insertions.pop();
+            }
+        }
+        for (row, count) in retractions.into_iter().rev() {
+            let diff_pair = DiffPair {
+                before: Some(row),
+                after: None,
+            };
+            diff_pairs.push((diff_pair, count));
+        }
+        for (row, count) in insertions.into_iter().rev() {
+            let diff_pair = DiffPair {
+                before: None,
+                after: Some(row),
+            };
+            diff_pairs.push((diff_pair, count));
+        }
+    }
+    diff_pairs
+}
+
+/// Routes records to the topics chosen by a sink's `topic_by` expression.
+///
+/// Each topic is created the first time a record is routed to it. Creating a
+/// topic is a round trip to the Kafka cluster, so it happens on a background
+/// thread rather than on the worker; records for a topic must be held back
+/// until `is_created` reports that the topic exists.
+struct TopicRouter {
+    expr: ScalarExpr,
+    prefix: String,
+    /// The topics whose creation has been requested from the background
+    /// thread.
+    requested_topics: HashSet<String>,
+    /// The topics that the background thread has created.
+    created_topics: HashSet<String>,
+    create_tx: mpsc::Sender<String>,
+    created_rx: mpsc::Receiver<Result<String, String>>,
+}
+
+impl TopicRouter {
+    /// Constructs a router and spawns the thread that creates its topics,
+    /// which activates the sink operator via `activator` whenever it finishes
+    /// creating a topic.
+    fn new(
+        expr: ScalarExpr,
+        prefix: String,
+        client: AdminClient<DefaultClientContext>,
+        partition_count: i32,
+        replication_factor: i32,
+        activator: SyncActivator,
+    ) -> TopicRouter {
+        let (create_tx, create_rx) = mpsc::channel::<String>();
+        let (created_tx, created_rx) = mpsc::channel();
+        thread::spawn(move || {
+            for topic in create_rx {
+                let result = create_topic(&client, &topic, partition_count, replication_factor)
+                    .map(|()| topic);
+                if created_tx.send(result).is_err() {
+                    // The sink has been dropped.
+                    break;
+                }
+                let _ = activator.activate();
+            }
+        });
+        TopicRouter {
+            expr,
+            prefix,
+            requested_topics: HashSet::new(),
+            created_topics: HashSet::new(),
+            create_tx,
+            created_rx,
+        }
+    }
+
+    /// Splits `diff_pair` into the diff pairs to send to each topic.
+    ///
+    /// An update whose before and after rows route to different topics
+    /// becomes a delete in the old topic and a create in the new one.
+    fn route(
+        &mut self,
+        diff_pair: DiffPair<Row>,
+    ) -> Result<Vec<(Option<String>, DiffPair<Row>)>, String> {
+        let before_topic = diff_pair
+            .before
+            .as_ref()
+            .map(|row| self.topic_for(row))
+            .transpose()?;
+        let after_topic = diff_pair
+            .after
+            .as_ref()
+            .map(|row| self.topic_for(row))
+            .transpose()?;
+        match (before_topic, after_topic) {
+            (Some(before_topic), Some(after_topic)) if before_topic != after_topic => {
+                let delete = DiffPair {
+                    before: diff_pair.before,
+                    after: None,
+                };
+                let create = DiffPair {
+                    before: None,
+                    after: diff_pair.after,
+                };
+                Ok(vec![
+                    (Some(before_topic), delete),
+                    (Some(after_topic), create),
+                ])
+            }
+            (before_topic, after_topic) => Ok(vec![(after_topic.or(before_topic), diff_pair)]),
+        }
+    }
+
+    /// Computes the topic for `row`, requesting its creation if it has not
+    /// been requested before.
+    fn topic_for(&mut self, row: &Row) -> Result<String, String> {
+        let datums = row.unpack();
+        let arena = RowArena::new();
+        let suffix = match self.expr.eval(&datums, &arena) {
+            Ok(Datum::String(suffix)) => suffix,
+            Ok(Datum::Null) => return Err("topic_by expression evaluated to NULL".into()),
+            Ok(datum) => unreachable!("topic_by expression produced non-string {:?}", datum),
+            Err(e) => return Err(format!("error evaluating topic_by expression: {}", e)),
+        };
+        let topic = format!("{}-{}", self.prefix, suffix);
+        validate_topic_name(&topic)?;
+        if self.requested_topics.insert(topic.clone()) {
+            self.create_tx
+                .send(topic.clone())
+                .map_err(|_| format!("unable to request creation of topic {}", topic))?;
+        }
+        Ok(topic)
+    }
+
+    /// Records the topics that the background thread has created since the
+    /// last call, returning the error of any topic it failed to create.
+    fn poll_created(&mut self) -> Result<(), String> {
+        while let Ok(result) = self.created_rx.try_recv() {
+            self.created_topics.insert(result?);
+        }
+        Ok(())
+    }
+
+    /// Reports whether `topic` is known to exist.
+    fn is_created(&self, topic: &str) -> bool {
+        self.created_topics.contains(topic)
+    }
+}
+
+/// Creates `topic`, succeeding if it already exists.
+///
+/// Blocks until the Kafka cluster responds, so must not be called from a
+/// worker thread.
+fn create_topic(
+    client: &AdminClient<DefaultClientContext>,
+    topic: &str,
+    partition_count: i32,
+    replication_factor: i32,
+) -> Result<(), String> {
+    let res = block_on(client.create_topics(
+        &[NewTopic::new(
+            topic,
+            partition_count,
+            TopicReplication::Fixed(replication_factor),
+        )],
+        &AdminOptions::new().request_timeout(Some(Duration::from_secs(5))),
+    ))
+    .map_err(|e| format!("error creating topic {} for sink: {}", topic, e))?;
+    for result in res {
+        match result {
+            Ok(_) | Err((_, RDKafkaErrorCode::TopicAlreadyExists)) => (),
+            Err((_, e)) => return Err(format!("error creating topic {} for sink: {}", topic, e)),
+        }
+    }
+    Ok(())
+}
+
+/// Validates that `topic` is a legal Kafka topic name.
+///
+/// Kafka topic names may contain only ASCII alphanumerics, `.`, `_`, and `-`,
+/// and may be at most 249 characters long.
+fn validate_topic_name(topic: &str) -> Result<(), String> {
+    if topic.len() > 249 {
+        return Err(format!(
+            "topic_by produced topic name {} longer than 249 characters",
+            topic
+        ));
+    }
+    if let Some(c) = topic
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '.' | '_' | '-'))
+    {
+        return Err(format!(
+            "topic_by produced topic name {} containing invalid character {:?}",
+            topic, c
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 struct EncodedRow {
+    /// The topic to send the row to, if it differs from the sink's topic.
+    topic: Option<String>,
     key: Option<Vec<u8>>,
     value: Vec<u8>,
     count: usize,
@@ -235,6 +565,7 @@ pub fn kafka<G>(
     id: GlobalId,
     mut connector: KafkaSinkConnector,
     desc: RelationDesc,
+    status_updates: SinkStatusUpdates,
 ) -> Box<dyn Any>
 where
     G: Scope<Timestamp = Timestamp>,
@@ -270,6 +601,7 @@ where
 
     let name = format!("kafka-{}", id);
     let shutdown_flag = Arc::new(AtomicBool::new(false));
+    let status = SinkStatusReporter::new(id, status_updates);
     let mut builder = OperatorBuilder::new(name.clone(), stream.scope());
 
     let s = {
@@ -279,16 +611,32 @@ where
             &stream.scope().index().to_string(),
         );
 
-        let encoder = Encoder::new(
-            desc,
-            connector.consistency.is_some(),
-            connector.key_indices.take(),
-        );
+        let encoder = match connector.format {
+            KafkaSinkFormat::Avro {
+                key_schema_id,
+                value_schema_id,
+            } => SinkEncoder::Avro {
+                encoder: avro::Encoder::new(
+                    desc,
+                    connector.consistency.is_some(),
+                    connector.key_indices.clone(),
+                    connector.debezium,
+                ),
+                key_schema_id,
+                value_schema_id,
+            },
+            KafkaSinkFormat::Json => SinkEncoder::Json(json::Encoder::new(
+                desc,
+                connector.key_indices.clone(),
+                connector.debezium,
+            )),
+        };
 
         let producer = config
             .create_with_context::<_, ThreadedProducer<_>>(SinkProducerContext::new(
                 metrics.clone(),
                 shutdown_flag.clone(),
+                status.clone(),
             ))
             .expect("creating kafka producer for kafka sinks failed");
 
@@ -304,10 +652,39 @@ where
             producer,
             activator,
             txn_timeout: Duration::from_secs(5),
+            status,
         }
     };
 
-    let mut pending_rows: HashMap<Timestamp, Vec<EncodedRow>> = HashMap::new();
+    // Debezium sinks pair up the retractions and insertions of each key at a
+    // timestamp, which requires knowing where the key columns are.
+    let debezium_key_indices = if connector.debezium {
+        Some(
+            connector
+                .key_indices
+                .take()
+                .expect("debezium sinks require a key"),
+        )
+    } else {
+        None
+    };
+
+    let mut topic_router = connector.topic_by.take().map(|expr| {
+        TopicRouter::new(
+            expr,
+            connector.topic.clone(),
+            config
+                .create::<AdminClient<_>>()
+                .expect("creating admin client for kafka sink failed"),
+            connector.partition_count as i32,
+            connector.replication_factor as i32,
+            stream
+                .scope()
+                .sync_activator_for(&builder.operator_info().address[..]),
+        )
+    });
+
+    let mut pending_rows: HashMap<Timestamp, Vec<(Row, Diff)>> = HashMap::new();
     let mut ready_rows: VecDeque<(Timestamp, Vec<EncodedRow>)> = VecDeque::new();
     let mut state = SendState::Init;
     let mut vector = Vec::new();
@@ -318,7 +695,18 @@ where
             return false;
         }
 
-        // Encode and queue all pending rows waiting to be sent to kafka
+        if let Some(router) = &mut topic_router {
+            if let Err(e) = router.poll_created() {
+                error!("unable to create topic for {}: {}", s.name, e);
+                s.status.error(e);
+                s.shutdown_flag.store(true, Ordering::SeqCst);
+                return false;
+            }
+        }
+        // Whether sending is blocked on the creation of a routed topic.
+        let mut awaiting_topic = false;
+
+        // Queue all pending rows waiting to be sent to kafka
         input.for_each(|_, rows| {
             rows.swap(&mut vector);
             for (row, time, diff) in vector.drain(..) {
@@ -338,55 +726,91 @@ where
                     continue;
                 };
 
-                let diff_pair = if diff < 0 {
-                    DiffPair {
-                        before: Some(&row),
-                        after: None,
-                    }
-                } else {
-                    DiffPair {
-                        before: None,
-                        after: Some(&row),
-                    }
-                };
-
-                let transaction_id = match connector.consistency {
-                    Some(_) => Some(time.to_string()),
-                    None => None,
-                };
-
-                let (key, value) = s.encoder.encode_unchecked(
-                    connector.key_schema_id,
-                    connector.value_schema_id,
-                    diff_pair,
-                    transaction_id,
-                );
-
-                // For diffs other than +/- 1, we send repeated copies of the
-                // Avro record [diff] times. Since the format and envelope
-                // capture the "polarity" of the update, we need to remember
-                // how many times to send the data.
-                let rows = pending_rows.entry(time).or_default();
-                rows.push(EncodedRow {
-                    key,
-                    value,
-                    count: diff.abs() as usize,
-                });
+                pending_rows.entry(time).or_default().push((row, diff));
                 s.metrics.rows_queued.inc();
             }
         });
 
-        // Move any newly closed timestamps from pending to ready
+        // Encode any newly closed timestamps and move them from pending to
+        // ready
         let mut closed_ts: Vec<u64> = pending_rows
             .iter()
             .filter(|(ts, _)| !input.frontier.less_equal(*ts))
             .map(|(&ts, _)| ts)
             .collect();
         closed_ts.sort_unstable();
-        closed_ts.into_iter().for_each(|ts| {
-            let rows = pending_rows.remove(&ts).unwrap();
-            ready_rows.push_back((ts, rows));
-        });
+        for ts in closed_ts {
+            let updates = pending_rows.remove(&ts).unwrap();
+            let num_updates = updates.len();
+
+            let diff_pairs = match &debezium_key_indices {
+                Some(key_indices) => pair_updates_by_key(updates, key_indices),
+                // For diffs other than +/- 1, we send repeated copies of the
+                // record [diff] times. Since the format and envelope
+                // capture the "polarity" of the update, we need to remember
+                // how many times to send the data.
+                None => updates
+                    .into_iter()
+                    .map(|(row, diff)| {
+                        let diff_pair = if diff < 0 {
+                            DiffPair {
+                                before: Some(row),
+                                after: None,
+                            }
+                        } else {
+                            DiffPair {
+                                before: None,
+                                after: Some(row),
+                            }
+                        };
+                        (diff_pair, diff.abs() as usize)
+                    })
+                    .collect(),
+            };
+
+            let transaction_id = match connector.consistency {
+                Some(_) => Some(ts.to_string()),
+                None => None,
+            };
+
+            let mut rows = Vec::with_capacity(diff_pairs.len());
+            for (diff_pair, count) in diff_pairs {
+                let routed = match &mut topic_router {
+                    None => vec![(None, diff_pair)],
+                    Some(router) => match router.route(diff_pair) {
+                        Ok(routed) => routed,
+                        Err(e) => {
+                            error!("unable to route message in {}: {}", s.name, e);
+                            s.status.error(e);
+                            s.shutdown_flag.store(true, Ordering::SeqCst);
+                            return false;
+                        }
+                    },
+                };
+                for (topic, diff_pair) in routed {
+                    let diff_pair = DiffPair {
+                        before: diff_pair.before.as_ref(),
+                        after: diff_pair.after.as_ref(),
+                    };
+                    let (key, value) = s
+                        .encoder
+                        .encode_unchecked(diff_pair, transaction_id.clone());
+                    rows.push(EncodedRow {
+                        topic,
+                        key,
+                        value,
+                        count,
+                    });
+                }
+            }
+
+            // Pairing updates by key can merge or cancel out queued rows, and
+            // routing can split an update across two topics.
+            s.metrics.rows_queued.sub((num_updates - rows.len()) as u64);
+            if !rows.is_empty() {
+                ready_rows.push_back((ts, rows));
+            }
+        }
 
         // Send a bounded number of records to Kafka from the ready queue.
         // This loop has explicitly been designed so that each iteration sends
@@ -444,7 +868,21 @@ where
                         mut total_sent,
                     } => {
                         let encoded_row = &rows[row_index];
-                        let record = BaseRecord::to(&connector.topic).payload(&encoded_row.value);
+                        if let Some(topic) = &encoded_row.topic {
+                            let created = match &topic_router {
+                                Some(router) => router.is_created(topic),
+                                None => true,
+                            };
+                            if !created {
+                                // The topic router's background thread
+                                // reactivates the operator once it has
+                                // created the topic.
+                                awaiting_topic = true;
+                                break;
+                            }
+                        }
+                        let topic = encoded_row.topic.as_deref().unwrap_or(&connector.topic);
+                        let record = BaseRecord::to(topic).payload(&encoded_row.value);
                         let record = if encoded_row.key.is_some() {
                             record.key(encoded_row.key.as_ref().unwrap())
                         } else {
@@ -501,6 +939,7 @@ where
                         match result {
                             Ok(()) => {
                                 ready_rows.pop_front();
+                                s.status.healthy();
                                 SendState::BeginTxn
                             }
                             Err(e) => s.transition_on_txn_error(state, *ts, e),
@@ -534,7 +973,9 @@ where
         if !ready_rows.is_empty() {
             // We need timely to reschedule this operator as we have pending
             // items that we need to send to Kafka
-            s.activator.activate();
+            if !awaiting_topic {
+                s.activator.activate();
+            }
             return true;
         }
 
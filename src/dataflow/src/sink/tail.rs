@@ -7,36 +7,78 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::cmp;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 
 use differential_dataflow::trace::cursor::Cursor;
 use differential_dataflow::trace::implementations::ord::OrdValBatch;
 use differential_dataflow::trace::BatchReader;
 use timely::dataflow::channels::pact::Pipeline;
-use timely::dataflow::operators::Operator;
+use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+use timely::dataflow::operators::generic::FrontieredInputHandle;
 use timely::dataflow::{Scope, Stream};
+use timely::scheduling::SyncActivator;
 
 use futures::executor::block_on;
-use futures::sink::SinkExt;
+use futures::sink::{Sink, SinkExt};
+use futures::task::ArcWake;
 
-use dataflow_types::TailSinkConnector;
+use dataflow_types::{TailBackpressure, TailSinkConnector};
 use expr::GlobalId;
 use ore::cast::CastFrom;
 use repr::adt::decimal::Significand;
 use repr::{Datum, Diff, Row, RowPacker, Timestamp};
 
+use crate::server::TailCompletions;
+
 pub fn tail<G>(
     stream: Stream<G, Rc<OrdValBatch<GlobalId, Row, Timestamp, Diff>>>,
     id: GlobalId,
     connector: TailSinkConnector,
+    completions: TailCompletions,
 ) where
     G: Scope<Timestamp = Timestamp>,
 {
-    let mut tx = block_on(connector.tx.connect()).expect("tail transmitter failed");
+    let mut tx = Some(block_on(connector.tx.connect()).expect("tail transmitter failed"));
     let mut packer = RowPacker::new();
-    stream.sink(Pipeline, &format!("tail-{}", id), move |input| {
+    // The updates that a slow consumer has not been able to accept, if the
+    // tail coalesces them.
+    let mut pending = PendingUpdates::default();
+    // The time of the earliest update that was dropped because the consumer
+    // could not accept it, if the tail drops updates.
+    let mut gap = None;
+    // The number of updates that the tail may still produce, if it is limited.
+    let mut remaining = connector.limit.map(|limit| limit.count);
+
+    let mut builder = OperatorBuilder::new(format!("tail-{}", id), stream.scope());
+    // The drop and latest modes never wait for the consumer. Instead, the
+    // channel wakes the operator once the consumer can accept more, so that
+    // any backlog is sent even if no further input arrives.
+    let waker = futures::task::waker(Arc::new(ActivatorWaker(
+        stream
+            .scope()
+            .sync_activator_for(&builder.operator_info().address[..]),
+    )));
+    let mut sink_logic = move |input: &mut FrontieredInputHandle<
+        _,
+        Rc<OrdValBatch<GlobalId, Row, Timestamp, Diff>>,
+        _,
+    >| {
         input.for_each(|_, batches| {
-            let mut results = vec![];
+            let tx = match &mut tx {
+                Some(tx) => tx,
+                None => return,
+            };
+            if remaining == Some(0) {
+                return;
+            }
+
+            let mut updates = vec![];
             for batch in batches.iter() {
                 let mut cursor = batch.cursor();
                 while cursor.key_valid(&batch) {
@@ -48,15 +90,8 @@ pub fn tail<G>(
                             } else {
                                 connector.frontier.less_equal(time)
                             };
-                            if should_emit {
-                                packer.push(Datum::Decimal(Significand::new(i128::from(*time))));
-                                if connector.emit_progress {
-                                    packer.push(Datum::False);
-                                }
-                                packer.push(Datum::Int64(i64::cast_from(*diff)));
-                                packer.extend_by_row(row);
-                                // Add the unpacked timestamp so we can sort by them later.
-                                results.push((*time, packer.finish_and_reuse()));
+                            if should_emit && !connector.until.less_equal(time) {
+                                updates.push((*time, row, *diff));
                             }
                         });
                         cursor.step_val(&batch);
@@ -65,35 +100,328 @@ pub fn tail<G>(
                 }
             }
 
-            // Sort results by time and convert to Vec<Row>. We use stable sort here even
-            // though it is slower because it will produce deterministic results since the
+            // Sort updates by time. We use stable sort here even though it is
+            // slower because it will produce deterministic results since the
             // cursor will always produce rows in the same order.
-            results.sort_by_key(|(time, _)| *time);
-            let mut results: Vec<Row> = results.into_iter().map(|(_, row)| row).collect();
-
-            if connector.emit_progress {
-                if let Some(upper) = batch_upper(batches.last()) {
-                    // The user has requested progress messages and there's at least one
-                    // batch. All of the batches might have zero rows, so we do not depend on
-                    // results at all. Another benefit of using upper (instead of the largest row
-                    // time) is that the batch's upper may be larger than the row time.
-                    packer.push(Datum::Decimal(Significand::new(i128::from(upper))));
-                    packer.push(Datum::True);
-                    // Fill in the diff column and all table columns with NULL.
-                    for _ in 0..(connector.object_columns + 1) {
-                        packer.push(Datum::Null);
+            updates.sort_by_key(|(time, _, _)| *time);
+
+            if let (Some(limit), Some(remaining)) = (&connector.limit, &mut remaining) {
+                apply_limit(&mut updates, remaining, &connector, limit.include_snapshot);
+            }
+
+            // The user has requested progress messages and there's at least
+            // one batch. All of the batches might have zero rows, so we do not
+            // depend on updates at all. Another benefit of using upper (instead
+            // of the largest row time) is that the batch's upper may be larger
+            // than the row time.
+            // Once the limit is reached, the tail makes no further progress,
+            // so there is none to report.
+            let upper = if connector.emit_progress && remaining != Some(0) {
+                batch_upper(batches.last()).map(|upper| {
+                    // Progress past the `until` frontier is not meaningful, as
+                    // no updates at those times will be produced.
+                    match connector.until.elements().get(0) {
+                        Some(until) => cmp::min(upper, *until),
+                        None => upper,
+                    }
+                })
+            } else {
+                None
+            };
+
+            match connector.on_backpressure {
+                // TODO(benesch): this blocks the Timely thread until the send
+                // completes. Hopefully it's just a quick write to a kernel
+                // buffer, but perhaps not if the batch gets too large? We may
+                // need to do something smarter, like offloading to a
+                // networking thread.
+                TailBackpressure::Block => {
+                    let results = pack_updates(&mut packer, &connector, updates, upper);
+                    block_on(tx.send(results)).expect("tail send failed");
+                }
+                TailBackpressure::Drop => {
+                    if poll_ready(tx, &waker) {
+                        let mut results = vec![];
+                        if let Some(time) = gap.take() {
+                            results.push(pack_gap(&mut packer, &connector, time));
+                        }
+                        results.extend(pack_updates(&mut packer, &connector, updates, upper));
+                        start_send(tx, results, &waker);
+                    } else if let Some((time, _, _)) = updates.first() {
+                        gap.get_or_insert(*time);
+                    }
+                }
+                TailBackpressure::Latest => {
+                    // Updates are only coalesced while there is a backlog, so
+                    // that a consumer that keeps up sees every update.
+                    let ready = poll_ready(tx, &waker);
+                    if ready && pending.is_empty() {
+                        let results = pack_updates(&mut packer, &connector, updates, upper);
+                        start_send(tx, results, &waker);
+                    } else {
+                        pending.absorb(&mut packer, &connector, updates, upper);
+                        if ready {
+                            start_send(tx, pending.drain(&mut packer, &connector), &waker);
+                        }
                     }
-                    results.push(packer.finish_and_reuse());
                 }
             }
-
-            // TODO(benesch): this blocks the Timely thread until the send
-            // completes. Hopefully it's just a quick write to a kernel buffer,
-            // but perhaps not if the batch gets too large? We may need to do
-            // something smarter, like offloading to a networking thread.
-            block_on(tx.send(results)).expect("tail send failed");
         });
-    })
+
+        if let Some(tx) = &mut tx {
+            if connector.on_backpressure != TailBackpressure::Block {
+                // A backlog may have cleared since the last batch arrived.
+                if !pending.is_empty() && poll_ready(tx, &waker) {
+                    start_send(tx, pending.drain(&mut packer, &connector), &waker);
+                }
+                if let Some(time) = gap {
+                    if poll_ready(tx, &waker) {
+                        gap = None;
+                        start_send(tx, vec![pack_gap(&mut packer, &connector, time)], &waker);
+                    }
+                }
+                // Make progress on sending buffered messages to the consumer,
+                // without waiting for it.
+                poll_flush(tx, &waker);
+            }
+        }
+
+        // Once the input frontier has advanced to `until`, or the tail has
+        // reached its limit, every update the tail will produce has been sent.
+        // Close our half of the channel and let the coordinator know, so that
+        // it can drop the dataflow.
+        let until_reached = !connector.until.is_empty()
+            && connector
+                .until
+                .elements()
+                .iter()
+                .all(|t| !input.frontier().less_than(t));
+        let limit_reached = remaining == Some(0);
+        if tx.is_some() && (until_reached || limit_reached) {
+            let mut tx = tx.take().unwrap();
+            // The consumer must receive any backlog before the channel
+            // closes, even if that means waiting for it.
+            let mut results = vec![];
+            if let Some(time) = gap.take() {
+                results.push(pack_gap(&mut packer, &connector, time));
+            }
+            if !pending.is_empty() {
+                results.extend(pending.drain(&mut packer, &connector));
+            }
+            if !results.is_empty() {
+                block_on(tx.feed(results)).expect("tail send failed");
+            }
+            block_on(tx.flush()).expect("tail send failed");
+            completions.borrow_mut().push((id, limit_reached));
+        }
+    };
+
+    let mut input = builder.new_input(&stream, Pipeline);
+    builder.build(|_capabilities| {
+        move |frontiers| {
+            let mut input_handle = FrontieredInputHandle::new(&mut input, &frontiers[0]);
+            sink_logic(&mut input_handle)
+        }
+    });
+}
+
+/// Wakes a tail's operator by asking timely to reschedule it.
+struct ActivatorWaker(SyncActivator);
+
+impl ArcWake for ActivatorWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        // The activation fails only if the dataflow has been dropped, in
+        // which case there is nothing left to send.
+        let _ = arc_self.0.activate();
+    }
+}
+
+/// Truncates `updates`, which must be sorted by time, to the number of updates
+/// that the tail may still produce, as recorded in `remaining`, and deducts
+/// the updates that are kept from `remaining`.
+///
+/// An update with a diff of `n` counts as `n` updates, and its diff is reduced
+/// if fewer than `n` updates remain. Updates in the tail's initial snapshot do
+/// not count toward the limit unless `include_snapshot` is set.
+fn apply_limit(
+    updates: &mut Vec<(Timestamp, &Row, Diff)>,
+    remaining: &mut usize,
+    connector: &TailSinkConnector,
+    include_snapshot: bool,
+) {
+    let mut len = 0;
+    for (time, _row, diff) in updates.iter_mut() {
+        // The snapshot is reported at the time from which the tail starts,
+        // and only if the tail is not strict.
+        let in_snapshot = !connector.strict && !connector.frontier.less_than(time);
+        if in_snapshot && !include_snapshot {
+            len += 1;
+            continue;
+        }
+        if *remaining == 0 {
+            break;
+        }
+        let count = diff.abs() as usize;
+        if count > *remaining {
+            *diff = diff.signum() * (*remaining as Diff);
+            *remaining = 0;
+        } else {
+            *remaining -= count;
+        }
+        len += 1;
+    }
+    updates.truncate(len);
+}
+
+/// Packs `updates`, which must be sorted by time, into rows in the format that
+/// the tail produces, followed by a progress message for `upper`, if any.
+fn pack_updates(
+    packer: &mut RowPacker,
+    connector: &TailSinkConnector,
+    updates: Vec<(Timestamp, &Row, Diff)>,
+    upper: Option<Timestamp>,
+) -> Vec<Row> {
+    let mut results = Vec::with_capacity(updates.len() + 1);
+    for (time, row, diff) in updates {
+        packer.push(Datum::Decimal(Significand::new(i128::from(time))));
+        if connector.emit_progress {
+            packer.push(Datum::False);
+        }
+        packer.push(Datum::Int64(i64::cast_from(diff)));
+        packer.extend_by_row(row);
+        results.push(packer.finish_and_reuse());
+    }
+    if let Some(upper) = upper {
+        packer.push(Datum::Decimal(Significand::new(i128::from(upper))));
+        packer.push(Datum::True);
+        // Fill in the diff column and all table columns with NULL.
+        for _ in 0..(connector.object_columns + 1) {
+            packer.push(Datum::Null);
+        }
+        results.push(packer.finish_and_reuse());
+    }
+    results
+}
+
+/// Packs the marker that stands in for the updates that were dropped because
+/// the consumer could not keep up. The marker carries the time of the earliest
+/// dropped update, and NULL in the diff column and all table columns.
+fn pack_gap(packer: &mut RowPacker, connector: &TailSinkConnector, time: Timestamp) -> Row {
+    packer.push(Datum::Decimal(Significand::new(i128::from(time))));
+    if connector.emit_progress {
+        packer.push(Datum::False);
+    }
+    for _ in 0..(connector.object_columns + 1) {
+        packer.push(Datum::Null);
+    }
+    packer.finish_and_reuse()
+}
+
+/// The consolidated updates that a slow consumer has not yet accepted.
+#[derive(Default)]
+struct PendingUpdates {
+    /// The net change to each row, grouped by the row's key. The rows of a
+    /// relation without a key are their own keys.
+    diffs: BTreeMap<Row, BTreeMap<Row, Diff>>,
+    /// The time of the latest update absorbed.
+    time: Option<Timestamp>,
+    /// The latest progress message absorbed.
+    upper: Option<Timestamp>,
+}
+
+impl PendingUpdates {
+    fn is_empty(&self) -> bool {
+        self.time.is_none() && self.upper.is_none()
+    }
+
+    fn absorb(
+        &mut self,
+        packer: &mut RowPacker,
+        connector: &TailSinkConnector,
+        updates: Vec<(Timestamp, &Row, Diff)>,
+        upper: Option<Timestamp>,
+    ) {
+        for (time, row, diff) in updates {
+            let key = match &connector.key {
+                Some(key) => {
+                    let datums = row.unpack();
+                    packer.extend(key.iter().map(|i| datums[*i]));
+                    packer.finish_and_reuse()
+                }
+                None => row.clone(),
+            };
+            *self
+                .diffs
+                .entry(key)
+                .or_default()
+                .entry(row.clone())
+                .or_insert(0) += diff;
+            self.time = cmp::max(self.time, Some(time));
+        }
+        self.upper = cmp::max(self.upper, upper);
+    }
+
+    /// Packs the net change to each key, as of the latest time absorbed, and
+    /// the latest progress message, leaving `self` empty.
+    ///
+    /// The intermediate values of a key cancel out, so what remains of each
+    /// key is the retraction of the value that the consumer last saw, if any,
+    /// followed by the newest value, if any.
+    fn drain(&mut self, packer: &mut RowPacker, connector: &TailSinkConnector) -> Vec<Row> {
+        let diffs = mem::take(&mut self.diffs);
+        let mut updates = vec![];
+        if let Some(time) = self.time.take() {
+            for rows in diffs.values() {
+                let start = updates.len();
+                updates.extend(
+                    rows.iter()
+                        .filter(|(_row, diff)| **diff != 0)
+                        .map(|(row, diff)| (time, row, *diff)),
+                );
+                updates[start..].sort_by_key(|(_time, _row, diff)| *diff > 0);
+            }
+        }
+        pack_updates(packer, connector, updates, self.upper.take())
+    }
+}
+
+/// Reports whether `tx` can accept a message without blocking. If it cannot,
+/// `waker` is woken once it can.
+fn poll_ready<S>(tx: &mut S, waker: &Waker) -> bool
+where
+    S: Sink<Vec<Row>> + Unpin,
+    S::Error: fmt::Debug,
+{
+    match tx.poll_ready_unpin(&mut Context::from_waker(waker)) {
+        Poll::Ready(res) => {
+            res.expect("tail send failed");
+            true
+        }
+        Poll::Pending => false,
+    }
+}
+
+/// Hands `results` to `tx`, which must be ready, and starts sending them to
+/// the consumer without waiting for it to accept them.
+fn start_send<S>(tx: &mut S, results: Vec<Row>, waker: &Waker)
+where
+    S: Sink<Vec<Row>> + Unpin,
+    S::Error: fmt::Debug,
+{
+    tx.start_send_unpin(results).expect("tail send failed");
+    poll_flush(tx, waker);
+}
+
+/// Sends as much of the buffered data in `tx` as possible without blocking.
+/// If any remains, `waker` is woken once more can be sent.
+fn poll_flush<S>(tx: &mut S, waker: &Waker)
+where
+    S: Sink<Vec<Row>> + Unpin,
+    S::Error: fmt::Debug,
+{
+    if let Poll::Ready(res) = tx.poll_flush_unpin(&mut Context::from_waker(waker)) {
+        res.expect("tail send failed");
+    }
 }
 
 fn batch_upper(
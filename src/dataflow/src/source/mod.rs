@@ -41,7 +41,8 @@ use super::source::util::source;
 use crate::logging::materialized::{Logger, MaterializedEvent};
 use crate::operator::StreamExt;
 use crate::server::{
-    TimestampDataUpdate, TimestampDataUpdates, TimestampMetadataUpdate, TimestampMetadataUpdates,
+    SourcesPaused, TimestampDataUpdate, TimestampDataUpdates, TimestampMetadataUpdate,
+    TimestampMetadataUpdates,
 };
 use crate::source::cache::CacheSender;
 
@@ -78,6 +79,8 @@ pub struct SourceConfig<'a, G> {
     pub timestamp_histories: TimestampDataUpdates,
     /// Control-timestamping updates: information about when to start/stop timestamping a source
     pub timestamp_tx: TimestampMetadataUpdates,
+    /// Whether the ingestion of new data by all sources is paused.
+    pub paused: SourcesPaused,
     /// A source can use Real-Time consistency timestamping or BYO consistency information.
     pub consistency: Consistency,
     /// Source Type
@@ -872,6 +875,7 @@ where
         scope,
         timestamp_histories,
         timestamp_tx,
+        paused,
         worker_id,
         worker_count,
         consistency,
@@ -937,6 +941,16 @@ where
                 return SourceStatus::Done;
             }
 
+            // While sources are paused, neither read new messages nor close
+            // timestamps, so that the source's frontier holds still. Check
+            // back once per timestamp interval.
+            if paused.get() {
+                activator.activate_after(Duration::from_millis(
+                    consistency_info.downgrade_capability_frequency,
+                ));
+                return SourceStatus::Alive;
+            }
+
             // Downgrade capability (if possible)
             consistency_info.downgrade_capability(&id, cap, source_info, &timestamp_histories);
 
@@ -28,7 +28,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::iter;
 
-use repr::RelationType;
+use repr::{Datum, RelationType, Row};
 
 use crate::{ExprHumanizer, Id, JoinImplementation, LocalId, RelationExpr, RowSetFinishing};
 
@@ -432,6 +432,22 @@ impl<'a> Explanation<'a> {
     }
 }
 
+/// The placeholder that replaces literal values in redacted explanations.
+pub const REDACTED: &str = "<redacted>";
+
+/// Returns a copy of `row` in which every datum other than `NULL` is replaced
+/// with the [`REDACTED`] placeholder. Nulls are retained, as they reveal
+/// nothing that the type of a literal does not.
+pub fn redact_row(row: &Row) -> Row {
+    Row::pack(row.iter().map(|datum| {
+        if datum.is_null() {
+            Datum::Null
+        } else {
+            Datum::String(REDACTED)
+        }
+    }))
+}
+
 /// Creates a type whose [`fmt::Display`] implementation outputs each item in
 /// `iter` separated by `separator`.
 pub fn separated<'a, I>(separator: &'a str, iter: I) -> impl fmt::Display + 'a
@@ -19,7 +19,7 @@ use ore::collections::CollectionExt;
 use repr::{ColumnType, Datum, RelationType, Row};
 
 use self::func::{AggregateFunc, TableFunc};
-use crate::explain::Explanation;
+use crate::explain::{self, Explanation};
 use crate::{DummyHumanizer, ExprHumanizer, GlobalId, Id, LocalId, ScalarExpr};
 
 pub mod func;
@@ -1010,6 +1010,22 @@ impl RelationExpr {
         Explanation::new(self, &DummyHumanizer).to_string()
     }
 
+    /// Replaces the values of all literals and constant rows in the
+    /// expression with placeholders, preserving its structure and types, so
+    /// that it can be explained without revealing data.
+    ///
+    /// The resulting expression is only fit to be explained.
+    pub fn redact_literals(&mut self) {
+        self.visit_mut(&mut |e| {
+            if let RelationExpr::Constant { rows, typ: _ } = e {
+                for (row, _diff) in rows {
+                    *row = explain::redact_row(row);
+                }
+            }
+        });
+        self.visit_scalars_mut(&mut |s| s.redact_literals());
+    }
+
     /// Take ownership of `self`, leaving an empty `RelationExpr::Constant` with the correct type.
     pub fn take_safely(&mut self) -> RelationExpr {
         let typ = self.typ();
@@ -1227,6 +1243,13 @@ pub struct RowSetFinishing {
     pub offset: usize,
     /// Include only given columns.
     pub project: Vec<usize>,
+    /// Include only rows that sort strictly after this key.
+    ///
+    /// The key holds values for a prefix of the `order_by` columns, and is
+    /// compared against rows in the direction of each column's ordering.
+    /// Rows whose leading columns equal the key are omitted, so paging
+    /// through a result with ties requires ordering by a unique column.
+    pub after: Option<Row>,
 }
 
 impl RowSetFinishing {
@@ -1235,15 +1258,44 @@ impl RowSetFinishing {
         self.limit.is_none()
             && self.order_by.is_empty()
             && self.offset == 0
+            && self.after.is_none()
             && self.project.iter().copied().eq(0..arity)
     }
+
+    /// True if `row` sorts strictly after the `after` key, if any.
+    pub fn is_after_key(&self, row: &Row) -> bool {
+        match &self.after {
+            None => true,
+            Some(key) => {
+                let datums = row.unpack();
+                for (order, key) in self.order_by.iter().zip(key.iter()) {
+                    let cmp = if order.desc {
+                        key.cmp(&datums[order.column])
+                    } else {
+                        datums[order.column].cmp(&key)
+                    };
+                    if cmp != Ordering::Equal {
+                        return cmp == Ordering::Greater;
+                    }
+                }
+                false
+            }
+        }
+    }
     /// Applies finishing actions to a row set.
+    ///
+    /// Rows that compare equal under `order_by` are ordered by their packed
+    /// representation, so the output is deterministic even when `order_by` is
+    /// empty.
     pub fn finish(&self, rows: &mut Vec<Row>) {
         let mut sort_by = |left: &Row, right: &Row| {
             compare_columns(&self.order_by, &left.unpack(), &right.unpack(), || {
                 left.cmp(right)
             })
         };
+        if self.after.is_some() {
+            rows.retain(|row| self.is_after_key(row));
+        }
         let offset = self.offset;
         if offset > rows.len() {
             *rows = Vec::new();
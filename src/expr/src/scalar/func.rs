@@ -41,19 +41,41 @@ use repr::adt::regex::Regex;
 use repr::{strconv, ColumnName, ColumnType, Datum, RowArena, RowPacker, ScalarType};
 
 use crate::scalar::func::format::DateTimeFormat;
-use crate::{like_pattern, EvalError, ScalarExpr};
+use crate::{like_pattern, EvalError, GlobalId, ScalarExpr};
 
 mod format;
 
 #[derive(Ord, PartialOrd, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum NullaryFunc {
     MzLogicalTimestamp,
+    /// Sleeps for the specified number of milliseconds before the query that
+    /// contains it produces results. Evaluates to `NULL` of type `void`.
+    MzSleep {
+        duration_ms: u64,
+    },
+    /// The `since` frontier of the specified index, as the coordinator
+    /// currently knows it. Evaluates to `NULL` if the frontier is empty.
+    MzIndexSince {
+        id: GlobalId,
+    },
+    /// The `upper` frontier of the specified index, as the coordinator
+    /// currently knows it. Evaluates to `NULL` if the frontier is empty.
+    MzIndexUpper {
+        id: GlobalId,
+    },
+    /// How long the coordinator has been running.
+    MzUptime,
 }
 
 impl NullaryFunc {
     pub fn output_type(&self) -> ColumnType {
         match self {
             NullaryFunc::MzLogicalTimestamp => ScalarType::Decimal(38, 0).nullable(false),
+            NullaryFunc::MzSleep { .. } => ScalarType::Void.nullable(true),
+            NullaryFunc::MzIndexSince { .. } | NullaryFunc::MzIndexUpper { .. } => {
+                ScalarType::Decimal(38, 0).nullable(true)
+            }
+            NullaryFunc::MzUptime => ScalarType::Interval.nullable(false),
         }
     }
 }
@@ -62,6 +84,10 @@ impl fmt::Display for NullaryFunc {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             NullaryFunc::MzLogicalTimestamp => f.write_str("mz_logical_timestamp"),
+            NullaryFunc::MzSleep { .. } => f.write_str("mz_sleep"),
+            NullaryFunc::MzIndexSince { .. } => f.write_str("mz_index_since"),
+            NullaryFunc::MzIndexUpper { .. } => f.write_str("mz_index_upper"),
+            NullaryFunc::MzUptime => f.write_str("mz_uptime"),
         }
     }
 }
@@ -3811,6 +3837,7 @@ where
                 stringify_datum(buf.nonnull_buffer(), d, value_type)
             }
         }),
+        Void => unreachable!("void datums are always null"),
     }
 }
 
@@ -225,6 +225,22 @@ impl ScalarExpr {
         )
     }
 
+    /// Replaces the value of every literal in the expression with a
+    /// placeholder, leaving its type intact. Errors are replaced too, as
+    /// their messages may quote the values that caused them.
+    ///
+    /// The resulting expression is only fit to be explained.
+    pub fn redact_literals(&mut self) {
+        self.visit_mut(&mut |e| {
+            if let ScalarExpr::Literal(res, _column_type) = e {
+                *res = match res {
+                    Ok(row) => Ok(explain::redact_row(row)),
+                    Err(_) => Err(EvalError::Internal(explain::REDACTED.into())),
+                };
+            }
+        });
+    }
+
     pub fn as_literal(&self) -> Option<Result<Datum, &EvalError>> {
         if let ScalarExpr::Literal(lit, _column_type) = self {
             Some(lit.as_ref().map(|row| row.unpack_first()))
@@ -858,4 +874,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_redact_literals() {
+        let mut expr = ScalarExpr::CallVariadic {
+            func: VariadicFunc::Coalesce,
+            exprs: vec![
+                ScalarExpr::literal_null(ScalarType::String.nullable(true)),
+                ScalarExpr::literal_ok(Datum::String("secret"), ScalarType::String.nullable(false)),
+                ScalarExpr::literal(
+                    Err(EvalError::InvalidTimezone("secret".into())),
+                    ScalarType::String.nullable(false),
+                ),
+                ScalarExpr::Column(0),
+            ],
+        };
+        let typ = expr.typ(&RelationType::new(vec![ScalarType::String.nullable(true)]));
+        expr.redact_literals();
+        assert_eq!(
+            expr.to_string(),
+            "coalesce(null, \"<redacted>\", (err: internal error: <redacted>), #0)"
+        );
+        assert_eq!(
+            expr.typ(&RelationType::new(vec![ScalarType::String.nullable(true)])),
+            typ
+        );
+    }
 }
@@ -1271,6 +1271,19 @@ pub struct DiffPair<T> {
     pub after: Option<T>,
 }
 
+impl<T> DiffPair<T> {
+    /// Returns the Debezium operation that this diff pair represents: `c` for
+    /// a create, `u` for an update, or `d` for a delete.
+    pub fn op(&self) -> &'static str {
+        match (&self.before, &self.after) {
+            (None, Some(_)) => "c",
+            (Some(_), Some(_)) => "u",
+            (Some(_), None) => "d",
+            (None, None) => panic!("diff pair has neither a before nor an after"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct BinlogSchemaIndices {
     /// Index of the "source" field in the payload schema
@@ -2272,7 +2285,15 @@ impl Decoder {
 ///   * Union schemas are only used to represent nullability. The first
 ///     variant is always the null variant, and the second and last variant
 ///     is the non-null variant.
-fn build_schema(columns: &[(ColumnName, ColumnType)], include_transaction: bool) -> Schema {
+///
+/// If `include_op` is set, the schema additionally contains Debezium's `op`
+/// field, which labels each record as a create (`c`), update (`u`), or delete
+/// (`d`).
+fn build_schema(
+    columns: &[(ColumnName, ColumnType)],
+    include_transaction: bool,
+    include_op: bool,
+) -> Schema {
     let row_schema = build_row_schema_json(columns, "row");
     let mut schema_fields = Vec::new();
     schema_fields.push(json!({
@@ -2288,6 +2309,13 @@ fn build_schema(columns: &[(ColumnName, ColumnType)], include_transaction: bool)
         "type": ["null", "row"],
     }));
 
+    if include_op {
+        schema_fields.push(json!({
+            "name": "op",
+            "type": "string",
+        }));
+    }
+
     // TODO(rkhaitan): this schema omits the total_order and data collection_order
     // fields found in Debezium's transaction metadata struct. We chose to omit
     // those because the order is not stable across reruns and has no semantic
@@ -2374,6 +2402,7 @@ pub struct Encoder {
     columns: Vec<(ColumnName, ColumnType)>,
     writer_schema: Schema,
     include_transaction: bool,
+    include_op: bool,
     key_schema_and_indices: Option<(Schema, Vec<usize>)>,
 }
 
@@ -2395,9 +2424,10 @@ impl Encoder {
         desc: RelationDesc,
         include_transaction: bool,
         key_indices: Option<Vec<usize>>,
+        include_op: bool,
     ) -> Self {
         let columns = column_names_and_types(desc);
-        let writer_schema = build_schema(&columns, include_transaction);
+        let writer_schema = build_schema(&columns, include_transaction, include_op);
         let key_schema_and_indices = key_indices.map(|key_indices| {
             let key_columns = key_indices
                 .iter()
@@ -2413,6 +2443,7 @@ impl Encoder {
             columns,
             writer_schema,
             include_transaction,
+            include_op,
             key_schema_and_indices,
         }
     }
@@ -2520,8 +2551,8 @@ impl Encoder {
         };
 
         // TODO [btv]: Decoding the key twice and then validating that they match is probably wasteful.
-        // But it doesn't matter for now since (1) in sinks we always have before or after populated, but not both,
-        // and (2) avro encoding for sinks is un-optimized anyway.
+        // But it doesn't matter for now since (1) in sinks we only populate both before and after for
+        // Debezium updates, which are paired up by key, and (2) avro encoding for sinks is un-optimized anyway.
         //
         // Look into it if/when sink encoding becomes a bottleneck.
         if let (Some(before_key), Some(after_key)) = (before_key.as_ref(), after_key.as_ref()) {
@@ -2537,10 +2568,20 @@ impl Encoder {
             None
         };
 
+        let op = if self.include_op {
+            Some(Value::String(diff_pair.op().to_owned()))
+        } else {
+            None
+        };
+
         let mut fields = Vec::new();
         fields.push(("before".into(), before));
         fields.push(("after".into(), after));
 
+        if let Some(op) = op {
+            fields.push(("op".into(), op));
+        }
+
         if let Some(transaction) = transaction {
             fields.push(("transaction".into(), transaction));
         }
@@ -2679,6 +2720,7 @@ impl<'a> mz_avro::types::ToAvro for TypedDatum<'a> {
                 ScalarType::List { .. } => unimplemented!("list types"),
                 ScalarType::Record { .. } => unimplemented!("record types"),
                 ScalarType::Map { .. } => unimplemented!("map types"),
+                ScalarType::Void => unimplemented!("void type"),
             };
             if typ.nullable {
                 val = Value::Union {
@@ -2800,6 +2842,7 @@ fn build_row_schema_json(
             ScalarType::List { .. } => unimplemented!("list types"),
             ScalarType::Record { .. } => unimplemented!("record types"),
             ScalarType::Map { .. } => unimplemented!("map types"),
+            ScalarType::Void => unimplemented!("void type"),
         };
         if typ.nullable {
             field_type = json!(["null", field_type]);
@@ -3226,7 +3269,7 @@ mod tests {
         for (typ, datum, expected) in valid_pairings {
             let desc = RelationDesc::empty().with_column("column1", typ.nullable(false));
             let (_, avro_value) =
-                Encoder::new(desc, false, None).row_to_avro(std::iter::once(datum));
+                Encoder::new(desc, false, None, false).row_to_avro(std::iter::once(datum));
             assert_eq!(
                 Value::Record(vec![("column1".into(), expected)]),
                 avro_value
@@ -0,0 +1,309 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! JSON encoding.
+
+use serde_json::{json, Map, Number, Value};
+
+use repr::adt::jsonb::JsonbRef;
+use repr::{strconv, ColumnName, ColumnType, Datum, RelationDesc, Row, ScalarType};
+
+use crate::avro::{column_names_and_types, DiffPair};
+
+/// Manages encoding of JSON-encoded bytes.
+///
+/// Updates are encoded as Debezium-style envelopes, i.e., as a JSON object
+/// with a `before` and an `after` field. Retractions populate `before` and
+/// insertions populate `after`; the other field is `null`. Each row is encoded
+/// as a JSON object keyed by column name. If the encoder is constructed with
+/// `include_op`, the envelope additionally contains Debezium's `op` field.
+#[derive(Debug)]
+pub struct Encoder {
+    columns: Vec<(ColumnName, ColumnType)>,
+    key_indices: Option<Vec<usize>>,
+    include_op: bool,
+}
+
+impl Encoder {
+    pub fn new(desc: RelationDesc, key_indices: Option<Vec<usize>>, include_op: bool) -> Self {
+        Encoder {
+            columns: column_names_and_types(desc),
+            key_indices,
+            include_op,
+        }
+    }
+
+    /// Encodes a diff pair as a JSON key and value.
+    ///
+    /// The key is only present if the encoder was constructed with key
+    /// indices, in which case it is a JSON object containing the key columns of
+    /// whichever of `before` or `after` is present.
+    pub fn encode_unchecked(&self, diff_pair: DiffPair<&Row>) -> (Option<Vec<u8>>, Vec<u8>) {
+        let op = diff_pair.op();
+        let (before_key, before) = match diff_pair.before {
+            None => (None, Value::Null),
+            Some(row) => self.row_to_json(row),
+        };
+        let (after_key, after) = match diff_pair.after {
+            None => (None, Value::Null),
+            Some(row) => self.row_to_json(row),
+        };
+        let key = after_key
+            .or(before_key)
+            .map(|key| key.to_string().into_bytes());
+        let mut value = json!({
+            "before": before,
+            "after": after,
+        });
+        if self.include_op {
+            value["op"] = Value::from(op);
+        }
+        (key, value.to_string().into_bytes())
+    }
+
+    fn row_to_json(&self, row: &Row) -> (Option<Value>, Value) {
+        encode_datums_as_json(row.iter(), &self.columns, self.key_indices.as_deref())
+    }
+}
+
+/// Encodes a sequence of `Datum` as JSON (key and value), using supplied column
+/// names and types.
+pub fn encode_datums_as_json<'a, I>(
+    datums: I,
+    names_types: &[(ColumnName, ColumnType)],
+    key_indices: Option<&[usize]>,
+) -> (Option<Value>, Value)
+where
+    I: IntoIterator<Item = Datum<'a>>,
+{
+    let value_fields: Vec<(String, Value)> = names_types
+        .iter()
+        .zip(datums)
+        .map(|((name, typ), datum)| (name.as_str().to_owned(), datum_to_json(datum, typ)))
+        .collect();
+    let k = key_indices.map(|key_indices| {
+        Value::Object(
+            key_indices
+                .iter()
+                .map(|&idx| value_fields[idx].clone())
+                .collect(),
+        )
+    });
+    let v = Value::Object(value_fields.into_iter().collect());
+    (k, v)
+}
+
+fn datum_to_json(datum: Datum, typ: &ColumnType) -> Value {
+    if typ.nullable && datum.is_null() {
+        Value::Null
+    } else {
+        scalar_to_json(datum, &typ.scalar_type)
+    }
+}
+
+/// Converts a datum of the specified type to JSON.
+///
+/// Numbers, booleans, strings and `jsonb` values map onto their natural JSON
+/// counterparts. Values that JSON cannot represent precisely, like decimals and
+/// temporal values, are encoded as strings in the same format that PostgreSQL
+/// uses for their text representation.
+fn scalar_to_json(datum: Datum, scalar_type: &ScalarType) -> Value {
+    if datum.is_null() {
+        return Value::Null;
+    }
+    let mut buf = String::new();
+    match scalar_type {
+        ScalarType::Bool => return Value::Bool(datum.unwrap_bool()),
+        ScalarType::Int32 | ScalarType::Oid => return Value::from(datum.unwrap_int32()),
+        ScalarType::Int64 => return Value::from(datum.unwrap_int64()),
+        ScalarType::Float32 => return float_to_json(datum.unwrap_float32().into()),
+        ScalarType::Float64 => return float_to_json(datum.unwrap_float64()),
+        ScalarType::Decimal(_, s) => {
+            strconv::format_decimal(&mut buf, &datum.unwrap_decimal().with_scale(*s));
+        }
+        ScalarType::Date => {
+            strconv::format_date(&mut buf, datum.unwrap_date());
+        }
+        ScalarType::Time => {
+            strconv::format_time(&mut buf, datum.unwrap_time());
+        }
+        ScalarType::Timestamp => {
+            strconv::format_timestamp(&mut buf, datum.unwrap_timestamp());
+        }
+        ScalarType::TimestampTz => {
+            strconv::format_timestamptz(&mut buf, datum.unwrap_timestamptz());
+        }
+        ScalarType::Interval => {
+            strconv::format_interval(&mut buf, datum.unwrap_interval());
+        }
+        ScalarType::Bytes => {
+            strconv::format_bytes(&mut buf, datum.unwrap_bytes());
+        }
+        ScalarType::String => return Value::String(datum.unwrap_str().to_owned()),
+        ScalarType::Jsonb => return JsonbRef::from_datum(datum).to_serde_json(),
+        ScalarType::Uuid => {
+            strconv::format_uuid(&mut buf, datum.unwrap_uuid());
+        }
+        ScalarType::Array(element_type) => {
+            return Value::Array(
+                datum
+                    .unwrap_array()
+                    .elements()
+                    .iter()
+                    .map(|e| scalar_to_json(e, element_type))
+                    .collect(),
+            )
+        }
+        ScalarType::List { element_type, .. } => {
+            return Value::Array(
+                datum
+                    .unwrap_list()
+                    .iter()
+                    .map(|e| scalar_to_json(e, element_type))
+                    .collect(),
+            )
+        }
+        ScalarType::Record { fields } => {
+            return Value::Object(
+                fields
+                    .iter()
+                    .zip(datum.unwrap_list().iter())
+                    .map(|((name, typ), e)| (name.as_str().to_owned(), datum_to_json(e, typ)))
+                    .collect(),
+            )
+        }
+        ScalarType::Map { value_type, .. } => {
+            return Value::Object(
+                datum
+                    .unwrap_map()
+                    .iter()
+                    .map(|(k, v)| (k.to_owned(), scalar_to_json(v, value_type)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        ScalarType::Void => return Value::Null,
+    }
+    Value::String(buf)
+}
+
+fn float_to_json(f: f64) -> Value {
+    // JSON has no representation for NaN or infinity, so fall back to the
+    // PostgreSQL spelling of those values.
+    match Number::from_f64(f) {
+        Some(n) => Value::Number(n),
+        None => {
+            let mut buf = String::new();
+            strconv::format_float64(&mut buf, f);
+            Value::String(buf)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use repr::adt::decimal::Significand;
+    use repr::{Datum, RelationDesc, RelationType, Row, ScalarType};
+
+    use super::Encoder;
+    use crate::avro::DiffPair;
+
+    #[test]
+    fn test_encode_diff_pair() {
+        let desc = RelationDesc::empty()
+            .with_column("a", ScalarType::Int64.nullable(false))
+            .with_column("b", ScalarType::String.nullable(true))
+            .with_column("c", ScalarType::Decimal(10, 2).nullable(false));
+        let encoder = Encoder::new(desc, Some(vec![0]), false);
+        let row = Row::pack_slice(&[
+            Datum::Int64(1),
+            Datum::Null,
+            Datum::Decimal(Significand::new(12345)),
+        ]);
+
+        let (key, value) = encoder.encode_unchecked(DiffPair {
+            before: None,
+            after: Some(&row),
+        });
+        assert_eq!(key.unwrap(), br#"{"a":1}"#.to_vec());
+        assert_eq!(
+            String::from_utf8(value).unwrap(),
+            r#"{"after":{"a":1,"b":null,"c":"123.45"},"before":null}"#
+        );
+
+        let (key, value) = encoder.encode_unchecked(DiffPair {
+            before: Some(&row),
+            after: None,
+        });
+        assert_eq!(key.unwrap(), br#"{"a":1}"#.to_vec());
+        assert_eq!(
+            String::from_utf8(value).unwrap(),
+            r#"{"after":null,"before":{"a":1,"b":null,"c":"123.45"}}"#
+        );
+    }
+
+    #[test]
+    fn test_encode_debezium_op() {
+        let desc = RelationDesc::empty()
+            .with_column("k", ScalarType::Int64.nullable(false))
+            .with_column("v", ScalarType::String.nullable(false));
+        let encoder = Encoder::new(desc, Some(vec![0]), true);
+        let old = Row::pack_slice(&[Datum::Int64(1), Datum::String("a")]);
+        let new = Row::pack_slice(&[Datum::Int64(1), Datum::String("b")]);
+
+        let (_, value) = encoder.encode_unchecked(DiffPair {
+            before: None,
+            after: Some(&old),
+        });
+        assert_eq!(
+            String::from_utf8(value).unwrap(),
+            r#"{"after":{"k":1,"v":"a"},"before":null,"op":"c"}"#
+        );
+
+        let (key, value) = encoder.encode_unchecked(DiffPair {
+            before: Some(&old),
+            after: Some(&new),
+        });
+        assert_eq!(key.unwrap(), br#"{"k":1}"#.to_vec());
+        assert_eq!(
+            String::from_utf8(value).unwrap(),
+            r#"{"after":{"k":1,"v":"b"},"before":{"k":1,"v":"a"},"op":"u"}"#
+        );
+
+        let (_, value) = encoder.encode_unchecked(DiffPair {
+            before: Some(&new),
+            after: None,
+        });
+        assert_eq!(
+            String::from_utf8(value).unwrap(),
+            r#"{"after":null,"before":{"k":1,"v":"b"},"op":"d"}"#
+        );
+    }
+
+    #[test]
+    fn test_encode_unnamed_columns() {
+        let desc = RelationDesc::new(
+            RelationType::new(vec![
+                ScalarType::Float64.nullable(false),
+                ScalarType::Bool.nullable(false),
+            ]),
+            vec![None::<String>, None],
+        );
+        let encoder = Encoder::new(desc, None, false);
+        let row = Row::pack_slice(&[Datum::Float64(f64::NAN.into()), Datum::True]);
+        let (key, value) = encoder.encode_unchecked(DiffPair {
+            before: None,
+            after: Some(&row),
+        });
+        assert!(key.is_none());
+        assert_eq!(
+            String::from_utf8(value).unwrap(),
+            r#"{"after":{"column1":"NaN","column2":true},"before":null}"#
+        );
+    }
+}
@@ -109,12 +109,99 @@ fn run() -> Result<(), anyhow::Error> {
         "historical detail maintained for arrangements (default 60s)",
         "DURATION/\"off\"",
     );
+    opts.optopt(
+        "",
+        "logical-compaction-hysteresis",
+        "how far an arrangement's compaction frontier must advance before compaction is requested, as a percentage of its logical compaction window (default 0)",
+        "PERCENT",
+    );
+    opts.optopt(
+        "",
+        "feedback-batch-window",
+        "how long to buffer frontier feedback from dataflow workers before applying it (default off)",
+        "DURATION/\"off\"",
+    );
+    opts.optopt(
+        "",
+        "max-peek-dataflows",
+        "maximum number of concurrent queries that require a temporary dataflow (default unlimited)",
+        "N",
+    );
+    opts.optopt(
+        "",
+        "max-peek-dataflow-wait",
+        "how long a query may wait to start a temporary dataflow before failing (default unlimited)",
+        "DURATION/\"off\"",
+    );
+    opts.optopt(
+        "",
+        "bootstrap-timeout",
+        "how long startup may spend rebuilding catalog items before failing (default unlimited)",
+        "DURATION/\"off\"",
+    );
+    opts.optopt(
+        "",
+        "idle-session-timeout",
+        "how long a connection may sit idle before it is terminated (default off)",
+        "DURATION/\"off\"",
+    );
+    opts.optopt(
+        "",
+        "max-connections",
+        "maximum number of concurrent client connections (default unlimited)",
+        "N",
+    );
+    opts.optopt(
+        "",
+        "max-indexes-per-relation",
+        &format!(
+            "maximum number of indexes on any one relation (default {})",
+            coord::DEFAULT_MAX_INDEXES_PER_RELATION
+        ),
+        "N",
+    );
+    opts.optopt(
+        "",
+        "copy-to-file-directory",
+        "directory within which COPY ... TO may write files (default disabled)",
+        "PATH",
+    );
+    opts.optopt(
+        "",
+        "copy-to-file-max-size",
+        &format!(
+            "size in bytes at which COPY ... TO a file starts a new file (default {})",
+            coord::DEFAULT_COPY_TO_FILE_MAX_SIZE
+        ),
+        "N",
+    );
+    opts.optflag(
+        "",
+        "default-source-materialization",
+        "materialize sources created with CREATE SOURCE by default",
+    );
+    opts.optflag(
+        "",
+        "object-history",
+        "record the creation, drop, and rename of each object in mz_object_history",
+    );
+    opts.optflag(
+        "",
+        "skip-failed-sink-rebuilds",
+        "start up even if some sinks cannot be rebuilt, marking them as failed",
+    );
     opts.optopt(
         "",
         "timestamp-frequency",
         "timestamp advancement frequency (default 10ms)",
         "DURATION",
     );
+    opts.optopt(
+        "",
+        "timestamp-quantum",
+        "granularity of the timestamps assigned to reads and writes (default 1ms)",
+        "DURATION",
+    );
     opts.optopt(
         "",
         "cache-max-pending-records",
@@ -261,10 +348,71 @@ fn run() -> Result<(), anyhow::Error> {
         Some("off") => None,
         Some(d) => Some(parse_duration::parse(&d)?),
     };
+    let logical_compaction_hysteresis =
+        popts.opt_get_default("logical-compaction-hysteresis", 0)?;
+    let feedback_batch_window = match popts.opt_str("feedback-batch-window").as_deref() {
+        None | Some("off") => None,
+        Some(d) => match parse_duration::parse(&d)? {
+            d if d == Duration::from_secs(0) => None,
+            d => Some(d),
+        },
+    };
+    let peek_admission = match popts.opt_get::<usize>("max-peek-dataflows")? {
+        None => None,
+        Some(max_concurrent) => Some(coord::PeekAdmissionConfig {
+            max_concurrent,
+            max_wait: match popts.opt_str("max-peek-dataflow-wait").as_deref() {
+                None | Some("off") => None,
+                Some(d) => Some(parse_duration::parse(&d)?),
+            },
+        }),
+    };
+    if peek_admission.is_none() && popts.opt_present("max-peek-dataflow-wait") {
+        bail!("--max-peek-dataflow-wait requires --max-peek-dataflows");
+    }
+    let bootstrap_timeout = match popts.opt_str("bootstrap-timeout").as_deref() {
+        None | Some("off") => None,
+        Some(d) => Some(parse_duration::parse(&d)?),
+    };
+    let idle_session_timeout = match popts.opt_str("idle-session-timeout").as_deref() {
+        None | Some("off") => None,
+        Some(d) => match parse_duration::parse(&d)? {
+            d if d == Duration::from_secs(0) => bail!("--idle-session-timeout must be positive"),
+            d => Some(d),
+        },
+    };
+    let max_connections = popts.opt_get("max-connections")?;
+    let max_indexes_per_relation = popts.opt_get_default(
+        "max-indexes-per-relation",
+        coord::DEFAULT_MAX_INDEXES_PER_RELATION,
+    )?;
+    let copy_to_file = match popts.opt_str("copy-to-file-directory") {
+        None => None,
+        Some(directory) => Some(coord::CopyToFileConfig {
+            directory: PathBuf::from(directory),
+            max_file_size: popts.opt_get_default(
+                "copy-to-file-max-size",
+                coord::DEFAULT_COPY_TO_FILE_MAX_SIZE,
+            )?,
+        }),
+    };
+    if copy_to_file.is_none() && popts.opt_present("copy-to-file-max-size") {
+        bail!("--copy-to-file-max-size requires --copy-to-file-directory");
+    }
+    let default_source_materialization = popts.opt_present("default-source-materialization");
+    let object_history = popts.opt_present("object-history");
+    let skip_failed_sink_rebuilds = popts.opt_present("skip-failed-sink-rebuilds");
     let timestamp_frequency = match popts.opt_str("timestamp-frequency").as_deref() {
         None => Duration::from_millis(10),
         Some(d) => parse_duration::parse(&d)?,
     };
+    let timestamp_quantum = match popts.opt_str("timestamp-quantum").as_deref() {
+        None => Duration::from_millis(1),
+        Some(d) => parse_duration::parse(&d)?,
+    };
+    if timestamp_quantum < Duration::from_millis(1) {
+        bail!("--timestamp-quantum must be at least 1ms");
+    }
     let cache_max_pending_records = popts.opt_get_default("cache-max-pending-records", 1000000)?;
     let timely_progress_mode = popts
         .opt_get_default(
@@ -473,13 +621,25 @@ swap: {swap_total}KB total, {swap_used}KB used",
             timely_worker,
             logging,
             logical_compaction_window,
+            logical_compaction_hysteresis,
+            feedback_batch_window,
+            peek_admission,
+            max_connections,
+            idle_session_timeout,
+            max_indexes_per_relation,
+            copy_to_file,
+            bootstrap_timeout,
             timestamp_frequency,
+            timestamp_quantum,
             cache,
             listen_addr,
             tls,
             data_directory,
             symbiosis_url,
             experimental_mode,
+            default_source_materialization,
+            object_history,
+            skip_failed_sink_rebuilds,
             telemetry_url,
         },
         runtime.clone(),
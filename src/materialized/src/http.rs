@@ -11,7 +11,8 @@
 //!
 //! materialized embeds an HTTP server for introspection into the running
 //! process. At the moment, its primary exports are Prometheus metrics, heap
-//! profiles, and catalog dumps.
+//! profiles, catalog dumps, query cancellation, per-connection resource usage,
+//! and pausing of sources.
 
 use std::pin::Pin;
 use std::time::Instant;
@@ -28,7 +29,9 @@ mod catalog;
 mod memory;
 mod metrics;
 mod prof;
+mod queries;
 mod root;
+mod sources;
 mod sql;
 mod util;
 
@@ -105,7 +108,22 @@ impl Server {
             (&Method::GET, "/memory") => self.handle_memory(req).boxed(),
             (&Method::POST, "/prof") => self.handle_prof(req).boxed(),
             (&Method::POST, "/sql") => self.handle_sql(req).boxed(),
+            (&Method::POST, "/sql/batch") => self.handle_sql_batch(req).boxed(),
             (&Method::GET, "/internal/catalog") => self.handle_internal_catalog(req).boxed(),
+            (&Method::GET, "/internal/catalog/snapshot") => {
+                self.handle_internal_catalog_snapshot(req).boxed()
+            }
+            (&Method::GET, "/internal/queries") => self.handle_internal_queries(req).boxed(),
+            (&Method::GET, "/internal/connections") => {
+                self.handle_internal_connections(req).boxed()
+            }
+            (&Method::POST, "/internal/cancel") => self.handle_internal_cancel(req).boxed(),
+            (&Method::POST, "/internal/sources/pause") => {
+                self.handle_internal_sources_pause(req).boxed()
+            }
+            (&Method::POST, "/internal/sources/resume") => {
+                self.handle_internal_sources_resume(req).boxed()
+            }
             _ => self.handle_static(req).boxed(),
         });
         let http = hyper::server::conn::Http::new();
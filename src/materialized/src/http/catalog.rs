@@ -28,4 +28,18 @@ impl Server {
                 .unwrap())
         }
     }
+
+    pub fn handle_internal_catalog_snapshot(
+        &self,
+        _: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let mut coord_client = self.coord_client.clone();
+        async move {
+            let snapshot = coord_client.snapshot_catalog().await;
+            Ok(Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(snapshot))
+                .unwrap())
+        }
+    }
 }
@@ -0,0 +1,93 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Query and connection management HTTP endpoints.
+
+use std::collections::HashMap;
+use std::future::Future;
+
+use anyhow::{anyhow, bail};
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde_json::json;
+use url::form_urlencoded;
+
+use crate::http::{util, Server};
+
+impl Server {
+    pub fn handle_internal_queries(
+        &self,
+        _: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let mut coord_client = self.coord_client.clone();
+        async move {
+            let conn_ids = coord_client.list_active_queries().await;
+            Ok(Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({ "conn_ids": conn_ids }).to_string()))
+                .unwrap())
+        }
+    }
+
+    pub fn handle_internal_connections(
+        &self,
+        _: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let mut coord_client = self.coord_client.clone();
+        async move {
+            let connections: Vec<_> = coord_client
+                .list_connections()
+                .await
+                .into_iter()
+                .map(|conn| {
+                    json!({
+                        "conn_id": conn.conn_id,
+                        "temporary_items": conn.temporary_items,
+                        "active_peeks": conn.active_peeks,
+                        "active_tails": conn.active_tails,
+                    })
+                })
+                .collect();
+            Ok(Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({ "connections": connections }).to_string(),
+                ))
+                .unwrap())
+        }
+    }
+
+    pub fn handle_internal_cancel(
+        &self,
+        req: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let mut coord_client = self.coord_client.clone();
+        async move {
+            let res = async {
+                let body = hyper::body::to_bytes(req).await?;
+                let body: HashMap<_, _> = form_urlencoded::parse(&body).collect();
+                let conn_id: u32 = match body.get("conn_id") {
+                    Some(conn_id) => conn_id
+                        .parse()
+                        .map_err(|_| anyhow!("invalid `conn_id` parameter: {}", conn_id))?,
+                    None => bail!("expected `conn_id` parameter"),
+                };
+                let canceled = coord_client.cancel_connection(conn_id).await;
+                Ok(Response::builder()
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(json!({ "canceled": canceled }).to_string()))
+                    .unwrap())
+            }
+            .await;
+            match res {
+                Ok(res) => Ok(res),
+                Err(e) => Ok(util::error_response(StatusCode::BAD_REQUEST, e.to_string())),
+            }
+        }
+    }
+}
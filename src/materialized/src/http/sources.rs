@@ -0,0 +1,47 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Source management HTTP endpoints.
+
+use std::future::Future;
+
+use hyper::{header, Body, Request, Response};
+use serde_json::json;
+
+use crate::http::Server;
+
+impl Server {
+    pub fn handle_internal_sources_pause(
+        &self,
+        _: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let mut coord_client = self.coord_client.clone();
+        async move {
+            let paused = coord_client.pause_sources().await;
+            Ok(Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({ "paused": paused }).to_string()))
+                .unwrap())
+        }
+    }
+
+    pub fn handle_internal_sources_resume(
+        &self,
+        _: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let mut coord_client = self.coord_client.clone();
+        async move {
+            let resumed = coord_client.resume_sources().await;
+            Ok(Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({ "resumed": resumed }).to_string()))
+                .unwrap())
+        }
+    }
+}
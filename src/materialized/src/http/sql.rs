@@ -17,7 +17,7 @@ use serde_json::{Number, Value};
 use url::form_urlencoded;
 
 use crate::http::{util, Server};
-use coord::ExecuteResponse;
+use coord::{ExecuteResponse, NoSessionExecuteResponse};
 use dataflow_types::PeekResponse;
 use ore::collections::CollectionExt;
 use repr::Datum;
@@ -52,6 +52,33 @@ impl Server {
             }
         }
     }
+
+    pub fn handle_sql_batch(
+        &self,
+        req: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let coord_client = self.coord_client.clone();
+        async move {
+            let res = async {
+                let body = hyper::body::to_bytes(req).await?;
+                let body: HashMap<_, _> = form_urlencoded::parse(&body).collect();
+                let sql = match body.get("sql") {
+                    Some(sql) => sql,
+                    None => bail!("expected `sql` parameter"),
+                };
+                let res = query_sql_batch_as_system(coord_client, sql.to_string()).await?;
+                Ok(Response::builder()
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(serde_json::to_string(&res)?))
+                    .unwrap())
+            }
+            .await;
+            match res {
+                Ok(res) => Ok(res),
+                Err(e) => Ok(util::error_response(StatusCode::BAD_REQUEST, e.to_string())),
+            }
+        }
+    }
 }
 
 // Execute a single SQL statement as the system user.
@@ -66,6 +93,24 @@ async fn query_sql_as_system(
     }
     let stmt = stmts.into_element();
     let res = coord_client.execute(stmt, params).await?;
+    sql_result(res).await
+}
+
+// Execute several SQL statements as the system user, all at the same
+// timestamp.
+async fn query_sql_batch_as_system(
+    mut coord_client: coord::Client,
+    sql: String,
+) -> anyhow::Result<Vec<SqlResult>> {
+    let stmts = parse_statements(&sql)?;
+    let mut results = vec![];
+    for res in coord_client.execute_batch(stmts).await? {
+        results.push(sql_result(res).await?);
+    }
+    Ok(results)
+}
+
+async fn sql_result(res: NoSessionExecuteResponse) -> anyhow::Result<SqlResult> {
     let rows = match res.response {
         ExecuteResponse::SendingRows(rows) => {
             let response = rows.await?;
@@ -76,7 +121,7 @@ async fn query_sql_as_system(
     let rows = match rows {
         PeekResponse::Rows(rows) => rows,
         PeekResponse::Error(e) => bail!("{}", e),
-        _ => bail!("unexpected PeekResponse type"),
+        PeekResponse::Canceled => bail!("canceling statement due to user request"),
     };
     let mut sql_rows: Vec<Vec<Value>> = vec![];
     for row in rows {
@@ -33,7 +33,7 @@ use tokio::sync::oneshot;
 
 use build_info::BuildInfo;
 use comm::Switchboard;
-use coord::{CacheConfig, LoggingConfig};
+use coord::{CacheConfig, CopyToFileConfig, LoggingConfig, PeekAdmissionConfig};
 use ore::thread::{JoinHandleExt, JoinOnDropHandle};
 
 use crate::mux::Mux;
@@ -42,6 +42,8 @@ mod http;
 mod mux;
 mod version_check;
 
+pub use coord::DEFAULT_MAX_INDEXES_PER_RELATION;
+
 // Disable jemalloc on macOS, as it is not well supported [0][1][2].
 // The issues present as runaway latency on load test workloads that are
 // comfortably handled by the macOS system allocator. Consider re-evaluating if
@@ -107,8 +109,43 @@ pub struct Config {
     /// able to use the arrangement in a query that has other constraints on the
     /// timestamps used (e.g. when joined with other arrangements).
     pub logical_compaction_window: Option<Duration>,
+    /// How far, as a percentage of an arrangement's logical compaction
+    /// window, the arrangement's compaction frontier must advance before
+    /// compaction is requested.
+    ///
+    /// The compaction frontier only ever advances by whole windows, so values
+    /// of 100 or less request compaction whenever possible. Larger values
+    /// reduce the number of compaction commands issued for arrangements whose
+    /// frontiers advance rapidly, at the cost of retaining more historical
+    /// detail.
+    pub logical_compaction_hysteresis: u64,
+    /// How long the coordinator buffers frontier feedback from dataflow
+    /// workers before applying it and requesting compaction. If `None`,
+    /// feedback is applied as soon as it arrives.
+    pub feedback_batch_window: Option<Duration>,
+    /// Limits on the number of concurrent peeks that require a transient
+    /// dataflow. If `None`, such peeks are not limited.
+    pub peek_admission: Option<PeekAdmissionConfig>,
+    /// The maximum number of concurrent client connections. If `None`,
+    /// connections are not limited.
+    pub max_connections: Option<usize>,
+    /// How long a client connection may sit idle before it is terminated. If
+    /// `None`, idle connections are never terminated.
+    pub idle_session_timeout: Option<Duration>,
+    /// The maximum number of indexes that may exist on a single relation.
+    pub max_indexes_per_relation: usize,
+    /// Where `COPY ... TO` may write files on the server. If `None`, copying
+    /// to files is disabled.
+    pub copy_to_file: Option<CopyToFileConfig>,
+    /// How long bootstrapping the coordinator may take before startup is
+    /// aborted. If `None`, bootstrapping may take arbitrarily long.
+    pub bootstrap_timeout: Option<Duration>,
     /// The interval at which sources should be timestamped.
     pub timestamp_frequency: Duration,
+    /// The granularity of the timestamps that the coordinator assigns to
+    /// reads and writes. The wall clock is rounded down to a multiple of this
+    /// duration, so that operations within the same quantum share a timestamp.
+    pub timestamp_quantum: Duration,
 
     // === Connection options. ===
     /// The IP address and port to listen on -- defaults to 0.0.0.0:<addr_port>,
@@ -126,6 +163,15 @@ pub struct Config {
     pub symbiosis_url: Option<String>,
     /// Whether to permit usage of experimental features.
     pub experimental_mode: bool,
+    /// Whether `CREATE SOURCE` creates materialized sources when the statement
+    /// does not say otherwise.
+    pub default_source_materialization: bool,
+    /// Whether to record the creation, drop, and rename of each object in
+    /// `mz_object_history`.
+    pub object_history: bool,
+    /// Whether a sink whose connector fails to rebuild during startup is
+    /// skipped, rather than failing startup.
+    pub skip_failed_sink_rebuilds: bool,
     /// An optional telemetry endpoint. Use None to disable telemetry.
     pub telemetry_url: Option<String>,
 }
@@ -291,7 +337,19 @@ pub async fn serve(
                 },
                 cache: config.cache,
                 logical_compaction_window: config.logical_compaction_window,
+                logical_compaction_hysteresis: config.logical_compaction_hysteresis,
+                feedback_batch_window: config.feedback_batch_window,
+                peek_admission: config.peek_admission,
+                max_connections: config.max_connections,
+                idle_session_timeout: config.idle_session_timeout,
+                max_indexes_per_relation: config.max_indexes_per_relation,
+                copy_to_file: config.copy_to_file,
+                bootstrap_timeout: config.bootstrap_timeout,
+                timestamp_quantum: config.timestamp_quantum,
                 experimental_mode: config.experimental_mode,
+                default_source_materialization: config.default_source_materialization,
+                object_history: config.object_history,
+                skip_failed_sink_rebuilds: config.skip_failed_sink_rebuilds,
                 build_info: &BUILD_INFO,
             },
             runtime,
@@ -10,8 +10,10 @@
 //! Integration tests for pgwire functionality.
 
 use std::error::Error;
-use std::io::Read;
+use std::io::{Read, Write};
+use std::net::TcpListener;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
@@ -21,6 +23,7 @@ use pgrepr::{Numeric, Record};
 use postgres::binary_copy::BinaryCopyOutIter;
 use repr::adt::decimal::Significand;
 
+use futures::future::FutureExt;
 use futures::stream::{self, StreamExt, TryStreamExt};
 use openssl::ssl::{SslConnector, SslConnectorBuilder, SslMethod, SslVerifyMode};
 use postgres::config::SslMode;
@@ -235,6 +238,449 @@ fn test_conn_params() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_row_locking_notice() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, _client) = util::start_server(util::Config::default())?;
+
+    Runtime::new()?.block_on(async {
+        let (client, mut conn) = server.pg_config_async().connect(postgres::NoTls).await?;
+        let (notice_tx, mut notice_rx) = futures::channel::mpsc::unbounded();
+        tokio::spawn(
+            stream::poll_fn(move |cx| conn.poll_message(cx))
+                .map_err(|e| panic!(e))
+                .forward(notice_tx),
+        );
+
+        // A row-locking clause is ignored, and the client is told as much.
+        assert_eq!(
+            client
+                .query_one("SELECT 1 FOR UPDATE", &[])
+                .await?
+                .get::<_, i32>(0),
+            1,
+        );
+        match notice_rx.next().await {
+            Some(tokio_postgres::AsyncMessage::Notice(n)) => {
+                assert_eq!(*n.code(), SqlState::WARNING);
+                assert_eq!(n.message(), "FOR UPDATE has no effect");
+            }
+            _ => panic!("row locking notice not generated"),
+        }
+
+        // In strict mode, the clause is rejected outright.
+        client.batch_execute("SET strict_row_locking = on").await?;
+        let err = client
+            .query_one("SELECT 1 FOR SHARE", &[])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_db_error().unwrap().message(),
+            "FOR SHARE is not supported"
+        );
+
+        Ok::<_, Box<dyn Error>>(())
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn test_optimizer_fuel_notice() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1), (2)")?;
+
+    // Plan the query without a fuel limit first, so that a fully optimized
+    // plan for it is cached.
+    let query = "SELECT t1.a FROM t t1, t t2 WHERE t1.a = t2.a ORDER BY t1.a";
+    client.query(query, &[])?;
+
+    Runtime::new()?.block_on(async {
+        let (client, mut conn) = server.pg_config_async().connect(postgres::NoTls).await?;
+        let (notice_tx, mut notice_rx) = futures::channel::mpsc::unbounded();
+        tokio::spawn(
+            stream::poll_fn(move |cx| conn.poll_message(cx))
+                .map_err(|e| panic!(e))
+                .forward(notice_tx),
+        );
+
+        // With too little fuel, the optimizer stops early, even though a fully
+        // optimized plan is cached, but the query still produces the correct
+        // results.
+        client.batch_execute("SET optimizer_fuel = 1").await?;
+        let rows = client.query(query, &[]).await?;
+        let rows: Vec<i32> = rows.into_iter().map(|row| row.get(0)).collect();
+        assert_eq!(rows, vec![1, 2]);
+        match notice_rx.next().await {
+            Some(tokio_postgres::AsyncMessage::Notice(n)) => {
+                assert_eq!(*n.code(), SqlState::WARNING);
+                assert!(n.message().starts_with("optimizer fuel exhausted"));
+            }
+            _ => panic!("optimizer fuel notice not generated"),
+        }
+
+        Ok::<_, Box<dyn Error>>(())
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn test_timestamp_notice() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, _client) = util::start_server(util::Config::default())?;
+
+    Runtime::new()?.block_on(async {
+        let (client, mut conn) = server.pg_config_async().connect(postgres::NoTls).await?;
+        let (notice_tx, mut notice_rx) = futures::channel::mpsc::unbounded();
+        tokio::spawn(
+            stream::poll_fn(move |cx| conn.poll_message(cx))
+                .map_err(|e| panic!(e))
+                .forward(notice_tx),
+        );
+
+        client.batch_execute("CREATE TABLE t (a int)").await?;
+
+        // No notices are emitted by default.
+        client.query("SELECT * FROM t", &[]).await?;
+        assert!(notice_rx.next().now_or_never().is_none());
+
+        client
+            .batch_execute("SET emit_timestamp_notices = on")
+            .await?;
+
+        // Constant-folded queries do not consult a timestamp.
+        client.query("SELECT 1", &[]).await?;
+        assert!(notice_rx.next().now_or_never().is_none());
+
+        client.query("SELECT * FROM t", &[]).await?;
+        match notice_rx.next().await {
+            Some(tokio_postgres::AsyncMessage::Notice(n)) => {
+                assert!(n.message().starts_with("query executed at timestamp "));
+            }
+            _ => panic!("timestamp notice not generated"),
+        }
+
+        Ok::<_, Box<dyn Error>>(())
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn test_result_checksum_notice() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, _client) = util::start_server(util::Config::default())?;
+
+    Runtime::new()?.block_on(async {
+        let (client, mut conn) = server.pg_config_async().connect(postgres::NoTls).await?;
+        let (notice_tx, mut notice_rx) = futures::channel::mpsc::unbounded();
+        tokio::spawn(
+            stream::poll_fn(move |cx| conn.poll_message(cx))
+                .map_err(|e| panic!(e))
+                .forward(notice_tx),
+        );
+
+        client
+            .batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1), (2), (2)")
+            .await?;
+
+        // No notices are emitted by default.
+        client.query("SELECT * FROM t", &[]).await?;
+        assert!(notice_rx.next().now_or_never().is_none());
+
+        client
+            .batch_execute("SET emit_result_checksums = on")
+            .await?;
+
+        async fn checksum(
+            client: &tokio_postgres::Client,
+            notice_rx: &mut futures::channel::mpsc::UnboundedReceiver<tokio_postgres::AsyncMessage>,
+            sql: &str,
+        ) -> Result<String, Box<dyn Error>> {
+            client.query(sql, &[]).await?;
+            match notice_rx.next().await {
+                Some(tokio_postgres::AsyncMessage::Notice(n)) => Ok(n.message().to_owned()),
+                _ => panic!("result checksum notice not generated"),
+            }
+        }
+
+        // The checksum does not depend on the order of the rows.
+        let asc = checksum(&client, &mut notice_rx, "SELECT a FROM t ORDER BY a").await?;
+        let desc = checksum(&client, &mut notice_rx, "SELECT a FROM t ORDER BY a DESC").await?;
+        assert!(asc.starts_with("result checksum "));
+        assert!(asc.ends_with(" over 3 rows"));
+        assert_eq!(asc, desc);
+
+        // But it does depend on their multiplicity.
+        let distinct = checksum(&client, &mut notice_rx, "SELECT DISTINCT a FROM t").await?;
+        assert!(distinct.ends_with(" over 2 rows"));
+        let dup = checksum(&client, &mut notice_rx, "SELECT a FROM t WHERE a = 2").await?;
+        let single = checksum(
+            &client,
+            &mut notice_rx,
+            "SELECT DISTINCT a FROM t WHERE a = 2",
+        )
+        .await?;
+        assert_ne!(
+            dup.split(' ').nth(2),
+            single.split(' ').nth(2),
+            "duplicate rows must not cancel out"
+        );
+
+        Ok::<_, Box<dyn Error>>(())
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn test_read_only_snapshot_transaction() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut reader) = util::start_server(util::Config::default())?;
+    let mut writer = server.connect()?;
+    writer.batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1)")?;
+
+    let count = |client: &mut postgres::Client| -> Result<i64, postgres::Error> {
+        Ok(client.query_one("SELECT count(*) FROM t", &[])?.get(0))
+    };
+
+    // Every query in the transaction observes the snapshot chosen for its
+    // first query, even after concurrent writes.
+    reader.batch_execute("BEGIN READ ONLY DEFERRABLE")?;
+    assert_eq!(count(&mut reader)?, 1);
+    writer.batch_execute("INSERT INTO t VALUES (2)")?;
+    assert_eq!(count(&mut writer)?, 2);
+    assert_eq!(count(&mut reader)?, 1);
+    assert_eq!(
+        reader.query("SELECT a FROM t", &[])?.len(),
+        1,
+        "snapshot not consistent across queries"
+    );
+    reader.batch_execute("COMMIT")?;
+    assert_eq!(count(&mut reader)?, 2);
+
+    // Writes are rejected, and fail the transaction.
+    reader.batch_execute("BEGIN READ ONLY DEFERRABLE")?;
+    let err = reader
+        .batch_execute("INSERT INTO t VALUES (3)")
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("cannot modify data or the catalog in a read-only transaction"));
+    reader.batch_execute("ROLLBACK")?;
+
+    // As are notifications.
+    reader.batch_execute("BEGIN READ ONLY DEFERRABLE")?;
+    let err = reader.batch_execute("NOTIFY c").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("cannot modify data or the catalog in a read-only transaction"));
+    reader.batch_execute("ROLLBACK")?;
+
+    // Neither `READ ONLY` nor `DEFERRABLE` alone pins a snapshot.
+    reader.batch_execute("BEGIN READ ONLY")?;
+    assert_eq!(count(&mut reader)?, 2);
+    writer.batch_execute("INSERT INTO t VALUES (3)")?;
+    assert_eq!(count(&mut reader)?, 3);
+    reader.batch_execute("COMMIT")?;
+
+    // A query with an explicit `AS OF` neither observes nor chooses the
+    // snapshot.
+    let ts = writer
+        .query_one("SELECT mz_logical_timestamp() FROM t LIMIT 1", &[])?
+        .get::<_, util::MzTimestamp>(0)
+        .0;
+    reader.batch_execute("BEGIN READ ONLY DEFERRABLE")?;
+    let as_of = format!("SELECT count(*) FROM t AS OF {}", ts);
+    assert_eq!(reader.query_one(&*as_of, &[])?.get::<_, i64>(0), 3);
+    writer.batch_execute("INSERT INTO t VALUES (4)")?;
+    assert_eq!(count(&mut writer)?, 4);
+    assert_eq!(count(&mut reader)?, 4);
+    writer.batch_execute("INSERT INTO t VALUES (5)")?;
+    assert_eq!(count(&mut reader)?, 4);
+    reader.batch_execute("COMMIT")?;
+
+    Ok(())
+}
+
+// Ensures that a canceled query fails with the `query_canceled` error code,
+// rather than completing as if it had returned no rows.
+#[test]
+fn test_cancel_query() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    // Admit no peeks that require a temporary dataflow, so that such peeks
+    // remain active until they are canceled.
+    let config = util::Config::default().peek_admission(0, None);
+    let (_server, mut client) = util::start_server(config)?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+
+    let cancel_token = client.cancel_token();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || tx.send(client.query("SELECT count(*) FROM t", &[])));
+
+    // The cancellation is a no-op if it arrives before the query, so retry
+    // until the query finishes.
+    let res = loop {
+        cancel_token.cancel_query(postgres::NoTls)?;
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(res) => break res,
+            Err(mpsc::RecvTimeoutError::Timeout) => (),
+            Err(e) => return Err(e.into()),
+        }
+    };
+    let err = res.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+    assert!(err
+        .to_string()
+        .contains("canceling statement due to user request"));
+
+    Ok(())
+}
+
+// Ensures that a statement canceled while it is being purified fails with the
+// `query_canceled` error code, rather than executing once purification
+// completes.
+#[test]
+fn test_cancel_during_purification() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    // Create a listener that will simulate a slow Confluent Schema Registry.
+    let listener = TcpListener::bind("localhost:0")?;
+    let listener_port = listener.local_addr()?.port();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    let cancel_token = client.cancel_token();
+    let slow_thread = thread::spawn(move || {
+        let res = client.batch_execute(&format!(
+            "CREATE SOURCE foo \
+             FROM KAFKA BROKER 'localhost:9092' TOPIC 'foo' \
+             FORMAT AVRO USING CONFLUENT SCHEMA REGISTRY 'http://localhost:{}'",
+            listener_port,
+        ));
+        (client, res)
+    });
+
+    // Wait for the coordinator to contact the schema registry, which
+    // indicates the statement is being purified, then cancel it.
+    let (mut stream, _) = listener.accept()?;
+    cancel_token.cancel_query(postgres::NoTls)?;
+
+    // The cancel request is delivered asynchronously, so give the coordinator
+    // a moment to process it. Then respond with an error; the cancellation
+    // must take precedence.
+    thread::sleep(Duration::from_millis(500));
+    write!(stream, "HTTP/1.1 503 Service Unavailable\r\n\r\n")?;
+    drop(stream);
+
+    let (mut client, res) = slow_thread.join().unwrap();
+    let err = res.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+
+    // The session remains usable, and its next statement is unaffected by the
+    // earlier cancellation.
+    let answer: i32 = client.query_one("SELECT 1 + 1", &[])?.get(0);
+    assert_eq!(answer, 2);
+
+    // Other sessions are likewise unaffected.
+    let mut client = server.connect()?;
+    let answer: i32 = client.query_one("SELECT 1 + 1", &[])?.get(0);
+    assert_eq!(answer, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_max_connections() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().max_connections(2);
+    let (server, mut client) = util::start_server(config)?;
+
+    // The connection returned by `start_server` counts against the limit, so
+    // only one more connection is admitted.
+    let other_client = server.connect()?;
+    match server.connect() {
+        Ok(_) => panic!("connection unexpectedly admitted"),
+        Err(e) => {
+            let e = e.downcast::<postgres::Error>().unwrap();
+            assert_eq!(e.code(), Some(&SqlState::TOO_MANY_CONNECTIONS));
+        }
+    }
+
+    // Closing a connection frees up a slot, though the coordinator learns about
+    // the closed connection asynchronously.
+    drop(other_client);
+    let mut attempts = 0;
+    let mut other_client = loop {
+        match server.connect() {
+            Ok(client) => break client,
+            Err(_) if attempts < 50 => {
+                attempts += 1;
+                thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => return Err(e),
+        }
+    };
+
+    // Rejected connections do not leak temporary schemas or otherwise disturb
+    // the admitted connections.
+    assert_eq!(client.query_one("SELECT 1", &[])?.get::<_, i32>(0), 1);
+    assert_eq!(other_client.query_one("SELECT 2", &[])?.get::<_, i32>(0), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_idle_session_timeout() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().idle_session_timeout(Duration::from_secs(1));
+    let (server, mut client) = util::start_server(config)?;
+
+    let mut idle_client = server.connect()?;
+    idle_client.batch_execute("CREATE TEMPORARY VIEW v AS SELECT 1")?;
+
+    // Keep `client` busy while `idle_client` sits idle, until the coordinator
+    // has terminated `idle_client` and forgotten its session.
+    let mut attempts = 0;
+    loop {
+        let sessions: i64 = client
+            .query_one("SELECT count(*) FROM mz_sessions", &[])?
+            .get(0);
+        if sessions == 1 {
+            break;
+        }
+        assert!(attempts < 50, "idle session was never terminated");
+        attempts += 1;
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    // The temporary view went with the session.
+    let views: i64 = client
+        .query_one("SELECT count(*) FROM mz_views WHERE name = 'v'", &[])?
+        .get(0);
+    assert_eq!(views, 0);
+
+    // The terminated connection's next command fails.
+    match idle_client.query_one("SELECT 1", &[]) {
+        Ok(_) => panic!("query unexpectedly succeeded on terminated connection"),
+        Err(e) => assert_eq!(e.code(), Some(&SqlState::ADMIN_SHUTDOWN)),
+    }
+
+    // A connection that stays active is not terminated.
+    assert_eq!(client.query_one("SELECT 1", &[])?.get::<_, i32>(0), 1);
+
+    Ok(())
+}
+
 #[test]
 fn test_multiple_statements() -> Result<(), Box<dyn Error>> {
     ore::test::init_logging();
@@ -285,6 +731,60 @@ fn test_simple_query_no_hang() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_listen_notify() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    // A coarse timestamp quantum assigns consecutive writes the same
+    // timestamp, so a notification whose retraction shared its timestamp would
+    // never be delivered.
+    let config = util::Config::default().timestamp_quantum(Duration::from_secs(3600));
+    let (server, mut listener) = util::start_server(config)?;
+    let mut notifier = server.connect()?;
+
+    listener.batch_execute("LISTEN c1; LISTEN c2")?;
+    notifier.batch_execute("NOTIFY c1, 'hello'; NOTIFY other, 'ignored'; NOTIFY c2")?;
+
+    // The listener is idle, so the notifications must arrive without it
+    // issuing another query.
+    let notifications: Vec<_> = listener
+        .notifications()
+        .timeout_iter(Duration::from_secs(30))
+        .take(2)
+        .collect()?;
+    let mut notifications: Vec<_> = notifications
+        .iter()
+        .map(|n| (n.channel().to_owned(), n.payload().to_owned()))
+        .collect();
+    notifications.sort();
+    assert_eq!(
+        notifications,
+        vec![
+            ("c1".to_owned(), "hello".to_owned()),
+            ("c2".to_owned(), "".to_owned()),
+        ]
+    );
+
+    // After UNLISTEN, notifications on the channel are no longer delivered.
+    listener.batch_execute("UNLISTEN c1")?;
+    notifier.batch_execute("NOTIFY c1, 'dropped'; NOTIFY c2, 'kept'")?;
+    let notification = listener
+        .notifications()
+        .timeout_iter(Duration::from_secs(30))
+        .next()?
+        .expect("notification not delivered");
+    assert_eq!(notification.channel(), "c2");
+    assert_eq!(notification.payload(), "kept");
+
+    // Payloads are limited in size.
+    let err = notifier
+        .batch_execute(&format!("NOTIFY c2, '{}'", "a".repeat(8001)))
+        .unwrap_err();
+    assert!(err.to_string().contains("payload string too long"));
+
+    Ok(())
+}
+
 #[test]
 fn test_copy() -> Result<(), Box<dyn Error>> {
     ore::test::init_logging();
@@ -324,6 +824,358 @@ fn test_copy() -> Result<(), Box<dyn Error>> {
         assert_eq!(buf, "\\N\t2\n\\t\t4\n");
     }
 
+    // Test CSV COPY, which quotes values only when necessary.
+    {
+        let mut buf = String::new();
+        client
+            .copy_out("COPY (SELECT 1 WHERE FALSE) TO STDOUT (FORMAT CSV, HEADER)")?
+            .read_to_string(&mut buf)?;
+        assert_eq!(buf, "?column?\n");
+
+        let mut buf = String::new();
+        client
+            .copy_out(
+                "COPY (VALUES (NULL, 'a,b', 'c\"d', '', 'e')) TO STDOUT (FORMAT CSV, HEADER)",
+            )?
+            .read_to_string(&mut buf)?;
+        assert_eq!(
+            buf,
+            "column1,column2,column3,column4,column5\n,\"a,b\",\"c\"\"d\",\"\",e\n"
+        );
+
+        let mut buf = String::new();
+        client
+            .copy_out(
+                "COPY (VALUES (NULL, 'a|b', 'c''d')) TO STDOUT \
+                 (FORMAT CSV, DELIMITER '|', QUOTE '''', ESCAPE '\\', NULL 'NULL')",
+            )?
+            .read_to_string(&mut buf)?;
+        assert_eq!(buf, "NULL|'a|b'|'c\\'d'\n");
+    }
+
+    // Test the representations of non-finite floats in CSV COPY.
+    {
+        let query = |options: &str| {
+            format!(
+                "COPY (SELECT 'NaN'::float8, 'Infinity'::float8, '-Infinity'::float4, 1.5::float8) \
+                 TO STDOUT (FORMAT CSV{})",
+                options
+            )
+        };
+        for (options, expected) in &[
+            ("", "NaN,Infinity,-Infinity,1.5\n"),
+            (", NONFINITE_FLOATS 'text'", "NaN,Infinity,-Infinity,1.5\n"),
+            (", NONFINITE_FLOATS 'null'", ",,,1.5\n"),
+            (
+                ", NONFINITE_FLOATS 'null', NULL 'NULL'",
+                "NULL,NULL,NULL,1.5\n",
+            ),
+        ] {
+            let mut buf = String::new();
+            client
+                .copy_out(&*query(options))?
+                .read_to_string(&mut buf)?;
+            assert_eq!(buf, *expected, "options: {}", options);
+        }
+
+        for (value, typ) in &[
+            ("NaN", "float8"),
+            ("Infinity", "float4"),
+            ("-Infinity", "float8"),
+        ] {
+            let mut buf = String::new();
+            let err = client
+                .copy_out(&*format!(
+                    "COPY (SELECT '{}'::{}) TO STDOUT (FORMAT CSV, NONFINITE_FLOATS 'error')",
+                    value, typ
+                ))?
+                .read_to_string(&mut buf)
+                .unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains(&format!("COPY cannot represent {} in CSV output", value)),
+                "unexpected error: {}",
+                err
+            );
+        }
+
+        // The connection remains usable after a failed COPY.
+        assert_eq!(client.query_one("SELECT 1", &[])?.get::<_, i32>(0), 1);
+    }
+
+    // Test that decimals are rendered with their column's declared scale,
+    // including any trailing zeros, in both text and CSV COPY.
+    {
+        client.batch_execute(
+            "CREATE TABLE copy_decimals (a numeric(10, 2), b numeric(10, 4), c numeric(10, 0));
+             INSERT INTO copy_decimals VALUES (1.5, 2, 3), (-0.1, 0, NULL)",
+        )?;
+        for (format, expected) in &[
+            ("TEXT", "-0.10\t0.0000\t\\N\n1.50\t2.0000\t3\n"),
+            ("CSV", "-0.10,0.0000,\n1.50,2.0000,3\n"),
+        ] {
+            let mut buf = String::new();
+            client
+                .copy_out(&*format!(
+                    "COPY (SELECT * FROM copy_decimals ORDER BY a) TO STDOUT (FORMAT {})",
+                    format
+                ))?
+                .read_to_string(&mut buf)?;
+            assert_eq!(buf, *expected, "format: {}", format);
+        }
+
+        // Decimals of large scale, or nested in other types, keep their scale,
+        // too.
+        let mut buf = String::new();
+        client
+            .copy_out(
+                "COPY (SELECT 0.1::numeric(38, 10), LIST[1.5::numeric(5, 2)]) \
+                 TO STDOUT (FORMAT CSV)",
+            )?
+            .read_to_string(&mut buf)?;
+        assert_eq!(buf, "0.1000000000,{1.50}\n");
+    }
+
+    // Test that orderless queries, whose rows are streamed as they arrive from
+    // the workers, copy out every row exactly once, and that ordered queries,
+    // whose rows are buffered, copy out in order.
+    {
+        client.batch_execute(
+            "CREATE TABLE copy_stream (a int, b text);
+             INSERT INTO copy_stream SELECT n, 'v' || n FROM generate_series(1, 100) AS n",
+        )?;
+
+        let mut buf = String::new();
+        client
+            .copy_out("COPY (SELECT b, a + 1 FROM copy_stream WHERE a % 2 = 0) TO STDOUT")?
+            .read_to_string(&mut buf)?;
+        let mut lines: Vec<_> = buf.lines().collect();
+        lines.sort_by_key(|line| line.split('\t').nth(1).unwrap().parse::<i32>().unwrap());
+        let expected: Vec<_> = (1..=50)
+            .map(|n| format!("v{}\t{}", 2 * n, 2 * n + 1))
+            .collect();
+        assert_eq!(lines, expected);
+
+        let mut buf = String::new();
+        client
+            .copy_out("COPY (SELECT a FROM copy_stream ORDER BY a DESC LIMIT 3) TO STDOUT")?
+            .read_to_string(&mut buf)?;
+        assert_eq!(buf, "100\n99\n98\n");
+    }
+
+    // Test that invalid CSV options are rejected.
+    {
+        for options in &[
+            "FORMAT CSV, DELIMITER '||'",
+            "FORMAT CSV, QUOTE ''",
+            "FORMAT CSV, DELIMITER '\"'",
+            "FORMAT TEXT, HEADER",
+            "FORMAT TEXT, NONFINITE_FLOATS 'null'",
+            "FORMAT CSV, NONFINITE_FLOATS 'bogus'",
+        ] {
+            let query = format!("COPY (SELECT 1) TO STDOUT ({})", options);
+            assert!(client.copy_out(&*query).is_err(), "{} succeeded", query);
+        }
+    }
+
+    // Test COPY of a named relation, optionally selecting and reordering its
+    // columns.
+    {
+        client.batch_execute(
+            "CREATE TABLE copy_t (a int, b text, c int);
+             INSERT INTO copy_t VALUES (1, 'x', 10), (2, 'y', 20)",
+        )?;
+        for (columns, expected) in &[
+            ("", "a,b,c\n1,x,10\n2,y,20\n"),
+            ("(c, a)", "c,a\n10,1\n20,2\n"),
+            ("(b)", "b\nx\ny\n"),
+            ("(\"b\", C)", "b,c\nx,10\ny,20\n"),
+        ] {
+            let mut buf = String::new();
+            client
+                .copy_out(&*format!(
+                    "COPY copy_t {} TO STDOUT (FORMAT CSV, HEADER)",
+                    columns
+                ))?
+                .read_to_string(&mut buf)?;
+            let mut lines: Vec<_> = buf.lines().collect();
+            lines[1..].sort();
+            assert_eq!(lines.join("\n") + "\n", *expected, "columns: {}", columns);
+        }
+
+        for (columns, expected) in &[
+            (
+                "(a, d)",
+                "column \"d\" of relation \"copy_t\" does not exist",
+            ),
+            ("(a, b, a)", "column \"a\" specified more than once"),
+        ] {
+            let err = client
+                .copy_out(&*format!("COPY copy_t {} TO STDOUT", columns))
+                .unwrap_err();
+            assert!(
+                err.to_string().contains(expected),
+                "unexpected error: {}",
+                err
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_copy_to_file() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let dir = tempfile::tempdir()?;
+    let config = util::Config::default().copy_to_file(dir.path().to_path_buf(), 4);
+    let (_server, mut client) = util::start_server(config)?;
+
+    // Each row encodes to two bytes, so every file but the last should hold
+    // two rows.
+    let files: Vec<(String, i64, i64)> = client
+        .query(
+            "COPY (SELECT generate_series(1, 5) ORDER BY 1) TO 'out'",
+            &[],
+        )?
+        .into_iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2)))
+        .collect();
+    let expected = &[
+        ("out-0001.txt", 2, 4, "1\n2\n"),
+        ("out-0002.txt", 2, 4, "3\n4\n"),
+        ("out-0003.txt", 1, 2, "5\n"),
+    ];
+    assert_eq!(files.len(), expected.len());
+    let dir_path = dir.path().canonicalize()?;
+    for ((path, rows, bytes), (name, expected_rows, expected_bytes, contents)) in
+        files.iter().zip(expected)
+    {
+        assert_eq!(Path::new(path), dir_path.join(name));
+        assert_eq!(rows, expected_rows);
+        assert_eq!(bytes, expected_bytes);
+        assert_eq!(std::fs::read_to_string(path)?, *contents);
+    }
+
+    // An empty result still produces a file, with a header if requested.
+    let files = client.query(
+        "COPY (SELECT 1 AS a WHERE FALSE) TO 'empty' WITH (FORMAT CSV, HEADER)",
+        &[],
+    )?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].get::<_, i64>(1), 0);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("empty-0001.csv"))?,
+        "a\n"
+    );
+
+    // Existing files are never overwritten.
+    let err = client
+        .query("COPY (SELECT 1) TO 'empty' WITH (FORMAT CSV)", &[])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("File exists"), "{}", err);
+
+    // Files may not be written outside of the configured directory.
+    for path in &["/out", "../out", "missing/out"] {
+        let query = format!("COPY (SELECT 1) TO '{}'", path);
+        assert!(client.query(&*query, &[]).is_err(), "{} succeeded", query);
+    }
+
+    // Copying to files is disabled unless a directory is configured.
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    let err = client
+        .query("COPY (SELECT 1) TO 'out'", &[])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("COPY to a file is not enabled"), "{}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_timezone() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let dir = tempfile::tempdir()?;
+    let config = util::Config::default().copy_to_file(dir.path().to_path_buf(), 1 << 20);
+    let (_server, mut client) = util::start_server(config)?;
+
+    let query = "SELECT TIMESTAMPTZ '2021-01-15 12:30:45.5+00', TIMESTAMP '2021-01-15 12:30:45'";
+    let select = |client: &mut postgres::Client| -> Result<Vec<String>, Box<dyn Error>> {
+        match client.simple_query(query)?.into_first() {
+            SimpleQueryMessage::Row(row) => Ok((0..row.len())
+                .map(|i| row.get(i).unwrap().to_owned())
+                .collect()),
+            _ => panic!("unexpected simple query message"),
+        }
+    };
+
+    // Timestamps with time zones are displayed in UTC by default.
+    assert_eq!(
+        client.query_one("SHOW timezone", &[])?.get::<_, String>(0),
+        "UTC"
+    );
+    assert_eq!(
+        select(&mut client)?,
+        &["2021-01-15 12:30:45.5+00", "2021-01-15 12:30:45"]
+    );
+
+    // Once the session's time zone is set, they are displayed in that time
+    // zone. Timestamps without time zones are unaffected.
+    client.batch_execute("SET TIME ZONE 'America/New_York'")?;
+    assert_eq!(
+        client.query_one("SHOW timezone", &[])?.get::<_, String>(0),
+        "America/New_York"
+    );
+    assert_eq!(
+        select(&mut client)?,
+        &["2021-01-15 07:30:45.5-05", "2021-01-15 12:30:45"]
+    );
+
+    // Time zone names are case insensitive, and offsets with minutes are
+    // displayed in full.
+    client.batch_execute("SET timezone = 'asia/kolkata'")?;
+    assert_eq!(
+        client.query_one("SHOW timezone", &[])?.get::<_, String>(0),
+        "Asia/Kolkata"
+    );
+    assert_eq!(
+        select(&mut client)?,
+        &["2021-01-15 18:00:45.5+05:30", "2021-01-15 12:30:45"]
+    );
+
+    // COPY uses the session's time zone too, both to STDOUT and to files.
+    let mut buf = String::new();
+    client
+        .copy_out("COPY (SELECT TIMESTAMPTZ '2021-01-15 12:30:45.5+00') TO STDOUT")?
+        .read_to_string(&mut buf)?;
+    assert_eq!(buf, "2021-01-15 18:00:45.5+05:30\n");
+    let files = client.query(
+        "COPY (SELECT TIMESTAMPTZ '2021-01-15 12:30:45.5+00') TO 'out' WITH (FORMAT CSV)",
+        &[],
+    )?;
+    assert_eq!(
+        std::fs::read_to_string(files[0].get::<_, String>(0))?,
+        "2021-01-15 18:00:45.5+05:30\n"
+    );
+
+    // Unknown time zones are rejected, and leave the time zone unchanged.
+    let err = client
+        .batch_execute("SET TIME ZONE 'Mars/Olympus_Mons'")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("invalid value for parameter TimeZone: \"Mars/Olympus_Mons\""),
+        "{}",
+        err
+    );
+    assert_eq!(
+        client.query_one("SHOW timezone", &[])?.get::<_, String>(0),
+        "Asia/Kolkata"
+    );
+
     Ok(())
 }
 
@@ -11,6 +11,10 @@
 
 use std::collections::HashMap;
 use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use reqwest::{blocking::Client, StatusCode, Url};
 use tempfile::NamedTempFile;
@@ -87,10 +91,10 @@ fn test_persistence() -> Result<(), Box<dyn Error>> {
                 "s3009", "s3010", "s3011", "s3012", "s3013", "s3014", "s3015", "s3016", "s3017",
                 "s3018", "s3019", "s3020", "s3021", "s3022", "s3023", "s3024", "s3025", "s3026",
                 "s3027", "s3028", "s3029", "s3030", "s3031", "s3032", "s3033", "s3034", "s3035",
-                "s3036", "s4000", "s4001", "s4002", "s4003", "s4004", "s4005", "s4006", "s4007",
-                "s4008", "s4009", "s4010", "s4011", "s4012", "s4013", "s4014", "s4015", "s4016",
-                "s4017", "s4018", "s4019", "s4020", "s4021", "s4022", "s4023", "s4024", "u1", "u2",
-                "u3", "u4", "u5", "u6"
+                "s3036", "s3037", "s3038", "s3039", "s3040", "s4000", "s4001", "s4002", "s4003",
+                "s4004", "s4005", "s4006", "s4007", "s4008", "s4009", "s4010", "s4011", "s4012",
+                "s4013", "s4014", "s4015", "s4016", "s4017", "s4018", "s4019", "s4020", "s4021",
+                "s4022", "s4023", "s4024", "u1", "u2", "u3", "u4", "u5", "u6"
             ]
         );
     }
@@ -110,6 +114,50 @@ fn test_persistence() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Ensures that comments survive a reboot, and that comments removed before
+// the reboot stay removed.
+#[test]
+fn test_comment_persistence() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let data_dir = tempfile::tempdir()?;
+    let config = util::Config::default().data_directory(data_dir.path());
+
+    {
+        let (_server, mut client) = util::start_server(config.clone())?;
+        client.batch_execute(
+            "CREATE TABLE t (a int, b text); \
+             CREATE VIEW v AS SELECT 1; \
+             COMMENT ON TABLE t IS 'a table'; \
+             COMMENT ON COLUMN t.b IS 'a column'; \
+             COMMENT ON VIEW v IS 'a view'; \
+             COMMENT ON VIEW v IS NULL;",
+        )?;
+    }
+
+    {
+        let (_server, mut client) = util::start_server(config)?;
+        assert_eq!(
+            client
+                .query(
+                    "SELECT o.name, c.column_position, c.comment \
+                     FROM mz_catalog.mz_comments c JOIN mz_catalog.mz_objects o ON c.id = o.id \
+                     ORDER BY 3",
+                    &[],
+                )?
+                .into_iter()
+                .map(|row| (row.get(0), row.get(1), row.get(2)))
+                .collect::<Vec<(String, Option<i64>, String)>>(),
+            &[
+                ("t".into(), Some(2), "a column".into()),
+                ("t".into(), None, "a table".into()),
+            ]
+        );
+    }
+
+    Ok(())
+}
+
 // Ensures that once a node is started with `--experimental`, it requires
 // `--experimental` on reboot.
 #[test]
@@ -169,6 +217,150 @@ fn test_experimental_mode_on_init_or_never() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Ensures that a catalog opened by a newer version of materialized is rejected.
+#[test]
+fn test_newer_catalog_rejected() -> Result<(), Box<dyn Error>> {
+    let data_dir = tempfile::tempdir()?;
+    let config = util::Config::default().data_directory(data_dir.path());
+
+    {
+        let (_server, _) = util::start_server(config.clone())?;
+    }
+
+    {
+        let (_server, _) = util::start_server(config.clone())?;
+    }
+
+    let catalog = rusqlite::Connection::open(data_dir.path().join("catalog"))?;
+    catalog.execute(
+        "UPDATE settings SET value = '999.0.0' WHERE name = 'build_version'",
+        rusqlite::params![],
+    )?;
+    drop(catalog);
+
+    match util::start_server(config) {
+        Ok((_server, _)) => panic!("unexpected success"),
+        Err(e) => {
+            if !e
+                .to_string()
+                .contains("catalog was last opened by materialized v999.0.0")
+            {
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Ensures that a placeholder sink orphaned by a crash during the sink's
+// creation is removed on reboot, while completed sinks survive.
+#[test]
+fn test_orphaned_placeholder_sink_removed() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let data_dir = tempfile::tempdir()?;
+    let config = util::Config::default().data_directory(data_dir.path());
+
+    {
+        let (_server, mut client) = util::start_server(config.clone())?;
+        client.batch_execute(&format!(
+            "CREATE MATERIALIZED VIEW v AS SELECT 1; \
+             CREATE SINK complete FROM v INTO AVRO OCF '{}'; \
+             CREATE SINK orphaned FROM v INTO AVRO OCF '{}';",
+            data_dir.path().join("complete.ocf").display(),
+            data_dir.path().join("orphaned.ocf").display(),
+        ))?;
+    }
+
+    // Simulate a crash after the placeholder for `orphaned` was committed but
+    // before its connector was ready.
+    let catalog = rusqlite::Connection::open(data_dir.path().join("catalog"))?;
+    catalog.execute(
+        "INSERT INTO pending_items SELECT gid FROM items WHERE name = 'orphaned'",
+        rusqlite::params![],
+    )?;
+    drop(catalog);
+
+    {
+        let (_server, mut client) = util::start_server(config)?;
+        assert_eq!(
+            client
+                .query("SHOW SINKS", &[])?
+                .into_iter()
+                .map(|row| row.get(0))
+                .collect::<Vec<String>>(),
+            &["complete"]
+        );
+    }
+
+    let catalog = rusqlite::Connection::open(data_dir.path().join("catalog"))?;
+    let pending: i64 = catalog.query_row(
+        "SELECT count(*) FROM pending_items",
+        rusqlite::params![],
+        |row| row.get(0),
+    )?;
+    assert_eq!(pending, 0);
+
+    Ok(())
+}
+
+// Ensures that a sink whose connector cannot be rebuilt fails startup, unless
+// failed sink rebuilds are skipped, in which case the sink is marked as failed
+// and can be dropped.
+#[test]
+fn test_skip_failed_sink_rebuilds() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let data_dir = tempfile::tempdir()?;
+    let sink_dir = data_dir.path().join("sinks");
+    fs::create_dir(&sink_dir)?;
+    let config = util::Config::default().data_directory(data_dir.path());
+
+    {
+        let (_server, mut client) = util::start_server(config.clone())?;
+        client.batch_execute(&format!(
+            "CREATE MATERIALIZED VIEW v AS SELECT 1; \
+             CREATE SINK healthy FROM v INTO AVRO OCF '{}'; \
+             CREATE SINK broken FROM v INTO AVRO OCF '{}';",
+            data_dir.path().join("healthy.ocf").display(),
+            sink_dir.join("broken.ocf").display(),
+        ))?;
+    }
+
+    // Rebuilding `broken` requires creating a new file in `sink_dir`.
+    fs::remove_dir_all(&sink_dir)?;
+
+    assert!(util::start_server(config.clone()).is_err());
+
+    {
+        let (_server, mut client) = util::start_server(config.clone().skip_failed_sink_rebuilds())?;
+        let statuses: Vec<(String, String)> = client
+            .query(
+                "SELECT name, status FROM mz_sink_status \
+                 JOIN mz_catalog_names ON sink_id = global_id \
+                 ORDER BY name",
+                &[],
+            )?
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
+        assert_eq!(
+            statuses,
+            &[
+                ("materialize.public.broken".into(), "failed".into()),
+                ("materialize.public.healthy".into(), "running".into()),
+            ]
+        );
+        client.batch_execute("DROP SINK broken")?;
+    }
+
+    // With the broken sink dropped, startup succeeds without skipping it.
+    util::start_server(config)?;
+
+    Ok(())
+}
+
 // Test the /sql POST endpoint of the HTTP server.
 #[test]
 fn test_http_sql() -> Result<(), Box<dyn Error>> {
@@ -212,3 +404,683 @@ fn test_http_sql() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+// Test the /sql/batch POST endpoint of the HTTP server.
+#[test]
+fn test_http_sql_batch() -> Result<(), Box<dyn Error>> {
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1), (2)")?;
+    let url = Url::parse(&format!("http://{}/sql/batch", server.inner.local_addr()))?;
+    let mut params = HashMap::new();
+
+    struct TestCase {
+        query: &'static str,
+        status: StatusCode,
+        body: &'static str,
+    }
+
+    let tests = vec![
+        // Each query produces its own result.
+        TestCase {
+            query: "select 1+2 as col; select a from t order by a; select count(*) as c from t",
+            status: StatusCode::OK,
+            body: r#"[{"rows":[[3]],"col_names":["col"]},{"rows":[[1],[2]],"col_names":["a"]},{"rows":[[2]],"col_names":["c"]}]"#,
+        },
+        // Queries cannot choose their own timestamp.
+        TestCase {
+            query: "select 1; select a from t as of 0",
+            status: StatusCode::BAD_REQUEST,
+            body: r#"queries in a batch cannot use AS OF or COPY ... TO"#,
+        },
+        // CREATEs should not work.
+        TestCase {
+            query: "select 1; create view v as select 1",
+            status: StatusCode::BAD_REQUEST,
+            body: r#"unsupported plan"#,
+        },
+    ];
+
+    for tc in tests {
+        params.insert("sql", tc.query);
+        let res = Client::new().post(url.clone()).form(&params).send()?;
+        assert_eq!(res.status(), tc.status);
+        assert_eq!(res.text()?, tc.body);
+    }
+
+    Ok(())
+}
+
+// Test that queries running on other connections can be listed and canceled via
+// the /internal/queries and /internal/cancel endpoints of the HTTP server.
+#[test]
+fn test_http_cancel() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    // Admit no peeks that require a temporary dataflow, so that such peeks
+    // remain active until they are canceled.
+    let config = util::Config::default().peek_admission(0, None);
+    let (server, mut client) = util::start_server(config)?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+
+    let addr = server.inner.local_addr();
+    let queries_url = Url::parse(&format!("http://{}/internal/queries", addr))?;
+    let cancel_url = Url::parse(&format!("http://{}/internal/cancel", addr))?;
+
+    let list_active_queries = || -> Result<Vec<u32>, Box<dyn Error>> {
+        let res = Client::new().get(queries_url.clone()).send()?;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res: serde_json::Value = serde_json::from_str(&res.text()?)?;
+        Ok(serde_json::from_value(res["conn_ids"].clone())?)
+    };
+    let cancel = |conn_id: &str| -> Result<(StatusCode, String), Box<dyn Error>> {
+        let mut params = HashMap::new();
+        params.insert("conn_id", conn_id);
+        let res = Client::new()
+            .post(cancel_url.clone())
+            .form(&params)
+            .send()?;
+        Ok((res.status(), res.text()?))
+    };
+
+    assert_eq!(list_active_queries()?, Vec::<u32>::new());
+
+    let query_thread = thread::spawn(move || client.query("SELECT count(*) FROM t", &[]));
+    let conn_id = loop {
+        match list_active_queries()?.as_slice() {
+            [] => thread::sleep(Duration::from_millis(10)),
+            [conn_id] => break *conn_id,
+            conn_ids => panic!("unexpected active queries: {:?}", conn_ids),
+        }
+    };
+
+    assert_eq!(
+        cancel(&conn_id.to_string())?,
+        (StatusCode::OK, r#"{"canceled":true}"#.into())
+    );
+    match query_thread.join().unwrap() {
+        Ok(_) => panic!("query unexpectedly succeeded"),
+        Err(e) => assert!(e
+            .to_string()
+            .contains("canceling statement due to user request")),
+    }
+    assert_eq!(list_active_queries()?, Vec::<u32>::new());
+
+    // Canceling a connection that is not running a query is reported.
+    assert_eq!(
+        cancel(&conn_id.to_string())?,
+        (StatusCode::OK, r#"{"canceled":false}"#.into())
+    );
+    assert_eq!(
+        cancel("bogus")?,
+        (
+            StatusCode::BAD_REQUEST,
+            "invalid `conn_id` parameter: bogus".into()
+        )
+    );
+
+    Ok(())
+}
+
+// Ensures that the /internal/connections endpoint of the HTTP server reports
+// the resources held on behalf of each connection, and forgets connections
+// once they terminate.
+#[test]
+fn test_http_connections() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, _client) = util::start_server(util::Config::default())?;
+    let addr = server.inner.local_addr();
+    let connections_url = Url::parse(&format!("http://{}/internal/connections", addr))?;
+    let list_connections = || -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
+        let res = Client::new().get(connections_url.clone()).send()?;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res: serde_json::Value = serde_json::from_str(&res.text()?)?;
+        Ok(serde_json::from_value(res["connections"].clone())?)
+    };
+
+    let conns = list_connections()?;
+    assert_eq!(conns.len(), 1);
+    assert_eq!(conns[0]["temporary_items"], 0);
+    assert_eq!(conns[0]["active_peeks"], 0);
+    assert_eq!(conns[0]["active_tails"], 0);
+
+    {
+        let mut other = server.connect()?;
+        other.batch_execute(
+            "CREATE TEMPORARY VIEW v1 AS SELECT 1; \
+             CREATE TEMPORARY VIEW v2 AS SELECT 2; \
+             LISTEN c",
+        )?;
+        let conns = list_connections()?;
+        assert_eq!(conns.len(), 2);
+        assert_eq!(conns[1]["temporary_items"], 2);
+        assert_eq!(conns[1]["active_peeks"], 0);
+        assert_eq!(conns[1]["active_tails"], 1);
+    }
+
+    // Connection termination is processed asynchronously.
+    let start = Instant::now();
+    while list_connections()?.len() != 1 {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "terminated connection still reported"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Ok(())
+}
+
+// Ensures that a peek that is waiting on an arrangement fails with an error,
+// rather than hanging, if the arrangement is dropped while the peek is in
+// flight.
+#[test]
+fn test_drop_index_during_peek() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (a int); CREATE VIEW v AS SELECT a FROM t")?;
+
+    let addr = server.inner.local_addr();
+    let queries_url = Url::parse(&format!("http://{}/internal/queries", addr))?;
+    let list_active_queries = || -> Result<Vec<u32>, Box<dyn Error>> {
+        let res = Client::new().get(queries_url.clone()).send()?;
+        let res: serde_json::Value = serde_json::from_str(&res.text()?)?;
+        Ok(serde_json::from_value(res["conn_ids"].clone())?)
+    };
+
+    // Both a fast-path peek, which reads from the index directly, and a
+    // slow-path peek, which reads from it via a temporary dataflow, are
+    // affected. Peeking at a timestamp far in the future ensures that the
+    // peeks are still waiting when the index is dropped.
+    for query in &["SELECT * FROM v", "SELECT count(*) FROM v"] {
+        client.batch_execute("CREATE DEFAULT INDEX ON v")?;
+        let mut peek_client = server.connect()?;
+        let query = format!("{} AS OF 9999999999999", query);
+        let query_thread = thread::spawn(move || peek_client.query(&*query, &[]));
+        while list_active_queries()?.is_empty() {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        client.batch_execute("DROP INDEX v_primary_idx")?;
+        match query_thread.join().unwrap() {
+            Ok(_) => panic!("query unexpectedly succeeded"),
+            Err(e) => assert!(e.to_string().contains("arrangement dropped during query")),
+        }
+        while !list_active_queries()?.is_empty() {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    Ok(())
+}
+
+// Ensures that peeks that require a temporary dataflow are subject to the
+// configured admission limits, and that their permits are returned when they
+// complete.
+#[test]
+fn test_peek_admission() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    {
+        let config = util::Config::default().peek_admission(1, None);
+        let (_server, mut client) = util::start_server(config)?;
+        client.batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1), (2)")?;
+        for _ in 0..3 {
+            let count: i64 = client.query_one("SELECT count(*) FROM t", &[])?.get(0);
+            assert_eq!(count, 2);
+        }
+    }
+
+    {
+        let config = util::Config::default().peek_admission(0, Some(Duration::from_millis(10)));
+        let (_server, mut client) = util::start_server(config)?;
+        client.batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1), (2)")?;
+
+        // Reading out of an existing arrangement does not require a permit.
+        assert_eq!(client.query("SELECT a FROM t", &[])?.len(), 2);
+
+        match client.query_one("SELECT count(*) FROM t", &[]) {
+            Ok(_) => panic!("peek unexpectedly admitted"),
+            Err(e) => assert!(e
+                .to_string()
+                .contains("too many concurrent queries require temporary dataflows")),
+        }
+    }
+
+    // A limit of zero with no wait bound fails queries immediately, rather
+    // than blocking them forever.
+    {
+        let config = util::Config::default().peek_admission(0, None);
+        let (_server, mut client) = util::start_server(config)?;
+        client.batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1), (2)")?;
+        match client.query_one("SELECT count(*) FROM t", &[]) {
+            Ok(_) => panic!("peek unexpectedly admitted"),
+            Err(e) => assert!(e
+                .to_string()
+                .contains("too many concurrent queries require temporary dataflows")),
+        }
+    }
+
+    Ok(())
+}
+
+// Ensures that sources are materialized by default when so configured, and
+// that sources are otherwise only materialized when explicitly requested.
+#[test]
+fn test_default_source_materialization() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let source_file = NamedTempFile::new()?;
+    let count_indexes = |client: &mut postgres::Client, source: &str| {
+        client
+            .query(&*format!("SHOW INDEXES FROM {}", source), &[])
+            .map(|rows| rows.len())
+    };
+
+    {
+        let (_server, mut client) = util::start_server(util::Config::default())?;
+        client.batch_execute(&format!(
+            "CREATE SOURCE plain FROM FILE '{0}' FORMAT BYTES; \
+             CREATE MATERIALIZED SOURCE mat FROM FILE '{0}' FORMAT BYTES;",
+            source_file.path().display(),
+        ))?;
+        assert_eq!(count_indexes(&mut client, "plain")?, 0);
+        assert!(count_indexes(&mut client, "mat")? > 0);
+    }
+
+    {
+        let config = util::Config::default().default_source_materialization();
+        let (_server, mut client) = util::start_server(config)?;
+        client.batch_execute(&format!(
+            "CREATE SOURCE plain FROM FILE '{0}' FORMAT BYTES; \
+             CREATE MATERIALIZED SOURCE mat FROM FILE '{0}' FORMAT BYTES;",
+            source_file.path().display(),
+        ))?;
+        assert!(count_indexes(&mut client, "plain")? > 0);
+        assert!(count_indexes(&mut client, "mat")? > 0);
+    }
+
+    Ok(())
+}
+
+// Ensures that `mz_sessions` reports the `application_name` of each
+// connection, and forgets connections once they terminate.
+#[test]
+fn test_sessions() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    let mut list_sessions = || -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(client
+            .query(
+                "SELECT application_name FROM mz_sessions ORDER BY conn_id",
+                &[],
+            )?
+            .into_iter()
+            .map(|row| row.get(0))
+            .collect())
+    };
+
+    {
+        let mut other = server.connect()?;
+        other.batch_execute("SET application_name = 'other'")?;
+        assert_eq!(list_sessions()?, vec!["", "other"]);
+    }
+
+    // Connection termination is processed asynchronously.
+    let start = Instant::now();
+    while list_sessions()? != vec![""] {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "terminated session still present in mz_sessions"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Ok(())
+}
+
+// Ensures that `mz_object_history` records the creation, rename, and drop of
+// objects when so configured, and is otherwise empty.
+#[test]
+fn test_object_history() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let ddl = "CREATE TABLE t (a int); \
+               ALTER TABLE t RENAME TO u; \
+               DROP TABLE u;";
+    let history_query = "SELECT event, name, previous_name FROM mz_object_history \
+                         WHERE object_type = 'table' ORDER BY occurred_at";
+
+    {
+        let (_server, mut client) = util::start_server(util::Config::default())?;
+        client.batch_execute(ddl)?;
+        let count: i64 = client
+            .query_one("SELECT count(*) FROM mz_object_history", &[])?
+            .get(0);
+        assert_eq!(count, 0);
+    }
+
+    {
+        let config = util::Config::default().object_history();
+        let (_server, mut client) = util::start_server(config)?;
+        client.batch_execute(ddl)?;
+        let history: Vec<(String, String, Option<String>)> = client
+            .query(history_query, &[])?
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1), row.get(2)))
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                ("create".into(), "materialize.public.t".into(), None),
+                (
+                    "rename".into(),
+                    "materialize.public.u".into(),
+                    Some("materialize.public.t".into())
+                ),
+                ("drop".into(), "materialize.public.u".into(), None),
+            ]
+        );
+    }
+
+    Ok(())
+}
+
+// Ensures that `ALTER INDEX ALL` reports the number of indexes whose compaction
+// window it overrode, and that resetting the override covers indexes created
+// while it was in effect.
+#[test]
+fn test_alter_all_indexes_compaction() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t1 (a int)")?;
+
+    let overridden = client.execute(
+        "ALTER INDEX ALL SET (logical_compaction_window = '1ms')",
+        &[],
+    )?;
+    assert!(overridden > 0);
+
+    client.batch_execute("CREATE TABLE t2 (a int); INSERT INTO t2 VALUES (1)")?;
+    assert_eq!(client.query("SELECT a FROM t2", &[])?.len(), 1);
+
+    let restored = client.execute("ALTER INDEX ALL RESET (logical_compaction_window)", &[])?;
+    assert_eq!(restored, overridden + 1);
+
+    // With no override in effect, there is nothing to restore.
+    let restored = client.execute("ALTER INDEX ALL RESET (logical_compaction_window)", &[])?;
+    assert_eq!(restored, 0);
+
+    Ok(())
+}
+
+// Ensures that `mz_sleep` delays the results of a peek, that it is only
+// available in experimental mode, and that it cannot be used in views.
+#[test]
+fn test_mz_sleep() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    {
+        let (_server, mut client) = util::start_server(util::Config::default())?;
+        let err = client.query_one("SELECT mz_sleep(0)", &[]).unwrap_err();
+        assert!(err.to_string().contains("requires experimental mode"));
+    }
+
+    {
+        let config = util::Config::default().experimental_mode();
+        let (_server, mut client) = util::start_server(config)?;
+
+        let start = Instant::now();
+        let row = client.query_one("SELECT mz_sleep(0.5)", &[])?;
+        assert!(start.elapsed() >= Duration::from_millis(500));
+        assert_eq!(row.columns()[0].type_(), &postgres::types::Type::VOID);
+
+        for (sql, expected) in &[
+            ("SELECT mz_sleep(-1)", "mz_sleep duration must be between"),
+            ("SELECT mz_sleep(3600)", "mz_sleep duration must be between"),
+            (
+                "CREATE VIEW v AS SELECT mz_sleep(0)",
+                "mz_sleep and pg_sleep cannot be used in static queries",
+            ),
+        ] {
+            let err = client.batch_execute(sql).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", sql, err);
+        }
+    }
+
+    Ok(())
+}
+
+// Ensures that `pg_sleep` delays the results of a peek without requiring
+// experimental mode, and that other clients are served while it does.
+#[test]
+fn test_pg_sleep() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+
+    let sleep_thread = thread::spawn(move || {
+        let start = Instant::now();
+        let row = client.query_one("SELECT pg_sleep(2)", &[])?;
+        assert!(start.elapsed() >= Duration::from_secs(2));
+        assert_eq!(row.columns()[0].type_(), &postgres::types::Type::VOID);
+        Ok::<_, postgres::Error>(())
+    });
+
+    let mut other = server.connect()?;
+    let start = Instant::now();
+    other.query_one("SELECT 1", &[])?;
+    assert!(start.elapsed() < Duration::from_secs(2));
+    sleep_thread.join().unwrap()?;
+
+    for (sql, expected) in &[
+        ("SELECT pg_sleep(-1)", "pg_sleep duration must be between"),
+        ("SELECT pg_sleep(3600)", "pg_sleep duration must be between"),
+        (
+            "CREATE VIEW v AS SELECT pg_sleep(0)",
+            "mz_sleep and pg_sleep cannot be used in static queries",
+        ),
+    ] {
+        let err = other.batch_execute(sql).unwrap_err();
+        assert!(err.to_string().contains(expected), "{}: {}", sql, err);
+    }
+
+    Ok(())
+}
+
+// Ensures that `CREATE INDEX` enforces the configured limit on the number of
+// indexes per relation.
+#[test]
+fn test_max_indexes_per_relation() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().max_indexes_per_relation(2);
+    let (_server, mut client) = util::start_server(config)?;
+
+    // The table's default index counts toward the limit.
+    client.batch_execute("CREATE TABLE t (a int, b int); CREATE INDEX i1 ON t (a)")?;
+
+    let err = client
+        .batch_execute("CREATE INDEX i2 ON t (b)")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("already has 2 indexes"), "{}", err);
+    assert!(err.contains("materialize.public.i1"), "{}", err);
+
+    // An existing index still satisfies `IF NOT EXISTS` at the limit.
+    client.batch_execute("CREATE INDEX IF NOT EXISTS i1 ON t (a)")?;
+
+    // Other relations are unaffected, and dropping an index frees a slot.
+    client.batch_execute("CREATE TABLE u (a int); CREATE INDEX i3 ON u (a)")?;
+    client.batch_execute("DROP INDEX i1; CREATE INDEX i2 ON t (b)")?;
+
+    Ok(())
+}
+
+// Ensures that the /internal/catalog/snapshot endpoint reports the catalog
+// together with the frontiers of its indexes.
+#[test]
+fn test_http_catalog_snapshot() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (a int); CREATE INDEX i ON t (a)")?;
+    let id: String = client
+        .query_one("SELECT id FROM mz_indexes WHERE name = 'i'", &[])?
+        .get(0);
+    let id: u64 = id.trim_start_matches('u').parse()?;
+
+    let url = Url::parse(&format!(
+        "http://{}/internal/catalog/snapshot",
+        server.inner.local_addr()
+    ))?;
+    let res = Client::new().get(url).send()?;
+    assert_eq!(res.status(), StatusCode::OK);
+    let snapshot: serde_json::Value = serde_json::from_str(&res.text()?)?;
+
+    assert!(snapshot["catalog"]["materialize"].is_object());
+    assert_eq!(snapshot["pending_sinks"], serde_json::json!([]));
+    let index = snapshot["indexes"]
+        .as_array()
+        .expect("indexes is an array")
+        .iter()
+        .find(|index| index["id"] == serde_json::json!({ "User": id }))
+        .expect("index missing from snapshot");
+    assert!(index["since"].is_array());
+    assert!(index["upper"].is_array());
+
+    Ok(())
+}
+
+// Ensures that the /internal/sources/pause and /internal/sources/resume
+// endpoints hold a real-time source's data steady while paused, and report
+// the number of sources whose state they changed.
+#[test]
+fn test_http_pause_sources() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let mut source_file = NamedTempFile::new()?;
+    source_file.write_all(b"a\n")?;
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute(&format!(
+        "CREATE MATERIALIZED SOURCE src FROM FILE '{}' WITH (tail = true) FORMAT BYTES",
+        source_file.path().display(),
+    ))?;
+    // Once the source's data is visible, its instance is known to be running.
+    let count_rows = |client: &mut postgres::Client| -> Result<i64, Box<dyn Error>> {
+        Ok(client.query_one("SELECT count(*) FROM src", &[])?.get(0))
+    };
+    while count_rows(&mut client)? == 0 {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let addr = server.inner.local_addr();
+    let post = |path: &str, key: &str| -> Result<u64, Box<dyn Error>> {
+        let url = Url::parse(&format!("http://{}/internal/sources/{}", addr, path))?;
+        let res = Client::new().post(url).send()?;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res: serde_json::Value = serde_json::from_str(&res.text()?)?;
+        Ok(serde_json::from_value(res[key].clone())?)
+    };
+
+    assert_eq!(post("pause", "paused")?, 1);
+    assert_eq!(post("pause", "paused")?, 0);
+    // The query is sequenced after the pause on every worker, so data
+    // appended from here on must not become visible until sources resume.
+    assert_eq!(count_rows(&mut client)?, 1);
+    source_file.write_all(b"b\n")?;
+    source_file.flush()?;
+    thread::sleep(Duration::from_secs(2));
+    assert_eq!(count_rows(&mut client)?, 1, "paused source ingested data");
+
+    assert_eq!(post("resume", "resumed")?, 1);
+    assert_eq!(post("resume", "resumed")?, 0);
+    let start = Instant::now();
+    while count_rows(&mut client)? != 2 {
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "resumed source did not ingest data"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Ok(())
+}
+
+// Ensures that the coordinator reuses the statements it parsed from
+// `create_sql`, as reported by the parse cache metrics.
+#[test]
+fn test_parse_cache_hits() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    let url = Url::parse(&format!("http://{}/metrics", server.inner.local_addr()))?;
+    let hits = || -> Result<u64, Box<dyn Error>> {
+        let res = Client::new().get(url.clone()).send()?;
+        assert_eq!(res.status(), StatusCode::OK);
+        let line = res
+            .text()?
+            .lines()
+            .find(|line| line.starts_with("mz_parse_cache_hits_total "))
+            .map(|line| line.to_owned())
+            .ok_or("mz_parse_cache_hits_total missing from metrics")?;
+        Ok(line["mz_parse_cache_hits_total ".len()..].parse()?)
+    };
+
+    // Creating an index parses its `create_sql` to report it to
+    // `mz_indexes`, and dropping the index reuses that parse.
+    client.batch_execute("CREATE TABLE t (a int); CREATE INDEX i ON t (a)")?;
+    let before = hits()?;
+    client.batch_execute("DROP INDEX i")?;
+    assert!(hits()? > before);
+
+    Ok(())
+}
+
+// Ensures that repeated ad-hoc queries reuse their cached plans, and that
+// queries that must be prepared for each execution are not served stale
+// plans.
+#[test]
+fn test_plan_cache_hits() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    let url = Url::parse(&format!("http://{}/metrics", server.inner.local_addr()))?;
+    let hits = || -> Result<u64, Box<dyn Error>> {
+        let res = Client::new().get(url.clone()).send()?;
+        assert_eq!(res.status(), StatusCode::OK);
+        let line = res
+            .text()?
+            .lines()
+            .find(|line| line.starts_with("mz_plan_cache_hits_total "))
+            .map(|line| line.to_owned())
+            .ok_or("mz_plan_cache_hits_total missing from metrics")?;
+        Ok(line["mz_plan_cache_hits_total ".len()..].parse()?)
+    };
+
+    client.batch_execute("CREATE TABLE t (a int); INSERT INTO t VALUES (1)")?;
+    let count = |client: &mut postgres::Client| -> Result<i64, Box<dyn Error>> {
+        Ok(client.query_one("SELECT count(*) FROM t", &[])?.get(0))
+    };
+    assert_eq!(count(&mut client)?, 1);
+    let before = hits()?;
+    client.batch_execute("INSERT INTO t VALUES (2)")?;
+    assert_eq!(count(&mut client)?, 2);
+    assert!(hits()? > before);
+
+    let ts = |client: &mut postgres::Client| -> Result<u64, Box<dyn Error>> {
+        Ok(client
+            .query_one("SELECT mz_logical_timestamp() FROM t LIMIT 1", &[])?
+            .get::<_, util::MzTimestamp>(0)
+            .0)
+    };
+    let first = ts(&mut client)?;
+    thread::sleep(Duration::from_millis(100));
+    assert!(ts(&mut client)? > first);
+
+    Ok(())
+}
@@ -13,7 +13,9 @@
 //! scripts. The tests here are simply too complicated to be easily expressed
 //! in testdrive, e.g., because they depend on the current time.
 
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::fs;
 use std::io::Write;
 use std::net::TcpListener;
 use std::thread;
@@ -159,6 +161,333 @@ fn test_tail_basic() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_tail_until() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().threads(2);
+    let (_server, mut client) = util::start_server(config)?;
+
+    client.batch_execute(
+        "CREATE TABLE t (data text);
+         BEGIN;
+         DECLARE c CURSOR FOR TAIL t;",
+    )?;
+
+    let mut timestamps = vec![];
+    for i in 1..=3 {
+        client.execute("INSERT INTO t VALUES ($1)", &[&format!("line {}", i)])?;
+        let row = client.query_one("FETCH ALL c", &[])?;
+        timestamps.push(row.get::<_, MzTimestamp>("timestamp").0);
+    }
+    client.batch_execute("COMMIT")?;
+
+    // A bounded TAIL terminates on its own, having produced only the updates
+    // before the `UNTIL` timestamp.
+    let rows = client.query(
+        &*format!("TAIL t AS OF {} UNTIL {}", timestamps[0], timestamps[2]),
+        &[],
+    )?;
+    let data: Vec<String> = rows.iter().map(|row| row.get("data")).collect();
+    assert_eq!(data, vec!["line 1", "line 2"]);
+
+    // The connection is free to run other queries afterward.
+    let row = client.query_one("SELECT count(*) FROM t", &[])?;
+    assert_eq!(row.get::<_, i64>(0), 3);
+
+    let err = client
+        .query(
+            &*format!("TAIL t AS OF {} UNTIL {}", timestamps[2], timestamps[0]),
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("is before the starting timestamp"));
+
+    Ok(())
+}
+
+#[test]
+fn test_select_over_tail() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().threads(2);
+    let (_server, mut client) = util::start_server(config)?;
+
+    client.batch_execute(
+        "CREATE TABLE t (data text);
+         BEGIN;
+         DECLARE c CURSOR FOR TAIL t;",
+    )?;
+
+    let mut timestamps = vec![];
+    for i in 1..=3 {
+        client.execute("INSERT INTO t VALUES ($1)", &[&format!("line {}", i)])?;
+        let row = client.query_one("FETCH ALL c", &[])?;
+        timestamps.push(row.get::<_, MzTimestamp>("timestamp").0);
+    }
+    client.batch_execute("COMMIT")?;
+
+    // Selecting from a bounded TAIL returns the updates before the `UNTIL`
+    // timestamp as a single result set, with the same columns as the TAIL.
+    let rows = client.query(
+        &*format!(
+            "SELECT * FROM (TAIL t AS OF {} UNTIL {})",
+            timestamps[0], timestamps[2]
+        ),
+        &[],
+    )?;
+    let mut updates: Vec<(u64, i64, String)> = rows
+        .iter()
+        .map(|row| {
+            (
+                row.get::<_, MzTimestamp>("timestamp").0,
+                row.get("diff"),
+                row.get("data"),
+            )
+        })
+        .collect();
+    updates.sort();
+    assert_eq!(
+        updates,
+        vec![
+            (timestamps[0], 1, "line 1".into()),
+            (timestamps[1], 1, "line 2".into()),
+        ]
+    );
+
+    let err = client.query("SELECT * FROM (TAIL t)", &[]).unwrap_err();
+    assert!(err.to_string().contains("requires UNTIL"));
+
+    let err = client
+        .query("SELECT data FROM (TAIL t UNTIL now())", &[])
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("TAIL is only supported in SELECT * FROM (TAIL ...)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_tail_limit() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().threads(2);
+    let (server, mut client) = util::start_server(config)?;
+    let mut client_insert = server.connect()?;
+
+    client.batch_execute(
+        "CREATE TABLE t (data text);
+         INSERT INTO t VALUES ('a'), ('b'), ('b'), ('b');",
+    )?;
+
+    // A limited TAIL terminates on its own once it has produced the requested
+    // number of updates. Snapshot updates count toward the limit by default,
+    // and updates are counted by their diffs.
+    let rows = client.query("TAIL t LIMIT 3", &[])?;
+    let count: i64 = rows.iter().map(|row| row.get::<_, i64>("diff")).sum();
+    assert_eq!(count, 3);
+
+    // The connection is free to run other queries afterward.
+    let row = client.query_one("SELECT count(*) FROM t", &[])?;
+    assert_eq!(row.get::<_, i64>(0), 4);
+
+    // If snapshot updates do not count toward the limit, the snapshot is
+    // emitted in full, and the TAIL ends after the requested number of
+    // subsequent updates.
+    client.batch_execute(
+        "BEGIN;
+         DECLARE c CURSOR FOR TAIL t WITH (limit_snapshot = false) LIMIT 1;",
+    )?;
+    let rows = client.query("FETCH ALL c", &[])?;
+    let count: i64 = rows.iter().map(|row| row.get::<_, i64>("diff")).sum();
+    assert_eq!(count, 4);
+    client_insert.batch_execute("INSERT INTO t VALUES ('c')")?;
+    let row = client.query_one("FETCH ALL c", &[])?;
+    assert_eq!(row.get::<_, String>("data"), "c");
+    let rows = client.query("FETCH ALL c", &[])?;
+    assert_eq!(rows.len(), 0);
+    client.batch_execute("COMMIT")?;
+
+    for (query, error) in &[
+        ("TAIL t LIMIT 0", "TAIL LIMIT must be a positive integer"),
+        ("TAIL t LIMIT 'a'", "TAIL LIMIT must be a positive integer"),
+        (
+            "TAIL t WITH (on_backpressure = 'drop') LIMIT 1",
+            "TAIL LIMIT requires on_backpressure = 'block'",
+        ),
+        (
+            "TAIL t WITH (limit_snapshot = false)",
+            "TAIL limit_snapshot requires a LIMIT",
+        ),
+    ] {
+        let err = client.query(*query, &[]).unwrap_err();
+        assert!(err.to_string().contains(error), "{}: {}", query, err);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_tail_on_backpressure() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().threads(2);
+    let (_server, mut client) = util::start_server(config)?;
+
+    client.batch_execute("CREATE TABLE t (data text)")?;
+
+    // While the client keeps up, every mode delivers every update, including
+    // through to the end of a bounded TAIL.
+    for (i, mode) in ["block", "drop", "latest"].iter().enumerate() {
+        client.batch_execute(&format!(
+            "BEGIN;
+             DECLARE c CURSOR FOR TAIL t WITH (SNAPSHOT = false, ON_BACKPRESSURE = '{}');",
+            mode
+        ))?;
+        let data = format!("{} line", mode);
+        client.execute("INSERT INTO t VALUES ($1)", &[&data])?;
+        let row = client.query_one("FETCH ALL c", &[])?;
+        assert_eq!(row.get::<_, String>("data"), data);
+        let timestamp = row.get::<_, MzTimestamp>("timestamp").0;
+        client.batch_execute("COMMIT")?;
+
+        let rows = client.query(
+            &*format!(
+                "TAIL t WITH (ON_BACKPRESSURE = '{}') AS OF {} UNTIL {}",
+                mode,
+                timestamp,
+                timestamp + 1
+            ),
+            &[],
+        )?;
+        assert_eq!(rows.len(), i + 1);
+    }
+
+    let err = client
+        .query("TAIL t WITH (ON_BACKPRESSURE = 'sometimes')", &[])
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("TAIL on_backpressure must be one of 'block', 'drop', or 'latest'"));
+
+    Ok(())
+}
+
+// Ensures that a TAIL that drops updates while its client falls behind
+// marks the gap, and delivers later updates once the client catches up.
+#[test]
+fn test_tail_on_backpressure_drop() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    let mut writer = server.connect()?;
+    writer.batch_execute("CREATE TABLE t (data text)")?;
+    client.batch_execute(
+        "BEGIN;
+         DECLARE c CURSOR FOR TAIL t WITH (SNAPSHOT = false, ON_BACKPRESSURE = 'drop');",
+    )?;
+
+    // Without fetching, write far more than the channel to the client can
+    // buffer.
+    let insert = format!(
+        "INSERT INTO t SELECT '{}' || n::text FROM generate_series(1, 2000) AS n",
+        "x".repeat(100)
+    );
+    for _ in 0..20 {
+        writer.batch_execute(&insert)?;
+    }
+
+    let mut data_rows = 0;
+    let mut gap = false;
+    let start = Instant::now();
+    while !gap {
+        assert!(start.elapsed() < Duration::from_secs(60), "no gap marker");
+        for row in client.query("FETCH ALL c WITH (TIMEOUT = '1s')", &[])? {
+            match row.get::<_, Option<String>>("data") {
+                Some(_) => data_rows += 1,
+                None => {
+                    assert_eq!(row.get::<_, Option<i64>>("diff"), None);
+                    gap = true;
+                }
+            }
+        }
+    }
+    assert!(data_rows < 20 * 2000, "no updates were dropped");
+
+    // Now that the client has caught up, updates are delivered again.
+    let mut caught_up = false;
+    while !caught_up {
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "TAIL did not resume"
+        );
+        writer.batch_execute("INSERT INTO t VALUES ('last')")?;
+        for row in client.query("FETCH ALL c WITH (TIMEOUT = '1s')", &[])? {
+            caught_up |= row.get::<_, Option<String>>("data").as_deref() == Some("last");
+        }
+    }
+
+    Ok(())
+}
+
+// Ensures that a TAIL that coalesces updates while its client falls behind
+// delivers only the newest value of each key, and that the updates it delivers
+// still describe the complete contents of the relation.
+#[test]
+fn test_tail_on_backpressure_latest() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    let mut writer = server.connect()?;
+    writer.batch_execute(
+        "CREATE TABLE t (k int, v int);
+         CREATE MATERIALIZED VIEW m AS SELECT k, max(v) AS v FROM t GROUP BY k;",
+    )?;
+    client.batch_execute(
+        "BEGIN;
+         DECLARE c CURSOR FOR TAIL m WITH (SNAPSHOT = false, ON_BACKPRESSURE = 'latest');",
+    )?;
+
+    // Without fetching, change the value of every key many times, which
+    // produces far more updates than the channel to the client can buffer.
+    for v in 1..=20 {
+        writer.batch_execute(&format!(
+            "INSERT INTO t SELECT k, {} FROM generate_series(1, 5000) AS k",
+            v
+        ))?;
+    }
+
+    // Once the backlog is delivered, applying the updates yields the newest
+    // value of each key.
+    let mut contents = BTreeMap::new();
+    let mut update_rows = 0;
+    let start = Instant::now();
+    while contents.len() != 5000 || contents.iter().any(|(&(_k, v), &n)| v != 20 || n != 1) {
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "TAIL did not deliver the newest values"
+        );
+        for row in client.query("FETCH ALL c WITH (TIMEOUT = '1s')", &[])? {
+            let k: i32 = row.get("k");
+            let v: i32 = row.get("v");
+            let n = contents.entry((k, v)).or_insert(0);
+            *n += row.get::<_, i64>("diff");
+            if *n == 0 {
+                contents.remove(&(k, v));
+            }
+            update_rows += 1;
+        }
+    }
+    // The first insert adds each key, and each later one replaces its value.
+    assert!(
+        update_rows < 5000 + 19 * 2 * 5000,
+        "no updates were coalesced"
+    );
+
+    Ok(())
+}
+
 /// Test the done messages by sending inserting a single row and waiting to
 /// observe it. Since TAIL always sends a progressed message at the end of its
 /// batches and we won't yet insert a second row, we know that if we've seen a
@@ -263,6 +592,22 @@ fn test_tail_fetch_timeout() -> Result<(), Box<dyn Error>> {
     let rows = client.query("FETCH c WITH (TIMEOUT = '0s')", &[])?;
     assert_eq!(rows.len(), 0);
 
+    // A FETCH without a timeout uses the session's `fetch_timeout`, rather
+    // than waiting for a row that will never arrive.
+    client.batch_execute("SET fetch_timeout = '1s'")?;
+    let before = Instant::now();
+    let rows = client.query("FETCH c", &[])?;
+    let duration = before.elapsed();
+    assert_eq!(rows.len(), 0);
+    assert!(duration >= Duration::from_secs(1));
+    assert!(duration < Duration::from_secs(10));
+
+    // An explicit timeout takes precedence over `fetch_timeout`.
+    let before = Instant::now();
+    let rows = client.query("FETCH c WITH (TIMEOUT = '0s')", &[])?;
+    assert_eq!(rows.len(), 0);
+    assert!(before.elapsed() < Duration::from_secs(1));
+
     Ok(())
 }
 
@@ -420,6 +765,154 @@ fn test_tail_shutdown() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Tests that an object cannot be dropped out from under an active TAIL without
+// CASCADE, and that dropping it with CASCADE cancels the TAIL.
+#[test]
+fn test_tail_drop_source() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client_tail) = util::start_server(util::Config::default())?;
+    let mut client_drop = server.connect()?;
+
+    client_tail.batch_execute(
+        "CREATE TABLE t (i INT8);
+         INSERT INTO t VALUES (1);
+         BEGIN;
+         DECLARE c CURSOR FOR TAIL t;",
+    )?;
+    // Wait for the first row to ensure the TAIL is running.
+    let row = client_tail.query_one("FETCH c", &[])?;
+    assert_eq!(row.get::<_, i64>("i"), 1);
+
+    let err = client_drop.batch_execute("DROP TABLE t").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("cannot drop materialize.public.t: still being read by an active TAIL"));
+    let err = client_drop
+        .batch_execute("DROP TABLE t RESTRICT")
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("still being read by an active TAIL"));
+
+    // With CASCADE, the drop succeeds and the TAIL ends.
+    client_drop.batch_execute("DROP TABLE t CASCADE")?;
+    let rows = client_tail.query("FETCH ALL c", &[])?;
+    assert_eq!(rows.len(), 0);
+    client_tail.batch_execute("COMMIT")?;
+
+    // Once the TAIL is gone, objects can be dropped as usual.
+    client_drop.batch_execute(
+        "CREATE TABLE u (i INT8);
+         DROP TABLE u;",
+    )?;
+
+    Ok(())
+}
+
+// Tests that dropping a database cancels any TAIL that reads from an object in
+// that database.
+#[test]
+fn test_tail_drop_database() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client_tail) = util::start_server(util::Config::default())?;
+    let mut client_drop = server.connect()?;
+
+    client_tail.batch_execute(
+        "CREATE DATABASE d;
+         CREATE TABLE d.public.t (i INT8);
+         INSERT INTO d.public.t VALUES (1);
+         BEGIN;
+         DECLARE c CURSOR FOR TAIL d.public.t;",
+    )?;
+    // Wait for the first row to ensure the TAIL is running.
+    let row = client_tail.query_one("FETCH c", &[])?;
+    assert_eq!(row.get::<_, i64>("i"), 1);
+
+    // Dropping the database succeeds, and the TAIL ends cleanly rather than
+    // erroring.
+    client_drop.batch_execute("DROP DATABASE d")?;
+    let rows = client_tail.query("FETCH ALL c", &[])?;
+    assert_eq!(rows.len(), 0);
+    client_tail.batch_execute("COMMIT")?;
+
+    // The connection remains usable.
+    assert_eq!(client_tail.query_one("SELECT 1", &[])?.get::<_, i32>(0), 1);
+
+    Ok(())
+}
+
+// Tests that `max_timestamp_age_ms` rejects queries whose timestamp lags too
+// far behind the current time.
+#[test]
+fn test_max_timestamp_age() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (i int); INSERT INTO t VALUES (1)")?;
+    let ts = client
+        .query_one("SELECT mz_logical_timestamp() FROM t", &[])?
+        .get::<_, MzTimestamp>(0)
+        .0;
+    thread::sleep(Duration::from_millis(200));
+
+    // With the check disabled, which is the default, stale reads succeed.
+    let query = format!("SELECT * FROM t AS OF {}", ts);
+    assert_eq!(client.query_one(&*query, &[])?.get::<_, i32>(0), 1);
+
+    client.batch_execute("SET max_timestamp_age_ms = 100")?;
+    let err = client.query_one(&*query, &[]).unwrap_err();
+    let message = err.as_db_error().unwrap().message();
+    assert!(message.starts_with(&format!("query timestamp {} is ", ts)));
+    assert!(message.ends_with("which exceeds max_timestamp_age_ms (100ms)"));
+
+    // Fresh reads are unaffected.
+    assert_eq!(
+        client.query_one("SELECT * FROM t", &[])?.get::<_, i32>(0),
+        1
+    );
+
+    client.batch_execute("SET max_timestamp_age_ms = 0")?;
+    assert_eq!(client.query_one(&*query, &[])?.get::<_, i32>(0), 1);
+
+    Ok(())
+}
+
+// Tests that `--timestamp-quantum` coarsens the timestamps assigned to reads,
+// while writes that follow reads still receive strictly later timestamps.
+#[test]
+fn test_timestamp_quantum() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().timestamp_quantum(Duration::from_secs(3600));
+    let (_server, mut client) = util::start_server(config)?;
+    client.batch_execute("CREATE TABLE t (i int); INSERT INTO t VALUES (1)")?;
+
+    let read_ts = |client: &mut postgres::Client| -> Result<u64, Box<dyn Error>> {
+        Ok(client
+            .query_one("SELECT mz_logical_timestamp() FROM t LIMIT 1", &[])?
+            .get::<_, MzTimestamp>(0)
+            .0)
+    };
+
+    // Reads within the same quantum share a timestamp.
+    let ts1 = read_ts(&mut client)?;
+    thread::sleep(Duration::from_millis(50));
+    let ts2 = read_ts(&mut client)?;
+    assert_eq!(ts1, ts2);
+
+    // A write that follows a read is assigned a later timestamp, and is
+    // visible to subsequent reads.
+    client.batch_execute("INSERT INTO t VALUES (2)")?;
+    let ts3 = read_ts(&mut client)?;
+    assert!(ts3 > ts1);
+    let count: i64 = client.query_one("SELECT count(*) FROM t", &[])?.get(0);
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
 // Tests that temporary views created by one connection cannot be viewed
 // by another connection.
 #[test]
@@ -451,3 +944,338 @@ fn test_temporary_views() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+// Tests that the arrangements of a connection's temporary items are dropped
+// when the connection terminates.
+#[test]
+fn test_temporary_items_cleanup() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client_a) = util::start_server(util::Config::default())?;
+    let mut client_b = server.connect()?;
+    client_b.batch_execute("CREATE TEMPORARY TABLE t (a int)")?;
+    for i in 0..5 {
+        client_b.batch_execute(&format!(
+            "CREATE TEMPORARY VIEW v{0} AS SELECT a + {0} AS b FROM t; \
+             CREATE DEFAULT INDEX ON v{0}",
+            i
+        ))?;
+    }
+    // The table's primary index, plus one index per view.
+    let count: i64 = client_b
+        .query_one("SELECT count(*) FROM mz_indexes WHERE id LIKE 'u%'", &[])?
+        .get(0);
+    assert_eq!(count, 6);
+
+    // Logging is not synchronized with DDL, so wait for the expected number of
+    // arrangements to be reported.
+    let await_materializations =
+        |client: &mut postgres::Client, expected: i64| -> Result<(), Box<dyn Error>> {
+            let deadline = Instant::now() + Duration::from_secs(30);
+            loop {
+                let count: i64 = client
+                    .query_one(
+                        "SELECT count(DISTINCT name) FROM mz_materializations WHERE name LIKE 'u%'",
+                        &[],
+                    )?
+                    .get(0);
+                if count == expected {
+                    return Ok(());
+                } else if Instant::now() > deadline {
+                    panic!("expected {} materializations, found {}", expected, count);
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+        };
+    await_materializations(&mut client_a, 6)?;
+
+    drop(client_b);
+    await_materializations(&mut client_a, 0)?;
+    let count: i64 = client_a
+        .query_one("SELECT count(*) FROM mz_indexes WHERE id LIKE 'u%'", &[])?
+        .get(0);
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+// Tests that point lookups against an unindexed view yield an index
+// recommendation, which disappears once the index is created.
+#[test]
+fn test_index_recommendations() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute(
+        "CREATE TABLE t (a int, b text); \
+         INSERT INTO t VALUES (1, 'x'), (2, 'y'); \
+         CREATE VIEW v AS SELECT a, b FROM t WHERE a > 0",
+    )?;
+
+    let recommendations = |client: &mut postgres::Client| {
+        client
+            .query(
+                "SELECT key, peeks, create_sql FROM mz_index_recommendations",
+                &[],
+            )
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| (row.get(0), row.get(1), row.get(2)))
+                    .collect::<Vec<(String, i64, String)>>()
+            })
+    };
+
+    for i in 0..10 {
+        assert_eq!(recommendations(&mut client)?, vec![]);
+        client.query("SELECT b FROM v WHERE a = $1", &[&(i % 2 + 1)])?;
+    }
+    let recommendations_after = recommendations(&mut client)?;
+    match recommendations_after.as_slice() {
+        [(key, 10, create_sql)] => {
+            assert_eq!(key, "a");
+            assert!(create_sql.starts_with("CREATE INDEX"), "{}", create_sql);
+            client.batch_execute(create_sql)?;
+        }
+        _ => panic!("unexpected recommendations: {:?}", recommendations_after),
+    }
+
+    // Once the index exists, the recommendation is withdrawn and lookups no
+    // longer take the slow path.
+    assert_eq!(recommendations(&mut client)?, vec![]);
+    client.query("SELECT b FROM v WHERE a = 1", &[])?;
+    let count: i64 = client
+        .query_one("SELECT count(*) FROM mz_slow_path_peeks", &[])?
+        .get(0);
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+// Ensures that `EXPLAIN (SINK)` validates a sink and reports what it would
+// create, without creating the sink or any external state.
+#[test]
+fn test_explain_sink() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    let dir = tempfile::tempdir()?;
+    client.batch_execute(
+        "CREATE VIEW v AS SELECT 1 AS a; \
+         CREATE TEMPORARY VIEW tv AS SELECT 1 AS a;",
+    )?;
+
+    let explain = |client: &mut postgres::Client, sql: &str| {
+        client.query(sql, &[]).map(|rows| {
+            rows.into_iter()
+                .map(|row| (row.get(0), row.get(1)))
+                .collect::<BTreeMap<String, String>>()
+        })
+    };
+
+    let properties = explain(
+        &mut client,
+        "EXPLAIN (SINK) CREATE SINK s FROM v \
+         INTO KAFKA BROKER 'localhost:9092' TOPIC 'snk' \
+         FORMAT AVRO USING CONFLUENT SCHEMA REGISTRY 'http://localhost:8081'",
+    )?;
+    assert_eq!(properties["action"], "create");
+    assert_eq!(properties["name"], "materialize.public.s");
+    assert_eq!(properties["from"], "materialize.public.v");
+    assert_eq!(properties["connector"], "kafka");
+    assert_eq!(properties["broker"], "localhost:9092");
+    assert_eq!(properties["format"], "avro");
+    assert!(
+        properties["topic"].starts_with("snk-<id>-"),
+        "{:?}",
+        properties
+    );
+    assert_eq!(properties["with_snapshot"], "true");
+    // `v` is not materialized, so the sink would begin at the start of time.
+    assert_eq!(properties["as_of"], "0");
+
+    let path = dir.path().join("out.ocf");
+    let properties = explain(
+        &mut client,
+        &format!(
+            "EXPLAIN (SINK) CREATE SINK s FROM v INTO AVRO OCF '{}' WITHOUT SNAPSHOT",
+            path.display()
+        ),
+    )?;
+    assert_eq!(properties["connector"], "avro-ocf");
+    assert_eq!(properties["with_snapshot"], "false");
+    let prefix = format!("{}-<id>-", dir.path().join("out").display());
+    assert!(properties["path"].starts_with(&prefix), "{:?}", properties);
+    assert!(properties["path"].ends_with(".ocf"), "{:?}", properties);
+
+    // Nothing was created.
+    assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+    let count: i64 = client
+        .query_one("SELECT count(*) FROM mz_sinks", &[])?
+        .get(0);
+    assert_eq!(count, 0);
+
+    // Sinks that could not be created are rejected.
+    let sql = format!(
+        "EXPLAIN (SINK) CREATE SINK v FROM v INTO AVRO OCF '{}'",
+        path.display()
+    );
+    let err = explain(&mut client, &sql).unwrap_err();
+    assert_eq!(
+        err.as_db_error().unwrap().message(),
+        "catalog item 'v' already exists"
+    );
+    let sql = format!(
+        "EXPLAIN (SINK) CREATE SINK s FROM tv INTO AVRO OCF '{}'",
+        path.display()
+    );
+    let err = explain(&mut client, &sql).unwrap_err();
+    assert!(err
+        .as_db_error()
+        .unwrap()
+        .message()
+        .starts_with("non-temporary items cannot depend on temporary item"));
+
+    // ...unless the statement would do nothing.
+    let sql = format!(
+        "EXPLAIN (SINK) CREATE SINK IF NOT EXISTS v FROM v INTO AVRO OCF '{}'",
+        path.display()
+    );
+    assert_eq!(
+        explain(&mut client, &sql)?["action"],
+        "none (already exists)"
+    );
+
+    Ok(())
+}
+
+// Ensures that sinks and tails of system objects are only permitted in
+// experimental mode.
+#[test]
+fn test_system_object_export() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let dir = tempfile::tempdir()?;
+    let create_sink = format!(
+        "CREATE SINK s FROM mz_views INTO AVRO OCF '{}'",
+        dir.path().join("out.ocf").display()
+    );
+    let explain_sink = format!("EXPLAIN (SINK) {}", create_sink);
+
+    {
+        let (_server, mut client) = util::start_server(util::Config::default())?;
+        for (sql, statement) in &[
+            (&*create_sink, "CREATE SINK"),
+            (&*explain_sink, "CREATE SINK"),
+            ("TAIL mz_views LIMIT 1", "TAIL"),
+        ] {
+            let err = client.query(*sql, &[]).unwrap_err();
+            assert!(
+                err.to_string().contains(&format!(
+                    "{} from system object 'mz_catalog.mz_views' requires experimental mode",
+                    statement
+                )),
+                "{}: {}",
+                sql,
+                err
+            );
+        }
+        let count: i64 = client
+            .query_one("SELECT count(*) FROM mz_sinks", &[])?
+            .get(0);
+        assert_eq!(count, 0);
+    }
+
+    {
+        let config = util::Config::default().experimental_mode();
+        let (_server, mut client) = util::start_server(config)?;
+        client.batch_execute(&create_sink)?;
+        let rows = client.query("TAIL mz_views LIMIT 1", &[])?;
+        assert_eq!(rows.len(), 1);
+    }
+
+    Ok(())
+}
+
+// Ensures that `EXPLAIN ANALYZE` executes its explainee and reports how it was
+// executed in place of its results.
+#[test]
+fn test_explain_analyze() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().experimental_mode();
+    let (_server, mut client) = util::start_server(config)?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+
+    let explain = |client: &mut postgres::Client, sql: &str| {
+        client.query(sql, &[]).map(|rows| {
+            rows.into_iter()
+                .map(|row| (row.get(0), row.get(1)))
+                .collect::<BTreeMap<String, String>>()
+        })
+    };
+    let millis = |properties: &BTreeMap<String, String>, property: &str| {
+        properties[property].parse::<f64>().unwrap()
+    };
+
+    // Writes report the number of affected rows, and take effect.
+    let properties = explain(
+        &mut client,
+        "EXPLAIN ANALYZE INSERT INTO t VALUES (1), (2), (3)",
+    )?;
+    assert_eq!(properties["affected_rows"], "3");
+    assert!(millis(&properties, "total_time_ms") >= 0.0);
+    let count: i64 = client.query_one("SELECT count(*) FROM t", &[])?.get(0);
+    assert_eq!(count, 3);
+
+    // Constant queries are not read at any timestamp.
+    let properties = explain(&mut client, "EXPLAIN ANALYZE SELECT 1")?;
+    assert_eq!(properties["path"], "constant");
+    assert_eq!(properties["rows"], "1");
+    assert!(!properties.contains_key("timestamp"), "{:?}", properties);
+
+    // Tables are indexed by default, so reading one takes the fast path.
+    let properties = explain(&mut client, "EXPLAIN ANALYZE SELECT * FROM t")?;
+    assert_eq!(properties["path"], "fast");
+    assert_eq!(properties["rows"], "3");
+    assert!(
+        properties["timestamp"].parse::<u64>().is_ok(),
+        "{:?}",
+        properties
+    );
+    assert!(millis(&properties, "time_to_first_row_ms") <= millis(&properties, "total_time_ms"));
+
+    let properties = explain(
+        &mut client,
+        "EXPLAIN ANALYZE SELECT a % 2, count(*) FROM t GROUP BY a % 2",
+    )?;
+    assert_eq!(properties["path"], "slow");
+    assert_eq!(properties["rows"], "2");
+
+    // Queries that return no rows have no first row.
+    let properties = explain(&mut client, "EXPLAIN ANALYZE SELECT * FROM t WHERE a > 3")?;
+    assert_eq!(properties["rows"], "0");
+    assert!(
+        !properties.contains_key("time_to_first_row_ms"),
+        "{:?}",
+        properties
+    );
+
+    // Delays in delivering the results are included in the timings.
+    let properties = explain(&mut client, "EXPLAIN ANALYZE SELECT mz_sleep(0.2)")?;
+    assert!(millis(&properties, "time_to_first_row_ms") >= 200.0);
+    assert!(millis(&properties, "total_time_ms") >= 200.0);
+
+    let err = explain(
+        &mut client,
+        "EXPLAIN ANALYZE INSERT INTO t VALUES (4) RETURNING a",
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("INSERT ... RETURNING not yet supported"),
+        "{}",
+        err
+    );
+
+    Ok(())
+}
@@ -33,6 +33,15 @@ pub struct Config {
     tls: Option<materialized::TlsConfig>,
     experimental_mode: bool,
     threads: usize,
+    peek_admission: Option<coord::PeekAdmissionConfig>,
+    max_connections: Option<usize>,
+    idle_session_timeout: Option<Duration>,
+    max_indexes_per_relation: usize,
+    copy_to_file: Option<coord::CopyToFileConfig>,
+    timestamp_quantum: Duration,
+    default_source_materialization: bool,
+    object_history: bool,
+    skip_failed_sink_rebuilds: bool,
 }
 
 impl Default for Config {
@@ -43,6 +52,15 @@ impl Default for Config {
             tls: None,
             experimental_mode: false,
             threads: 1,
+            peek_admission: None,
+            max_connections: None,
+            idle_session_timeout: None,
+            max_indexes_per_relation: coord::DEFAULT_MAX_INDEXES_PER_RELATION,
+            copy_to_file: None,
+            timestamp_quantum: Duration::from_millis(1),
+            default_source_materialization: false,
+            object_history: false,
+            skip_failed_sink_rebuilds: false,
         }
     }
 }
@@ -79,6 +97,57 @@ impl Config {
         self.threads = threads;
         self
     }
+
+    pub fn peek_admission(mut self, max_concurrent: usize, max_wait: Option<Duration>) -> Self {
+        self.peek_admission = Some(coord::PeekAdmissionConfig {
+            max_concurrent,
+            max_wait,
+        });
+        self
+    }
+
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = Some(max_connections);
+        self
+    }
+
+    pub fn idle_session_timeout(mut self, idle_session_timeout: Duration) -> Self {
+        self.idle_session_timeout = Some(idle_session_timeout);
+        self
+    }
+
+    pub fn max_indexes_per_relation(mut self, max_indexes_per_relation: usize) -> Self {
+        self.max_indexes_per_relation = max_indexes_per_relation;
+        self
+    }
+
+    pub fn copy_to_file(mut self, directory: PathBuf, max_file_size: u64) -> Self {
+        self.copy_to_file = Some(coord::CopyToFileConfig {
+            directory,
+            max_file_size,
+        });
+        self
+    }
+
+    pub fn timestamp_quantum(mut self, timestamp_quantum: Duration) -> Self {
+        self.timestamp_quantum = timestamp_quantum;
+        self
+    }
+
+    pub fn default_source_materialization(mut self) -> Self {
+        self.default_source_materialization = true;
+        self
+    }
+
+    pub fn object_history(mut self) -> Self {
+        self.object_history = true;
+        self
+    }
+
+    pub fn skip_failed_sink_rebuilds(mut self) -> Self {
+        self.skip_failed_sink_rebuilds = true;
+        self
+    }
 }
 
 pub fn start_server(config: Config) -> Result<(Server, postgres::Client), Box<dyn Error>> {
@@ -103,8 +172,17 @@ pub fn start_server(config: Config) -> Result<(Server, postgres::Client), Box<dy
                     log_logging: false,
                 }),
             timestamp_frequency: Duration::from_millis(10),
+            timestamp_quantum: config.timestamp_quantum,
             cache: None,
             logical_compaction_window: None,
+            logical_compaction_hysteresis: 0,
+            feedback_batch_window: None,
+            peek_admission: config.peek_admission,
+            max_connections: config.max_connections,
+            idle_session_timeout: config.idle_session_timeout,
+            max_indexes_per_relation: config.max_indexes_per_relation,
+            copy_to_file: config.copy_to_file,
+            bootstrap_timeout: None,
             threads: config.threads,
             process: 0,
             addresses: vec![SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0)],
@@ -114,6 +192,9 @@ pub fn start_server(config: Config) -> Result<(Server, postgres::Client), Box<dy
             listen_addr: None,
             tls: config.tls,
             experimental_mode: config.experimental_mode,
+            default_source_materialization: config.default_source_materialization,
+            object_history: config.object_history,
+            skip_failed_sink_rebuilds: config.skip_failed_sink_rebuilds,
             telemetry_url: None,
         },
         runtime.clone(),
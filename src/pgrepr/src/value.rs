@@ -15,6 +15,7 @@ use std::str;
 
 use bytes::{BufMut, BytesMut};
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
 use postgres_types::{FromSql, IsNull, ToSql, Type as PgType};
 use repr::ColumnType;
 use uuid::Uuid;
@@ -234,10 +235,19 @@ impl Value {
     }
 
     /// Serializes this value to `buf` in the specified `format`.
-    pub fn encode(&self, ty: &Type, format: Format, buf: &mut BytesMut) -> Result<(), io::Error> {
+    ///
+    /// Timestamps with time zones are displayed in `timezone` by the text
+    /// format. The binary format always represents them in UTC.
+    pub fn encode(
+        &self,
+        ty: &Type,
+        format: Format,
+        timezone: Tz,
+        buf: &mut BytesMut,
+    ) -> Result<(), io::Error> {
         match format {
             Format::Text => {
-                self.encode_text(buf);
+                self.encode_text_in_timezone(buf, timezone);
                 Ok(())
             }
             Format::Binary => self.encode_binary(ty, buf),
@@ -245,8 +255,17 @@ impl Value {
     }
 
     /// Serializes this value to `buf` using the [text encoding
-    /// format](Format::Text).
+    /// format](Format::Text), displaying timestamps with time zones in UTC.
     pub fn encode_text<F>(&self, buf: &mut F) -> Nestable
+    where
+        F: FormatBuffer,
+    {
+        self.encode_text_in_timezone(buf, Tz::UTC)
+    }
+
+    /// Like [`Value::encode_text`], but displays timestamps with time zones in
+    /// `timezone`.
+    pub fn encode_text_in_timezone<F>(&self, buf: &mut F, timezone: Tz) -> Nestable
     where
         F: FormatBuffer,
     {
@@ -254,7 +273,7 @@ impl Value {
             Value::Array { dims, elements } => {
                 strconv::format_array(buf, dims, elements, |buf, elem| match elem {
                     None => buf.write_null(),
-                    Some(elem) => elem.encode_text(buf.nonnull_buffer()),
+                    Some(elem) => elem.encode_text_in_timezone(buf.nonnull_buffer(), timezone),
                 })
             }
             Value::Bool(b) => strconv::format_bool(buf, *b),
@@ -268,21 +287,21 @@ impl Value {
             Value::Jsonb(js) => strconv::format_jsonb(buf, js.0.as_ref()),
             Value::List(elems) => strconv::format_list(buf, elems, |buf, elem| match elem {
                 None => buf.write_null(),
-                Some(elem) => elem.encode_text(buf.nonnull_buffer()),
+                Some(elem) => elem.encode_text_in_timezone(buf.nonnull_buffer(), timezone),
             }),
             Value::Map(elems) => strconv::format_map(buf, elems, |buf, value| match value {
                 None => buf.write_null(),
-                Some(elem) => elem.encode_text(buf.nonnull_buffer()),
+                Some(elem) => elem.encode_text_in_timezone(buf.nonnull_buffer(), timezone),
             }),
             Value::Numeric(n) => strconv::format_decimal(buf, &n.0),
             Value::Record(elems) => strconv::format_record(buf, elems, |buf, elem| match elem {
                 None => buf.write_null(),
-                Some(elem) => elem.encode_text(buf.nonnull_buffer()),
+                Some(elem) => elem.encode_text_in_timezone(buf.nonnull_buffer(), timezone),
             }),
             Value::Text(s) => strconv::format_string(buf, s),
             Value::Time(t) => strconv::format_time(buf, *t),
             Value::Timestamp(ts) => strconv::format_timestamp(buf, *ts),
-            Value::TimestampTz(ts) => strconv::format_timestamptz(buf, *ts),
+            Value::TimestampTz(ts) => strconv::format_timestamptz_in_timezone(buf, *ts, timezone),
             Value::Uuid(u) => strconv::format_uuid(buf, *u),
         }
     }
@@ -404,6 +423,7 @@ impl Value {
             Type::Timestamp => Value::Timestamp(strconv::parse_timestamp(raw)?),
             Type::TimestampTz => Value::TimestampTz(strconv::parse_timestamptz(raw)?),
             Type::Uuid => Value::Uuid(Uuid::parse_str(raw)?),
+            Type::Void => return Err("input of void is not implemented".into()),
         })
     }
 
@@ -430,6 +450,7 @@ impl Value {
             Type::Timestamp => NaiveDateTime::from_sql(ty.inner(), raw).map(Value::Timestamp),
             Type::TimestampTz => DateTime::<Utc>::from_sql(ty.inner(), raw).map(Value::TimestampTz),
             Type::Uuid => Uuid::from_sql(ty.inner(), raw).map(Value::Uuid),
+            Type::Void => Err("input of void is not implemented".into()),
         }
     }
 }
@@ -487,6 +508,7 @@ pub fn null_datum(ty: &Type) -> (Datum<'static>, ScalarType) {
         Type::Timestamp => ScalarType::Timestamp,
         Type::TimestampTz => ScalarType::TimestampTz,
         Type::Uuid => ScalarType::Uuid,
+        Type::Void => ScalarType::Void,
         Type::Record(fields) => {
             let fields = fields
                 .iter()
@@ -367,7 +367,7 @@ pub fn run_test(tf: &mut datadriven::TestFile, addr: &str, user: &str, timeout:
                             frontend::parse(
                                 &v.name.unwrap_or_else(|| "".into()),
                                 &v.query,
-                                vec![],
+                                v.param_types.unwrap_or_default(),
                                 buf,
                             )
                             .unwrap();
@@ -454,6 +454,8 @@ pub struct Query {
 pub struct Parse {
     pub name: Option<String>,
     pub query: String,
+    /// The OIDs of the parameter types, where `0` leaves the type unspecified.
+    pub param_types: Option<Vec<u32>>,
 }
 
 #[derive(Deserialize)]
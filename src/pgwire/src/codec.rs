@@ -22,6 +22,7 @@ use std::str;
 use async_trait::async_trait;
 use byteorder::{ByteOrder, NetworkEndian};
 use bytes::{Buf, BufMut, BytesMut};
+use chrono_tz::Tz;
 use futures::{sink, SinkExt, TryStreamExt};
 use lazy_static::lazy_static;
 use log::trace;
@@ -143,9 +144,16 @@ where
     /// information in each message, we use this side channel to install the
     /// type information in the codec before sending any data row messages. This
     /// violates the abstraction boundary a bit but results in much better
-    /// performance.
-    pub fn set_encode_state(&mut self, encode_state: Vec<(pgrepr::Type, pgrepr::Format)>) {
-        self.inner.get_mut().codec_mut().encode_state = encode_state;
+    /// performance. The session's time zone, which determines how timestamps
+    /// with time zones are displayed, is installed the same way.
+    pub fn set_encode_state(
+        &mut self,
+        encode_state: Vec<(pgrepr::Type, pgrepr::Format)>,
+        timezone: Tz,
+    ) {
+        let codec = self.inner.get_mut().codec_mut();
+        codec.encode_state = encode_state;
+        codec.timezone = timezone;
     }
 }
 
@@ -162,6 +170,7 @@ where
 struct Codec {
     decode_state: DecodeState,
     encode_state: Vec<(pgrepr::Type, pgrepr::Format)>,
+    timezone: Tz,
 }
 
 impl Codec {
@@ -170,6 +179,7 @@ impl Codec {
         Codec {
             decode_state: DecodeState::Head,
             encode_state: vec![],
+            timezone: Tz::UTC,
         }
     }
 }
@@ -210,6 +220,7 @@ impl Encoder<BackendMessage> for Codec {
             BackendMessage::CopyOutResponse { .. } => b'H',
             BackendMessage::CopyData(_) => b'd',
             BackendMessage::CopyDone => b'c',
+            BackendMessage::NotificationResponse { .. } => b'A',
         };
         dst.put_u8(byte);
 
@@ -233,6 +244,15 @@ impl Encoder<BackendMessage> for Codec {
                 dst.put_slice(&data);
             }
             BackendMessage::CopyDone => (),
+            BackendMessage::NotificationResponse {
+                conn_id,
+                channel,
+                payload,
+            } => {
+                dst.put_u32(conn_id);
+                dst.put_string(&channel);
+                dst.put_string(&payload);
+            }
             BackendMessage::AuthenticationOk => {
                 dst.put_u32(0);
             }
@@ -255,7 +275,7 @@ impl Encoder<BackendMessage> for Codec {
                     if let Some(f) = f {
                         let base = dst.len();
                         dst.put_u32(0);
-                        f.encode(ty, *format, dst)?;
+                        f.encode(ty, *format, self.timezone, dst)?;
                         let len = dst.len() - base - 4;
                         let len = i32::try_from(len).map_err(|_| {
                             io::Error::new(
@@ -14,8 +14,8 @@ use std::iter;
 use std::mem;
 
 use byteorder::{ByteOrder, NetworkEndian};
-use futures::future::{BoxFuture, FutureExt};
-use futures::stream::{self, StreamExt, TryStreamExt};
+use futures::future::{self, BoxFuture, Either, FutureExt};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use itertools::izip;
 use lazy_static::lazy_static;
 use log::debug;
@@ -24,12 +24,16 @@ use prometheus::{register_histogram_vec, register_uint_counter};
 use tokio::io::{AsyncRead, AsyncWrite, Interest};
 use tokio::time::{self, Duration, Instant};
 
-use coord::session::{Portal, PortalState, RowBatchStream, TransactionStatus};
-use coord::{ExecuteResponse, StartupMessage};
+use coord::session::{
+    Notice, Notification, Portal, PortalState, RowBatchStream, TransactionStatus,
+};
+use coord::{
+    copy, ExecuteResponse, IdleSessionTimeout, QueryCanceled, StartupMessage, TooManyConnections,
+};
 use dataflow_types::PeekResponse;
 use ore::cast::CastFrom;
 use ore::netio::AsyncReady;
-use repr::{Datum, RelationDesc, RelationType, Row, RowArena};
+use repr::{Datum, RelationDesc, Row, RowArena};
 use sql::ast::display::AstDisplay;
 use sql::ast::{FetchDirection, Ident, Statement};
 use sql::plan::{CopyFormat, ExecuteTimeout, StatementDesc};
@@ -116,7 +120,27 @@ where
     }
 
     async fn advance_ready(&mut self) -> Result<State, comm::Error> {
-        let message = self.conn.recv().await?;
+        // While waiting for the next message, deliver any notifications that
+        // arrive for the channels the session is listening on. Clients must
+        // be prepared to receive notifications at any time.
+        let message = loop {
+            let next = {
+                let recv = self.conn.recv();
+                let notifications = self.coord_client.session().recv_notifications();
+                futures::pin_mut!(recv, notifications);
+                match future::select(recv, notifications).await {
+                    Either::Left((message, _)) => Either::Left(message),
+                    Either::Right((notifications, _)) => Either::Right(notifications),
+                }
+            };
+            match next {
+                Either::Left(message) => break message?,
+                Either::Right(notifications) => {
+                    self.send_notifications(notifications).await?;
+                    self.conn.flush().await?;
+                }
+            }
+        };
         let timer = Instant::now();
         let name = match &message {
             Some(message) => message.name(),
@@ -209,7 +233,12 @@ where
         }
 
         for (name, value) in params {
-            let _ = self.coord_client.session().vars_mut().set(&name, &value);
+            let vars = self.coord_client.session().vars_mut();
+            if name == "user" {
+                vars.set_user(&value);
+            } else {
+                let _ = vars.set(&name, &value);
+            }
         }
 
         let notices: Vec<_> = match self.coord_client.startup().await {
@@ -231,6 +260,14 @@ where
                     .into_message(),
                 })
                 .collect(),
+            Err(e) if e.is::<TooManyConnections>() => {
+                return self
+                    .error(ErrorResponse::fatal(
+                        SqlState::TOO_MANY_CONNECTIONS,
+                        e.to_string(),
+                    ))
+                    .await;
+            }
             Err(e) => {
                 return self
                     .error(ErrorResponse::error(
@@ -271,12 +308,7 @@ where
             .declare(EMPTY_PORTAL.to_string(), stmt.clone(), param_types)
             .await
         {
-            return self
-                .error(ErrorResponse::error(
-                    SqlState::INTERNAL_ERROR,
-                    format!("{:#}", e),
-                ))
-                .await;
+            return self.error(coord_error(e)).await;
         }
 
         let stmt_desc = self
@@ -319,13 +351,7 @@ where
                 )
                 .await
             }
-            Err(e) => {
-                self.error(ErrorResponse::error(
-                    SqlState::INTERNAL_ERROR,
-                    format!("{:#}", e),
-                ))
-                .await
-            }
+            Err(e) => self.error(coord_error(e)).await,
         };
 
         // Destroy the portal.
@@ -434,13 +460,7 @@ where
                 self.conn.send(BackendMessage::ParseComplete).await?;
                 Ok(State::Ready)
             }
-            Err(e) => {
-                self.error(ErrorResponse::error(
-                    SqlState::INTERNAL_ERROR,
-                    format!("{:#}", e),
-                ))
-                .await
-            }
+            Err(e) => self.error(coord_error(e)).await,
         }
     }
 
@@ -584,13 +604,7 @@ where
                             )
                             .await
                         }
-                        Err(e) => {
-                            self.error(ErrorResponse::error(
-                                SqlState::INTERNAL_ERROR,
-                                format!("{:#}", e),
-                            ))
-                            .await
-                        }
+                        Err(e) => self.error(coord_error(e)).await,
                     }
                 }
                 PortalState::InProgress(rows) => {
@@ -769,6 +783,12 @@ where
     }
 
     async fn sync(&mut self) -> Result<State, comm::Error> {
+        // Notifications are delivered between transactions, as in PostgreSQL.
+        let session = self.coord_client.session();
+        if let TransactionStatus::Idle = session.transaction() {
+            let notifications = session.drain_notifications();
+            self.send_notifications(notifications).await?;
+        }
         let txn_state = self.coord_client.session().transaction().into();
         self.conn
             .send(BackendMessage::ReadyForQuery(txn_state))
@@ -776,6 +796,61 @@ where
         self.flush().await
     }
 
+    async fn send_pending_notices(&mut self) -> Result<(), comm::Error> {
+        let notices = self
+            .coord_client
+            .session()
+            .drain_notices()
+            .into_iter()
+            .map(|notice| match notice {
+                Notice::RowLockingIgnored(locking) => {
+                    ErrorResponse::notice(SqlState::WARNING, format!("{} has no effect", locking))
+                        .with_hint("Materialize does not support row-level locking.")
+                        .into_message()
+                }
+                Notice::QueryTimestamp(timestamp) => ErrorResponse::notice(
+                    SqlState::SUCCESSFUL_COMPLETION,
+                    format!("query executed at timestamp {}", timestamp),
+                )
+                .into_message(),
+                Notice::OptimizerFuelExhausted(fuel) => ErrorResponse::notice(
+                    SqlState::WARNING,
+                    format!(
+                        "optimizer fuel exhausted after {} transforms; the query plan may be suboptimal",
+                        fuel
+                    ),
+                )
+                .with_hint("Raise optimizer_fuel, or set it to 0 to remove the limit.")
+                .into_message(),
+                Notice::ResultChecksum { checksum, rows } => ErrorResponse::notice(
+                    SqlState::SUCCESSFUL_COMPLETION,
+                    format!("result checksum {:016x} over {} rows", checksum, rows),
+                )
+                .into_message(),
+                Notice::RefreshIgnored(name) => ErrorResponse::notice(
+                    SqlState::SUCCESSFUL_COMPLETION,
+                    format!("REFRESH MATERIALIZED VIEW {} has no effect", name),
+                )
+                .with_hint("Materialize keeps materialized views continuously up to date.")
+                .into_message(),
+            });
+        Ok(self.conn.send_all(notices).await?)
+    }
+
+    async fn send_notifications(
+        &mut self,
+        notifications: Vec<Notification>,
+    ) -> Result<(), comm::Error> {
+        let messages = notifications
+            .into_iter()
+            .map(|n| BackendMessage::NotificationResponse {
+                conn_id: n.conn_id,
+                channel: n.channel,
+                payload: n.payload,
+            });
+        Ok(self.conn.send_all(messages).await?)
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn send_execute_response(
         &mut self,
@@ -787,6 +862,8 @@ where
         fetch_portal_name: Option<String>,
         timeout: ExecuteTimeout,
     ) -> Result<State, comm::Error> {
+        self.send_pending_notices().await?;
+
         macro_rules! command_complete {
             ($($arg:tt)*) => {{
                 // N.B.: the output of format! must be stored into a
@@ -837,12 +914,15 @@ where
                 created!(existed, SqlState::DUPLICATE_OBJECT, "view")
             }
             ExecuteResponse::CreatedType => command_complete!("CREATE TYPE"),
+            ExecuteResponse::CreatedFunction => command_complete!("CREATE FUNCTION"),
             ExecuteResponse::DeclaredCursor => {
                 self.complete_portal(&portal_name);
                 command_complete!("DECLARE CURSOR")
             }
             ExecuteResponse::Deleted(n) => command_complete!("DELETE {}", n),
             ExecuteResponse::DiscardedTemp => command_complete!("DISCARD TEMP"),
+            ExecuteResponse::DiscardedPlans => command_complete!("DISCARD PLANS"),
+            ExecuteResponse::DiscardedSequences => command_complete!("DISCARD SEQUENCES"),
             ExecuteResponse::DiscardedAll => command_complete!("DISCARD ALL"),
             ExecuteResponse::DroppedDatabase => command_complete!("DROP DATABASE"),
             ExecuteResponse::DroppedSchema => command_complete!("DROP SCHEMA"),
@@ -852,6 +932,7 @@ where
             ExecuteResponse::DroppedTable => command_complete!("DROP TABLE"),
             ExecuteResponse::DroppedView => command_complete!("DROP VIEW"),
             ExecuteResponse::DroppedType => command_complete!("DROP TYPE"),
+            ExecuteResponse::DroppedFunction => command_complete!("DROP FUNCTION"),
             ExecuteResponse::EmptyQuery => {
                 self.conn.send(BackendMessage::EmptyQueryResponse).await?;
                 Ok(State::Ready)
@@ -880,22 +961,27 @@ where
                 // have OIDs.
                 command_complete!("INSERT 0 {}", n)
             }
+            ExecuteResponse::Listened => command_complete!("LISTEN"),
+            ExecuteResponse::Commented => command_complete!("COMMENT"),
+            ExecuteResponse::Notified => command_complete!("NOTIFY"),
+            ExecuteResponse::SelectedInto(n) => {
+                // PostgreSQL reports `SELECT INTO` with the same command tag
+                // as a plain `SELECT`, i.e., `SELECT <count>`.
+                command_complete!("SELECT {}", n)
+            }
             ExecuteResponse::SendingRows(rx) => {
                 let row_desc =
                     row_desc.expect("missing row description for ExecuteResponse::SendingRows");
                 match rx.await? {
-                    PeekResponse::Canceled => {
-                        self.error(ErrorResponse::error(
-                            SqlState::QUERY_CANCELED,
-                            "canceling statement due to user request",
-                        ))
-                        .await
-                    }
-                    PeekResponse::Error(text) => {
-                        self.error(ErrorResponse::error(SqlState::INTERNAL_ERROR, text))
-                            .await
-                    }
                     PeekResponse::Rows(rows) => {
+                        let session = self.coord_client.session();
+                        if session.vars().emit_result_checksums() {
+                            session.add_notice(Notice::ResultChecksum {
+                                checksum: result_checksum(&rows),
+                                rows: rows.len(),
+                            });
+                            self.send_pending_notices().await?;
+                        }
                         self.send_rows(
                             row_desc,
                             portal_name,
@@ -907,6 +993,7 @@ where
                         )
                         .await
                     }
+                    resp => self.error(peek_response_error(resp)).await,
                 }
             }
             ExecuteResponse::SetVariable { name } => {
@@ -958,28 +1045,26 @@ where
                 )
                 .await
             }
+            ExecuteResponse::StreamingRows(_) => {
+                // The coordinator only streams rows that are copied out.
+                self.error(ErrorResponse::error(
+                    SqlState::INTERNAL_ERROR,
+                    "unexpected streaming response outside of COPY".to_string(),
+                ))
+                .await
+            }
             ExecuteResponse::CopyTo { format, resp } => {
                 let row_desc =
                     row_desc.expect("missing row description for ExecuteResponse::CopyTo");
-                let rows: RowBatchStream = match *resp {
-                    ExecuteResponse::Tailing { rx } => Box::new(rx),
+                let rows: PeekResponseStream = match *resp {
+                    ExecuteResponse::Tailing { rx } => Box::new(rx.map_ok(PeekResponse::Rows)),
                     ExecuteResponse::SendingRows(rx) => match rx.await? {
-                        // TODO(mjibson): This logic is duplicated from SendingRows. Dedup?
-                        PeekResponse::Canceled => {
-                            return self
-                                .error(ErrorResponse::error(
-                                    SqlState::QUERY_CANCELED,
-                                    "canceling statement due to user request",
-                                ))
-                                .await;
+                        PeekResponse::Rows(rows) => {
+                            Box::new(stream::iter(vec![Ok(PeekResponse::Rows(rows))]))
                         }
-                        PeekResponse::Error(text) => {
-                            return self
-                                .error(ErrorResponse::error(SqlState::INTERNAL_ERROR, text))
-                                .await;
-                        }
-                        PeekResponse::Rows(rows) => Box::new(stream::iter(vec![Ok(rows)])),
+                        resp => return self.error(peek_response_error(resp)).await,
                     },
+                    ExecuteResponse::StreamingRows(rx) => Box::new(rx),
                     _ => {
                         return self
                             .error(ErrorResponse::error(
@@ -991,9 +1076,19 @@ where
                 };
                 self.copy_rows(format, row_desc, rows).await
             }
+            ExecuteResponse::Unlistened => command_complete!("UNLISTEN"),
             ExecuteResponse::Updated(n) => command_complete!("UPDATE {}", n),
             ExecuteResponse::AlteredObject(o) => command_complete!("ALTER {}", o),
             ExecuteResponse::AlteredIndexLogicalCompaction => command_complete!("ALTER INDEX"),
+            ExecuteResponse::AlteredAllIndexesLogicalCompaction(n) => {
+                command_complete!("ALTER INDEX {}", n)
+            }
+            ExecuteResponse::RematerializedView(n) => {
+                command_complete!("ALTER MATERIALIZED VIEW {}", n)
+            }
+            ExecuteResponse::RefreshedMaterializedView => {
+                command_complete!("REFRESH MATERIALIZED VIEW")
+            }
             ExecuteResponse::PgError { code, message } => {
                 self.error(ErrorResponse::error(code, message)).await
             }
@@ -1093,6 +1188,7 @@ where
             }
         }
 
+        let timezone = self.coord_client.session().vars().timezone();
         self.conn.set_encode_state(
             row_desc
                 .typ()
@@ -1101,6 +1197,7 @@ where
                 .map(|ty| pgrepr::Type::from(&ty.scalar_type))
                 .zip(result_formats)
                 .collect(),
+            timezone,
         );
 
         let mut total_sent_rows = 0;
@@ -1153,25 +1250,15 @@ where
         &mut self,
         format: CopyFormat,
         row_desc: RelationDesc,
-        mut stream: RowBatchStream,
+        mut stream: PeekResponseStream,
     ) -> Result<State, comm::Error> {
-        let (encode_fn, encode_format): (
-            fn(Row, &RelationType, &mut Vec<u8>) -> Result<(), std::io::Error>,
-            pgrepr::Format,
-        ) = match format {
-            CopyFormat::Text => (message::encode_copy_row_text, pgrepr::Format::Text),
-            CopyFormat::Binary => (message::encode_copy_row_binary, pgrepr::Format::Binary),
-            _ => {
-                return self
-                    .error(ErrorResponse::error(
-                        SqlState::FEATURE_NOT_SUPPORTED,
-                        format!("COPY TO format {:?} not supported", format),
-                    ))
-                    .await
-            }
+        let encode_format = match format {
+            CopyFormat::Text | CopyFormat::Csv(_) => pgrepr::Format::Text,
+            CopyFormat::Binary => pgrepr::Format::Binary,
         };
 
         let typ = row_desc.typ();
+        let timezone = self.coord_client.session().vars().timezone();
         let column_formats = iter::repeat(encode_format)
             .take(typ.column_types.len())
             .collect();
@@ -1187,25 +1274,40 @@ where
         // common vec that we can extend one time now and then fill up with the encode
         // functions.
         let mut out = Vec::new();
+        copy::encode_copy_header(&format, &row_desc, &mut out);
 
-        if let CopyFormat::Binary = format {
-            // 11-byte signature.
-            out.extend(b"PGCOPY\n\xFF\r\n\0");
-            // 32-bit flags field.
-            out.extend(&[0, 0, 0, 0]);
-            // 32-bit header extension length field.
-            out.extend(&[0, 0, 0, 0]);
+        // The CSV header, by contrast, is sent in its own CopyData.
+        if let CopyFormat::Csv(_) = &format {
+            if !out.is_empty() {
+                self.conn
+                    .send(BackendMessage::CopyData(mem::take(&mut out)))
+                    .await?;
+            }
         }
 
         let mut count = 0;
         loop {
             match time::timeout(Duration::from_secs(1), stream.next()).await {
                 Ok(None) => break,
-                Ok(Some(rows)) => {
-                    let rows = rows?;
+                Ok(Some(resp)) => {
+                    // A stream that delivers rows as they arrive from the
+                    // workers may fail after some rows have been sent, in
+                    // which case the error ends the copy.
+                    let rows = match resp? {
+                        PeekResponse::Rows(rows) => rows,
+                        resp => return self.error(peek_response_error(resp)).await,
+                    };
                     count += rows.len();
                     for row in rows {
-                        encode_fn(row, typ, &mut out)?;
+                        if let Err(e) = copy::encode_copy_row(&format, row, typ, timezone, &mut out)
+                        {
+                            return self
+                                .error(ErrorResponse::error(
+                                    SqlState::INTERNAL_ERROR,
+                                    e.to_string(),
+                                ))
+                                .await;
+                        }
                         self.conn
                             .send(BackendMessage::CopyData(mem::take(&mut out)))
                             .await?;
@@ -1237,9 +1339,8 @@ where
             self.conn.flush().await?;
         }
         // Send required trailers.
-        if let CopyFormat::Binary = format {
-            let trailer: i16 = -1;
-            out.extend(&trailer.to_be_bytes());
+        copy::encode_copy_trailer(&format, &mut out);
+        if !out.is_empty() {
             self.conn
                 .send(BackendMessage::CopyData(mem::take(&mut out)))
                 .await?;
@@ -1262,6 +1363,7 @@ where
             err.message
         );
         let is_fatal = err.severity.is_fatal();
+        self.send_pending_notices().await?;
         self.conn.send(BackendMessage::ErrorResponse(err)).await?;
         let session = self.coord_client.session();
         // Errors in implicit transactions move it back to idle, not failed.
@@ -1305,6 +1407,43 @@ fn pad_formats(formats: Vec<pgrepr::Format>, n: usize) -> Result<Vec<pgrepr::For
     }
 }
 
+/// Converts an error returned by the coordinator in response to a declare,
+/// describe, or execute request into the error that is reported to the client.
+fn coord_error(e: anyhow::Error) -> ErrorResponse {
+    if e.is::<QueryCanceled>() {
+        ErrorResponse::error(SqlState::QUERY_CANCELED, e.to_string())
+    } else if e.is::<IdleSessionTimeout>() {
+        ErrorResponse::fatal(SqlState::ADMIN_SHUTDOWN, e.to_string())
+    } else {
+        ErrorResponse::error(SqlState::INTERNAL_ERROR, format!("{:#}", e))
+    }
+}
+
+/// Converts a `PeekResponse` that does not carry rows into the error that is
+/// reported to the client.
+fn peek_response_error(resp: PeekResponse) -> ErrorResponse {
+    match resp {
+        PeekResponse::Canceled => ErrorResponse::error(
+            SqlState::QUERY_CANCELED,
+            "canceling statement due to user request",
+        ),
+        PeekResponse::Error(text) => ErrorResponse::error(SqlState::INTERNAL_ERROR, text),
+        PeekResponse::Rows(_) => unreachable!("peek response carries rows"),
+    }
+}
+
+/// Computes a checksum of `rows` that does not depend on their order.
+///
+/// Each row is hashed independently with CRC-32 over its binary encoding, and
+/// the hashes are summed rather than XORed, so that duplicate rows do not
+/// cancel each other out. CRC-32 has a fixed specification, so the checksum of
+/// a given result is stable across processes, platforms, and Rust versions.
+fn result_checksum(rows: &[Row]) -> u64 {
+    rows.iter()
+        .map(|row| u64::from(crc32fast::hash(row.data())))
+        .fold(0, u64::wrapping_add)
+}
+
 fn describe_rows(stmt_desc: &StatementDesc, formats: &[pgrepr::Format]) -> BackendMessage {
     match &stmt_desc.relation_desc {
         Some(desc) if !stmt_desc.is_copy => {
@@ -1323,6 +1462,10 @@ fn parse_sql(sql: &str) -> Result<Vec<Statement>, ErrorResponse> {
     })
 }
 
+// A stream of peek responses, each of which carries a batch of rows, that
+// copy_rows copies out to the client.
+type PeekResponseStream = Box<dyn Stream<Item = Result<PeekResponse, comm::Error>> + Send + Unpin>;
+
 type GetResponse = fn(
     max_rows: ExecuteCount,
     total_sent_rows: usize,
@@ -720,6 +720,10 @@ pub enum ScalarType {
         value_type: Box<ScalarType>,
         custom_oid: Option<u32>,
     },
+    /// The type of functions that return no value, like `pg_sleep`.
+    ///
+    /// The only valid datum for this type is [`Datum::Null`].
+    Void,
 }
 
 impl<'a> ScalarType {
@@ -843,7 +847,8 @@ impl PartialEq for ScalarType {
             | (String, String)
             | (Uuid, Uuid)
             | (Jsonb, Jsonb)
-            | (Oid, Oid) => true,
+            | (Oid, Oid)
+            | (Void, Void) => true,
             (
                 List {
                     element_type: element_l,
@@ -887,7 +892,8 @@ impl PartialEq for ScalarType {
             | (List { .. }, _)
             | (Record { .. }, _)
             | (Oid, _)
-            | (Map { .. }, _) => false,
+            | (Map { .. }, _)
+            | (Void, _) => false,
         }
     }
 }
@@ -941,6 +947,7 @@ impl Hash for ScalarType {
                 value_type.hash(state);
                 custom_oid.hash(state);
             }
+            Void => state.write_u8(19),
         }
     }
 }
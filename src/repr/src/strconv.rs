@@ -30,6 +30,7 @@ use std::fmt;
 
 use chrono::offset::{Offset, TimeZone};
 use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
 use ore::lex::LexBuf;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -329,9 +330,31 @@ pub fn format_timestamptz<F>(buf: &mut F, ts: DateTime<Utc>) -> Nestable
 where
     F: FormatBuffer,
 {
+    format_timestamptz_in_timezone(buf, ts, Tz::UTC)
+}
+
+/// Writes `ts` to `buf` as the local time in `tz`, followed by the UTC offset
+/// in effect in `tz` at that instant.
+///
+/// Like PostgreSQL, the offset's minutes and seconds are only written when
+/// they are nonzero, e.g. `-05`, `+05:30`, or `-00:25:21`.
+pub fn format_timestamptz_in_timezone<F>(buf: &mut F, ts: DateTime<Utc>, tz: Tz) -> Nestable
+where
+    F: FormatBuffer,
+{
+    let ts = ts.with_timezone(&tz);
     write!(buf, "{}", ts.format("%Y-%m-%d %H:%M:%S"));
     format_nanos_to_micros(buf, ts.timestamp_subsec_nanos());
-    write!(buf, "+00");
+    let offset = ts.offset().fix().local_minus_utc();
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    write!(buf, "{}{:02}", sign, offset / 3600);
+    if offset % 3600 != 0 {
+        write!(buf, ":{:02}", offset / 60 % 60);
+    }
+    if offset % 60 != 0 {
+        write!(buf, ":{:02}", offset % 60);
+    }
     // This always needs escaping because of the whitespace
     Nestable::MayNeedEscaping
 }
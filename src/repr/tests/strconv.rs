@@ -8,6 +8,7 @@
 // by the Apache License, Version 2.0.
 
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 
 use repr::adt::datetime::DateTimeField;
 use repr::adt::interval::Interval;
@@ -272,6 +273,34 @@ fn test_parse_timestamptz_errors() {
     }
 }
 
+#[test]
+fn test_format_timestamptz_in_timezone() {
+    let ts = Utc.ymd(2021, 1, 15).and_hms_micro(12, 30, 45, 123_000);
+    let test_cases = [
+        (Tz::UTC, "2021-01-15 12:30:45.123+00"),
+        (Tz::America__New_York, "2021-01-15 07:30:45.123-05"),
+        (Tz::Asia__Kolkata, "2021-01-15 18:00:45.123+05:30"),
+        (Tz::Asia__Kathmandu, "2021-01-15 18:15:45.123+05:45"),
+    ];
+    for (tz, expected) in test_cases.iter() {
+        let mut actual = String::new();
+        strconv::format_timestamptz_in_timezone(&mut actual, ts, *tz);
+        assert_eq!(actual, *expected);
+    }
+
+    // Offsets before the adoption of standard time have seconds.
+    let ts = Utc.ymd(1900, 1, 1).and_hms(0, 0, 0);
+    let mut actual = String::new();
+    strconv::format_timestamptz_in_timezone(&mut actual, ts, Tz::Europe__Amsterdam);
+    assert_eq!(actual, "1900-01-01 00:19:32+00:19:32");
+
+    // Daylight saving time is applied according to the instant.
+    let ts = Utc.ymd(2021, 7, 15).and_hms(12, 0, 0);
+    let mut actual = String::new();
+    strconv::format_timestamptz_in_timezone(&mut actual, ts, Tz::America__New_York);
+    assert_eq!(actual, "2021-07-15 08:00:00-04");
+}
+
 #[test]
 fn test_parse_interval_monthlike() {
     run_test_parse_interval_monthlike(
@@ -21,7 +21,7 @@
 use std::mem;
 
 use crate::ast::display::{self, AstDisplay, AstFormatter};
-use crate::ast::{Expr, FunctionArgs, Ident, ObjectName, SqlOption};
+use crate::ast::{Expr, FunctionArgs, Ident, ObjectName, SqlOption, TailStatement};
 
 /// The most complete variant of a `SELECT` query expression, optionally
 /// including `WITH`, `UNION` / other set operations, and `ORDER BY`.
@@ -29,6 +29,9 @@ use crate::ast::{Expr, FunctionArgs, Ident, ObjectName, SqlOption};
 pub struct Query {
     /// WITH (common table expressions, or CTEs)
     pub ctes: Vec<Cte>,
+    /// `RECURSIVE ... (MAX ITERATIONS <N>)`; present iff the WITH clause is
+    /// recursive.
+    pub max_iterations: Option<u64>,
     /// SELECT or UNION / EXCEPT / INTECEPT
     pub body: SetExpr,
     /// ORDER BY
@@ -44,8 +47,16 @@ impl AstDisplay for Query {
     fn fmt(&self, f: &mut AstFormatter) {
         if !self.ctes.is_empty() {
             f.write_str("WITH ");
+            if self.max_iterations.is_some() {
+                f.write_str("RECURSIVE ");
+            }
             f.write_node(&display::comma_separated(&self.ctes));
             f.write_str(" ");
+            if let Some(max_iterations) = self.max_iterations {
+                f.write_str("(MAX ITERATIONS ");
+                f.write_str(max_iterations);
+                f.write_str(") ");
+            }
         }
         f.write_node(&self.body);
         if !self.order_by.is_empty() {
@@ -82,6 +93,7 @@ impl Query {
     pub fn select(select: Select) -> Query {
         Query {
             ctes: vec![],
+            max_iterations: None,
             body: SetExpr::Select(Box::new(select)),
             order_by: vec![],
             limit: None,
@@ -94,6 +106,7 @@ impl Query {
             self,
             Query {
                 ctes: vec![],
+                max_iterations: None,
                 order_by: vec![],
                 body: SetExpr::Values(Values(vec![])),
                 limit: None,
@@ -179,6 +192,8 @@ pub struct Select {
     pub distinct: Option<Distinct>,
     /// projection expressions
     pub projection: Vec<SelectItem>,
+    /// INTO
+    pub into: Option<SelectInto>,
     /// FROM
     pub from: Vec<TableWithJoins>,
     /// WHERE
@@ -202,6 +217,10 @@ impl AstDisplay for Select {
             f.write_str(" ");
             f.write_node(&display::comma_separated(&self.projection));
         }
+        if let Some(into) = &self.into {
+            f.write_str(" ");
+            f.write_node(into);
+        }
         if !self.from.is_empty() {
             f.write_str(" FROM ");
             f.write_node(&display::comma_separated(&self.from));
@@ -244,6 +263,25 @@ impl Select {
     }
 }
 
+/// The target of a `SELECT ... INTO` clause, which creates a new table from
+/// the results of the query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SelectInto {
+    pub name: ObjectName,
+    pub temporary: bool,
+}
+
+impl AstDisplay for SelectInto {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("INTO ");
+        if self.temporary {
+            f.write_str("TEMPORARY ");
+        }
+        f.write_node(&self.name);
+    }
+}
+impl_display!(SelectInto);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Distinct {
     EntireRow,
@@ -355,6 +393,11 @@ pub enum TableFactor {
         subquery: Box<Query>,
         alias: Option<TableAlias>,
     },
+    /// A parenthesized `TAIL`, as in `SELECT * FROM (TAIL v UNTIL now())`.
+    Tail {
+        tail: Box<TailStatement>,
+        alias: Option<TableAlias>,
+    },
     /// Represents a parenthesized join expression, such as
     /// `(foo <JOIN> bar [ <JOIN> baz ... ])`.
     /// The inner `TableWithJoins` can have no joins only if its
@@ -401,6 +444,15 @@ impl AstDisplay for TableFactor {
                     f.write_node(alias);
                 }
             }
+            TableFactor::Tail { tail, alias } => {
+                f.write_str("(");
+                f.write_node(tail);
+                f.write_str(")");
+                if let Some(alias) = alias {
+                    f.write_str(" AS ");
+                    f.write_node(alias);
+                }
+            }
             TableFactor::NestedJoin { join, alias } => {
                 f.write_str("(");
                 f.write_node(join);
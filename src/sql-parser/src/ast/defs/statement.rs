@@ -20,7 +20,7 @@
 
 use crate::ast::display::{self, AstDisplay, AstFormatter};
 use crate::ast::{
-    ColumnDef, Connector, DataType, Envelope, Expr, Format, Ident, ObjectName, Query,
+    ColumnDef, Connector, DataType, Envelope, Expr, Format, Ident, ObjectName, Query, SelectItem,
     TableConstraint, Value,
 };
 
@@ -41,8 +41,15 @@ pub enum Statement {
     CreateTable(CreateTableStatement),
     CreateIndex(CreateIndexStatement),
     CreateType(CreateTypeStatement),
+    CreateFunction(CreateFunctionStatement),
     AlterObjectRename(AlterObjectRenameStatement),
+    AlterViewRenameColumn(AlterViewRenameColumnStatement),
     AlterIndexOptions(AlterIndexOptionsStatement),
+    AlterIndexKeys(AlterIndexKeysStatement),
+    AlterAllIndexesOptions(AlterAllIndexesOptionsStatement),
+    AlterMaterializedViewOptions(AlterMaterializedViewOptionsStatement),
+    AlterMaterializedViewRematerialize(AlterMaterializedViewRematerializeStatement),
+    RefreshMaterializedView(RefreshMaterializedViewStatement),
     Discard(DiscardStatement),
     DropDatabase(DropDatabaseStatement),
     DropObjects(DropObjectsStatement),
@@ -66,6 +73,10 @@ pub enum Statement {
     Declare(DeclareStatement),
     Fetch(FetchStatement),
     Close(CloseStatement),
+    Listen(ListenStatement),
+    Unlisten(UnlistenStatement),
+    Notify(NotifyStatement),
+    Comment(CommentStatement),
 }
 
 impl Statement {
@@ -91,8 +102,15 @@ impl AstDisplay for Statement {
             Statement::CreateTable(stmt) => f.write_node(stmt),
             Statement::CreateIndex(stmt) => f.write_node(stmt),
             Statement::CreateType(stmt) => f.write_node(stmt),
+            Statement::CreateFunction(stmt) => f.write_node(stmt),
             Statement::AlterObjectRename(stmt) => f.write_node(stmt),
+            Statement::AlterViewRenameColumn(stmt) => f.write_node(stmt),
             Statement::AlterIndexOptions(stmt) => f.write_node(stmt),
+            Statement::AlterIndexKeys(stmt) => f.write_node(stmt),
+            Statement::AlterAllIndexesOptions(stmt) => f.write_node(stmt),
+            Statement::AlterMaterializedViewOptions(stmt) => f.write_node(stmt),
+            Statement::AlterMaterializedViewRematerialize(stmt) => f.write_node(stmt),
+            Statement::RefreshMaterializedView(stmt) => f.write_node(stmt),
             Statement::Discard(stmt) => f.write_node(stmt),
             Statement::DropDatabase(stmt) => f.write_node(stmt),
             Statement::DropObjects(stmt) => f.write_node(stmt),
@@ -116,6 +134,10 @@ impl AstDisplay for Statement {
             Statement::Declare(stmt) => f.write_node(stmt),
             Statement::Close(stmt) => f.write_node(stmt),
             Statement::Fetch(stmt) => f.write_node(stmt),
+            Statement::Listen(stmt) => f.write_node(stmt),
+            Statement::Unlisten(stmt) => f.write_node(stmt),
+            Statement::Notify(stmt) => f.write_node(stmt),
+            Statement::Comment(stmt) => f.write_node(stmt),
         }
     }
 }
@@ -125,12 +147,17 @@ impl_display!(Statement);
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SelectStatement {
     pub query: Query,
+    pub locking: Option<LockingClause>,
     pub as_of: Option<Expr>,
 }
 
 impl AstDisplay for SelectStatement {
     fn fmt(&self, f: &mut AstFormatter) {
         f.write_node(&self.query);
+        if let Some(locking) = &self.locking {
+            f.write_str(" ");
+            f.write_node(locking);
+        }
         if let Some(as_of) = &self.as_of {
             f.write_str(" AS OF ");
             f.write_node(as_of);
@@ -139,6 +166,25 @@ impl AstDisplay for SelectStatement {
 }
 impl_display!(SelectStatement);
 
+/// A row-locking clause, like `FOR UPDATE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockingClause {
+    /// `FOR UPDATE`
+    Update,
+    /// `FOR SHARE`
+    Share,
+}
+
+impl AstDisplay for LockingClause {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            LockingClause::Update => f.write_str("FOR UPDATE"),
+            LockingClause::Share => f.write_str("FOR SHARE"),
+        }
+    }
+}
+impl_display!(LockingClause);
+
 /// `INSERT`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct InsertStatement {
@@ -148,6 +194,8 @@ pub struct InsertStatement {
     pub columns: Vec<Ident>,
     /// A SQL query that specifies what to insert.
     pub source: InsertSource,
+    /// RETURNING
+    pub returning: Vec<SelectItem>,
 }
 
 impl AstDisplay for InsertStatement {
@@ -161,6 +209,10 @@ impl AstDisplay for InsertStatement {
         }
         f.write_str(" ");
         f.write_node(&self.source);
+        if !self.returning.is_empty() {
+            f.write_str(" RETURNING ");
+            f.write_node(&display::comma_separated(&self.returning));
+        }
     }
 }
 impl_display!(InsertStatement);
@@ -195,14 +247,21 @@ impl_display!(CopyDirection);
 pub enum CopyTarget {
     Stdin,
     Stdout,
+    /// A path prefix for files on the server.
+    File(String),
 }
 
 impl AstDisplay for CopyTarget {
     fn fmt(&self, f: &mut AstFormatter) {
-        f.write_str(match self {
-            CopyTarget::Stdin => "STDIN",
-            CopyTarget::Stdout => "STDOUT",
-        })
+        match self {
+            CopyTarget::Stdin => f.write_str("STDIN"),
+            CopyTarget::Stdout => f.write_str("STDOUT"),
+            CopyTarget::File(path) => {
+                f.write_str("'");
+                f.write_node(&display::escape_single_quote_string(path));
+                f.write_str("'");
+            }
+        }
     }
 }
 impl_display!(CopyTarget);
@@ -397,6 +456,7 @@ pub struct CreateSinkStatement {
     pub connector: Connector,
     pub with_options: Vec<SqlOption>,
     pub format: Option<Format>,
+    pub envelope: Option<Envelope>,
     pub with_snapshot: bool,
     pub as_of: Option<Expr>,
     pub if_not_exists: bool,
@@ -422,6 +482,10 @@ impl AstDisplay for CreateSinkStatement {
             f.write_str(" FORMAT ");
             f.write_node(format);
         }
+        if let Some(envelope) = &self.envelope {
+            f.write_str(" ENVELOPE ");
+            f.write_node(envelope);
+        }
         if self.with_snapshot {
             f.write_str(" WITH SNAPSHOT");
         } else {
@@ -499,11 +563,16 @@ pub struct CreateTableStatement {
     pub constraints: Vec<TableConstraint>,
     pub with_options: Vec<SqlOption>,
     pub if_not_exists: bool,
+    pub temporary: bool,
 }
 
 impl AstDisplay for CreateTableStatement {
     fn fmt(&self, f: &mut AstFormatter) {
-        f.write_str("CREATE TABLE ");
+        f.write_str("CREATE ");
+        if self.temporary {
+            f.write_str("TEMPORARY ");
+        }
+        f.write_str("TABLE ");
         if self.if_not_exists {
             f.write_str("IF NOT EXISTS ");
         }
@@ -607,6 +676,49 @@ impl AstDisplay for CreateTypeAs {
 }
 impl_display!(CreateTypeAs);
 
+/// `CREATE FUNCTION`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateFunctionStatement {
+    /// Name of the created function.
+    pub name: ObjectName,
+    /// The function's parameters.
+    pub params: Vec<FunctionParameter>,
+    /// The type of the function's result.
+    pub return_type: DataType,
+    /// The expression that computes the function's result.
+    pub body: Expr,
+}
+
+impl AstDisplay for CreateFunctionStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("CREATE FUNCTION ");
+        f.write_node(&self.name);
+        f.write_str(" (");
+        f.write_node(&display::comma_separated(&self.params));
+        f.write_str(") RETURNS ");
+        f.write_node(&self.return_type);
+        f.write_str(" RETURN ");
+        f.write_node(&self.body);
+    }
+}
+impl_display!(CreateFunctionStatement);
+
+/// A parameter of a function created by `CREATE FUNCTION`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionParameter {
+    pub name: Ident,
+    pub data_type: DataType,
+}
+
+impl AstDisplay for FunctionParameter {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_node(&self.name);
+        f.write_str(" ");
+        f.write_node(&self.data_type);
+    }
+}
+impl_display!(FunctionParameter);
+
 /// `ALTER <OBJECT> ... RENAME TO`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AlterObjectRenameStatement {
@@ -631,6 +743,30 @@ impl AstDisplay for AlterObjectRenameStatement {
 }
 impl_display!(AlterObjectRenameStatement);
 
+/// `ALTER VIEW ... RENAME COLUMN ... TO`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlterViewRenameColumnStatement {
+    pub if_exists: bool,
+    pub name: ObjectName,
+    pub column_name: Ident,
+    pub to_column_name: Ident,
+}
+
+impl AstDisplay for AlterViewRenameColumnStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("ALTER VIEW ");
+        if self.if_exists {
+            f.write_str("IF EXISTS ");
+        }
+        f.write_node(&self.name);
+        f.write_str(" RENAME COLUMN ");
+        f.write_node(&self.column_name);
+        f.write_str(" TO ");
+        f.write_node(&self.to_column_name);
+    }
+}
+impl_display!(AlterViewRenameColumnStatement);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AlterIndexOptionsList {
     Set(Vec<SqlOption>),
@@ -653,8 +789,15 @@ impl AstDisplay for AlterIndexOptionsStatement {
         }
         f.write_node(&self.index_name);
         f.write_str(" ");
+        f.write_node(&self.options);
+    }
+}
+
+impl_display!(AlterIndexOptionsStatement);
 
-        match &self.options {
+impl AstDisplay for AlterIndexOptionsList {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
             AlterIndexOptionsList::Set(options) => {
                 f.write_str("SET (");
                 f.write_node(&display::comma_separated(&options));
@@ -668,8 +811,97 @@ impl AstDisplay for AlterIndexOptionsStatement {
         }
     }
 }
+impl_display!(AlterIndexOptionsList);
 
-impl_display!(AlterIndexOptionsStatement);
+/// `ALTER INDEX ... SET (KEY = (...))`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlterIndexKeysStatement {
+    pub index_name: ObjectName,
+    pub if_exists: bool,
+    pub key_parts: Vec<Expr>,
+}
+
+impl AstDisplay for AlterIndexKeysStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("ALTER INDEX ");
+        if self.if_exists {
+            f.write_str("IF EXISTS ");
+        }
+        f.write_node(&self.index_name);
+        f.write_str(" SET (KEY = (");
+        f.write_node(&display::comma_separated(&self.key_parts));
+        f.write_str("))");
+    }
+}
+impl_display!(AlterIndexKeysStatement);
+
+/// `ALTER INDEX ALL ... {RESET, SET}`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlterAllIndexesOptionsStatement {
+    pub options: AlterIndexOptionsList,
+}
+
+impl AstDisplay for AlterAllIndexesOptionsStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("ALTER INDEX ALL ");
+        f.write_node(&self.options);
+    }
+}
+impl_display!(AlterAllIndexesOptionsStatement);
+
+/// `ALTER MATERIALIZED VIEW ... {RESET, SET}`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlterMaterializedViewOptionsStatement {
+    pub view_name: ObjectName,
+    pub if_exists: bool,
+    pub options: AlterIndexOptionsList,
+}
+
+impl AstDisplay for AlterMaterializedViewOptionsStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("ALTER MATERIALIZED VIEW ");
+        if self.if_exists {
+            f.write_str("IF EXISTS ");
+        }
+        f.write_node(&self.view_name);
+        f.write_str(" ");
+        f.write_node(&self.options);
+    }
+}
+impl_display!(AlterMaterializedViewOptionsStatement);
+
+/// `ALTER MATERIALIZED VIEW ... REMATERIALIZE`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlterMaterializedViewRematerializeStatement {
+    pub view_name: ObjectName,
+    pub if_exists: bool,
+}
+
+impl AstDisplay for AlterMaterializedViewRematerializeStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("ALTER MATERIALIZED VIEW ");
+        if self.if_exists {
+            f.write_str("IF EXISTS ");
+        }
+        f.write_node(&self.view_name);
+        f.write_str(" REMATERIALIZE");
+    }
+}
+impl_display!(AlterMaterializedViewRematerializeStatement);
+
+/// `REFRESH MATERIALIZED VIEW ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RefreshMaterializedViewStatement {
+    pub view_name: ObjectName,
+}
+
+impl AstDisplay for RefreshMaterializedViewStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("REFRESH MATERIALIZED VIEW ");
+        f.write_node(&self.view_name);
+    }
+}
+impl_display!(RefreshMaterializedViewStatement);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DiscardStatement {
@@ -846,7 +1078,7 @@ impl AstDisplay for ShowObjectsStatement {
             ObjectType::Sink => "SINKS",
             ObjectType::Type => "TYPES",
             ObjectType::Object => "OBJECTS",
-            ObjectType::Index => unreachable!(),
+            ObjectType::Index | ObjectType::Function => unreachable!(),
         });
         if let Some(from) = &self.from {
             f.write_str(" FROM ");
@@ -1056,6 +1288,8 @@ pub struct TailStatement {
     pub name: ObjectName,
     pub options: Vec<WithOption>,
     pub as_of: Option<Expr>,
+    pub until: Option<Expr>,
+    pub limit: Option<Expr>,
 }
 
 impl AstDisplay for TailStatement {
@@ -1071,6 +1305,14 @@ impl AstDisplay for TailStatement {
             f.write_str(" AS OF ");
             f.write_node(as_of);
         }
+        if let Some(until) = &self.until {
+            f.write_str(" UNTIL ");
+            f.write_node(until);
+        }
+        if let Some(limit) = &self.limit {
+            f.write_str(" LIMIT ");
+            f.write_node(limit);
+        }
     }
 }
 impl_display!(TailStatement);
@@ -1086,6 +1328,21 @@ pub struct ExplainStatement {
 impl AstDisplay for ExplainStatement {
     fn fmt(&self, f: &mut AstFormatter) {
         f.write_str("EXPLAIN ");
+        if let ExplainStage::Arrangements | ExplainStage::Sink = self.stage {
+            f.write_str("(");
+            f.write_node(&self.stage);
+            f.write_str(") FOR ");
+            f.write_node(&self.explainee);
+            return;
+        }
+        if let ExplainStage::Analyze = self.stage {
+            f.write_str("ANALYZE ");
+            f.write_node(&self.explainee);
+            return;
+        }
+        if self.options.redacted {
+            f.write_str("(REDACTED) ");
+        }
         if self.options.typed {
             f.write_str("TYPED ");
         }
@@ -1121,6 +1378,7 @@ pub enum ObjectType {
     Sink,
     Index,
     Type,
+    Function,
     Object,
 }
 
@@ -1134,6 +1392,7 @@ impl AstDisplay for ObjectType {
             ObjectType::Sink => "SINK",
             ObjectType::Index => "INDEX",
             ObjectType::Type => "TYPE",
+            ObjectType::Function => "FUNCTION",
             ObjectType::Object => "OBJECT",
         })
     }
@@ -1170,6 +1429,11 @@ pub enum SqlOption {
         name: Ident,
         value: Value,
     },
+    /// A parenthesized, comma-separated list of values.
+    Values {
+        name: Ident,
+        values: Vec<Value>,
+    },
     ObjectName {
         name: Ident,
         object_name: ObjectName,
@@ -1184,6 +1448,7 @@ impl SqlOption {
     pub fn name(&self) -> &Ident {
         match self {
             SqlOption::Value { name, .. } => name,
+            SqlOption::Values { name, .. } => name,
             SqlOption::ObjectName { name, .. } => name,
             SqlOption::DataType { name, .. } => name,
         }
@@ -1198,6 +1463,12 @@ impl AstDisplay for SqlOption {
                 f.write_str(" = ");
                 f.write_node(value);
             }
+            SqlOption::Values { name, values } => {
+                f.write_node(name);
+                f.write_str(" = (");
+                f.write_node(&display::comma_separated(values));
+                f.write_str(")");
+            }
             SqlOption::ObjectName { name, object_name } => {
                 f.write_node(name);
                 f.write_str(" = ");
@@ -1250,6 +1521,8 @@ impl_display!(WithOptionValue);
 pub enum TransactionMode {
     AccessMode(TransactionAccessMode),
     IsolationLevel(TransactionIsolationLevel),
+    /// `DEFERRABLE` if true, or `NOT DEFERRABLE` if false.
+    Deferrable(bool),
 }
 
 impl AstDisplay for TransactionMode {
@@ -1261,6 +1534,8 @@ impl AstDisplay for TransactionMode {
                 f.write_str("ISOLATION LEVEL ");
                 f.write_node(iso_level);
             }
+            Deferrable(true) => f.write_str("DEFERRABLE"),
+            Deferrable(false) => f.write_str("NOT DEFERRABLE"),
         }
     }
 }
@@ -1308,6 +1583,7 @@ impl_display!(TransactionIsolationLevel);
 pub enum SetVariableValue {
     Ident(Ident),
     Literal(Value),
+    List(Vec<SetVariableValue>),
 }
 
 impl AstDisplay for SetVariableValue {
@@ -1316,6 +1592,7 @@ impl AstDisplay for SetVariableValue {
         match self {
             Ident(ident) => f.write_node(ident),
             Literal(literal) => f.write_node(literal),
+            List(values) => f.write_node(&display::comma_separated(values)),
         }
     }
 }
@@ -1346,6 +1623,12 @@ pub enum ExplainStage {
     DecorrelatedPlan,
     /// The expr::RelationExpr after optimization
     OptimizedPlan,
+    /// The indexes that a dataflow for the explainee would import and export
+    Arrangements,
+    /// The external state that a sink would create, without creating it
+    Sink,
+    /// The timing and outcome of executing the explainee
+    Analyze,
 }
 
 impl AstDisplay for ExplainStage {
@@ -1354,6 +1637,9 @@ impl AstDisplay for ExplainStage {
             ExplainStage::RawPlan => f.write_str("RAW PLAN"),
             ExplainStage::DecorrelatedPlan => f.write_str("DECORRELATED PLAN"),
             ExplainStage::OptimizedPlan => f.write_str("OPTIMIZED PLAN"),
+            ExplainStage::Arrangements => f.write_str("ARRANGEMENTS"),
+            ExplainStage::Sink => f.write_str("SINK"),
+            ExplainStage::Analyze => f.write_str("ANALYZE"),
         }
     }
 }
@@ -1363,11 +1649,21 @@ impl_display!(ExplainStage);
 pub enum Explainee {
     View(ObjectName),
     Query(Query),
+    Insert(InsertStatement),
+    /// Only valid for [`ExplainStage::Arrangements`].
+    CreateView(CreateViewStatement),
+    /// Only valid for [`ExplainStage::Arrangements`].
+    CreateIndex(CreateIndexStatement),
+    /// Only valid for [`ExplainStage::Sink`].
+    CreateSink(CreateSinkStatement),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ExplainOptions {
     pub typed: bool,
+    /// Whether to replace the values of literals in the plan with
+    /// placeholders.
+    pub redacted: bool,
 }
 
 impl AstDisplay for Explainee {
@@ -1378,6 +1674,10 @@ impl AstDisplay for Explainee {
                 f.write_node(&name);
             }
             Explainee::Query(query) => f.write_node(query),
+            Explainee::Insert(insert) => f.write_node(insert),
+            Explainee::CreateView(create_view) => f.write_node(create_view),
+            Explainee::CreateIndex(create_index) => f.write_node(create_index),
+            Explainee::CreateSink(create_sink) => f.write_node(create_sink),
         }
     }
 }
@@ -1460,3 +1760,118 @@ impl AstDisplay for FetchDirection {
     }
 }
 impl_display!(FetchDirection);
+
+/// `LISTEN ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ListenStatement {
+    pub channel: Ident,
+}
+
+impl AstDisplay for ListenStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("LISTEN ");
+        f.write_node(&self.channel);
+    }
+}
+impl_display!(ListenStatement);
+
+/// `UNLISTEN ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnlistenStatement {
+    /// The channel to stop listening on, or `None` for all channels.
+    pub channel: Option<Ident>,
+}
+
+impl AstDisplay for UnlistenStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("UNLISTEN ");
+        match &self.channel {
+            Some(channel) => f.write_node(channel),
+            None => f.write_str("*"),
+        }
+    }
+}
+impl_display!(UnlistenStatement);
+
+/// `NOTIFY ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotifyStatement {
+    pub channel: Ident,
+    pub payload: Option<String>,
+}
+
+impl AstDisplay for NotifyStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("NOTIFY ");
+        f.write_node(&self.channel);
+        if let Some(payload) = &self.payload {
+            f.write_str(", '");
+            f.write_node(&display::escape_single_quote_string(payload));
+            f.write_str("'");
+        }
+    }
+}
+impl_display!(NotifyStatement);
+
+/// `COMMENT ON ... IS ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommentStatement {
+    pub object: CommentObject,
+    /// The new comment, or `None` to remove the existing comment, as for
+    /// `IS NULL`.
+    pub comment: Option<String>,
+}
+
+impl AstDisplay for CommentStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("COMMENT ON ");
+        f.write_node(&self.object);
+        f.write_str(" IS ");
+        match &self.comment {
+            Some(comment) => {
+                f.write_str("'");
+                f.write_node(&display::escape_single_quote_string(comment));
+                f.write_str("'");
+            }
+            None => f.write_str("NULL"),
+        }
+    }
+}
+impl_display!(CommentStatement);
+
+/// The object to which a `COMMENT ON` statement attaches its comment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CommentObject {
+    /// A table, view, or index.
+    Item {
+        object_type: ObjectType,
+        name: ObjectName,
+    },
+    /// A column of a table or view.
+    Column {
+        relation_name: ObjectName,
+        column_name: Ident,
+    },
+}
+
+impl AstDisplay for CommentObject {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            CommentObject::Item { object_type, name } => {
+                f.write_node(object_type);
+                f.write_str(" ");
+                f.write_node(name);
+            }
+            CommentObject::Column {
+                relation_name,
+                column_name,
+            } => {
+                f.write_str("COLUMN ");
+                f.write_node(relation_name);
+                f.write_str(".");
+                f.write_node(column_name);
+            }
+        }
+    }
+}
+impl_display!(CommentObject);
@@ -71,9 +71,11 @@ impl Keyword {
         matches!(
             self,
             // Keywords that can appear at the top-level of a SELECT statement.
-            WITH | SELECT | FROM | WHERE | GROUP | HAVING | ORDER | LIMIT | OFFSET | FETCH | OPTION |
+            WITH | SELECT | INTO | FROM | WHERE | GROUP | HAVING | ORDER | LIMIT | OFFSET | FETCH | OPTION |
             // Set operations.
-            UNION | EXCEPT | INTERSECT
+            UNION | EXCEPT | INTERSECT |
+            // Keywords that can follow the query in an INSERT statement.
+            RETURNING
         )
     }
 
@@ -94,7 +96,10 @@ impl Keyword {
             // `OUTER` is not strictly ambiguous, but it prevents `a OUTER JOIN
             // b` from parsing as `a AS outer JOIN b`, instead producing a nice
             // syntax error.
-            OUTER
+            OUTER |
+            // `FOR` introduces the row-locking clause that can end a SELECT
+            // statement, as in `FROM t FOR UPDATE`.
+            FOR
         ) || self.is_reserved()
     }
 
@@ -114,7 +119,10 @@ impl Keyword {
             // suffixes. They are not strictly ambiguous, but marking them
             // reserved prevents e.g. `SELECT pg_catalog.interval '1' year` from
             // parsing as `SELECT pg_catalog.interval '1' AS YEAR`.
-            YEAR | MONTH | DAY | HOUR | MINUTE | SECOND
+            YEAR | MONTH | DAY | HOUR | MINUTE | SECOND |
+            // `FOR` introduces the row-locking clause that can end a SELECT
+            // statement, as in `SELECT 1 FOR UPDATE`.
+            FOR
         ) || self.is_reserved()
     }
 
@@ -198,6 +198,7 @@ impl<'a> Parser<'a> {
                     self.prev_token();
                     Ok(Statement::Select(SelectStatement {
                         query: self.parse_query()?,
+                        locking: self.parse_optional_locking_clause()?,
                         as_of: self.parse_optional_as_of()?,
                     }))
                 }
@@ -208,8 +209,10 @@ impl<'a> Parser<'a> {
                 Token::Keyword(INSERT) => Ok(self.parse_insert()?),
                 Token::Keyword(UPDATE) => Ok(self.parse_update()?),
                 Token::Keyword(ALTER) => Ok(self.parse_alter()?),
+                Token::Keyword(REFRESH) => Ok(self.parse_refresh()?),
                 Token::Keyword(COPY) => Ok(self.parse_copy()?),
                 Token::Keyword(SET) => Ok(self.parse_set()?),
+                Token::Keyword(RESET) => Ok(self.parse_reset()?),
                 Token::Keyword(SHOW) => Ok(self.parse_show()?),
                 Token::Keyword(START) => Ok(self.parse_start_transaction()?),
                 // `BEGIN` is a nonstandard but common alias for the
@@ -223,6 +226,10 @@ impl<'a> Parser<'a> {
                 Token::Keyword(DECLARE) => Ok(self.parse_declare()?),
                 Token::Keyword(FETCH) => Ok(self.parse_fetch()?),
                 Token::Keyword(CLOSE) => Ok(self.parse_close()?),
+                Token::Keyword(LISTEN) => Ok(self.parse_listen()?),
+                Token::Keyword(UNLISTEN) => Ok(self.parse_unlisten()?),
+                Token::Keyword(NOTIFY) => Ok(self.parse_notify()?),
+                Token::Keyword(COMMENT) => Ok(self.parse_comment()?),
                 Token::Keyword(kw) => parser_err!(
                     self,
                     self.peek_prev_pos(),
@@ -232,6 +239,7 @@ impl<'a> Parser<'a> {
                     self.prev_token();
                     Ok(Statement::Select(SelectStatement {
                         query: self.parse_query()?,
+                        locking: self.parse_optional_locking_clause()?,
                         as_of: None, // Only the outermost SELECT may have an AS OF clause.
                     }))
                 }
@@ -460,7 +468,7 @@ impl<'a> Parser<'a> {
                         let query = SetExpr::Query(Box::new(query));
                         let ctes = vec![];
                         let body = parser.parse_query_body_seeded(SetPrecedence::Zero, query)?;
-                        Ok(Either::Query(parser.parse_query_tail(ctes, body)?))
+                        Ok(Either::Query(parser.parse_query_tail(ctes, None, body)?))
                     }
 
                     // The next token is something else. That implies we have a
@@ -1292,7 +1300,7 @@ impl<'a> Parser<'a> {
         } else if self.parse_keyword(SCHEMA) {
             self.parse_create_schema()
         } else if self.parse_keyword(TABLE) {
-            self.parse_create_table()
+            self.parse_create_table(false)
         } else if self.parse_keyword(OR) || self.parse_keyword(VIEW) {
             self.prev_token();
             self.parse_create_view()
@@ -1301,10 +1309,12 @@ impl<'a> Parser<'a> {
                 self.prev_token();
                 self.prev_token();
                 self.parse_create_view()
+            } else if self.parse_keyword(TABLE) {
+                self.parse_create_table(true)
             } else {
                 self.expected(
                     self.peek_pos(),
-                    "VIEW after CREATE TEMPORARY",
+                    "VIEW or TABLE after CREATE TEMPORARY",
                     self.peek_token(),
                 )
             }
@@ -1339,6 +1349,8 @@ impl<'a> Parser<'a> {
             self.parse_create_index()
         } else if self.parse_keyword(TYPE) {
             self.parse_create_type()
+        } else if self.parse_keyword(FUNCTION) {
+            self.parse_create_function()
         } else {
             self.expected(
                 self.peek_pos(),
@@ -1555,6 +1567,11 @@ impl<'a> Parser<'a> {
         } else {
             None
         };
+        let envelope = if self.parse_keyword(ENVELOPE) {
+            Some(self.parse_envelope()?)
+        } else {
+            None
+        };
         let with_snapshot = if self.parse_keyword(WITH) {
             self.expect_keyword(SNAPSHOT)?;
             true
@@ -1573,6 +1590,7 @@ impl<'a> Parser<'a> {
             connector,
             with_options,
             format,
+            envelope,
             with_snapshot,
             as_of,
             if_not_exists,
@@ -1719,6 +1737,35 @@ impl<'a> Parser<'a> {
         }))
     }
 
+    fn parse_create_function(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_object_name()?;
+        self.expect_token(&Token::LParen)?;
+        let params = if self.consume_token(&Token::RParen) {
+            vec![]
+        } else {
+            let params = self.parse_comma_separated(Parser::parse_function_parameter)?;
+            self.expect_token(&Token::RParen)?;
+            params
+        };
+        self.expect_keyword(RETURNS)?;
+        let return_type = self.parse_data_type()?;
+        self.expect_keyword(RETURN)?;
+        let body = self.parse_expr()?;
+        Ok(Statement::CreateFunction(CreateFunctionStatement {
+            name,
+            params,
+            return_type,
+            body,
+        }))
+    }
+
+    fn parse_function_parameter(&mut self) -> Result<FunctionParameter, ParserError> {
+        Ok(FunctionParameter {
+            name: self.parse_identifier()?,
+            data_type: self.parse_data_type()?,
+        })
+    }
+
     fn parse_data_type_option(&mut self) -> Result<SqlOption, ParserError> {
         let name = self.parse_identifier()?;
         self.expect_token(&Token::Eq)?;
@@ -1757,10 +1804,20 @@ impl<'a> Parser<'a> {
         Ok(Statement::Discard(DiscardStatement { target }))
     }
 
+    /// Parse a `REFRESH MATERIALIZED VIEW` statement, assuming that the
+    /// `REFRESH` token has already been consumed.
+    fn parse_refresh(&mut self) -> Result<Statement, ParserError> {
+        self.expect_keywords(&[MATERIALIZED, VIEW])?;
+        let view_name = self.parse_object_name()?;
+        Ok(Statement::RefreshMaterializedView(
+            RefreshMaterializedViewStatement { view_name },
+        ))
+    }
+
     fn parse_drop(&mut self) -> Result<Statement, ParserError> {
-        let object_type = match self
-            .parse_one_of_keywords(&[DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, TYPE])
-        {
+        let object_type = match self.parse_one_of_keywords(&[
+            DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, TYPE, FUNCTION,
+        ]) {
             Some(DATABASE) => {
                 return Ok(Statement::DropDatabase(DropDatabaseStatement {
                     if_exists: self.parse_if_exists()?,
@@ -1774,13 +1831,12 @@ impl<'a> Parser<'a> {
             Some(SINK) => ObjectType::Sink,
             Some(INDEX) => ObjectType::Index,
             Some(TYPE) => ObjectType::Type,
-            _ => {
-                return self.expected(
-                    self.peek_pos(),
-                    "DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, or TYPE after DROP",
-                    self.peek_token(),
-                )
-            }
+            Some(FUNCTION) => ObjectType::Function,
+            _ => return self.expected(
+                self.peek_pos(),
+                "DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, TYPE, or FUNCTION after DROP",
+                self.peek_token(),
+            ),
         };
 
         let if_exists = self.parse_if_exists()?;
@@ -1803,7 +1859,7 @@ impl<'a> Parser<'a> {
         }))
     }
 
-    fn parse_create_table(&mut self) -> Result<Statement, ParserError> {
+    fn parse_create_table(&mut self, temporary: bool) -> Result<Statement, ParserError> {
         let if_not_exists = self.parse_if_not_exists()?;
         let table_name = self.parse_object_name()?;
         // parse optional column list (schema)
@@ -1816,6 +1872,7 @@ impl<'a> Parser<'a> {
             constraints,
             with_options,
             if_not_exists,
+            temporary,
         }))
     }
 
@@ -1983,7 +2040,11 @@ impl<'a> Parser<'a> {
         let name = self.parse_identifier()?;
         self.expect_token(&Token::Eq)?;
         let token = self.peek_token();
-        let option = if let Ok(value) = self.parse_value() {
+        let option = if self.consume_token(&Token::LParen) {
+            let values = self.parse_comma_separated(Parser::parse_value)?;
+            self.expect_token(&Token::RParen)?;
+            SqlOption::Values { name, values }
+        } else if let Ok(value) = self.parse_value() {
             SqlOption::Value { name, value }
         } else {
             self.prev_token();
@@ -2039,7 +2100,85 @@ impl<'a> Parser<'a> {
         Ok(WithOption { key, value })
     }
 
+    fn parse_alter_index_options(&mut self) -> Result<Option<AlterIndexOptionsList>, ParserError> {
+        match self.parse_one_of_keywords(&[RESET, SET]) {
+            Some(RESET) => {
+                self.expect_token(&Token::LParen)?;
+                let reset_options = self.parse_comma_separated(Parser::parse_identifier)?;
+                self.expect_token(&Token::RParen)?;
+
+                Ok(Some(AlterIndexOptionsList::Reset(reset_options)))
+            }
+            Some(SET) => {
+                self.expect_token(&Token::LParen)?;
+                let set_options = self.parse_comma_separated(Parser::parse_sql_option)?;
+                self.expect_token(&Token::RParen)?;
+
+                Ok(Some(AlterIndexOptionsList::Set(set_options)))
+            }
+            Some(_) => unreachable!(),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses `SET (KEY = (...))` in `ALTER INDEX`, returning the new key
+    /// expressions, or `None` without consuming any tokens if the statement
+    /// sets other options.
+    fn parse_alter_index_keys(&mut self) -> Result<Option<Vec<Expr>>, ParserError> {
+        let index = self.index;
+        if !(self.parse_keyword(SET)
+            && self.consume_token(&Token::LParen)
+            && self.parse_keyword(KEY)
+            && self.consume_token(&Token::Eq))
+        {
+            self.index = index;
+            return Ok(None);
+        }
+        self.expect_token(&Token::LParen)?;
+        let key_parts = if self.consume_token(&Token::RParen) {
+            vec![]
+        } else {
+            let key_parts = self.parse_comma_separated(Parser::parse_expr)?;
+            self.expect_token(&Token::RParen)?;
+            key_parts
+        };
+        self.expect_token(&Token::RParen)?;
+        Ok(Some(key_parts))
+    }
+
     fn parse_alter(&mut self) -> Result<Statement, ParserError> {
+        // `ALTER MATERIALIZED VIEW {RESET, SET}` applies to the view's default
+        // index, so that users need not know the index's name.
+        // `ALTER MATERIALIZED VIEW ... REMATERIALIZE` rebuilds all of the
+        // view's indexes.
+        if self.parse_keyword(MATERIALIZED) {
+            self.expect_keyword(VIEW)?;
+            let if_exists = self.parse_if_exists()?;
+            let view_name = self.parse_object_name()?;
+            if self.parse_keyword(REMATERIALIZE) {
+                return Ok(Statement::AlterMaterializedViewRematerialize(
+                    AlterMaterializedViewRematerializeStatement {
+                        view_name,
+                        if_exists,
+                    },
+                ));
+            }
+            return match self.parse_alter_index_options()? {
+                Some(options) => Ok(Statement::AlterMaterializedViewOptions(
+                    AlterMaterializedViewOptionsStatement {
+                        view_name,
+                        if_exists,
+                        options,
+                    },
+                )),
+                None => self.expected(
+                    self.peek_pos(),
+                    "SET, RESET, or REMATERIALIZE",
+                    self.peek_token(),
+                ),
+            };
+        }
+
         let object_type = match self.expect_one_of_keywords(&[INDEX, SINK, SOURCE, VIEW, TABLE])? {
             INDEX => ObjectType::Index,
             SINK => ObjectType::Sink,
@@ -2049,31 +2188,30 @@ impl<'a> Parser<'a> {
             _ => unreachable!(),
         };
 
+        // `ALTER INDEX ALL {RESET, SET}` applies to every index at once. An
+        // index named `all` can still be renamed without quoting it.
+        if object_type == ObjectType::Index && self.parse_keyword(ALL) {
+            if let Some(options) = self.parse_alter_index_options()? {
+                return Ok(Statement::AlterAllIndexesOptions(
+                    AlterAllIndexesOptionsStatement { options },
+                ));
+            }
+            self.prev_token();
+        }
+
         let if_exists = self.parse_if_exists()?;
         let name = self.parse_object_name()?;
 
         // We support `ALTER INDEX ... {RESET, SET} and `ALTER <object type> RENAME
         if object_type == ObjectType::Index {
-            let options = match self.parse_one_of_keywords(&[RESET, SET]) {
-                Some(RESET) => {
-                    self.expect_token(&Token::LParen)?;
-                    let reset_options = self.parse_comma_separated(Parser::parse_identifier)?;
-                    self.expect_token(&Token::RParen)?;
-
-                    Some(AlterIndexOptionsList::Reset(reset_options))
-                }
-                Some(SET) => {
-                    self.expect_token(&Token::LParen)?;
-                    let set_options = self.parse_comma_separated(Parser::parse_sql_option)?;
-                    self.expect_token(&Token::RParen)?;
-
-                    Some(AlterIndexOptionsList::Set(set_options))
-                }
-                Some(_) => unreachable!(),
-                None => None,
-            };
-
-            if let Some(options) = options {
+            if let Some(key_parts) = self.parse_alter_index_keys()? {
+                return Ok(Statement::AlterIndexKeys(AlterIndexKeysStatement {
+                    index_name: name,
+                    if_exists,
+                    key_parts,
+                }));
+            }
+            if let Some(options) = self.parse_alter_index_options()? {
                 return Ok(Statement::AlterIndexOptions(AlterIndexOptionsStatement {
                     index_name: name,
                     if_exists,
@@ -2082,7 +2220,21 @@ impl<'a> Parser<'a> {
             }
         }
 
-        self.expect_keywords(&[RENAME, TO])?;
+        self.expect_keyword(RENAME)?;
+        if object_type == ObjectType::View && self.parse_keyword(COLUMN) {
+            let column_name = self.parse_identifier()?;
+            self.expect_keyword(TO)?;
+            let to_column_name = self.parse_identifier()?;
+            return Ok(Statement::AlterViewRenameColumn(
+                AlterViewRenameColumnStatement {
+                    if_exists,
+                    name,
+                    column_name,
+                    to_column_name,
+                },
+            ));
+        }
+        self.expect_keyword(TO)?;
         let to_item_name = self.parse_identifier()?;
 
         Ok(Statement::AlterObjectRename(AlterObjectRenameStatement {
@@ -2122,10 +2274,16 @@ impl<'a> Parser<'a> {
                 self.expect_keyword(STDIN)?;
                 (CopyDirection::From, CopyTarget::Stdin)
             }
-            TO => {
-                self.expect_keyword(STDOUT)?;
-                (CopyDirection::To, CopyTarget::Stdout)
-            }
+            TO => match self.peek_token() {
+                Some(Token::String(_)) => {
+                    let path = self.parse_literal_string()?;
+                    (CopyDirection::To, CopyTarget::File(path))
+                }
+                _ => {
+                    self.expect_keyword(STDOUT)?;
+                    (CopyDirection::To, CopyTarget::Stdout)
+                }
+            },
             _ => unreachable!(),
         };
         let mut options = vec![];
@@ -2572,40 +2730,53 @@ impl<'a> Parser<'a> {
     /// expect the initial keyword to be already consumed
     fn parse_query(&mut self) -> Result<Query, ParserError> {
         self.check_descent(|parser| {
-            let ctes = if parser.parse_keyword(WITH) {
-                // TODO: optional RECURSIVE
-                parser.parse_comma_separated(Parser::parse_cte)?
+            let (ctes, max_iterations) = if parser.parse_keyword(WITH) {
+                let recursive = parser.parse_keyword(RECURSIVE);
+                let ctes = parser.parse_comma_separated(Parser::parse_cte)?;
+                let max_iterations = if recursive {
+                    Some(parser.parse_max_iterations()?)
+                } else {
+                    None
+                };
+                (ctes, max_iterations)
             } else {
-                vec![]
+                (vec![], None)
             };
 
             let body = parser.parse_query_body(SetPrecedence::Zero)?;
 
-            parser.parse_query_tail(ctes, body)
+            parser.parse_query_tail(ctes, max_iterations, body)
         })
     }
 
-    fn parse_query_tail(&mut self, ctes: Vec<Cte>, body: SetExpr) -> Result<Query, ParserError> {
-        let (inner_ctes, inner_order_by, inner_limit, inner_offset, body) = match body {
-            SetExpr::Query(query) => {
-                let Query {
-                    ctes,
-                    body,
-                    order_by,
-                    limit,
-                    offset,
-                } = *query;
-                (ctes, order_by, limit, offset, body)
-            }
-            _ => (vec![], vec![], None, None, body),
-        };
+    fn parse_query_tail(
+        &mut self,
+        ctes: Vec<Cte>,
+        max_iterations: Option<u64>,
+        body: SetExpr,
+    ) -> Result<Query, ParserError> {
+        let (inner_ctes, inner_max_iterations, inner_order_by, inner_limit, inner_offset, body) =
+            match body {
+                SetExpr::Query(query) => {
+                    let Query {
+                        ctes,
+                        max_iterations,
+                        body,
+                        order_by,
+                        limit,
+                        offset,
+                    } = *query;
+                    (ctes, max_iterations, order_by, limit, offset, body)
+                }
+                _ => (vec![], None, vec![], None, None, body),
+            };
 
-        let ctes = if ctes.is_empty() {
-            inner_ctes
+        let (ctes, max_iterations) = if ctes.is_empty() {
+            (inner_ctes, inner_max_iterations)
         } else if !inner_ctes.is_empty() {
             return parser_err!(self, self.peek_pos(), "multiple WITH clauses not allowed");
         } else {
-            ctes
+            (ctes, max_iterations)
         };
 
         let order_by = if self.parse_keywords(&[ORDER, BY]) {
@@ -2684,6 +2855,7 @@ impl<'a> Parser<'a> {
 
         Ok(Query {
             ctes,
+            max_iterations,
             body,
             order_by,
             limit,
@@ -2691,6 +2863,27 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Parse the `(MAX ITERATIONS <n>)` bound that must follow the CTEs of a
+    /// `WITH RECURSIVE` clause.
+    fn parse_max_iterations(&mut self) -> Result<u64, ParserError> {
+        // Look ahead so that a parenthesized query body is not mistaken for
+        // the start of the bound.
+        if self.peek_token() != Some(Token::LParen)
+            || self.peek_nth_token(1) != Some(Token::Keyword(MAX))
+        {
+            return parser_err!(
+                self,
+                self.peek_pos(),
+                "WITH RECURSIVE requires an iteration bound: (MAX ITERATIONS <n>)"
+            );
+        }
+        self.expect_token(&Token::LParen)?;
+        self.expect_keywords(&[MAX, ITERATIONS])?;
+        let n = self.parse_literal_uint()?;
+        self.expect_token(&Token::RParen)?;
+        Ok(n)
+    }
+
     /// Parse a CTE (`alias [( col1, col2, ... )] AS (subquery)`)
     fn parse_cte(&mut self) -> Result<Cte, ParserError> {
         let alias = TableAlias {
@@ -2814,10 +3007,32 @@ impl<'a> Parser<'a> {
             // An empty target list is permissible to match PostgreSQL, which
             // permits these for symmetry with zero column tables.
             Some(Token::Keyword(kw)) if kw.is_reserved() => vec![],
+            // `FOR` is not reserved in expressions, but `SELECT FOR UPDATE`
+            // has an empty target list followed by a locking clause.
+            Some(Token::Keyword(FOR))
+                if matches!(
+                    self.peek_nth_token(1),
+                    Some(Token::Keyword(UPDATE)) | Some(Token::Keyword(SHARE))
+                ) =>
+            {
+                vec![]
+            }
             Some(Token::Semicolon) | None => vec![],
             _ => self.parse_comma_separated(Parser::parse_select_item)?,
         };
 
+        let into = if self.parse_keyword(INTO) {
+            let temporary = self.parse_one_of_keywords(&[TEMP, TEMPORARY]).is_some();
+            // The `TABLE` keyword is optional noise.
+            let _ = self.parse_keyword(TABLE);
+            Some(SelectInto {
+                name: self.parse_object_name()?,
+                temporary,
+            })
+        } else {
+            None
+        };
+
         // Note that for keywords to be properly handled here, they need to be
         // added to `RESERVED_FOR_COLUMN_ALIAS` / `RESERVED_FOR_TABLE_ALIAS`,
         // otherwise they may be parsed as an alias as part of the `projection`
@@ -2856,6 +3071,7 @@ impl<'a> Parser<'a> {
         Ok(Select {
             distinct,
             projection,
+            into,
             from,
             selection,
             group_by,
@@ -2872,14 +3088,16 @@ impl<'a> Parser<'a> {
             self.expect_keyword(ZONE)?;
             variable = Ident::new("timezone");
             normal = true;
+        } else if !normal && variable.as_str().parse() == Ok(ROLE) {
+            // `SET ROLE x` is an alias for `SET role = x`.
+            normal = true;
         }
         if normal {
-            let token = self.peek_token();
-            let value = match (self.parse_value(), token) {
-                (Ok(value), _) => SetVariableValue::Literal(value),
-                (Err(_), Some(Token::Keyword(kw))) => SetVariableValue::Ident(kw.into_ident()),
-                (Err(_), Some(Token::Ident(id))) => SetVariableValue::Ident(Ident::new(id)),
-                (Err(_), other) => self.expected(self.peek_pos(), "variable value", other)?,
+            let mut values = self.parse_comma_separated(Parser::parse_set_variable_value)?;
+            let value = if values.len() == 1 {
+                values.remove(0)
+            } else {
+                SetVariableValue::List(values)
             };
             Ok(Statement::SetVariable(SetVariableStatement {
                 local: modifier == Some(LOCAL),
@@ -2895,6 +3113,26 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn parse_reset(&mut self) -> Result<Statement, ParserError> {
+        // Only `RESET ROLE` is supported. It is equivalent to `SET ROLE NONE`.
+        self.expect_keyword(ROLE)?;
+        Ok(Statement::SetVariable(SetVariableStatement {
+            local: false,
+            variable: Ident::new("role"),
+            value: SetVariableValue::Ident(Ident::new("none")),
+        }))
+    }
+
+    fn parse_set_variable_value(&mut self) -> Result<SetVariableValue, ParserError> {
+        let token = self.peek_token();
+        Ok(match (self.parse_value(), token) {
+            (Ok(value), _) => SetVariableValue::Literal(value),
+            (Err(_), Some(Token::Keyword(kw))) => SetVariableValue::Ident(kw.into_ident()),
+            (Err(_), Some(Token::Ident(id))) => SetVariableValue::Ident(Ident::new(id)),
+            (Err(_), other) => self.expected(self.peek_pos(), "variable value", other)?,
+        })
+    }
+
     fn parse_show(&mut self) -> Result<Statement, ParserError> {
         if self.parse_keyword(DATABASES) {
             return Ok(Statement::ShowDatabases(ShowDatabasesStatement {
@@ -3154,6 +3392,10 @@ impl<'a> Parser<'a> {
             // this is case (3), and the next token would be `NATURAL`.
             maybe!(self.maybe_parse(|parser| parser.parse_derived_table_factor(NotLateral)));
 
+            // Likewise check if it started a `TAIL`, as in
+            // `SELECT * FROM (TAIL v UNTIL now())`.
+            maybe!(self.maybe_parse(Parser::parse_tail_table_factor));
+
             // The '(' we've recently consumed does not start a derived table.
             // For valid input this can happen either when the token following
             // the paren can't start a query (e.g. `foo` in `FROM (foo NATURAL
@@ -3214,6 +3456,19 @@ impl<'a> Parser<'a> {
         })
     }
 
+    fn parse_tail_table_factor(&mut self) -> Result<TableFactor, ParserError> {
+        self.expect_keyword(TAIL)?;
+        let tail = match self.parse_tail()? {
+            Statement::Tail(tail) => tail,
+            _ => unreachable!(),
+        };
+        self.expect_token(&Token::RParen)?;
+        Ok(TableFactor::Tail {
+            tail: Box::new(tail),
+            alias: self.parse_optional_table_alias()?,
+        })
+    }
+
     fn parse_join_constraint(&mut self, natural: bool) -> Result<JoinConstraint, ParserError> {
         if natural {
             Ok(JoinConstraint::Natural)
@@ -3242,10 +3497,16 @@ impl<'a> Parser<'a> {
         } else {
             InsertSource::Query(self.parse_query()?)
         };
+        let returning = if self.parse_keyword(RETURNING) {
+            self.parse_comma_separated(Parser::parse_select_item)?
+        } else {
+            vec![]
+        };
         Ok(Statement::Insert(InsertStatement {
             table_name,
             columns,
             source,
+            returning,
         }))
     }
 
@@ -3301,6 +3562,19 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parse a `FOR UPDATE` or `FOR SHARE` clause, if present.
+    fn parse_optional_locking_clause(&mut self) -> Result<Option<LockingClause>, ParserError> {
+        if self.parse_keyword(FOR) {
+            match self.expect_one_of_keywords(&[UPDATE, SHARE])? {
+                UPDATE => Ok(Some(LockingClause::Update)),
+                SHARE => Ok(Some(LockingClause::Share)),
+                _ => unreachable!(),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Parse a comma-delimited list of projections after SELECT
     fn parse_select_item(&mut self) -> Result<SelectItem, ParserError> {
         if self.consume_token(&Token::Star) {
@@ -3371,6 +3645,10 @@ impl<'a> Parser<'a> {
                 TransactionMode::AccessMode(TransactionAccessMode::ReadOnly)
             } else if self.parse_keywords(&[READ, WRITE]) {
                 TransactionMode::AccessMode(TransactionAccessMode::ReadWrite)
+            } else if self.parse_keyword(DEFERRABLE) {
+                TransactionMode::Deferrable(true)
+            } else if self.parse_keywords(&[NOT, DEFERRABLE]) {
+                TransactionMode::Deferrable(false)
             } else if required {
                 self.expected(self.peek_pos(), "transaction mode", self.peek_token())?
             } else {
@@ -3413,19 +3691,136 @@ impl<'a> Parser<'a> {
         let name = self.parse_object_name()?;
         let options = self.parse_opt_with_options()?;
         let as_of = self.parse_optional_as_of()?;
+        let until = if self.parse_keyword(UNTIL) {
+            match self.parse_expr() {
+                Ok(expr) => Some(expr),
+                Err(e) => {
+                    return self.expected(
+                        e.pos,
+                        "a timestamp value after 'UNTIL'",
+                        self.peek_token(),
+                    )
+                }
+            }
+        } else {
+            None
+        };
+        let limit = if self.parse_keyword(LIMIT) {
+            match self.parse_expr() {
+                Ok(expr) => Some(expr),
+                Err(e) => return self.expected(e.pos, "a count after 'LIMIT'", self.peek_token()),
+            }
+        } else {
+            None
+        };
         Ok(Statement::Tail(TailStatement {
             name,
             options,
             as_of,
+            until,
+            limit,
         }))
     }
 
     /// Parse an `EXPLAIN` statement, assuming that the `EXPLAIN` token
     /// has already been consumed.
     fn parse_explain(&mut self) -> Result<Statement, ParserError> {
+        // '(' ARRANGEMENTS ')' FOR? { VIEW view_name | CREATE create | query }
+        if self.peek_token() == Some(Token::LParen)
+            && self.peek_nth_token(1) == Some(Token::Keyword(ARRANGEMENTS))
+        {
+            self.expect_token(&Token::LParen)?;
+            self.expect_keyword(ARRANGEMENTS)?;
+            self.expect_token(&Token::RParen)?;
+            let _ = self.parse_keyword(FOR);
+            let pos = self.peek_pos();
+            let explainee = if self.parse_keyword(VIEW) {
+                Explainee::View(self.parse_object_name()?)
+            } else if self.parse_keyword(CREATE) {
+                match self.parse_create()? {
+                    Statement::CreateView(stmt) => Explainee::CreateView(stmt),
+                    Statement::CreateIndex(stmt) => Explainee::CreateIndex(stmt),
+                    _ => {
+                        return Err(self.error(
+                            pos,
+                            "EXPLAIN (ARRANGEMENTS) only supports CREATE VIEW and CREATE INDEX"
+                                .into(),
+                        ))
+                    }
+                }
+            } else {
+                Explainee::Query(self.parse_query()?)
+            };
+            return Ok(Statement::Explain(ExplainStatement {
+                stage: ExplainStage::Arrangements,
+                explainee,
+                options: ExplainOptions {
+                    typed: false,
+                    redacted: false,
+                },
+            }));
+        }
+
+        // '(' SINK ')' FOR? CREATE SINK create_sink
+        if self.peek_token() == Some(Token::LParen)
+            && self.peek_nth_token(1) == Some(Token::Keyword(SINK))
+        {
+            self.expect_token(&Token::LParen)?;
+            self.expect_keyword(SINK)?;
+            self.expect_token(&Token::RParen)?;
+            let _ = self.parse_keyword(FOR);
+            let pos = self.peek_pos();
+            self.expect_keyword(CREATE)?;
+            let explainee = match self.parse_create()? {
+                Statement::CreateSink(stmt) => Explainee::CreateSink(stmt),
+                _ => return Err(self.error(pos, "EXPLAIN (SINK) only supports CREATE SINK".into())),
+            };
+            return Ok(Statement::Explain(ExplainStatement {
+                stage: ExplainStage::Sink,
+                explainee,
+                options: ExplainOptions {
+                    typed: false,
+                    redacted: false,
+                },
+            }));
+        }
+
+        // ANALYZE { INSERT insert | query }
+        if self.parse_keyword(ANALYZE) {
+            let explainee = if self.parse_keyword(INSERT) {
+                match self.parse_insert()? {
+                    Statement::Insert(stmt) => Explainee::Insert(stmt),
+                    _ => unreachable!(),
+                }
+            } else {
+                Explainee::Query(self.parse_query()?)
+            };
+            return Ok(Statement::Explain(ExplainStatement {
+                stage: ExplainStage::Analyze,
+                explainee,
+                options: ExplainOptions {
+                    typed: false,
+                    redacted: false,
+                },
+            }));
+        }
+
+        // ('(' REDACTED ')')?
+        let redacted = if self.peek_token() == Some(Token::LParen)
+            && self.peek_nth_token(1) == Some(Token::Keyword(REDACTED))
+        {
+            self.expect_token(&Token::LParen)?;
+            self.expect_keyword(REDACTED)?;
+            self.expect_token(&Token::RParen)?;
+            true
+        } else {
+            false
+        };
+
         // (TYPED)?
         let options = ExplainOptions {
             typed: self.parse_keyword(TYPED),
+            redacted,
         };
 
         // (RAW | DECORRELATED | OPTIMIZED)? PLAN
@@ -3450,9 +3845,14 @@ impl<'a> Parser<'a> {
             _ => unreachable!(),
         };
 
-        // VIEW view_name | query
+        // VIEW view_name | INSERT insert | query
         let explainee = if self.parse_keyword(VIEW) {
             Explainee::View(self.parse_object_name()?)
+        } else if self.parse_keyword(INSERT) {
+            match self.parse_insert()? {
+                Statement::Insert(stmt) => Explainee::Insert(stmt),
+                _ => unreachable!(),
+            }
         } else {
             Explainee::Query(self.parse_query()?)
         };
@@ -3486,6 +3886,79 @@ impl<'a> Parser<'a> {
         Ok(Statement::Close(CloseStatement { name }))
     }
 
+    /// Parse a `LISTEN` statement, assuming that the `LISTEN` token
+    /// has already been consumed.
+    fn parse_listen(&mut self) -> Result<Statement, ParserError> {
+        let channel = self.parse_identifier()?;
+        Ok(Statement::Listen(ListenStatement { channel }))
+    }
+
+    /// Parse an `UNLISTEN` statement, assuming that the `UNLISTEN` token
+    /// has already been consumed.
+    fn parse_unlisten(&mut self) -> Result<Statement, ParserError> {
+        let channel = if self.consume_token(&Token::Star) {
+            None
+        } else {
+            Some(self.parse_identifier()?)
+        };
+        Ok(Statement::Unlisten(UnlistenStatement { channel }))
+    }
+
+    /// Parse a `NOTIFY` statement, assuming that the `NOTIFY` token
+    /// has already been consumed.
+    fn parse_notify(&mut self) -> Result<Statement, ParserError> {
+        let channel = self.parse_identifier()?;
+        let payload = if self.consume_token(&Token::Comma) {
+            Some(self.parse_literal_string()?)
+        } else {
+            None
+        };
+        Ok(Statement::Notify(NotifyStatement { channel, payload }))
+    }
+
+    /// Parse a `COMMENT ON` statement, assuming that the `COMMENT` token
+    /// has already been consumed.
+    fn parse_comment(&mut self) -> Result<Statement, ParserError> {
+        self.expect_keyword(ON)?;
+        let object = match self.expect_one_of_keywords(&[TABLE, VIEW, INDEX, COLUMN])? {
+            TABLE => CommentObject::Item {
+                object_type: ObjectType::Table,
+                name: self.parse_object_name()?,
+            },
+            VIEW => CommentObject::Item {
+                object_type: ObjectType::View,
+                name: self.parse_object_name()?,
+            },
+            INDEX => CommentObject::Item {
+                object_type: ObjectType::Index,
+                name: self.parse_object_name()?,
+            },
+            COLUMN => {
+                let mut name = self.parse_object_name()?;
+                if name.0.len() < 2 {
+                    return parser_err!(
+                        self,
+                        self.peek_prev_pos(),
+                        "column name must be qualified by a table or view name"
+                    );
+                }
+                let column_name = name.0.pop().unwrap();
+                CommentObject::Column {
+                    relation_name: name,
+                    column_name,
+                }
+            }
+            _ => unreachable!(),
+        };
+        self.expect_keyword(IS)?;
+        let comment = if self.parse_keyword(NULL) {
+            None
+        } else {
+            Some(self.parse_literal_string()?)
+        };
+        Ok(Statement::Comment(CommentStatement { object, comment }))
+    }
+
     /// Parse a `FETCH` statement, assuming that the `FETCH` token
     /// has already been consumed.
     fn parse_fetch(&mut self) -> Result<Statement, ParserError> {
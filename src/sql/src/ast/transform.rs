@@ -15,7 +15,7 @@ use crate::ast::visit::{self, Visit};
 use crate::ast::visit_mut::{self, VisitMut};
 use crate::ast::{
     CreateIndexStatement, CreateSinkStatement, CreateSourceStatement, CreateTableStatement,
-    CreateViewStatement, Expr, Ident, ObjectName, Query, Statement,
+    CreateViewStatement, Expr, Ident, ObjectName, Query, SelectItem, Statement,
 };
 use crate::names::FullName;
 
@@ -80,6 +80,58 @@ pub fn create_stmt_rename_refs(
     Ok(())
 }
 
+/// Changes the name of the column `from` to `to` in an item's `CREATE`
+/// statement, where `columns` are the item's current column names.
+///
+/// Views gain an explicit column list, so that the renamed column survives
+/// replanning the view's query. Indexes have any key parts that refer to
+/// `from` rewritten to refer to `to`.
+pub fn create_stmt_rename_column(
+    create_stmt: &mut Statement,
+    columns: &[Ident],
+    from: &Ident,
+    to: &Ident,
+) {
+    match create_stmt {
+        Statement::CreateView(CreateViewStatement {
+            columns: view_columns,
+            ..
+        }) => {
+            if view_columns.is_empty() {
+                *view_columns = columns.to_vec();
+            }
+            for column in view_columns.iter_mut().filter(|c| *c == from) {
+                *column = to.clone();
+            }
+        }
+        Statement::CreateIndex(CreateIndexStatement { key_parts, .. }) => {
+            let mut v = ColumnRewriter { from, to };
+            for key_part in key_parts.iter_mut().flatten() {
+                v.visit_expr_mut(key_part);
+            }
+        }
+        _ => unreachable!("Internal error: only views and indexes can rename columns"),
+    }
+}
+
+/// Reports whether `create_stmt` may refer to a column named `column`.
+///
+/// This is conservative: any identifier in a view's query that matches
+/// `column`, as well as any wildcard, is assumed to refer to the column. Sinks
+/// emit every column of the item they read from, so always refer to `column`.
+pub fn create_stmt_may_reference_column(create_stmt: &Statement, column: &Ident) -> bool {
+    let mut v = ColumnReferenceFinder {
+        column,
+        found: false,
+    };
+    match create_stmt {
+        Statement::CreateView(CreateViewStatement { query, .. }) => v.visit_query(query),
+        Statement::CreateSink(_) => v.found = true,
+        _ => v.visit_statement(create_stmt),
+    }
+    v.found
+}
+
 /// Rewrites `query`'s references of `from` to `to` or errors if too ambiguous.
 fn rewrite_query(from: FullName, to: String, query: &mut Query) -> Result<(), String> {
     let from_ident = Ident::new(from.item.clone());
@@ -293,3 +345,51 @@ impl<'ast> VisitMut<'ast> for CreateSqlRewriter {
         self.maybe_rewrite_idents(&mut object_name.0);
     }
 }
+
+/// Rewrites column references of `from` to `to`.
+struct ColumnRewriter<'a> {
+    from: &'a Ident,
+    to: &'a Ident,
+}
+
+impl<'a, 'ast> VisitMut<'ast> for ColumnRewriter<'a> {
+    fn visit_expr_mut(&mut self, e: &'ast mut Expr) {
+        match e {
+            Expr::Identifier(id) => {
+                let last = id.last_mut().unwrap();
+                if last == self.from {
+                    *last = self.to.clone();
+                }
+            }
+            _ => visit_mut::visit_expr_mut(self, e),
+        }
+    }
+}
+
+/// Visits a [`Statement`], searching for possible references to `column`.
+struct ColumnReferenceFinder<'a> {
+    column: &'a Ident,
+    found: bool,
+}
+
+impl<'a, 'ast> Visit<'ast> for ColumnReferenceFinder<'a> {
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        match e {
+            Expr::QualifiedWildcard(_) => self.found = true,
+            _ => visit::visit_expr(self, e),
+        }
+    }
+
+    fn visit_select_item(&mut self, item: &'ast SelectItem) {
+        match item {
+            SelectItem::Wildcard => self.found = true,
+            _ => visit::visit_select_item(self, item),
+        }
+    }
+
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        if ident == self.column {
+            self.found = true;
+        }
+    }
+}
@@ -56,6 +56,12 @@ pub trait Catalog: fmt::Debug + ExprHumanizer {
     /// Returns the database to use if one is not explicitly specified.
     fn default_database(&self) -> &str;
 
+    /// Returns the name of the user that established the session.
+    fn session_user(&self) -> &str;
+
+    /// Returns the name of the role that the session is acting as.
+    fn current_user(&self) -> &str;
+
     /// Resolves the named database.
     ///
     /// If `database_name` exists in the catalog, it returns the ID of the
@@ -138,6 +144,9 @@ pub struct CatalogConfig {
     pub cluster_id: Uuid,
     /// Expresses whether or not the catalog allows experimental mode features.
     pub experimental_mode: bool,
+    /// Whether `CREATE SOURCE` statements that do not specify `MATERIALIZED`
+    /// should nonetheless create materialized sources.
+    pub default_source_materialization: bool,
     /// The path in which source caching data is stored, if source caching is
     /// enabled.
     pub cache_directory: Option<PathBuf>,
@@ -207,6 +216,10 @@ pub trait CatalogItem {
     /// Returns the column defaults associated with the catalog item, if the
     /// catalog item is a table.
     fn table_details(&self) -> Option<&[Expr]>;
+
+    /// Returns the parameters, return type, and body associated with the
+    /// catalog item, if the catalog item is a function.
+    fn func_details(&self) -> Option<(&RelationDesc, &ScalarType, &Expr)>;
 }
 
 /// The type of a [`CatalogItem`].
@@ -224,6 +237,8 @@ pub enum CatalogItemType {
     Index,
     /// A type.
     Type,
+    /// A function.
+    Func,
 }
 
 impl fmt::Display for CatalogItemType {
@@ -235,6 +250,7 @@ impl fmt::Display for CatalogItemType {
             CatalogItemType::View => f.write_str("view"),
             CatalogItemType::Index => f.write_str("index"),
             CatalogItemType::Type => f.write_str("type"),
+            CatalogItemType::Func => f.write_str("function"),
         }
     }
 }
@@ -254,6 +270,8 @@ pub enum CatalogError {
     InvalidIndexDependency(String),
     /// Invalid attempt to depend on a type.
     InvalidTypeDependency(String),
+    /// Invalid attempt to use a function as a relation.
+    InvalidFuncDependency(String),
 }
 
 impl fmt::Display for CatalogError {
@@ -277,6 +295,11 @@ impl fmt::Display for CatalogError {
                 "catalog item '{}' is a type and so cannot be depended upon",
                 name
             ),
+            Self::InvalidFuncDependency(name) => write!(
+                f,
+                "catalog item '{}' is a function and so cannot be used as a relation",
+                name
+            ),
         }
     }
 }
@@ -295,6 +318,7 @@ const DUMMY_CONFIG: CatalogConfig = CatalogConfig {
     nonce: 0,
     cluster_id: Uuid::from_u128(0),
     experimental_mode: false,
+    default_source_materialization: false,
     cache_directory: None,
     build_info: &DUMMY_BUILD_INFO,
 };
@@ -308,6 +332,14 @@ impl Catalog for DummyCatalog {
         "dummy"
     }
 
+    fn session_user(&self) -> &str {
+        "dummy"
+    }
+
+    fn current_user(&self) -> &str {
+        "dummy"
+    }
+
     fn resolve_database(&self, _: &str) -> Result<&dyn CatalogDatabase, CatalogError> {
         unimplemented!();
     }
@@ -315,9 +347,9 @@ impl Catalog for DummyCatalog {
     fn resolve_schema(
         &self,
         _: Option<String>,
-        _: &str,
+        schema_name: &str,
     ) -> Result<&dyn CatalogSchema, CatalogError> {
-        unimplemented!();
+        Err(CatalogError::UnknownSchema(schema_name.into()))
     }
 
     fn resolve_item(&self, _: &PartialName) -> Result<&dyn CatalogItem, CatalogError> {
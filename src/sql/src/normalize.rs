@@ -10,16 +10,17 @@
 use std::collections::BTreeMap;
 
 use repr::ColumnName;
-use sql_parser::ast::display::AstDisplay;
+use sql_parser::ast::display::{self, AstDisplay};
 use sql_parser::ast::visit_mut::{self, VisitMut};
 use sql_parser::ast::{
-    CreateIndexStatement, CreateSinkStatement, CreateSourceStatement, CreateTableStatement,
-    CreateTypeStatement, CreateViewStatement, Function, FunctionArgs, Ident, IfExistsBehavior,
-    ObjectName, Query, SqlOption, Statement, TableFactor, Value,
+    CreateFunctionStatement, CreateIndexStatement, CreateSinkStatement, CreateSourceStatement,
+    CreateTableStatement, CreateTypeStatement, CreateViewStatement, Function, FunctionArgs, Ident,
+    IfExistsBehavior, ObjectName, Query, SqlOption, Statement, TableFactor, Value,
 };
 
 use crate::names::{DatabaseSpecifier, FullName, PartialName};
 use crate::plan::error::PlanError;
+use crate::plan::func;
 use crate::plan::statement::StatementContext;
 
 pub fn ident(ident: Ident) -> String {
@@ -52,6 +53,10 @@ pub fn options(options: &[SqlOption]) -> BTreeMap<String, Value> {
         .iter()
         .map(|o| match o {
             SqlOption::Value { name, value } => (ident(name.clone()), value.clone()),
+            SqlOption::Values { name, values } => (
+                ident(name.clone()),
+                Value::String(display::comma_separated(values).to_ast_string()),
+            ),
             SqlOption::ObjectName { name, object_name } => (
                 ident(name.clone()),
                 Value::String(object_name.to_ast_string()),
@@ -131,8 +136,17 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
         }
 
         fn visit_function_mut(&mut self, func: &'ast mut Function) {
-            // Don't visit the function name, because function names are not
-            // (yet) object names we can resolve.
+            // Don't visit the function name, because the names of built-in
+            // functions are not object names we can resolve. The names of
+            // user-defined functions, which are only consulted if no built-in
+            // function matches, are resolved like any other object name.
+            if let Ok(name) = object_name(func.name.clone()) {
+                if func::resolve_func(self.scx, &name).is_err() {
+                    if let Some(item) = self.scx.resolve_user_func(&name) {
+                        func.name = unresolve(item.name().clone());
+                    }
+                }
+            }
             match &mut func.args {
                 FunctionArgs::Star => (),
                 FunctionArgs::Args(args) => {
@@ -225,8 +239,13 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
             constraints: _,
             with_options: _,
             if_not_exists,
+            temporary,
         }) => {
-            *name = allocate_name(name)?;
+            *name = if *temporary {
+                allocate_temporary_name(name)?
+            } else {
+                allocate_name(name)?
+            };
             let mut normalizer = QueryNormalizer::new(scx);
             for c in columns {
                 normalizer.visit_column_def_mut(c);
@@ -243,6 +262,7 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
             connector: _,
             with_options: _,
             format: _,
+            envelope: _,
             with_snapshot: _,
             as_of: _,
             if_not_exists,
@@ -314,6 +334,24 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
             }
         }
 
+        Statement::CreateFunction(CreateFunctionStatement {
+            name,
+            params,
+            return_type,
+            body,
+        }) => {
+            *name = allocate_name(name)?;
+            let mut normalizer = QueryNormalizer::new(scx);
+            for param in params {
+                normalizer.visit_data_type_mut(&mut param.data_type);
+            }
+            normalizer.visit_data_type_mut(return_type);
+            normalizer.visit_expr_mut(body);
+            if let Some(err) = normalizer.err {
+                return Err(err);
+            }
+        }
+
         _ => unreachable!(),
     }
 
@@ -322,8 +360,8 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
 
 #[cfg(test)]
 mod tests {
-    use std::cell::RefCell;
-    use std::collections::BTreeMap;
+    use std::cell::{Cell, RefCell};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::error::Error;
     use std::rc::Rc;
 
@@ -339,6 +377,8 @@ mod tests {
             pcx: &PlanContext::default(),
             catalog: &DummyCatalog,
             param_types: Rc::new(RefCell::new(BTreeMap::new())),
+            func_uses: Rc::new(RefCell::new(BTreeSet::new())),
+            session_uses: Rc::new(Cell::new(false)),
         };
 
         let parsed = sql_parser::parser::parse_statements(
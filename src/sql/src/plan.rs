@@ -26,17 +26,22 @@
 // `plan_root_query` and fanning out based on the contents of the `SELECT`
 // statement.
 
+use std::path::PathBuf;
 use std::time::Duration;
 
+use anyhow::bail;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use ::expr::{GlobalId, RowSetFinishing};
-use dataflow_types::{SinkConnectorBuilder, SourceConnector};
+use dataflow_types::{SinkConnectorBuilder, SourceConnector, TailBackpressure, TailLimit};
+use repr::adt::interval::Interval;
 use repr::{ColumnName, RelationDesc, Row, ScalarType, Timestamp};
 use sql_parser::ast::Expr;
 
-use crate::ast::{ExplainOptions, ExplainStage, FetchDirection, ObjectType, Statement};
+use crate::ast::{
+    ExplainOptions, ExplainStage, FetchDirection, LockingClause, ObjectType, Statement,
+};
 use crate::names::{DatabaseSpecifier, FullName, SchemaName};
 
 pub(crate) mod decorrelate;
@@ -107,7 +112,13 @@ pub enum Plan {
         name: FullName,
         typ: Type,
     },
+    CreateFunction {
+        name: FullName,
+        func: Function,
+    },
     DiscardTemp,
+    DiscardPlans,
+    DiscardSequences,
     DiscardAll,
     DropDatabase {
         name: String,
@@ -118,6 +129,7 @@ pub enum Plan {
     DropItems {
         items: Vec<GlobalId>,
         ty: ObjectType,
+        cascade: bool,
     },
     EmptyQuery,
     ShowAllVariables,
@@ -126,22 +138,37 @@ pub enum Plan {
         name: String,
         value: String,
     },
-    StartTransaction,
+    StartTransaction {
+        /// Whether the transaction is read only and reads all of its queries
+        /// from a single, consistent snapshot, as requested by
+        /// `BEGIN READ ONLY DEFERRABLE`.
+        consistent_snapshot: bool,
+    },
     CommitTransaction,
     AbortTransaction,
     Peek {
         source: ::expr::RelationExpr,
         when: PeekWhen,
         finishing: RowSetFinishing,
-        copy_to: Option<CopyFormat>,
+        copy_to: Option<CopyTo>,
+        /// The index that the query's `OPTION (index = ...)` hint requires
+        /// the peek to read from, if any.
+        index_hint: Option<GlobalId>,
+        locking: Option<LockingClause>,
     },
     Tail {
         id: GlobalId,
         with_snapshot: bool,
         ts: Option<Timestamp>,
+        until: Option<Timestamp>,
         copy_to: Option<CopyFormat>,
         emit_progress: bool,
         object_columns: usize,
+        on_backpressure: TailBackpressure,
+        limit: Option<TailLimit>,
+        /// Whether to collect the rows of the `TAIL` into a single result set,
+        /// as for `SELECT * FROM (TAIL ...)`, rather than streaming them.
+        collect: bool,
     },
     SendRows(Vec<Row>),
     ExplainPlan {
@@ -150,7 +177,22 @@ pub enum Plan {
         row_set_finishing: Option<RowSetFinishing>,
         stage: ExplainStage,
         options: ExplainOptions,
+        /// Whether the plan is the values expression of an `INSERT`.
+        is_insert: bool,
+    },
+    ExplainArrangements(ArrangementsExplainee),
+    /// Validates a sink as `CreateSink` would, and reports the external
+    /// state that it would create, without creating the sink.
+    ExplainSink {
+        name: FullName,
+        sink: Sink,
+        with_snapshot: bool,
+        as_of: Option<Timestamp>,
+        if_not_exists: bool,
     },
+    /// Executes the wrapped `Peek` or `Insert` plan, and reports its timing
+    /// and outcome in place of its results.
+    ExplainAnalyze(Box<Plan>),
     SendDiffs {
         id: GlobalId,
         updates: Vec<(Row, isize)>,
@@ -160,13 +202,62 @@ pub enum Plan {
     Insert {
         id: GlobalId,
         values: ::expr::RelationExpr,
+        /// Expressions to evaluate over each inserted row, as in `INSERT ...
+        /// RETURNING`. If empty, the number of inserted rows is returned
+        /// instead.
+        returning: Vec<::expr::ScalarExpr>,
+    },
+    /// Creates a new table and populates it with the rows produced by
+    /// `values`, as in `SELECT ... INTO`.
+    SelectInto {
+        name: FullName,
+        table: Table,
+        values: ::expr::RelationExpr,
     },
     AlterItemRename {
         id: Option<GlobalId>,
         to_name: String,
         object_type: ObjectType,
     },
+    /// Renames the column `old` of the view `id` to `new`. `None` is a no-op.
+    AlterViewRenameColumn {
+        id: Option<GlobalId>,
+        old: String,
+        new: String,
+    },
     AlterIndexLogicalCompactionWindow(Option<AlterIndexLogicalCompactionWindow>),
+    /// Changes the key expressions of an index and rebuilds its arrangement.
+    /// `None` is a no-op.
+    AlterIndexKeys(Option<AlterIndexKeys>),
+    /// Sets the logical compaction window of the default index of a view.
+    /// `None` is a no-op.
+    AlterMaterializedViewLogicalCompactionWindow(
+        Option<AlterMaterializedViewLogicalCompactionWindow>,
+    ),
+    /// Rebuilds the indexes of the view `id` from scratch. `None` is a no-op.
+    RematerializeView {
+        id: Option<GlobalId>,
+    },
+    /// Accepts a `REFRESH MATERIALIZED VIEW` of the view named `name`, which
+    /// has no effect, as materialized views are always up to date.
+    RefreshMaterializedView {
+        name: String,
+    },
+    /// Overrides the logical compaction window of every index.
+    ///
+    /// `LogicalCompactionWindow::Default` removes the override, restoring each
+    /// index's own window. `None` is a no-op.
+    AlterAllIndexesCompaction {
+        window: Option<LogicalCompactionWindow>,
+    },
+    /// Sets the comment on the item `object_id`, or on its column at the
+    /// 0-indexed `column_pos`, if specified. A `comment` of `None` removes
+    /// the existing comment.
+    Comment {
+        object_id: GlobalId,
+        column_pos: Option<usize>,
+        comment: Option<String>,
+    },
     Declare {
         name: String,
         stmt: Statement,
@@ -174,11 +265,27 @@ pub enum Plan {
     Fetch {
         name: String,
         count: Option<FetchDirection>,
-        timeout: ExecuteTimeout,
+        /// The timeout specified by the statement, if any. If unspecified, the
+        /// session's `fetch_timeout` applies.
+        timeout: Option<ExecuteTimeout>,
     },
     Close {
         name: String,
     },
+    /// Starts delivering notifications sent on `channel` to the session.
+    Listen {
+        channel: String,
+    },
+    /// Stops delivering notifications sent on `channel`, or on all channels if
+    /// `channel` is `None`, to the session.
+    Unlisten {
+        channel: Option<String>,
+    },
+    /// Sends `payload` to all sessions listening on `channel`.
+    Notify {
+        channel: String,
+        payload: String,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -186,6 +293,9 @@ pub struct Table {
     pub create_sql: String,
     pub desc: RelationDesc,
     pub defaults: Vec<Expr>,
+    pub temporary: bool,
+    /// The user-defined functions called by the table's default expressions.
+    pub func_uses: Vec<GlobalId>,
 }
 
 #[derive(Clone, Debug)]
@@ -208,6 +318,9 @@ pub struct View {
     pub expr: ::expr::RelationExpr,
     pub column_names: Vec<Option<ColumnName>>,
     pub temporary: bool,
+    /// The user-defined functions called by the view, whose bodies have been
+    /// inlined into `expr`.
+    pub func_uses: Vec<GlobalId>,
 }
 
 #[derive(Clone, Debug)]
@@ -215,6 +328,23 @@ pub struct Index {
     pub create_sql: String,
     pub on: GlobalId,
     pub keys: Vec<::expr::ScalarExpr>,
+    /// The user-defined functions called by the index's keys, whose bodies
+    /// have been inlined into `keys`.
+    pub func_uses: Vec<GlobalId>,
+}
+
+/// The dataflow described by `EXPLAIN (ARRANGEMENTS)`.
+#[derive(Clone, Debug)]
+pub enum ArrangementsExplainee {
+    /// A view computing `expr`. If `materialized_name` is set, the view is
+    /// being created as a materialized view with that name, and so its default
+    /// index is exported too.
+    View {
+        expr: ::expr::RelationExpr,
+        materialized_name: Option<FullName>,
+    },
+    /// An index with the specified name, which is being created.
+    Index { name: FullName, index: Index },
 }
 
 #[derive(Clone, Debug)]
@@ -223,6 +353,19 @@ pub struct Type {
     pub inner: TypeInner,
 }
 
+/// A user-defined scalar function whose body is a single SQL expression.
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub create_sql: String,
+    /// The names and types of the function's parameters.
+    pub params: RelationDesc,
+    pub return_type: ScalarType,
+    /// The function's body, which refers to the parameters by name.
+    pub body: Expr,
+    /// The user-defined functions called by the function's body.
+    pub func_uses: Vec<GlobalId>,
+}
+
 #[derive(Clone, Debug)]
 pub enum TypeInner {
     List {
@@ -235,7 +378,7 @@ pub enum TypeInner {
 }
 
 /// Specifies when a `Peek` should occur.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum PeekWhen {
     /// The peek should occur at the latest possible timestamp that allows the
     /// peek to complete immediately.
@@ -251,13 +394,67 @@ pub enum MutationKind {
     Delete,
 }
 
-#[derive(Debug)]
+/// The parameters of a `COPY ... TO` statement.
+#[derive(Clone, Debug)]
+pub struct CopyTo {
+    /// The format in which to encode the rows.
+    pub format: CopyFormat,
+    /// Where to send the encoded rows.
+    pub target: CopyToTarget,
+}
+
+/// The destination of a `COPY ... TO` statement.
+#[derive(Clone, Debug)]
+pub enum CopyToTarget {
+    /// Stream the rows to the client.
+    Stdout,
+    /// Write the rows to files on the server. Each file's name is the path
+    /// prefix followed by a sequence number and an extension for the format,
+    /// e.g., `prefix-0001.csv`.
+    File {
+        /// The path prefix of the files.
+        prefix: PathBuf,
+        /// The description of the rows, used to write CSV headers.
+        desc: RelationDesc,
+    },
+}
+
+#[derive(Clone, Debug)]
 pub enum CopyFormat {
     Text,
-    Csv,
+    Csv(CopyCsvFormatParams),
     Binary,
 }
 
+/// Parameters for `COPY ... WITH (FORMAT csv)`.
+#[derive(Clone, Debug)]
+pub struct CopyCsvFormatParams {
+    /// The character that separates columns.
+    pub delimiter: u8,
+    /// The character that quotes a value.
+    pub quote: u8,
+    /// The character that precedes a quote or escape character appearing in
+    /// a quoted value.
+    pub escape: u8,
+    /// The string that represents a null value.
+    pub null: String,
+    /// Whether to emit a header line of column names.
+    pub header: bool,
+    /// How to represent the non-finite values of floating-point columns.
+    pub nonfinite_floats: CopyNonfiniteFloats,
+}
+
+/// The representation of `NaN`, `Infinity`, and `-Infinity` in `COPY` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyNonfiniteFloats {
+    /// Emit the values' text representations, e.g., `NaN`.
+    Text,
+    /// Emit the null string.
+    Null,
+    /// Fail the `COPY`.
+    Error,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum ExecuteTimeout {
     None,
@@ -265,12 +462,45 @@ pub enum ExecuteTimeout {
     WaitOnce,
 }
 
+impl ExecuteTimeout {
+    /// Constructs a timeout of the duration of `interval`, which must be
+    /// between zero and one day.
+    pub fn from_interval(interval: Interval) -> Result<ExecuteTimeout, anyhow::Error> {
+        // Limit timeouts to 1 day. If users have a legitimate need it can be
+        // bumped. If we do bump it, ensure that the new upper limit is within
+        // the bounds of a tokio time future, otherwise it'll panic.
+        const SECS_PER_DAY: f64 = 60f64 * 60f64 * 24f64;
+        let secs = interval.as_seconds();
+        if !secs.is_finite() || secs < 0f64 || secs > SECS_PER_DAY {
+            bail!("timeout out of range: {:#}", interval);
+        }
+        Ok(ExecuteTimeout::Seconds(secs))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct AlterIndexLogicalCompactionWindow {
     pub index: GlobalId,
     pub logical_compaction_window: LogicalCompactionWindow,
 }
 
+#[derive(Debug)]
+pub struct AlterIndexKeys {
+    pub id: GlobalId,
+    pub new_keys: Vec<::expr::ScalarExpr>,
+    /// The `CREATE INDEX` statement for the index with its new keys.
+    pub create_sql: String,
+    /// The user-defined functions called by `new_keys`, whose bodies have
+    /// been inlined into them.
+    pub func_uses: Vec<GlobalId>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AlterMaterializedViewLogicalCompactionWindow {
+    pub view: GlobalId,
+    pub logical_compaction_window: LogicalCompactionWindow,
+}
+
 /// Specifies what value the `logical_compaction_window` parameter should be set to.
 #[derive(Debug, PartialEq)]
 pub enum LogicalCompactionWindow {
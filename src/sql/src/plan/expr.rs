@@ -799,6 +799,63 @@ impl RelationExpr {
         }
     }
 
+    /// Replaces the values of all literals and constant rows in the
+    /// expression, including those in subqueries, with placeholders. See
+    /// [`expr::RelationExpr::redact_literals`].
+    pub fn redact_literals(&mut self) {
+        match self {
+            RelationExpr::Join {
+                on, left, right, ..
+            } => {
+                on.redact_literals();
+                left.redact_literals();
+                right.redact_literals();
+            }
+            RelationExpr::Map { scalars, input } => {
+                for scalar in scalars {
+                    scalar.redact_literals();
+                }
+                input.redact_literals();
+            }
+            RelationExpr::CallTable { exprs, .. } => {
+                for expr in exprs {
+                    expr.redact_literals();
+                }
+            }
+            RelationExpr::Filter { predicates, input } => {
+                for predicate in predicates {
+                    predicate.redact_literals();
+                }
+                input.redact_literals();
+            }
+            RelationExpr::Reduce {
+                aggregates, input, ..
+            } => {
+                for aggregate in aggregates {
+                    aggregate.redact_literals();
+                }
+                input.redact_literals();
+            }
+            RelationExpr::Union { base, inputs } => {
+                for input in inputs {
+                    input.redact_literals();
+                }
+                base.redact_literals();
+            }
+            RelationExpr::Project { input, .. }
+            | RelationExpr::Distinct { input, .. }
+            | RelationExpr::TopK { input, .. }
+            | RelationExpr::Negate { input, .. }
+            | RelationExpr::Threshold { input, .. } => input.redact_literals(),
+            RelationExpr::Constant { rows, .. } => {
+                for row in rows {
+                    *row = expr::explain::redact_row(row);
+                }
+            }
+            RelationExpr::Get { .. } => (),
+        }
+    }
+
     /// See the documentation for [`ScalarExpr::splice_parameters`].
     pub fn splice_parameters(&mut self, params: &[ScalarExpr], depth: usize) {
         match self {
@@ -926,6 +983,32 @@ impl ScalarExpr {
         }
     }
 
+    /// Replaces the value of every literal in the expression, including those
+    /// in subqueries, with a placeholder. See
+    /// [`expr::ScalarExpr::redact_literals`].
+    pub fn redact_literals(&mut self) {
+        match self {
+            ScalarExpr::Literal(row, _) => *row = expr::explain::redact_row(row),
+            ScalarExpr::Column(_) | ScalarExpr::Parameter(_) | ScalarExpr::CallNullary(_) => (),
+            ScalarExpr::CallUnary { expr, .. } => expr.redact_literals(),
+            ScalarExpr::CallBinary { expr1, expr2, .. } => {
+                expr1.redact_literals();
+                expr2.redact_literals();
+            }
+            ScalarExpr::CallVariadic { exprs, .. } => {
+                for expr in exprs {
+                    expr.redact_literals();
+                }
+            }
+            ScalarExpr::If { cond, then, els } => {
+                cond.redact_literals();
+                then.redact_literals();
+                els.redact_literals();
+            }
+            ScalarExpr::Exists(expr) | ScalarExpr::Select(expr) => expr.redact_literals(),
+        }
+    }
+
     // Like [`ScalarExpr::bind_parameters`]`, except that parameters are
     // replaced with the corresponding expression fragment from `params` rather
     // than a datum.
@@ -1142,6 +1225,25 @@ impl ScalarExpr {
         })
     }
 
+    /// Attempts to simplify this expression to a literal 64-bit float.
+    ///
+    /// Returns `None` if this expression cannot be simplified, e.g. because it
+    /// contains non-literal values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this expression does not have type [`ScalarType::Float64`].
+    pub fn into_literal_float64(self) -> Option<f64> {
+        self.simplify_to_literal().and_then(|row| {
+            let datum = row.unpack_first();
+            if datum.is_null() {
+                None
+            } else {
+                Some(datum.unwrap_float64())
+            }
+        })
+    }
+
     /// Attempts to simplify this expression to a literal string.
     ///
     /// Returns `None` if this expression cannot be simplified, e.g. because it
@@ -1217,6 +1319,12 @@ impl AggregateExpr {
         self.expr.bind_parameters(params)
     }
 
+    /// Replaces the value of every literal in the expression with a
+    /// placeholder.
+    pub fn redact_literals(&mut self) {
+        self.expr.redact_literals();
+    }
+
     pub fn typ(
         &self,
         outers: &[RelationType],
@@ -16,10 +16,11 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::rc::Rc;
 
-use anyhow::{bail, Context};
+use anyhow::{anyhow, bail, Context};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 
+use expr::GlobalId;
 use ore::collections::CollectionExt;
 use repr::{ColumnName, Datum, RelationType, ScalarBaseType, ScalarType};
 use sql_parser::ast::{Expr, ObjectName};
@@ -105,6 +106,7 @@ impl TypeCategory {
             ScalarType::String => Self::String,
             ScalarType::Record { .. } => Self::Pseudo,
             ScalarType::Map { .. } => Self::Pseudo,
+            ScalarType::Void => Self::Pseudo,
         }
     }
 
@@ -1214,6 +1216,12 @@ lazy_static! {
             "char_length" => Scalar {
                 params!(String) => UnaryFunc::CharLength
             },
+            "col_description" => Scalar {
+                params!(Oid, Int32) => sql_op!(
+                    "(SELECT description FROM pg_catalog.pg_description
+                     WHERE objoid = $1 AND objsubid = $2)"
+                )
+            },
             "concat" => Scalar {
                  params!((Any)...) => Operation::variadic(|ecx, cexprs| {
                     if cexprs.is_empty() {
@@ -1241,6 +1249,7 @@ lazy_static! {
             },
             "current_schemas" => Scalar {
                 params!(Bool) => Operation::unary(|ecx, e| {
+                    ecx.qcx.scx.session_uses.set(true);
                     let with_sys = ScalarExpr::literal_1d_array(
                         ecx.qcx.scx.catalog.search_path(true).iter().map(|s| Datum::String(s)).collect(),
                         ScalarType::String)?;
@@ -1257,6 +1266,13 @@ lazy_static! {
             "current_timestamp" => Scalar {
                 params!() => Operation::nullary(|ecx| plan_current_timestamp(ecx, "current_timestamp"))
             },
+            "current_user" => Scalar {
+                params!() => Operation::nullary(|ecx| {
+                    ecx.qcx.scx.session_uses.set(true);
+                    let user = ecx.catalog().current_user();
+                    Ok(ScalarExpr::literal(Datum::String(user), ScalarType::String))
+                })
+            },
             "date_part" => Scalar {
                 params!(String, Interval) => BinaryFunc::DatePartInterval,
                 params!(String, Timestamp) => BinaryFunc::DatePartTimestamp,
@@ -1345,12 +1361,16 @@ lazy_static! {
                 params!() => Operation::nullary(|ecx| plan_current_timestamp(ecx, "now"))
             },
             "obj_description" => Scalar {
-                params!(Oid, String) => Operation::binary(|_ecx, _oid, _catalog| {
-                    // This function is meant to return the comment on a
-                    // database object, but we don't presently support comments,
-                    // so stubbed out out to always return NULL.
-                    Ok(ScalarExpr::literal_null(ScalarType::String))
-                })
+                // Only relations and indexes, which PostgreSQL describes in
+                // `pg_class`, can have comments.
+                params!(Oid) => sql_op!(
+                    "(SELECT description FROM pg_catalog.pg_description
+                     WHERE objoid = $1 AND objsubid = 0)"
+                ),
+                params!(Oid, String) => sql_op!(
+                    "(SELECT description FROM pg_catalog.pg_description
+                     WHERE objoid = $1 AND objsubid = 0 AND $2 = 'pg_class')"
+                )
             },
             "pg_encoding_to_char" => Scalar {
                 // Materialize only supports UT8-encoded databases. Return 'UTF8' if Postgres'
@@ -1360,6 +1380,9 @@ lazy_static! {
             "pg_get_userbyid" => Scalar {
                 params!(Oid) => sql_op!("'unknown (OID=' || $1 || ')'")
             },
+            "pg_sleep" => Scalar {
+                params!(Float64) => Operation::unary(|_ecx, duration| sleep("pg_sleep", duration))
+            },
             "pg_table_is_visible" => Scalar {
                 params!(Oid) => sql_op!(
                     "(SELECT s.name = ANY(current_schemas(true))
@@ -1410,6 +1433,13 @@ lazy_static! {
                 params!(String) => UnaryFunc::TrimTrailingWhitespace,
                 params!(String, String) => BinaryFunc::TrimTrailing
             },
+            "session_user" => Scalar {
+                params!() => Operation::nullary(|ecx| {
+                    ecx.qcx.scx.session_uses.set(true);
+                    let user = ecx.catalog().session_user();
+                    Ok(ScalarExpr::literal(Datum::String(user), ScalarType::String))
+                })
+            },
             "split_part" => Scalar {
                 params!(String, String, Int64) => VariadicFunc::SplitPart
             },
@@ -1690,9 +1720,40 @@ lazy_static! {
             "mz_cluster_id" => Scalar {
                 params!() => Operation::nullary(mz_cluster_id)
             },
+            "mz_index_since" => Scalar {
+                params!(String) => Operation::unary(|_ecx, id| {
+                    let id = mz_index_id("mz_index_since", id)?;
+                    Ok(ScalarExpr::CallNullary(NullaryFunc::MzIndexSince { id }))
+                })
+            },
+            "mz_index_upper" => Scalar {
+                params!(String) => Operation::unary(|_ecx, id| {
+                    let id = mz_index_id("mz_index_upper", id)?;
+                    Ok(ScalarExpr::CallNullary(NullaryFunc::MzIndexUpper { id }))
+                })
+            },
             "mz_logical_timestamp" => Scalar {
                 params!() => NullaryFunc::MzLogicalTimestamp
             },
+            "mz_sleep" => Scalar {
+                params!(Float64) => Operation::unary(|ecx, duration| {
+                    ecx.require_experimental_mode("mz_sleep")?;
+                    sleep("mz_sleep", duration)
+                })
+            },
+            "mz_typeof" => Scalar {
+                params!(Any) => Operation::new(|ecx, spec, exprs, params| {
+                    // Unlike pg_typeof, mz_typeof reports the type *after*
+                    // coercion, under the name that describes the type over
+                    // pgwire.
+                    let exprs = coerce_args_to_types(ecx, spec, exprs, params)?;
+                    let ty = pgrepr::Type::from(&ecx.scalar_type(&exprs[0]));
+                    Ok(ScalarExpr::literal(Datum::String(ty.name()), ScalarType::String))
+                })
+            },
+            "mz_uptime" => Scalar {
+                params!() => NullaryFunc::MzUptime
+            },
             "mz_version" => Scalar {
                 params!() => Operation::nullary(|ecx| {
                     let version = ecx.catalog().config().build_info.human_version();
@@ -1805,6 +1866,39 @@ fn mz_cluster_id(ecx: &ExprContext) -> Result<ScalarExpr, anyhow::Error> {
     ))
 }
 
+/// The longest duration, in seconds, that a single `mz_sleep` or `pg_sleep`
+/// call may request.
+const MAX_SLEEP_SECONDS: f64 = 60.0;
+
+/// Plans a call to the sleep function `name`, i.e. `mz_sleep` or `pg_sleep`.
+fn sleep(name: &str, duration: ScalarExpr) -> Result<ScalarExpr, anyhow::Error> {
+    let seconds = match duration.into_literal_float64() {
+        None => bail!("{} requires a literal duration", name),
+        Some(seconds) => seconds,
+    };
+    if !(0.0..=MAX_SLEEP_SECONDS).contains(&seconds) {
+        bail!(
+            "{} duration must be between 0 and {} seconds",
+            name,
+            MAX_SLEEP_SECONDS
+        );
+    }
+    Ok(ScalarExpr::CallNullary(NullaryFunc::MzSleep {
+        duration_ms: (seconds * 1000.0) as u64,
+    }))
+}
+
+/// Extracts the index ID that is the argument to `name`, which must be a
+/// literal. The coordinator verifies that the ID names an index.
+fn mz_index_id(name: &str, id: ScalarExpr) -> Result<GlobalId, anyhow::Error> {
+    match id.into_literal_string() {
+        None => bail!("{} requires a literal index ID", name),
+        Some(id) => id
+            .parse()
+            .map_err(|_| anyhow!("{}: invalid index ID '{}'", name, id)),
+    }
+}
+
 fn array_to_string(ecx: &ExprContext, exprs: Vec<ScalarExpr>) -> Result<ScalarExpr, anyhow::Error> {
     let elem_type = match ecx.scalar_type(&exprs[0]) {
         ScalarType::Array(elem_type) => *elem_type,
@@ -1829,6 +1923,10 @@ pub fn resolve_func(
     // need to be revisited when either:
     //   * we support configuring the search path from its default, or
     //   * we support user-defined functions.
+    //
+    // User-defined functions, which are limited to inlinable SQL functions,
+    // are resolved through the catalog only if this resolution fails. See
+    // `StatementContext::resolve_user_func`.
 
     if let Some(database) = &name.database {
         // If a database name is provided, we need only verify that the
@@ -31,19 +31,19 @@ use itertools::Itertools;
 use ore::iter::IteratorExt;
 use sql_parser::ast::visit::{self, Visit};
 use sql_parser::ast::{
-    DataType, Distinct, Expr, Function, FunctionArgs, Ident, InsertSource, JoinConstraint,
+    Cte, DataType, Distinct, Expr, Function, FunctionArgs, Ident, InsertSource, JoinConstraint,
     JoinOperator, Limit, ObjectName, OrderByExpr, Query, Select, SelectItem, SetExpr, SetOperator,
     TableAlias, TableFactor, TableWithJoins, Value, Values,
 };
 
-use ::expr::{GlobalId, Id, RowSetFinishing};
+use ::expr::{GlobalId, Id, LocalId, RowSetFinishing};
 use repr::adt::decimal::{Decimal, MAX_DECIMAL_PRECISION};
 use repr::{
-    strconv, ColumnName, ColumnType, Datum, RelationDesc, RelationType, RowArena, ScalarType,
+    strconv, ColumnName, ColumnType, Datum, RelationDesc, RelationType, Row, RowArena, ScalarType,
     Timestamp,
 };
 
-use crate::catalog::{Catalog, CatalogItemType};
+use crate::catalog::{Catalog, CatalogItem, CatalogItemType};
 use crate::names::PartialName;
 use crate::normalize;
 use crate::plan::error::PlanError;
@@ -56,8 +56,17 @@ use crate::plan::plan_utils;
 use crate::plan::scope::{Scope, ScopeItem, ScopeItemName};
 use crate::plan::statement::StatementContext;
 use crate::plan::transform_ast;
+use crate::plan::transform_expr;
 use crate::plan::typeconv::{self, CastContext};
 
+/// The largest `MAX ITERATIONS` bound accepted for a `WITH RECURSIVE` clause.
+///
+/// Unrolling a CTE with bound `n` plans `n(n+1)/2` copies of its recursive
+/// term, as each iteration nests a copy of the iteration before it (see
+/// [`transform_expr::unroll_recursive_cte`]). The bound keeps the resulting
+/// plan and dataflow to at most a few hundred copies.
+const MAX_RECURSIVE_ITERATIONS: u64 = 20;
+
 /// Plans a top-level query, returning the `RelationExpr` describing the query
 /// plan, the `RelationDesc` describing the shape of the result set, a
 /// `RowSetFinishing` describing post-processing that must occur before results
@@ -200,6 +209,7 @@ pub fn plan_insert_query(
                 Query {
                     body: SetExpr::Values(values),
                     ctes,
+                    max_iterations: _,
                     order_by,
                     limit: None,
                     offset: None,
@@ -270,6 +280,54 @@ pub fn plan_insert_query(
     Ok((table.id(), expr.map(map_exprs).project(project_key)))
 }
 
+/// Plans the `RETURNING` clause of an `INSERT` into the table named
+/// `table_name`.
+///
+/// The returned expressions are evaluated over each inserted row, after
+/// omitted columns have been filled in with their defaults, and produce rows
+/// described by the returned `RelationDesc`.
+pub fn plan_returning(
+    scx: &StatementContext,
+    table_name: ObjectName,
+    returning: Vec<SelectItem>,
+) -> Result<(Vec<ScalarExpr>, RelationDesc), anyhow::Error> {
+    let table = scx.resolve_item(table_name)?;
+    let desc = table.desc()?;
+    let scope = Scope::from_source(
+        Some(table.name().clone().into()),
+        desc.iter_names(),
+        Some(Scope::empty(None)),
+    );
+    let qcx = &QueryContext::root(scx, QueryLifetime::OneShot);
+    let ecx = &ExprContext {
+        qcx: &qcx,
+        name: "RETURNING clause",
+        scope: &scope,
+        relation_type: desc.typ(),
+        allow_aggregates: false,
+        allow_subqueries: false,
+    };
+    let mut exprs = vec![];
+    let mut names = vec![];
+    for mut si in returning {
+        if let SelectItem::Expr { expr, .. } = &mut si {
+            transform_ast::transform_expr(scx, expr)?;
+        }
+        for (item, name) in expand_select_item(ecx, &si)? {
+            let expr = match item {
+                ExpandedSelectItem::InputOrdinal(column) => {
+                    ScalarExpr::Column(ColumnRef { level: 0, column })
+                }
+                ExpandedSelectItem::Expr(expr) => plan_expr(ecx, &expr)?.type_as_any(ecx)?,
+            };
+            exprs.push(expr);
+            names.push(name);
+        }
+    }
+    let typ = RelationType::new(exprs.iter().map(|e| ecx.column_type(e)).collect());
+    Ok((exprs, RelationDesc::new(typ, names)))
+}
+
 struct CastRelationError {
     column: usize,
     source_type: ScalarType,
@@ -373,6 +431,40 @@ pub fn eval_as_of<'a>(
     })
 }
 
+/// Evaluates the literal `values` of a keyset bound, as specified by the
+/// `after` option of a `SELECT`, as datums of the corresponding
+/// `target_types`.
+pub fn eval_keyset_bound<'a>(
+    scx: &StatementContext,
+    values: Vec<Value>,
+    target_types: impl IntoIterator<Item = &'a ScalarType>,
+) -> Result<Row, anyhow::Error> {
+    let qcx = &QueryContext::root(scx, QueryLifetime::OneShot);
+    let ecx = &ExprContext {
+        qcx: &qcx,
+        name: "OPTION (after)",
+        scope: &Scope::empty(None),
+        relation_type: &RelationType::empty(),
+        allow_aggregates: false,
+        allow_subqueries: false,
+    };
+    let exprs = values
+        .into_iter()
+        .zip(target_types)
+        .map(|(value, target_ty)| {
+            plan_expr(ecx, &Expr::Value(value))?
+                .cast_to(ecx.name, ecx, CastContext::Assignment, target_ty)?
+                .lower_uncorrelated()
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+    let temp_storage = &RowArena::new();
+    let datums = exprs
+        .iter()
+        .map(|ex| ex.eval(&[], temp_storage))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Row::pack_slice(&datums))
+}
+
 pub fn plan_default_expr(
     scx: &StatementContext,
     expr: &Expr,
@@ -414,6 +506,46 @@ pub fn plan_index_exprs<'a>(
     Ok(out)
 }
 
+/// Plans the expression that routes each row of a Kafka sink to a topic. The
+/// expression is evaluated over the sink's input relation, described by
+/// `on_desc`, and its result is cast to `text`.
+///
+/// The expression must be deterministic, as otherwise the same row could be
+/// routed to different topics at different times.
+pub fn plan_sink_topic_expr(
+    scx: &StatementContext,
+    on_desc: &RelationDesc,
+    mut expr: Expr,
+) -> Result<::expr::ScalarExpr, anyhow::Error> {
+    let scope = Scope::from_source(None, on_desc.iter_names(), Some(Scope::empty(None)));
+    let qcx = &QueryContext::root(scx, QueryLifetime::Static);
+    let ecx = &ExprContext {
+        qcx: &qcx,
+        name: "topic_by",
+        scope: &scope,
+        relation_type: on_desc.typ(),
+        allow_aggregates: false,
+        allow_subqueries: false,
+    };
+    transform_ast::transform_expr(scx, &mut expr)?;
+    let expr = plan_expr(ecx, &expr)?.cast_to(
+        ecx.name,
+        ecx,
+        CastContext::Explicit,
+        &ScalarType::String,
+    )?;
+    let mut deterministic = true;
+    expr.visit(&mut |e| {
+        if let ScalarExpr::CallNullary(_) | ScalarExpr::Parameter(_) = e {
+            deterministic = false;
+        }
+    });
+    if !deterministic {
+        bail!("topic_by must be deterministic, but it calls a function whose result can change");
+    }
+    expr.lower_uncorrelated()
+}
+
 fn plan_expr_or_col_index(ecx: &ExprContext, e: &Expr) -> Result<ScalarExpr, anyhow::Error> {
     match check_col_index(&ecx.name, e, ecx.relation_type.column_types.len())? {
         Some(column) => Ok(ScalarExpr::Column(ColumnRef { level: 0, column })),
@@ -445,6 +577,16 @@ fn plan_query(
     qcx: &mut QueryContext,
     q: &Query,
 ) -> Result<(RelationExpr, Scope, RowSetFinishing), anyhow::Error> {
+    if let Some(max_iterations) = q.max_iterations {
+        if max_iterations > MAX_RECURSIVE_ITERATIONS {
+            bail!(
+                "MAX ITERATIONS must not exceed {}, got {}",
+                MAX_RECURSIVE_ITERATIONS,
+                max_iterations
+            );
+        }
+    }
+
     // Retain the old values of various CTE names so that we can restore them after we're done
     // planning this SELECT.
     let mut old_cte_values = Vec::new();
@@ -459,14 +601,7 @@ fn plan_query(
         used_names.insert(cte_name.clone());
 
         // Plan CTE.
-        let (val, scope) = plan_subquery(qcx, &cte.query)?;
-        let typ = qcx.relation_type(&val);
-        let mut val_desc = RelationDesc::new(typ, scope.column_names());
-        val_desc = plan_utils::maybe_rename_columns(
-            format!("CTE {}", cte.alias.name),
-            val_desc,
-            &cte.alias.columns,
-        )?;
+        let (val, val_desc) = plan_cte(qcx, cte, &cte_name, q.max_iterations)?;
 
         let old_val = qcx.ctes.insert(
             cte_name.clone(),
@@ -507,6 +642,7 @@ fn plan_query(
                 project: plan.project,
                 limit,
                 offset,
+                after: None,
             };
             Ok((plan.expr, plan.scope, finishing))
         }
@@ -526,6 +662,7 @@ fn plan_query(
                 limit,
                 project: (0..ecx.relation_type.arity()).collect(),
                 offset,
+                after: None,
             };
             Ok((expr.map(map_exprs), scope, finishing))
         }
@@ -546,6 +683,140 @@ fn plan_query(
     result
 }
 
+/// Plans the CTE `cte`, named `cte_name`, returning its expression and
+/// description.
+///
+/// If the CTE belongs to a `WITH RECURSIVE` clause with the given
+/// `max_iterations` bound and has the form `<base> UNION [ALL] <recursive
+/// term>`, the recursive term is planned against a placeholder for the CTE
+/// and then unrolled `max_iterations` times. See
+/// [`transform_expr::unroll_recursive_cte`] for the semantics of the bound.
+fn plan_cte(
+    qcx: &mut QueryContext,
+    cte: &Cte,
+    cte_name: &str,
+    max_iterations: Option<u64>,
+) -> Result<(RelationExpr, RelationDesc), anyhow::Error> {
+    let rename = |desc| {
+        plan_utils::maybe_rename_columns(
+            format!("CTE {}", cte.alias.name),
+            desc,
+            &cte.alias.columns,
+        )
+    };
+
+    let (max_iterations, all, base, step) = match (max_iterations, &cte.query) {
+        (
+            Some(max_iterations),
+            Query {
+                ctes,
+                max_iterations: None,
+                body:
+                    SetExpr::SetOperation {
+                        op: SetOperator::Union,
+                        all,
+                        left,
+                        right,
+                    },
+                order_by,
+                limit: None,
+                offset: None,
+            },
+        ) if ctes.is_empty() && order_by.is_empty() => (max_iterations, *all, left, right),
+        _ => {
+            let (val, scope) = plan_subquery(qcx, &cte.query)?;
+            let typ = qcx.relation_type(&val);
+            let val_desc = rename(RelationDesc::new(typ, scope.column_names()))?;
+            return Ok((val, val_desc));
+        }
+    };
+
+    let (base, base_scope) = plan_set_expr(qcx, base)?;
+    let base_typ = qcx.relation_type(&base);
+    let val_desc = rename(RelationDesc::new(
+        base_typ.clone(),
+        base_scope.column_names(),
+    ))?;
+
+    // Plan the recursive term against a placeholder for the CTE. Every
+    // placeholder in scope belongs to an enclosing recursive CTE, so counting
+    // them yields an identifier that is unique among them. The placeholder's
+    // columns are nullable, as later iterations may introduce NULLs that the
+    // base term does not.
+    let id = LocalId::new(
+        qcx.ctes
+            .values()
+            .filter(|cte| cte.is_recursive_placeholder())
+            .count() as u64,
+    );
+    let mut placeholder_typ = base_typ.clone();
+    for typ in &mut placeholder_typ.column_types {
+        typ.nullable = true;
+    }
+    let old_val = qcx.ctes.insert(
+        cte_name.to_owned(),
+        CteDesc {
+            val: RelationExpr::Get {
+                id: Id::Local(id),
+                typ: placeholder_typ,
+            },
+            val_desc: val_desc.clone(),
+            level_offset: 0,
+        },
+    );
+    let step = plan_set_expr(qcx, step);
+    match old_val {
+        Some(old_val) => qcx.ctes.insert(cte_name.to_owned(), old_val),
+        None => qcx.ctes.remove(cte_name),
+    };
+    let (step, _step_scope) = step?;
+
+    let step_types = qcx.relation_type(&step).column_types;
+    if base_typ.column_types.len() != step_types.len() {
+        bail!(
+            "each UNION query must have the same number of columns: {} vs {}",
+            base_typ.column_types.len(),
+            step_types.len(),
+        );
+    }
+    for (base_col_type, step_col_type) in base_typ.column_types.iter().zip(step_types.iter()) {
+        if base_col_type.union(step_col_type).is_err() {
+            bail!(
+                "UNION types {} and {} cannot be matched",
+                qcx.humanize_scalar_type(&base_col_type.scalar_type),
+                qcx.humanize_scalar_type(&step_col_type.scalar_type)
+            );
+        }
+    }
+
+    let mut references = 0;
+    step.visit(&mut |e| match e {
+        RelationExpr::Get {
+            id: Id::Local(local_id),
+            ..
+        } if *local_id == id => references += 1,
+        _ => (),
+    });
+    let val = match references {
+        // The CTE does not actually refer to itself.
+        0 if all => base.union(step),
+        0 => base.union(step).distinct(),
+        1 => transform_expr::unroll_recursive_cte(
+            base,
+            &step,
+            id,
+            // Bounded by `MAX_RECURSIVE_ITERATIONS` in `plan_query`.
+            max_iterations as usize,
+            !all,
+        ),
+        _ => bail!(
+            "recursive reference to query \"{}\" must not appear more than once",
+            cte_name
+        ),
+    };
+    Ok((val, val_desc))
+}
+
 fn plan_subquery(
     qcx: &mut QueryContext,
     q: &Query,
@@ -782,6 +1053,7 @@ fn plan_view_select(
     let Select {
         distinct,
         projection,
+        into,
         from,
         selection,
         group_by,
@@ -789,6 +1061,12 @@ fn plan_view_select(
         options,
     } = s;
 
+    // The target of a top-level `SELECT ... INTO` is removed before the query
+    // is planned, so any that remain are in a disallowed position.
+    if into.is_some() {
+        bail!("SELECT ... INTO is not allowed here");
+    }
+
     // Extract hints about group size if there are any
     let mut options = crate::normalize::options(options);
 
@@ -800,6 +1078,17 @@ fn plan_view_select(
         None => None,
     };
 
+    // Index hints are removed from the outermost `SELECT` of a one-shot query
+    // before it is planned, so any that remain are in a disallowed position.
+    if options.contains_key("index") {
+        bail!("index hints are only allowed in the outermost SELECT of a query");
+    }
+
+    // Likewise for keyset bounds.
+    if options.contains_key("after") {
+        bail!("OPTION (after) is only allowed in the outermost SELECT of a query");
+    }
+
     // Step 1. Handle FROM clause, including joins.
     let (mut relation_expr, from_scope) =
         from.iter().fold(Ok(plan_join_identity(qcx)), |l, twj| {
@@ -1325,6 +1614,11 @@ fn plan_table_factor(
             (expr, scope)
         }
 
+        // A `TAIL` is not a relation, but `SELECT * FROM (TAIL ...)` is
+        // planned as a `TAIL` whose results are collected before they are
+        // returned. See `dml::plan_select`.
+        TableFactor::Tail { .. } => bail!("TAIL is only supported in SELECT * FROM (TAIL ...)"),
+
         TableFactor::NestedJoin { join, alias } => {
             let (identity, identity_scope) = plan_join_identity(&qcx);
             let (expr, scope) = plan_table_with_joins(
@@ -2438,7 +2732,16 @@ fn plan_function<'a>(
     sql_func: &'a Function,
 ) -> Result<ScalarExpr, anyhow::Error> {
     let name = normalize::object_name(sql_func.name.clone())?;
-    let impls = match func::resolve_func(&ecx.qcx.scx, &name)? {
+    let func = match func::resolve_func(&ecx.qcx.scx, &name) {
+        Ok(func) => func,
+        // Built-in functions take precedence over user-defined functions, as
+        // the schemas that contain them precede all others in the search path.
+        Err(e) => match ecx.qcx.scx.resolve_user_func(&name) {
+            Some(func) => return plan_user_function(ecx, func, sql_func),
+            None => return Err(e),
+        },
+    };
+    let impls = match func {
         Func::Aggregate(_) if ecx.allow_aggregates => {
             // should already have been caught by `scope.resolve_expr` in `plan_expr`
             bail!(
@@ -2475,6 +2778,86 @@ fn plan_function<'a>(
     func::select_impl(ecx, FuncSpec::Func(&name), impls, args)
 }
 
+/// Plans a call to a user-defined function.
+///
+/// The call is expanded inline: the function's body is planned in a scope
+/// containing only the function's parameters, and the arguments of the call
+/// are then substituted for the references to the parameters.
+fn plan_user_function(
+    ecx: &ExprContext,
+    func: &dyn CatalogItem,
+    sql_func: &Function,
+) -> Result<ScalarExpr, anyhow::Error> {
+    let name = func.name();
+    if sql_func.over.is_some() {
+        unsupported!(213, "window functions");
+    }
+    if sql_func.filter.is_some() {
+        bail!(
+            "FILTER specified but {}() is not an aggregate function",
+            name
+        );
+    }
+    let args = match &sql_func.args {
+        FunctionArgs::Star => bail!("* argument is invalid with non-aggregate function {}", name),
+        FunctionArgs::Args(args) => args,
+    };
+    let (params, return_type, body) = func.func_details().expect("attempted to call non-function");
+    if args.len() != params.arity() {
+        bail!(
+            "function {} takes {} arguments, but {} were provided",
+            name,
+            params.arity(),
+            args.len()
+        );
+    }
+    let mut planned_args = Vec::with_capacity(args.len());
+    for (arg, typ) in args.iter().zip(params.iter_types()) {
+        let arg = plan_expr(ecx, arg)?.cast_to(
+            &format!("function {}", name),
+            ecx,
+            CastContext::Implicit,
+            &typ.scalar_type,
+        )?;
+        planned_args.push(arg);
+    }
+
+    let mut expr = plan_func_body(ecx.qcx, params, return_type, body)?;
+    expr.visit_mut(&mut |e| {
+        if let ScalarExpr::Column(ColumnRef { level: 0, column }) = e {
+            *e = planned_args[*column].clone();
+        }
+    });
+    ecx.qcx.scx.func_uses.borrow_mut().insert(func.id());
+    Ok(expr)
+}
+
+/// Plans the body of a user-defined function, casting it to the function's
+/// return type.
+///
+/// The body is planned in a scope containing only the function's parameters,
+/// described by `params`, so that the body refers to the `i`th parameter as
+/// column `i` at level zero.
+pub fn plan_func_body(
+    qcx: &QueryContext,
+    params: &RelationDesc,
+    return_type: &ScalarType,
+    body: &Expr,
+) -> Result<ScalarExpr, anyhow::Error> {
+    let scope = Scope::from_source(None, params.iter_names(), Some(Scope::empty(None)));
+    let ecx = &ExprContext {
+        qcx,
+        name: "function body",
+        scope: &scope,
+        relation_type: params.typ(),
+        allow_aggregates: false,
+        allow_subqueries: false,
+    };
+    let mut body = body.clone();
+    transform_ast::transform_expr(qcx.scx, &mut body)?;
+    plan_expr(ecx, &body)?.cast_to(ecx.name, ecx, CastContext::Assignment, return_type)
+}
+
 fn plan_is_null_expr<'a>(
     ecx: &ExprContext,
     inner: &'a Expr,
@@ -2673,6 +3056,56 @@ pub fn scalar_type_from_sql(
     })
 }
 
+/// Returns a `DataType` that [`scalar_type_from_sql`] plans as `ty`.
+///
+/// Built-in and custom types are referred to by their fully-qualified names,
+/// so the result does not depend on the search path.
+pub fn scalar_type_to_sql(
+    scx: &StatementContext,
+    ty: &ScalarType,
+) -> Result<DataType, anyhow::Error> {
+    Ok(match ty {
+        ScalarType::Array(elem_type) => {
+            DataType::Array(Box::new(scalar_type_to_sql(scx, elem_type)?))
+        }
+        ScalarType::List {
+            custom_oid: Some(oid),
+            ..
+        }
+        | ScalarType::Map {
+            custom_oid: Some(oid),
+            ..
+        } => DataType::Other {
+            name: normalize::unresolve(scx.catalog.get_item_by_oid(oid).name().clone()),
+            typ_mod: vec![],
+        },
+        ScalarType::List { element_type, .. } => {
+            DataType::List(Box::new(scalar_type_to_sql(scx, element_type)?))
+        }
+        ScalarType::Map { value_type, .. } => DataType::Map {
+            key_type: Box::new(scalar_type_to_sql(scx, &ScalarType::String)?),
+            value_type: Box::new(scalar_type_to_sql(scx, value_type)?),
+        },
+        ScalarType::Record { .. } | ScalarType::Void => bail!(
+            "type {} cannot be named in SQL",
+            scx.humanize_scalar_type(ty)
+        ),
+        ty => {
+            let oid = pgrepr::Type::from(ty).oid();
+            let typ_mod = match ty {
+                ScalarType::Decimal(precision, scale) => {
+                    vec![u64::from(*precision), u64::from(*scale)]
+                }
+                _ => vec![],
+            };
+            DataType::Other {
+                name: normalize::unresolve(scx.catalog.get_item_by_oid(&oid).name().clone()),
+                typ_mod,
+            }
+        }
+    })
+}
+
 pub fn canonicalize_type_name_internal(name: &ObjectName) -> ObjectName {
     // Rewrite some unqualified aliases to the name we know they should
     // use in the catalog, i.e. canonicalize the catalog name.
@@ -2754,6 +3187,7 @@ pub fn scalar_type_from_pg(ty: &pgrepr::Type) -> Result<ScalarType, anyhow::Erro
         pgrepr::Type::Text => Ok(ScalarType::String),
         pgrepr::Type::Jsonb => Ok(ScalarType::Jsonb),
         pgrepr::Type::Uuid => Ok(ScalarType::Uuid),
+        pgrepr::Type::Void => Ok(ScalarType::Void),
         pgrepr::Type::Array(t) => Ok(ScalarType::Array(Box::new(scalar_type_from_pg(t)?))),
         pgrepr::Type::List(l) => Ok(ScalarType::List {
             element_type: Box::new(scalar_type_from_pg(l)?),
@@ -2864,6 +3298,20 @@ pub struct CteDesc {
     level_offset: usize,
 }
 
+impl CteDesc {
+    /// Reports whether this CTE is the placeholder for a recursive CTE whose
+    /// recursive term is being planned.
+    fn is_recursive_placeholder(&self) -> bool {
+        matches!(
+            self.val,
+            RelationExpr::Get {
+                id: Id::Local(_),
+                ..
+            }
+        )
+    }
+}
+
 /// The state required when planning a `Query`.
 #[derive(Debug, Clone)]
 pub struct QueryContext<'a> {
@@ -2923,6 +3371,15 @@ impl<'a> QueryContext<'a> {
         if name.0.len() == 1 {
             let norm_name = normalize::ident(name.0[0].clone());
             if let Some(cte) = self.ctes.get(&norm_name) {
+                if cte.is_recursive_placeholder() && cte.level_offset > 0 {
+                    return Err(PlanError::Unsupported {
+                        feature: format!(
+                            "recursive reference to query \"{}\" within a subquery",
+                            norm_name
+                        ),
+                        issue_no: None,
+                    });
+                }
                 let mut val = cte.val.clone();
                 val.visit_columns(0, &mut |depth, col| {
                     if col.level > depth {
@@ -11,12 +11,13 @@
 //!
 //! This module houses the entry points for planning a SQL statement.
 
-use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 
 use anyhow::bail;
 
+use expr::GlobalId;
 use ore::collections::CollectionExt;
 use repr::{ColumnType, RelationDesc, ScalarType};
 
@@ -102,6 +103,8 @@ pub fn describe(
         catalog,
         pcx: &PlanContext::default(),
         param_types: Rc::new(RefCell::new(param_types)),
+        func_uses: Rc::new(RefCell::new(BTreeSet::new())),
+        session_uses: Rc::new(Cell::new(false)),
     };
 
     let desc = match stmt {
@@ -114,10 +117,28 @@ pub fn describe(
         Statement::CreateTable(stmt) => ddl::describe_create_table(&scx, stmt)?,
         Statement::CreateView(stmt) => ddl::describe_create_view(&scx, stmt)?,
         Statement::CreateType(stmt) => ddl::describe_create_type(&scx, stmt)?,
+        Statement::CreateFunction(stmt) => ddl::describe_create_function(&scx, stmt)?,
         Statement::DropDatabase(stmt) => ddl::describe_drop_database(&scx, stmt)?,
         Statement::DropObjects(stmt) => ddl::describe_drop_objects(&scx, stmt)?,
         Statement::AlterObjectRename(stmt) => ddl::describe_alter_object_rename(&scx, stmt)?,
+        Statement::AlterViewRenameColumn(stmt) => {
+            ddl::describe_alter_view_rename_column(&scx, stmt)?
+        }
         Statement::AlterIndexOptions(stmt) => ddl::describe_alter_index_options(&scx, stmt)?,
+        Statement::AlterIndexKeys(stmt) => ddl::describe_alter_index_keys(&scx, stmt)?,
+        Statement::AlterAllIndexesOptions(stmt) => {
+            ddl::describe_alter_all_indexes_options(&scx, stmt)?
+        }
+        Statement::AlterMaterializedViewOptions(stmt) => {
+            ddl::describe_alter_materialized_view_options(&scx, stmt)?
+        }
+        Statement::AlterMaterializedViewRematerialize(stmt) => {
+            ddl::describe_alter_materialized_view_rematerialize(&scx, stmt)?
+        }
+        Statement::RefreshMaterializedView(stmt) => {
+            ddl::describe_refresh_materialized_view(&scx, stmt)?
+        }
+        Statement::Comment(stmt) => ddl::describe_comment(&scx, stmt)?,
 
         // `SHOW` statements.
         Statement::ShowColumns(stmt) => show::show_columns(&scx, stmt)?.describe()?,
@@ -137,6 +158,9 @@ pub fn describe(
         Statement::Declare(stmt) => scl::describe_declare(&scx, stmt)?,
         Statement::Fetch(stmt) => scl::describe_fetch(&scx, stmt)?,
         Statement::Close(stmt) => scl::describe_close(&scx, stmt)?,
+        Statement::Listen(stmt) => scl::describe_listen(&scx, stmt)?,
+        Statement::Unlisten(stmt) => scl::describe_unlisten(&scx, stmt)?,
+        Statement::Notify(stmt) => scl::describe_notify(&scx, stmt)?,
 
         // DML statements.
         Statement::Insert(stmt) => dml::describe_insert(&scx, stmt)?,
@@ -154,7 +178,7 @@ pub fn describe(
         Statement::Commit(stmt) => tcl::describe_commit(&scx, stmt)?,
     };
 
-    let desc = desc.with_params(scx.finalize_param_types()?);
+    let desc = desc.with_params(scx.finalize_param_types(param_types_in.len()));
     Ok(desc)
 }
 
@@ -180,6 +204,8 @@ pub fn plan(
         pcx,
         catalog,
         param_types: Rc::new(RefCell::new(param_types)),
+        func_uses: Rc::new(RefCell::new(BTreeSet::new())),
+        session_uses: Rc::new(Cell::new(false)),
     };
 
     match stmt {
@@ -192,10 +218,22 @@ pub fn plan(
         Statement::CreateSink(stmt) => ddl::plan_create_sink(scx, stmt),
         Statement::CreateIndex(stmt) => ddl::plan_create_index(scx, stmt),
         Statement::CreateType(stmt) => ddl::plan_create_type(scx, stmt),
+        Statement::CreateFunction(stmt) => ddl::plan_create_function(scx, stmt),
         Statement::DropDatabase(stmt) => ddl::plan_drop_database(scx, stmt),
         Statement::DropObjects(stmt) => ddl::plan_drop_objects(scx, stmt),
         Statement::AlterIndexOptions(stmt) => ddl::plan_alter_index_options(scx, stmt),
+        Statement::AlterIndexKeys(stmt) => ddl::plan_alter_index_keys(scx, stmt),
+        Statement::AlterAllIndexesOptions(stmt) => ddl::plan_alter_all_indexes_options(scx, stmt),
+        Statement::AlterMaterializedViewOptions(stmt) => {
+            ddl::plan_alter_materialized_view_options(scx, stmt)
+        }
+        Statement::AlterMaterializedViewRematerialize(stmt) => {
+            ddl::plan_alter_materialized_view_rematerialize(scx, stmt)
+        }
+        Statement::RefreshMaterializedView(stmt) => ddl::plan_refresh_materialized_view(scx, stmt),
         Statement::AlterObjectRename(stmt) => ddl::plan_alter_object_rename(scx, stmt),
+        Statement::AlterViewRenameColumn(stmt) => ddl::plan_alter_view_rename_column(scx, stmt),
+        Statement::Comment(stmt) => ddl::plan_comment(scx, stmt),
 
         // DML statements.
         Statement::Insert(stmt) => dml::plan_insert(scx, stmt, params),
@@ -224,6 +262,9 @@ pub fn plan(
         Statement::Declare(stmt) => scl::plan_declare(scx, stmt),
         Statement::Fetch(stmt) => scl::plan_fetch(scx, stmt),
         Statement::Close(stmt) => scl::plan_close(scx, stmt),
+        Statement::Listen(stmt) => scl::plan_listen(scx, stmt),
+        Statement::Unlisten(stmt) => scl::plan_unlisten(scx, stmt),
+        Statement::Notify(stmt) => scl::plan_notify(scx, stmt),
 
         // TCL statements.
         Statement::StartTransaction(stmt) => tcl::plan_start_transaction(scx, stmt),
@@ -247,7 +288,8 @@ impl PartialEq<ObjectType> for CatalogItemType {
             | (CatalogItemType::Sink, ObjectType::Sink)
             | (CatalogItemType::View, ObjectType::View)
             | (CatalogItemType::Index, ObjectType::Index)
-            | (CatalogItemType::Type, ObjectType::Type) => true,
+            | (CatalogItemType::Type, ObjectType::Type)
+            | (CatalogItemType::Func, ObjectType::Function) => true,
             (_, _) => false,
         }
     }
@@ -267,6 +309,12 @@ pub struct StatementContext<'a> {
     /// The types of the parameters in the query. This is filled in as planning
     /// occurs.
     pub param_types: Rc<RefCell<BTreeMap<usize, ScalarType>>>,
+    /// The IDs of the user-defined functions that the statement calls. This is
+    /// filled in as planning occurs.
+    pub func_uses: Rc<RefCell<BTreeSet<GlobalId>>>,
+    /// Whether the statement calls a function whose result depends on the
+    /// session, like `current_user`. This is filled in as planning occurs.
+    pub session_uses: Rc<Cell<bool>>,
 }
 
 impl<'a> StatementContext<'a> {
@@ -276,11 +324,23 @@ impl<'a> StatementContext<'a> {
                 Some(name) => DatabaseSpecifier::Name(name),
                 None => DatabaseSpecifier::Name(self.catalog.default_database().into()),
             },
-            schema: name.schema.unwrap_or_else(|| "public".into()),
+            schema: name.schema.unwrap_or_else(|| self.default_schema()),
             item: name.item,
         }
     }
 
+    /// Returns the name of the schema in which unqualified names are created,
+    /// which is the first schema in the search path that exists. If no schema
+    /// in the search path exists, `public` is returned.
+    fn default_schema(&self) -> String {
+        self.catalog
+            .search_path(false)
+            .into_iter()
+            .find(|schema| self.catalog.resolve_schema(None, schema).is_ok())
+            .unwrap_or("public")
+            .into()
+    }
+
     pub fn allocate_temporary_name(&self, name: PartialName) -> FullName {
         FullName {
             database: DatabaseSpecifier::Ambient,
@@ -295,7 +355,7 @@ impl<'a> StatementContext<'a> {
     }
 
     pub fn resolve_default_schema(&self) -> Result<&dyn CatalogSchema, PlanError> {
-        self.resolve_schema(ObjectName::unqualified("public"))
+        self.resolve_schema(ObjectName::unqualified(&self.default_schema()))
     }
 
     pub fn resolve_database(&self, name: ObjectName) -> Result<&dyn CatalogDatabase, PlanError> {
@@ -324,6 +384,14 @@ impl<'a> StatementContext<'a> {
         Ok(self.catalog.resolve_item(&name)?)
     }
 
+    /// Resolves `name` to a user-defined function, if such a function exists.
+    pub fn resolve_user_func(&self, name: &PartialName) -> Option<&dyn CatalogItem> {
+        match self.catalog.resolve_item(name) {
+            Ok(item) if item.item_type() == CatalogItemType::Func => Some(item),
+            _ => None,
+        }
+    }
+
     pub fn experimental_mode(&self) -> bool {
         self.catalog.config().experimental_mode
     }
@@ -339,16 +407,24 @@ impl<'a> StatementContext<'a> {
         Ok(())
     }
 
-    pub fn finalize_param_types(self) -> Result<Vec<ScalarType>, anyhow::Error> {
+    /// Returns the types of the statement's parameters, in order.
+    ///
+    /// Types that were neither provided by the client nor inferred from the
+    /// context in which the parameter appears are reported as `text`. The
+    /// returned list includes at least `min_params` parameters, so that
+    /// parameters the client declared but the statement does not reference
+    /// are reported too.
+    pub fn finalize_param_types(self, min_params: usize) -> Vec<ScalarType> {
         let param_types = Rc::try_unwrap(self.param_types).unwrap().into_inner();
-        let mut out = vec![];
-        for (i, (n, typ)) in param_types.into_iter().enumerate() {
-            if n != i + 1 {
-                bail!("unable to infer type for parameter ${}", i + 1);
-            }
-            out.push(typ);
-        }
-        Ok(out)
+        let num_params = param_types
+            .keys()
+            .next_back()
+            .copied()
+            .unwrap_or(0)
+            .max(min_params);
+        (1..=num_params)
+            .map(|n| param_types.get(&n).cloned().unwrap_or(ScalarType::String))
+            .collect()
     }
 
     pub fn humanize_scalar_type(&self, typ: &ScalarType) -> String {
@@ -23,8 +23,9 @@ use aws_arn::{Resource, ARN};
 use dataflow_types::{
     AvroEncoding, AvroOcfEncoding, AvroOcfSinkConnectorBuilder, Consistency, CsvEncoding,
     DataEncoding, Envelope, ExternalSourceConnector, FileSourceConnector,
-    KafkaSinkConnectorBuilder, KafkaSourceConnector, KinesisSourceConnector, ProtobufEncoding,
-    RegexEncoding, S3SourceConnector, SinkConnectorBuilder, SourceConnector,
+    KafkaSinkConnectorBuilder, KafkaSinkFormatBuilder, KafkaSourceConnector,
+    KinesisSourceConnector, ProtobufEncoding, RegexEncoding, S3SourceConnector,
+    SinkConnectorBuilder, SourceConnector,
 };
 use expr::GlobalId;
 use interchange::avro::{self, DebeziumDeduplicationStrategy, Encoder};
@@ -38,23 +39,28 @@ use rusoto_core::Region;
 
 use crate::ast::display::AstDisplay;
 use crate::ast::{
-    AlterIndexOptionsList, AlterIndexOptionsStatement, AlterObjectRenameStatement, AvroSchema,
-    ColumnOption, Connector, CreateDatabaseStatement, CreateIndexStatement, CreateSchemaStatement,
-    CreateSinkStatement, CreateSourceStatement, CreateTableStatement, CreateTypeAs,
-    CreateTypeStatement, CreateViewStatement, DataType, DropDatabaseStatement,
-    DropObjectsStatement, Expr, Format, Ident, IfExistsBehavior, ObjectName, ObjectType, SqlOption,
-    Statement, Value,
+    AlterAllIndexesOptionsStatement, AlterIndexKeysStatement, AlterIndexOptionsList,
+    AlterIndexOptionsStatement, AlterMaterializedViewOptionsStatement,
+    AlterMaterializedViewRematerializeStatement, AlterObjectRenameStatement,
+    AlterViewRenameColumnStatement, AvroSchema, ColumnOption, CommentObject, CommentStatement,
+    Connector, CreateDatabaseStatement, CreateFunctionStatement, CreateIndexStatement,
+    CreateSchemaStatement, CreateSinkStatement, CreateSourceStatement, CreateTableStatement,
+    CreateTypeAs, CreateTypeStatement, CreateViewStatement, DataType, DropDatabaseStatement,
+    DropObjectsStatement, Expr, Format, Ident, IfExistsBehavior, ObjectName, ObjectType,
+    RefreshMaterializedViewStatement, SqlOption, Statement, Value,
 };
 use crate::catalog::{CatalogItem, CatalogItemType};
 use crate::kafka_util;
 use crate::names::{DatabaseSpecifier, FullName, SchemaName};
 use crate::normalize;
-use crate::plan::query::QueryLifetime;
+use crate::plan::expr::ScalarExpr;
+use crate::plan::query::{QueryContext, QueryLifetime};
 use crate::plan::statement::with_options::aws_connect_info;
 use crate::plan::statement::{StatementContext, StatementDesc};
 use crate::plan::{
-    self, plan_utils, query, AlterIndexLogicalCompactionWindow, Index, LogicalCompactionWindow,
-    Params, Plan, Sink, Source, Table, Type, TypeInner, View,
+    self, plan_utils, query, AlterIndexKeys, AlterIndexLogicalCompactionWindow,
+    AlterMaterializedViewLogicalCompactionWindow, Function, Index, LogicalCompactionWindow, Params,
+    Plan, Sink, Source, Table, Type, TypeInner, View,
 };
 use crate::pure::Schema;
 
@@ -128,6 +134,7 @@ pub fn plan_create_table(
         constraints,
         with_options,
         if_not_exists,
+        temporary,
     } = &stmt;
 
     if !with_options.is_empty() {
@@ -176,7 +183,11 @@ pub fn plan_create_table(
 
     let typ = RelationType::new(column_types);
 
-    let name = scx.allocate_name(normalize::object_name(name.clone())?);
+    let name = if *temporary {
+        scx.allocate_temporary_name(normalize::object_name(name.clone())?)
+    } else {
+        scx.allocate_name(normalize::object_name(name.clone())?)
+    };
     let desc = RelationDesc::new(typ, names.into_iter().map(Some));
 
     let create_sql = normalize::create_statement(&scx, Statement::CreateTable(stmt.clone()))?;
@@ -184,6 +195,8 @@ pub fn plan_create_table(
         create_sql,
         desc,
         defaults,
+        temporary: *temporary,
+        func_uses: scx.func_uses.borrow().iter().copied().collect(),
     };
     Ok(Plan::CreateTable {
         name,
@@ -650,7 +663,9 @@ pub fn plan_create_source(
     }
 
     let if_not_exists = *if_not_exists;
-    let materialized = *materialized;
+    // An explicit `MATERIALIZED` always wins; otherwise defer to the
+    // server-wide default.
+    let materialized = *materialized || scx.catalog.config().default_source_materialization;
     let name = scx.allocate_name(normalize::object_name(name.clone())?);
     let create_sql = normalize::create_statement(&scx, Statement::CreateSource(stmt))?;
 
@@ -738,6 +753,7 @@ pub fn plan_create_view(
             expr: relation_expr,
             column_names: desc.iter_names().map(|n| n.cloned()).collect(),
             temporary,
+            func_uses: scx.func_uses.borrow().iter().copied().collect(),
         },
         replace,
         materialize,
@@ -753,21 +769,9 @@ fn kafka_sink_builder(
     desc: RelationDesc,
     topic_suffix: String,
     key_indices: Option<Vec<usize>>,
+    debezium: bool,
+    topic_by: Option<::expr::ScalarExpr>,
 ) -> Result<SinkConnectorBuilder, anyhow::Error> {
-    let (schema_registry_url, ccsr_with_options) = match format {
-        Some(Format::Avro(AvroSchema::CsrUrl {
-            url,
-            seed,
-            with_options,
-        })) => {
-            if seed.is_some() {
-                bail!("SEED option does not make sense with sinks");
-            }
-            (url.parse::<Url>()?, normalize::options(&with_options))
-        }
-        _ => unsupported!("non-confluent schema registry avro sinks"),
-    };
-
     let broker_addrs = broker.parse()?;
 
     let include_consistency = match with_options.remove("consistency") {
@@ -776,11 +780,10 @@ fn kafka_sink_builder(
         Some(_) => bail!("consistency must be a boolean"),
     };
 
-    let encoder = Encoder::new(desc, include_consistency, key_indices.clone());
-    let value_schema = encoder.writer_schema().canonical_form();
-    let key_schema = encoder
-        .key_writer_schema()
-        .map(|key_schema| key_schema.canonical_form());
+    // A consistency topic describes the transactions in a single data topic.
+    if include_consistency && topic_by.is_some() {
+        bail!("consistency topics are not supported for sinks with topic_by");
+    }
 
     // Use the user supplied value for replication factor, or default to 1
     let replication_factor = match with_options.remove("replication_factor") {
@@ -793,36 +796,84 @@ fn kafka_sink_builder(
         bail!("replication factor for sink topics has to be greater than zero");
     }
 
-    let consistency_value_schema = if include_consistency {
-        Some(avro::get_debezium_transaction_schema().canonical_form())
-    } else {
-        None
+    // Use the user supplied value for partition count, or default to 1
+    let partition_count = match with_options.remove("partition_count") {
+        None => 1,
+        Some(Value::Number(n)) => n.parse::<u32>()?,
+        Some(_) => bail!("partition count for sink topics has to be a positive integer"),
     };
 
+    if partition_count == 0 {
+        bail!("partition count for sink topics has to be greater than zero");
+    }
+
     let config_options = kafka_util::extract_config(with_options)?;
-    let ccsr_config = kafka_util::generate_ccsr_client_config(
-        schema_registry_url.clone(),
-        &config_options,
-        ccsr_with_options,
-    )?;
+
+    let format = match format {
+        Some(Format::Avro(AvroSchema::CsrUrl {
+            url,
+            seed,
+            with_options,
+        })) => {
+            if seed.is_some() {
+                bail!("SEED option does not make sense with sinks");
+            }
+            let schema_registry_url = url.parse::<Url>()?;
+            let ccsr_config = kafka_util::generate_ccsr_client_config(
+                schema_registry_url.clone(),
+                &config_options,
+                normalize::options(&with_options),
+            )?;
+
+            let encoder = Encoder::new(desc, include_consistency, key_indices.clone(), debezium);
+            let value_schema = encoder.writer_schema().canonical_form();
+            let key_schema = encoder
+                .key_writer_schema()
+                .map(|key_schema| key_schema.canonical_form());
+
+            let consistency_value_schema = if include_consistency {
+                Some(avro::get_debezium_transaction_schema().canonical_form())
+            } else {
+                None
+            };
+
+            KafkaSinkFormatBuilder::Avro {
+                schema_registry_url,
+                key_schema,
+                value_schema,
+                consistency_value_schema,
+                ccsr_config,
+            }
+        }
+        Some(Format::Json) => {
+            // Consistency topics are Avro-encoded and published to the schema
+            // registry, which JSON sinks do not have.
+            if include_consistency {
+                bail!("consistency topics are not supported for sinks with FORMAT JSON");
+            }
+            KafkaSinkFormatBuilder::Json
+        }
+        _ => unsupported!("non-confluent schema registry avro sinks"),
+    };
+
     Ok(SinkConnectorBuilder::Kafka(KafkaSinkConnectorBuilder {
         broker_addrs,
-        schema_registry_url,
-        value_schema,
+        format,
         topic_prefix,
         topic_suffix,
         replication_factor,
+        partition_count,
         fuel: 10000,
-        consistency_value_schema,
         config_options,
-        ccsr_config,
         key_indices,
-        key_schema,
+        debezium,
+        topic_by,
     }))
 }
 
 fn avro_ocf_sink_builder(
     format: Option<Format>,
+    with_options: &mut BTreeMap<String, Value>,
     path: String,
     file_name_suffix: String,
 ) -> Result<SinkConnectorBuilder, anyhow::Error> {
@@ -836,9 +887,29 @@ fn avro_ocf_sink_builder(
         bail!("avro ocf sink cannot write to a directory");
     }
 
+    let rotate_interval = match with_options.remove("rotate_interval") {
+        None => None,
+        Some(Value::String(s)) => match parse_duration::parse(&s) {
+            Ok(interval) if interval > Duration::from_secs(0) => Some(interval),
+            _ => bail!("rotate_interval must be a positive duration, like '1h'"),
+        },
+        Some(_) => bail!("rotate_interval must be a string"),
+    };
+
+    let max_file_size = match with_options.remove("max_file_size") {
+        None => None,
+        Some(Value::Number(n)) => match n.parse::<u64>() {
+            Ok(n) if n > 0 => Some(n),
+            _ => bail!("max_file_size must be a positive integer"),
+        },
+        Some(_) => bail!("max_file_size must be a positive integer"),
+    };
+
     Ok(SinkConnectorBuilder::AvroOcf(AvroOcfSinkConnectorBuilder {
         path,
         file_name_suffix,
+        rotate_interval,
+        max_file_size,
     }))
 }
 
@@ -860,6 +931,7 @@ pub fn plan_create_sink(
         connector,
         with_options,
         format,
+        envelope,
         with_snapshot,
         as_of,
         if_not_exists,
@@ -878,6 +950,32 @@ pub fn plan_create_sink(
 
     let mut with_options = normalize::options(&with_options);
 
+    // The `tombstone` option controls whether deletions are represented as
+    // Kafka tombstones, which only upsert sinks emit.
+    match with_options.remove("tombstone") {
+        None => (),
+        Some(Value::Boolean(_)) => match envelope {
+            Some(sql_parser::ast::Envelope::Upsert(_)) => (),
+            _ => bail!("tombstone option is only valid for sinks with ENVELOPE UPSERT"),
+        },
+        Some(_) => bail!("tombstone must be a boolean"),
+    }
+
+    // Sinks always emit Debezium-style records with `before` and `after`
+    // fields, as that is the only envelope that can represent both insertions
+    // and retractions. On keyed Kafka sinks, an explicit `ENVELOPE DEBEZIUM`
+    // additionally pairs up the retractions and insertions of each key into
+    // updates, and labels each record with a Debezium `op` field.
+    let debezium = match envelope {
+        None => false,
+        Some(sql_parser::ast::Envelope::Debezium) => true,
+        Some(sql_parser::ast::Envelope::None) => {
+            bail!("ENVELOPE NONE is not supported for sinks, as it cannot represent retractions")
+        }
+        Some(sql_parser::ast::Envelope::Upsert(_)) => unsupported!("ENVELOPE UPSERT sinks"),
+        Some(sql_parser::ast::Envelope::CdcV2) => unsupported!("ENVELOPE MATERIALIZE sinks"),
+    };
+
     let as_of = as_of.map(|e| query::eval_as_of(scx, e)).transpose()?;
     let connector_builder = match connector {
         Connector::File { .. } => unsupported!("file sinks"),
@@ -911,6 +1009,19 @@ pub fn plan_create_sink(
             } else {
                 None
             };
+            // Update and delete operations can only be inferred by matching
+            // up retractions and insertions by key, so unkeyed sinks emit
+            // the default records.
+            let debezium = debezium && key_indices.is_some();
+            let topic_by = match with_options.remove("topic_by") {
+                None => None,
+                Some(Value::String(s)) => {
+                    let expr = sql_parser::parser::parse_expr(&s)
+                        .map_err(|e| anyhow!("invalid topic_by expression: {}", e))?;
+                    Some(query::plan_sink_topic_expr(scx, &desc, expr)?)
+                }
+                Some(_) => bail!("topic_by must be a string containing an expression"),
+            };
             kafka_sink_builder(
                 format,
                 &mut with_options,
@@ -919,9 +1030,13 @@ pub fn plan_create_sink(
                 desc.clone(),
                 suffix,
                 key_indices,
+                debezium,
+                topic_by,
             )?
         }
-        Connector::AvroOcf { path } => avro_ocf_sink_builder(format, path, suffix)?,
+        Connector::AvroOcf { path } => {
+            avro_ocf_sink_builder(format, &mut with_options, path, suffix)?
+        }
         Connector::Kinesis { .. } => unsupported!("Kinesis sinks"),
         Connector::S3 { .. } => unsupported!("S3 sinks"),
     };
@@ -1057,6 +1172,7 @@ pub fn plan_create_index(
             create_sql,
             on: on.id(),
             keys,
+            func_uses: scx.func_uses.borrow().iter().copied().collect(),
         },
         if_not_exists,
     })
@@ -1175,6 +1291,93 @@ pub fn plan_create_type(
     })
 }
 
+pub fn describe_create_function(
+    _: &StatementContext,
+    _: CreateFunctionStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_create_function(
+    scx: &StatementContext,
+    stmt: CreateFunctionStatement,
+) -> Result<Plan, anyhow::Error> {
+    let create_sql = normalize::create_statement(scx, Statement::CreateFunction(stmt.clone()))?;
+    let CreateFunctionStatement {
+        name,
+        params,
+        return_type,
+        body: _,
+    } = stmt;
+    // Store the normalized body, whose names are resolved as they were when
+    // the function was created, rather than in the caller's search path.
+    let body = match crate::parse::parse(&create_sql)?.into_element() {
+        Statement::CreateFunction(CreateFunctionStatement { body, .. }) => body,
+        _ => unreachable!("normalized CREATE FUNCTION is not a CREATE FUNCTION"),
+    };
+
+    let name = scx.allocate_name(normalize::object_name(name)?);
+    if scx.catalog.item_exists(&name) {
+        bail!("catalog item \"{}\" already exists", name.to_string());
+    }
+
+    let names: Vec<_> = params
+        .iter()
+        .map(|p| normalize::column_name(p.name.clone()))
+        .collect();
+    if let Some(dup) = names.iter().duplicates().next() {
+        bail!(
+            "cannot CREATE FUNCTION: parameter \"{}\" specified more than once",
+            dup
+        );
+    }
+    let mut param_types = Vec::with_capacity(params.len());
+    for p in &params {
+        param_types.push(plan::scalar_type_from_sql(scx, &p.data_type)?.nullable(true));
+    }
+    let params = RelationDesc::new(RelationType::new(param_types), names.into_iter().map(Some));
+    let return_type = plan::scalar_type_from_sql(scx, &return_type)?;
+
+    // Ensure the body can be planned and yields the correct type. Only
+    // immutable functions are supported, as the body is inlined wherever the
+    // function is called. A body is mutable if it calls a function whose
+    // result depends on the time or the session, or if it reads a relation,
+    // as built-in functions like `pg_table_is_visible` do.
+    let qcx = QueryContext::root(scx, QueryLifetime::Static);
+    let expr = query::plan_func_body(&qcx, &params, &return_type, &body)?;
+    let mut mutable = scx.session_uses.get();
+    let mut reads_relation = false;
+    expr.visit(&mut |e| match e {
+        ScalarExpr::CallNullary(_) | ScalarExpr::Parameter(_) => mutable = true,
+        ScalarExpr::Exists(_) | ScalarExpr::Select(_) => reads_relation = true,
+        _ => (),
+    });
+    if mutable {
+        bail!(
+            "function {} must be immutable, but its body calls a function whose \
+            result can change between calls",
+            name
+        );
+    }
+    if reads_relation {
+        bail!(
+            "function {} must be immutable, but its body reads from a relation",
+            name
+        );
+    }
+
+    Ok(Plan::CreateFunction {
+        name,
+        func: Function {
+            create_sql,
+            params,
+            return_type,
+            body,
+            func_uses: scx.func_uses.borrow().iter().copied().collect(),
+        },
+    })
+}
+
 fn extract_timestamp_frequency_option(
     with_options: &mut BTreeMap<String, Value>,
 ) -> Result<Duration, anyhow::Error> {
@@ -1237,7 +1440,8 @@ pub fn plan_drop_objects(
         | ObjectType::View
         | ObjectType::Index
         | ObjectType::Sink
-        | ObjectType::Type => plan_drop_items(scx, object_type, if_exists, names, cascade),
+        | ObjectType::Type
+        | ObjectType::Function => plan_drop_items(scx, object_type, if_exists, names, cascade),
         ObjectType::Object => unreachable!("cannot drop generic OBJECT, must provide object type"),
     }
 }
@@ -1311,6 +1515,7 @@ pub fn plan_drop_items(
     Ok(Plan::DropItems {
         items: ids,
         ty: object_type,
+        cascade,
     })
 }
 
@@ -1337,7 +1542,17 @@ pub fn plan_drop_item(
                 | CatalogItemType::Source
                 | CatalogItemType::View
                 | CatalogItemType::Sink
-                | CatalogItemType::Type => {
+                | CatalogItemType::Type
+                | CatalogItemType::Func => {
+                    bail!(
+                        "cannot drop {}: still depended upon by catalog item '{}'",
+                        catalog_entry.name(),
+                        dep.name()
+                    );
+                }
+                // Indexes are dropped along with the relations they index,
+                // but not with the functions their keys call.
+                CatalogItemType::Index if catalog_entry.item_type() == CatalogItemType::Func => {
                     bail!(
                         "cannot drop {}: still depended upon by catalog item '{}'",
                         catalog_entry.name(),
@@ -1372,40 +1587,7 @@ pub fn plan_alter_index_options(
                 bail!("{} is a {} not a index", entry.name(), entry.item_type())
             }
 
-            let logical_compaction_window = match options {
-                AlterIndexOptionsList::Reset(o) => {
-                    let mut options: HashSet<_> =
-                        o.iter().map(|x| normalize::ident(x.clone())).collect();
-                    // Follow Postgres and don't complain if unknown parameters
-                    // are passed into ALTER INDEX ... RESET
-                    if options.remove("logical_compaction_window") {
-                        Some(LogicalCompactionWindow::Default)
-                    } else {
-                        None
-                    }
-                }
-                AlterIndexOptionsList::Set(o) => {
-                    let mut options = normalize::options(&o);
-
-                    let logical_compaction_window = match options
-                        .remove("logical_compaction_window")
-                    {
-                        Some(Value::String(window)) => match window.as_str() {
-                            "off" => Some(LogicalCompactionWindow::Off),
-                            s => Some(LogicalCompactionWindow::Custom(parse_duration::parse(s)?)),
-                        },
-                        Some(_) => bail!("\"logical_compaction_window\" must be a string"),
-                        None => None,
-                    };
-
-                    if !options.is_empty() {
-                        bail!("unrecognized parameter: \"{}\". Only \"logical_compaction_window\" is currently supported.",
-                              options.keys().next().expect("known to exist"))
-                    }
-
-                    logical_compaction_window
-                }
-            };
+            let logical_compaction_window = plan_index_options(options)?;
 
             if let Some(logical_compaction_window) = logical_compaction_window {
                 Some(AlterIndexLogicalCompactionWindow {
@@ -1426,6 +1608,216 @@ pub fn plan_alter_index_options(
     Ok(Plan::AlterIndexLogicalCompactionWindow(alter_index))
 }
 
+pub fn describe_alter_index_keys(
+    _: &StatementContext,
+    _: AlterIndexKeysStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_alter_index_keys(
+    scx: &StatementContext,
+    AlterIndexKeysStatement {
+        index_name,
+        if_exists,
+        key_parts,
+    }: AlterIndexKeysStatement,
+) -> Result<Plan, anyhow::Error> {
+    let entry = match scx.resolve_item(index_name) {
+        Ok(entry) => entry,
+        Err(_) if if_exists => return Ok(Plan::AlterIndexKeys(None)),
+        Err(e) => return Err(e.into()),
+    };
+    let on = match entry.index_details() {
+        Some((_keys, on)) => scx.catalog.get_item_by_id(&on),
+        None => bail!("{} is a {} not a index", entry.name(), entry.item_type()),
+    };
+    let new_keys = query::plan_index_exprs(scx, on.desc()?, key_parts.clone())?;
+
+    // Rewrite the index's `CREATE INDEX` statement with the new keys, so that
+    // the index keeps its name when it is reloaded.
+    let mut stmt = crate::parse::parse(entry.create_sql())?.into_element();
+    match &mut stmt {
+        Statement::CreateIndex(CreateIndexStatement { key_parts: kp, .. }) => *kp = Some(key_parts),
+        _ => unreachable!("index has a CREATE INDEX statement"),
+    }
+    let create_sql = normalize::create_statement(scx, stmt)?;
+
+    Ok(Plan::AlterIndexKeys(Some(AlterIndexKeys {
+        id: entry.id(),
+        new_keys,
+        create_sql,
+        func_uses: scx.func_uses.borrow().iter().copied().collect(),
+    })))
+}
+
+pub fn describe_alter_all_indexes_options(
+    _: &StatementContext,
+    _: AlterAllIndexesOptionsStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_alter_all_indexes_options(
+    _: &StatementContext,
+    AlterAllIndexesOptionsStatement { options }: AlterAllIndexesOptionsStatement,
+) -> Result<Plan, anyhow::Error> {
+    Ok(Plan::AlterAllIndexesCompaction {
+        window: plan_index_options(options)?,
+    })
+}
+
+pub fn describe_alter_materialized_view_options(
+    _: &StatementContext,
+    _: AlterMaterializedViewOptionsStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_alter_materialized_view_options(
+    scx: &StatementContext,
+    AlterMaterializedViewOptionsStatement {
+        view_name,
+        if_exists,
+        options,
+    }: AlterMaterializedViewOptionsStatement,
+) -> Result<Plan, anyhow::Error> {
+    let alter_view = match scx.resolve_item(view_name) {
+        Ok(entry) => {
+            if entry.item_type() != CatalogItemType::View {
+                bail!("{} is a {} not a view", entry.name(), entry.item_type())
+            }
+            plan_compaction_window_options(options, "retain_history")?.map(
+                |logical_compaction_window| AlterMaterializedViewLogicalCompactionWindow {
+                    view: entry.id(),
+                    logical_compaction_window,
+                },
+            )
+        }
+        Err(_) if if_exists => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Plan::AlterMaterializedViewLogicalCompactionWindow(
+        alter_view,
+    ))
+}
+
+pub fn describe_alter_materialized_view_rematerialize(
+    _: &StatementContext,
+    _: AlterMaterializedViewRematerializeStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_alter_materialized_view_rematerialize(
+    scx: &StatementContext,
+    AlterMaterializedViewRematerializeStatement {
+        view_name,
+        if_exists,
+    }: AlterMaterializedViewRematerializeStatement,
+) -> Result<Plan, anyhow::Error> {
+    let id = match scx.resolve_item(view_name) {
+        Ok(entry) => {
+            if entry.item_type() != CatalogItemType::View {
+                bail!("{} is a {} not a view", entry.name(), entry.item_type())
+            }
+            Some(entry.id())
+        }
+        Err(_) if if_exists => None,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(Plan::RematerializeView { id })
+}
+
+pub fn describe_refresh_materialized_view(
+    _: &StatementContext,
+    _: RefreshMaterializedViewStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_refresh_materialized_view(
+    scx: &StatementContext,
+    RefreshMaterializedViewStatement { view_name }: RefreshMaterializedViewStatement,
+) -> Result<Plan, anyhow::Error> {
+    let entry = scx.resolve_item(view_name)?;
+    if entry.item_type() != CatalogItemType::View {
+        bail!("{} is a {} not a view", entry.name(), entry.item_type())
+    }
+    let materialized =
+        entry
+            .used_by()
+            .iter()
+            .any(|id| match scx.catalog.get_item_by_id(id).index_details() {
+                Some((_keys, on)) => on == entry.id(),
+                None => false,
+            });
+    if !materialized {
+        bail!("{} is not materialized", entry.name());
+    }
+    // Materialized views are maintained incrementally, so they never need to
+    // be refreshed. The statement is accepted for compatibility with
+    // PostgreSQL tools that issue it.
+    Ok(Plan::RefreshMaterializedView {
+        name: entry.name().to_string(),
+    })
+}
+
+/// Extracts the `logical_compaction_window` parameter from the options of an
+/// `ALTER INDEX` statement.
+///
+/// Returns `None` if the statement does not mention the parameter.
+fn plan_index_options(
+    options: AlterIndexOptionsList,
+) -> Result<Option<LogicalCompactionWindow>, anyhow::Error> {
+    plan_compaction_window_options(options, "logical_compaction_window")
+}
+
+/// Extracts the compaction window parameter named `param` from the options of
+/// an `ALTER` statement.
+///
+/// Returns `None` if the statement does not mention the parameter.
+fn plan_compaction_window_options(
+    options: AlterIndexOptionsList,
+    param: &str,
+) -> Result<Option<LogicalCompactionWindow>, anyhow::Error> {
+    match options {
+        AlterIndexOptionsList::Reset(o) => {
+            let mut options: HashSet<_> = o.iter().map(|x| normalize::ident(x.clone())).collect();
+            // Follow Postgres and don't complain if unknown parameters
+            // are passed into ALTER INDEX ... RESET
+            if options.remove(param) {
+                Ok(Some(LogicalCompactionWindow::Default))
+            } else {
+                Ok(None)
+            }
+        }
+        AlterIndexOptionsList::Set(o) => {
+            let mut options = normalize::options(&o);
+
+            let logical_compaction_window = match options.remove(param) {
+                Some(Value::String(window)) => match window.as_str() {
+                    "off" => Some(LogicalCompactionWindow::Off),
+                    s => Some(LogicalCompactionWindow::Custom(parse_duration::parse(s)?)),
+                },
+                Some(_) => bail!("\"{}\" must be a string", param),
+                None => None,
+            };
+
+            if !options.is_empty() {
+                bail!(
+                    "unrecognized parameter: \"{}\". Only \"{}\" is currently supported.",
+                    options.keys().next().expect("known to exist"),
+                    param
+                )
+            }
+
+            Ok(logical_compaction_window)
+        }
+    }
+}
+
 pub fn describe_alter_object_rename(
     _: &StatementContext,
     _: AlterObjectRenameStatement,
@@ -1469,3 +1861,92 @@ pub fn plan_alter_object_rename(
         object_type,
     })
 }
+
+pub fn describe_alter_view_rename_column(
+    _: &StatementContext,
+    _: AlterViewRenameColumnStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_alter_view_rename_column(
+    scx: &StatementContext,
+    AlterViewRenameColumnStatement {
+        if_exists,
+        name,
+        column_name,
+        to_column_name,
+    }: AlterViewRenameColumnStatement,
+) -> Result<Plan, anyhow::Error> {
+    let old = normalize::column_name(column_name);
+    let new = normalize::column_name(to_column_name);
+    let id = match scx.resolve_item(name.clone()) {
+        Ok(entry) => {
+            if entry.item_type() != CatalogItemType::View {
+                bail!("{} is a {} not a view", name, entry.item_type())
+            }
+            let desc = entry.desc()?;
+            if desc.get_by_name(&old).is_none() {
+                bail!("column \"{}\" does not exist", old);
+            }
+            if desc.get_by_name(&new).is_some() {
+                bail!("column \"{}\" of relation \"{}\" already exists", new, name);
+            }
+            Some(entry.id())
+        }
+        Err(_) if if_exists => None,
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(Plan::AlterViewRenameColumn {
+        id,
+        old: old.as_str().into(),
+        new: new.as_str().into(),
+    })
+}
+
+pub fn describe_comment(
+    _: &StatementContext,
+    _: CommentStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_comment(
+    scx: &StatementContext,
+    CommentStatement { object, comment }: CommentStatement,
+) -> Result<Plan, anyhow::Error> {
+    let (object_id, column_pos) = match object {
+        CommentObject::Item { object_type, name } => {
+            let entry = scx.resolve_item(name.clone())?;
+            if entry.item_type() != object_type {
+                bail!("{} is a {} not a {}", name, entry.item_type(), object_type)
+            }
+            (entry.id(), None)
+        }
+        CommentObject::Column {
+            relation_name,
+            column_name,
+        } => {
+            let entry = scx.resolve_item(relation_name.clone())?;
+            match entry.item_type() {
+                CatalogItemType::Table | CatalogItemType::View => (),
+                ty => bail!("{} is a {} not a table or view", relation_name, ty),
+            }
+            let column_name = normalize::column_name(column_name);
+            match entry.desc()?.get_by_name(&column_name) {
+                Some((i, _typ)) => (entry.id(), Some(i)),
+                None => bail!(
+                    "column \"{}\" of relation \"{}\" does not exist",
+                    column_name,
+                    relation_name
+                ),
+            }
+        }
+    };
+    Ok(Plan::Comment {
+        object_id,
+        column_pos,
+        comment,
+    })
+}
@@ -13,23 +13,32 @@
 //! `INSERT`, `SELECT`, `TAIL`, and `COPY`.
 
 use std::convert::TryFrom;
+use std::path::PathBuf;
 
 use anyhow::bail;
+use itertools::Itertools;
 
-use expr::RowSetFinishing;
+use dataflow_types::{TailBackpressure, TailLimit};
+use expr::{GlobalId, RowSetFinishing};
 use ore::collections::CollectionExt;
-use repr::{RelationDesc, ScalarType};
+use repr::{ColumnName, RelationDesc, ScalarType};
 
 use crate::ast::{
-    CopyDirection, CopyRelation, CopyStatement, CopyTarget, CreateViewStatement, DeleteStatement,
-    ExplainStage, ExplainStatement, Explainee, InsertStatement, Query, SelectStatement, Statement,
-    TailStatement, UpdateStatement,
+    ColumnDef, CopyDirection, CopyRelation, CopyStatement, CopyTarget, CreateTableStatement,
+    CreateViewStatement, DeleteStatement, ExplainOptions, ExplainStage, ExplainStatement,
+    Explainee, Expr, Ident, InsertStatement, ObjectName, Query, Select, SelectInto, SelectItem,
+    SelectStatement, SetExpr, SqlOption, Statement, TableFactor, TableWithJoins, TailStatement,
+    UpdateStatement, Value,
 };
 use crate::catalog::CatalogItemType;
+use crate::normalize;
 use crate::plan::query;
 use crate::plan::query::QueryLifetime;
-use crate::plan::statement::{StatementContext, StatementDesc};
-use crate::plan::{CopyFormat, Params, PeekWhen, Plan};
+use crate::plan::statement::{ddl, StatementContext, StatementDesc};
+use crate::plan::{
+    ArrangementsExplainee, CopyCsvFormatParams, CopyFormat, CopyNonfiniteFloats, CopyTo,
+    CopyToTarget, Params, PeekWhen, Plan,
+};
 
 // TODO(benesch): currently, describing a `SELECT` or `INSERT` query
 // plans the whole query to determine its shape and parameter types,
@@ -43,11 +52,16 @@ pub fn describe_insert(
         table_name,
         columns,
         source,
-        ..
+        returning,
     }: InsertStatement,
 ) -> Result<StatementDesc, anyhow::Error> {
-    query::plan_insert_query(scx, table_name, columns, source)?;
-    Ok(StatementDesc::new(None))
+    query::plan_insert_query(scx, table_name.clone(), columns, source)?;
+    if returning.is_empty() {
+        Ok(StatementDesc::new(None))
+    } else {
+        let (_exprs, desc) = query::plan_returning(scx, table_name, returning)?;
+        Ok(StatementDesc::new(Some(desc)))
+    }
 }
 
 pub fn plan_insert(
@@ -56,14 +70,28 @@ pub fn plan_insert(
         table_name,
         columns,
         source,
+        returning,
     }: InsertStatement,
     params: &Params,
 ) -> Result<Plan, anyhow::Error> {
-    let (id, mut expr) = query::plan_insert_query(scx, table_name, columns, source)?;
+    let (id, mut expr) = query::plan_insert_query(scx, table_name.clone(), columns, source)?;
     expr.bind_parameters(&params)?;
     let expr = expr.decorrelate();
 
-    Ok(Plan::Insert { id, values: expr })
+    let (returning, _desc) = query::plan_returning(scx, table_name, returning)?;
+    let returning = returning
+        .into_iter()
+        .map(|mut expr| {
+            expr.bind_parameters(&params)?;
+            expr.lower_uncorrelated()
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Plan::Insert {
+        id,
+        values: expr,
+        returning,
+    })
 }
 
 pub fn describe_update(
@@ -98,20 +126,78 @@ pub fn plan_delete(
 
 pub fn describe_select(
     scx: &StatementContext,
-    SelectStatement { query, .. }: SelectStatement,
+    SelectStatement { mut query, .. }: SelectStatement,
 ) -> Result<StatementDesc, anyhow::Error> {
+    if let Some(tail) = select_over_tail(&query) {
+        return describe_tail(scx, tail.clone());
+    }
+    let into = take_select_into(&mut query);
+    if into.is_none() {
+        take_index_hint(scx, &mut query)?;
+        take_keyset_bound(&mut query)?;
+    }
     let (_relation_expr, desc, _finishing) =
         query::plan_root_query(scx, query, QueryLifetime::OneShot)?;
-    Ok(StatementDesc::new(Some(desc)))
+    // `SELECT ... INTO` stores its results rather than returning them.
+    Ok(StatementDesc::new(match into {
+        None => Some(desc),
+        Some(_) => None,
+    }))
 }
 
 pub fn plan_select(
     scx: &StatementContext,
-    SelectStatement { query, as_of }: SelectStatement,
+    SelectStatement {
+        mut query,
+        locking,
+        as_of,
+    }: SelectStatement,
     params: &Params,
-    copy_to: Option<CopyFormat>,
+    copy_to: Option<CopyTo>,
 ) -> Result<Plan, anyhow::Error> {
-    let (relation_expr, _, finishing) = plan_query(scx, query, params, QueryLifetime::OneShot)?;
+    if let Some(into) = take_select_into(&mut query) {
+        if copy_to.is_some() {
+            bail!("COPY (SELECT INTO) is not supported");
+        }
+        if locking.is_some() {
+            unsupported!("SELECT ... INTO with FOR UPDATE or FOR SHARE");
+        }
+        if as_of.is_some() {
+            unsupported!("SELECT ... INTO with AS OF");
+        }
+        return plan_select_into(scx, into, query, params);
+    }
+
+    if let Some(tail) = select_over_tail(&query) {
+        if copy_to.is_some() {
+            bail!("COPY (SELECT * FROM (TAIL ...)) is not supported; use COPY (TAIL ...) instead");
+        }
+        if locking.is_some() {
+            unsupported!("SELECT * FROM (TAIL ...) with FOR UPDATE or FOR SHARE");
+        }
+        if as_of.is_some() {
+            bail!("SELECT * FROM (TAIL ...) does not support AS OF; use TAIL ... AS OF instead");
+        }
+        return plan_select_over_tail(scx, tail.clone());
+    }
+
+    let index_hint = take_index_hint(scx, &mut query)?;
+    let after = take_keyset_bound(&mut query)?;
+    let (relation_expr, _, mut finishing) = plan_query(scx, query, params, QueryLifetime::OneShot)?;
+    if let Some(after) = after {
+        if finishing.order_by.is_empty() {
+            bail!("OPTION (after) requires an ORDER BY clause");
+        }
+        if after.len() > finishing.order_by.len() {
+            bail!("OPTION (after) has more values than the ORDER BY clause has columns");
+        }
+        let typ = relation_expr.typ();
+        let target_types = finishing
+            .order_by
+            .iter()
+            .map(|order| &typ.column_types[order.column].scalar_type);
+        finishing.after = Some(query::eval_keyset_bound(scx, after, target_types)?);
+    }
     let when = match as_of.map(|e| query::eval_as_of(scx, e)).transpose()? {
         Some(ts) => PeekWhen::AtTimestamp(ts),
         None => PeekWhen::Immediately,
@@ -122,36 +208,228 @@ pub fn plan_select(
         when,
         finishing,
         copy_to,
+        index_hint,
+        locking,
     })
 }
 
+/// Removes the `index` hint from the options of `query`'s outermost `SELECT`,
+/// if present, and returns the ID of the index that it names. The coordinator
+/// verifies that the index can serve the query.
+fn take_index_hint(
+    scx: &StatementContext,
+    query: &mut Query,
+) -> Result<Option<GlobalId>, anyhow::Error> {
+    let options = match &mut query.body {
+        SetExpr::Select(select) => &mut select.options,
+        _ => return Ok(None),
+    };
+    let pos = match options
+        .iter()
+        .position(|o| normalize::ident(o.name().clone()) == "index")
+    {
+        Some(pos) => pos,
+        None => return Ok(None),
+    };
+    let name = match options.remove(pos) {
+        SqlOption::ObjectName { object_name, .. } => object_name,
+        _ => bail!("index hint must name an index"),
+    };
+    let item = scx.resolve_item(name)?;
+    if item.item_type() != CatalogItemType::Index {
+        bail!("{} is not an index", item.name());
+    }
+    Ok(Some(item.id()))
+}
+
+/// Removes the `after` option from the options of `query`'s outermost
+/// `SELECT`, if present, and returns its values. The values are a key for a
+/// prefix of the `ORDER BY` columns, given either as a single value or as a
+/// parenthesized tuple: only rows that sort strictly after the key are
+/// returned, which allows clients to page through an ordered result without
+/// rescanning the rows of earlier pages, as `OFFSET` would.
+fn take_keyset_bound(query: &mut Query) -> Result<Option<Vec<Value>>, anyhow::Error> {
+    let options = match &mut query.body {
+        SetExpr::Select(select) => &mut select.options,
+        _ => return Ok(None),
+    };
+    let pos = match options
+        .iter()
+        .position(|o| normalize::ident(o.name().clone()) == "after")
+    {
+        Some(pos) => pos,
+        None => return Ok(None),
+    };
+    match options.remove(pos) {
+        SqlOption::Value { value, .. } => Ok(Some(vec![value])),
+        SqlOption::Values { values, .. } => Ok(Some(values)),
+        _ => bail!("OPTION (after) must be a literal or a tuple of literals"),
+    }
+}
+
+/// Returns the `TAIL` that `query` selects from, if `query` has the form
+/// `SELECT * FROM (TAIL ...)` and does nothing else.
+fn select_over_tail(query: &Query) -> Option<&TailStatement> {
+    let select = match &query.body {
+        SetExpr::Select(select) => select,
+        _ => return None,
+    };
+    if !query.ctes.is_empty()
+        || !query.order_by.is_empty()
+        || query.limit.is_some()
+        || query.offset.is_some()
+        || select.distinct.is_some()
+        || select.projection != [SelectItem::Wildcard]
+        || select.into.is_some()
+        || select.selection.is_some()
+        || !select.group_by.is_empty()
+        || select.having.is_some()
+        || !select.options.is_empty()
+    {
+        return None;
+    }
+    match &select.from[..] {
+        [TableWithJoins {
+            relation: TableFactor::Tail { tail, alias },
+            joins,
+        }] if joins.is_empty() && alias.iter().all(|alias| alias.columns.is_empty()) => Some(tail),
+        _ => None,
+    }
+}
+
+/// Plans `SELECT * FROM (TAIL ...)` as a `TAIL` whose rows are collected into
+/// a single result set. The `TAIL` must have an `UNTIL` bound, so that it
+/// produces a finite number of rows. The result has the same columns as the
+/// `TAIL`: the `timestamp` and `diff` of each update, followed by the columns
+/// of the tailed relation.
+fn plan_select_over_tail(
+    scx: &StatementContext,
+    tail: TailStatement,
+) -> Result<Plan, anyhow::Error> {
+    let mut plan = plan_tail(scx, tail, None)?;
+    if let Plan::Tail {
+        until,
+        emit_progress,
+        collect,
+        ..
+    } = &mut plan
+    {
+        if until.is_none() {
+            bail!("SELECT * FROM (TAIL ...) requires UNTIL");
+        }
+        if *emit_progress {
+            bail!("SELECT * FROM (TAIL ...) does not support the progress option");
+        }
+        *collect = true;
+    }
+    Ok(plan)
+}
+
+/// Removes and returns the target of `query`'s `INTO` clause, if `query` is a
+/// `SELECT ... INTO` statement.
+fn take_select_into(query: &mut Query) -> Option<SelectInto> {
+    match &mut query.body {
+        SetExpr::Select(select) => select.into.take(),
+        _ => None,
+    }
+}
+
+/// Plans `SELECT ... INTO` as the creation of a table whose columns match the
+/// query's output, followed by an insertion of the query's results into that
+/// table.
+///
+/// Like the columns of PostgreSQL's `SELECT ... INTO`, the new table's columns
+/// are all nullable.
+fn plan_select_into(
+    scx: &StatementContext,
+    SelectInto { name, temporary }: SelectInto,
+    query: Query,
+    params: &Params,
+) -> Result<Plan, anyhow::Error> {
+    let (mut relation_expr, desc, finishing) =
+        query::plan_root_query(scx, query, QueryLifetime::OneShot)?;
+    relation_expr.bind_parameters(&params)?;
+    relation_expr.finish(finishing);
+    let values = relation_expr.decorrelate();
+
+    let names: Vec<_> = desc
+        .iter_names()
+        .map(|n| n.cloned().unwrap_or_else(|| ColumnName::from("?column?")))
+        .collect();
+    if let Some(dup) = names.iter().duplicates().next() {
+        bail!(
+            "cannot SELECT INTO: column \"{}\" specified more than once",
+            dup
+        );
+    }
+    let mut columns = Vec::with_capacity(names.len());
+    for (name, typ) in names.iter().zip(desc.typ().column_types.iter()) {
+        columns.push(ColumnDef {
+            name: Ident::new(name.as_str()),
+            data_type: query::scalar_type_to_sql(scx, &typ.scalar_type)?,
+            collation: None,
+            options: vec![],
+        });
+    }
+
+    let stmt = CreateTableStatement {
+        name,
+        columns,
+        constraints: vec![],
+        with_options: vec![],
+        if_not_exists: false,
+        temporary,
+    };
+    match ddl::plan_create_table(scx, stmt)? {
+        Plan::CreateTable { name, table, .. } => Ok(Plan::SelectInto {
+            name,
+            table,
+            values,
+        }),
+        _ => unreachable!("plan_create_table returned non-CreateTable plan"),
+    }
+}
+
 pub fn describe_explain(
     scx: &StatementContext,
     ExplainStatement {
         stage, explainee, ..
     }: ExplainStatement,
 ) -> Result<StatementDesc, anyhow::Error> {
-    Ok(StatementDesc::new(Some(RelationDesc::empty().with_column(
-        match stage {
-            ExplainStage::RawPlan => "Raw Plan",
-            ExplainStage::DecorrelatedPlan => "Decorrelated Plan",
-            ExplainStage::OptimizedPlan { .. } => "Optimized Plan",
-        },
-        ScalarType::String.nullable(false),
-    )))
-    .with_pgrepr_params(match explainee {
+    let plan_column =
+        |name| RelationDesc::empty().with_column(name, ScalarType::String.nullable(false));
+    let desc = match stage {
+        ExplainStage::RawPlan => plan_column("Raw Plan"),
+        ExplainStage::DecorrelatedPlan => plan_column("Decorrelated Plan"),
+        ExplainStage::OptimizedPlan { .. } => plan_column("Optimized Plan"),
+        ExplainStage::Arrangements => RelationDesc::empty()
+            .with_column("kind", ScalarType::String.nullable(false))
+            .with_column("index_id", ScalarType::String.nullable(true))
+            .with_column("index_name", ScalarType::String.nullable(false))
+            .with_column("on_name", ScalarType::String.nullable(false)),
+        ExplainStage::Sink | ExplainStage::Analyze => RelationDesc::empty()
+            .with_column("property", ScalarType::String.nullable(false))
+            .with_column("value", ScalarType::String.nullable(false)),
+    };
+    let param_types = match explainee {
         Explainee::Query(q) => {
             describe_select(
                 scx,
                 SelectStatement {
                     query: q,
+                    locking: None,
                     as_of: None,
                 },
             )?
             .param_types
         }
-        _ => vec![],
-    }))
+        Explainee::Insert(insert) => describe_insert(scx, insert)?.param_types,
+        Explainee::View(_)
+        | Explainee::CreateView(_)
+        | Explainee::CreateIndex(_)
+        | Explainee::CreateSink(_) => vec![],
+    };
+    Ok(StatementDesc::new(Some(desc)).with_pgrepr_params(param_types))
 }
 
 pub fn plan_explain(
@@ -163,40 +441,69 @@ pub fn plan_explain(
     }: ExplainStatement,
     params: &Params,
 ) -> Result<Plan, anyhow::Error> {
+    if let ExplainStage::Arrangements = stage {
+        return plan_explain_arrangements(scx, explainee, options, params);
+    }
+    if let ExplainStage::Sink = stage {
+        return plan_explain_sink(scx, explainee);
+    }
+    if let ExplainStage::Analyze = stage {
+        return plan_explain_analyze(scx, explainee, params);
+    }
     let is_view = matches!(explainee, Explainee::View(_));
-    let (scx, query) = match explainee {
-        Explainee::View(name) => {
-            let view = scx.resolve_item(name.clone())?;
-            if view.item_type() != CatalogItemType::View {
-                bail!("Expected {} to be a view, not a {}", name, view.item_type());
-            }
-            let parsed = crate::parse::parse(view.create_sql())
-                .expect("Sql for existing view should be valid sql");
-            let query = match parsed.into_last() {
-                Statement::CreateView(CreateViewStatement { query, .. }) => query,
-                _ => panic!("Sql for existing view should parse as a view"),
-            };
-            let scx = StatementContext {
-                pcx: view.plan_cx(),
-                catalog: scx.catalog,
-                param_types: scx.param_types.clone(),
-            };
-            (scx, query)
-        }
-        Explainee::Query(query) => (scx.clone(), query),
-    };
-    // Previouly we would bail here for ORDER BY and LIMIT; this has been relaxed to silently
-    // report the plan without the ORDER BY and LIMIT decorations (which are done in post).
-    let (mut sql_expr, desc, finishing) =
-        query::plan_root_query(&scx, query, QueryLifetime::OneShot)?;
-    let finishing = if is_view {
-        // views don't use a separate finishing
-        sql_expr.finish(finishing);
-        None
-    } else if finishing.is_trivial(desc.arity()) {
-        None
+    let is_insert = matches!(explainee, Explainee::Insert(_));
+    let (mut sql_expr, finishing) = if let Explainee::Insert(InsertStatement {
+        table_name,
+        columns,
+        source,
+        ..
+    }) = explainee
+    {
+        let (_id, sql_expr) = query::plan_insert_query(scx, table_name, columns, source)?;
+        (sql_expr, None)
     } else {
-        Some(finishing)
+        let (scx, query) = match explainee {
+            Explainee::View(name) => {
+                let view = scx.resolve_item(name.clone())?;
+                if view.item_type() != CatalogItemType::View {
+                    bail!("Expected {} to be a view, not a {}", name, view.item_type());
+                }
+                let parsed = crate::parse::parse(view.create_sql())
+                    .expect("Sql for existing view should be valid sql");
+                let query = match parsed.into_last() {
+                    Statement::CreateView(CreateViewStatement { query, .. }) => query,
+                    _ => panic!("Sql for existing view should parse as a view"),
+                };
+                let scx = StatementContext {
+                    pcx: view.plan_cx(),
+                    catalog: scx.catalog,
+                    param_types: scx.param_types.clone(),
+                    func_uses: scx.func_uses.clone(),
+                    session_uses: scx.session_uses.clone(),
+                };
+                (scx, query)
+            }
+            Explainee::Query(query) => (scx.clone(), query),
+            Explainee::CreateView(_) | Explainee::CreateIndex(_) => {
+                bail!("only EXPLAIN (ARRANGEMENTS) supports CREATE statements")
+            }
+            Explainee::CreateSink(_) => bail!("only EXPLAIN (SINK) supports CREATE SINK"),
+            Explainee::Insert(_) => unreachable!(),
+        };
+        // Previouly we would bail here for ORDER BY and LIMIT; this has been relaxed to silently
+        // report the plan without the ORDER BY and LIMIT decorations (which are done in post).
+        let (mut sql_expr, desc, finishing) =
+            query::plan_root_query(&scx, query, QueryLifetime::OneShot)?;
+        let finishing = if is_view {
+            // views don't use a separate finishing
+            sql_expr.finish(finishing);
+            None
+        } else if finishing.is_trivial(desc.arity()) {
+            None
+        } else {
+            Some(finishing)
+        };
+        (sql_expr, finishing)
     };
     sql_expr.bind_parameters(&params)?;
     let expr = sql_expr.clone().decorrelate();
@@ -206,9 +513,111 @@ pub fn plan_explain(
         row_set_finishing: finishing,
         stage,
         options,
+        is_insert,
     })
 }
 
+/// Plans `EXPLAIN (ARRANGEMENTS)`, which reports the indexes that the
+/// dataflow for `explainee` would import and export.
+fn plan_explain_arrangements(
+    scx: &StatementContext,
+    explainee: Explainee,
+    options: ExplainOptions,
+    params: &Params,
+) -> Result<Plan, anyhow::Error> {
+    let explainee = match explainee {
+        Explainee::CreateView(stmt) => match ddl::plan_create_view(scx, stmt, params)? {
+            Plan::CreateView {
+                name,
+                view,
+                materialize,
+                ..
+            } => ArrangementsExplainee::View {
+                expr: view.expr,
+                materialized_name: if materialize { Some(name) } else { None },
+            },
+            _ => unreachable!("plan_create_view returned non-CreateView plan"),
+        },
+        Explainee::CreateIndex(stmt) => match ddl::plan_create_index(scx, stmt)? {
+            Plan::CreateIndex { name, index, .. } => ArrangementsExplainee::Index { name, index },
+            _ => unreachable!("plan_create_index returned non-CreateIndex plan"),
+        },
+        Explainee::Insert(_) => bail!("EXPLAIN (ARRANGEMENTS) does not support INSERT"),
+        explainee => {
+            let stmt = ExplainStatement {
+                stage: ExplainStage::DecorrelatedPlan,
+                explainee,
+                options,
+            };
+            match plan_explain(scx, stmt, params)? {
+                Plan::ExplainPlan {
+                    decorrelated_plan, ..
+                } => ArrangementsExplainee::View {
+                    expr: decorrelated_plan,
+                    materialized_name: None,
+                },
+                _ => unreachable!("plan_explain returned non-ExplainPlan plan"),
+            }
+        }
+    };
+    Ok(Plan::ExplainArrangements(explainee))
+}
+
+/// Plans `EXPLAIN (SINK)`, which validates a `CREATE SINK` statement and
+/// reports what the sink would create, without creating anything.
+fn plan_explain_sink(scx: &StatementContext, explainee: Explainee) -> Result<Plan, anyhow::Error> {
+    let stmt = match explainee {
+        Explainee::CreateSink(stmt) => stmt,
+        _ => bail!("EXPLAIN (SINK) only supports CREATE SINK"),
+    };
+    match ddl::plan_create_sink(scx, stmt)? {
+        Plan::CreateSink {
+            name,
+            sink,
+            with_snapshot,
+            as_of,
+            if_not_exists,
+        } => Ok(Plan::ExplainSink {
+            name,
+            sink,
+            with_snapshot,
+            as_of,
+            if_not_exists,
+        }),
+        _ => unreachable!("plan_create_sink returned non-CreateSink plan"),
+    }
+}
+
+/// Plans `EXPLAIN ANALYZE`, which executes a query or an `INSERT` and reports
+/// how long it took and what it did in place of its results.
+fn plan_explain_analyze(
+    scx: &StatementContext,
+    explainee: Explainee,
+    params: &Params,
+) -> Result<Plan, anyhow::Error> {
+    let plan = match explainee {
+        Explainee::Query(query) => {
+            let stmt = SelectStatement {
+                query,
+                locking: None,
+                as_of: None,
+            };
+            match plan_select(scx, stmt, params, None)? {
+                plan @ Plan::Peek { .. } => plan,
+                _ => bail!("EXPLAIN ANALYZE does not support SELECT ... INTO or TAIL"),
+            }
+        }
+        Explainee::Insert(stmt) => {
+            if !stmt.returning.is_empty() {
+                unsupported!("EXPLAIN ANALYZE with INSERT ... RETURNING");
+            }
+            plan_insert(scx, stmt, params)?
+        }
+        _ => bail!("EXPLAIN ANALYZE only supports queries and INSERT"),
+    };
+    Ok(Plan::ExplainAnalyze(Box::new(plan)))
+}
+
 /// Plans and decorrelates a `Query`. Like `query::plan_root_query`, but returns
 /// an `::expr::RelationExpr`, which cannot include correlated expressions.
 pub fn plan_query(
@@ -226,6 +635,8 @@ with_options! {
     struct TailOptions {
         snapshot: bool,
         progress: bool,
+        on_backpressure: String,
+        limit_snapshot: bool,
      }
 }
 
@@ -255,25 +666,68 @@ pub fn plan_tail(
         name,
         options,
         as_of,
+        until,
+        limit,
     }: TailStatement,
     copy_to: Option<CopyFormat>,
 ) -> Result<Plan, anyhow::Error> {
     let entry = scx.resolve_item(name)?;
     let ts = as_of.map(|e| query::eval_as_of(scx, e)).transpose()?;
+    let until = until.map(|e| query::eval_as_of(scx, e)).transpose()?;
     let options = TailOptions::try_from(options)?;
+    let on_backpressure = match options.on_backpressure {
+        None => TailBackpressure::Block,
+        Some(value) => match value.to_lowercase().as_str() {
+            "block" => TailBackpressure::Block,
+            "drop" => TailBackpressure::Drop,
+            "latest" => TailBackpressure::Latest,
+            _ => bail!("TAIL on_backpressure must be one of 'block', 'drop', or 'latest'"),
+        },
+    };
+    let limit = match limit {
+        None => {
+            if options.limit_snapshot.is_some() {
+                bail!("TAIL limit_snapshot requires a LIMIT");
+            }
+            None
+        }
+        Some(Expr::Value(Value::Number(n))) => {
+            let count = match n.parse::<usize>() {
+                Ok(count) if count > 0 => count,
+                _ => bail!("TAIL LIMIT must be a positive integer"),
+            };
+            // Dropped or coalesced updates have no sensible count, so limits
+            // only apply to tails that deliver every update.
+            if on_backpressure != TailBackpressure::Block {
+                bail!("TAIL LIMIT requires on_backpressure = 'block'");
+            }
+            Some(TailLimit {
+                count,
+                include_snapshot: options.limit_snapshot.unwrap_or(true),
+            })
+        }
+        Some(_) => bail!("TAIL LIMIT must be a positive integer"),
+    };
 
     match entry.item_type() {
         CatalogItemType::Table | CatalogItemType::Source | CatalogItemType::View => {
             Ok(Plan::Tail {
                 id: entry.id(),
                 ts,
+                until,
                 with_snapshot: options.snapshot.unwrap_or(true),
                 copy_to,
                 emit_progress: options.progress.unwrap_or(false),
                 object_columns: entry.desc()?.arity(),
+                on_backpressure,
+                limit,
+                collect: false,
             })
         }
-        CatalogItemType::Index | CatalogItemType::Sink | CatalogItemType::Type => bail!(
+        CatalogItemType::Index
+        | CatalogItemType::Sink
+        | CatalogItemType::Type
+        | CatalogItemType::Func => bail!(
             "'{}' cannot be tailed because it is a {}",
             entry.name(),
             entry.item_type(),
@@ -284,21 +738,99 @@ pub fn plan_tail(
 with_options! {
     struct CopyOptions {
         format: String,
+        delimiter: String,
+        quote: String,
+        escape: String,
+        null: String,
+        header: bool,
+        nonfinite_floats: String,
+    }
+}
+
+/// Extracts a single-byte character from the value of a `COPY` option.
+fn copy_option_char(name: &str, value: String) -> Result<u8, anyhow::Error> {
+    match value.as_bytes() {
+        [b] => Ok(*b),
+        _ => bail!("COPY {} must be a single one-byte character", name),
     }
 }
 
 pub fn describe_copy(
     scx: &StatementContext,
-    CopyStatement { relation, .. }: CopyStatement,
+    CopyStatement {
+        relation, target, ..
+    }: CopyStatement,
 ) -> Result<StatementDesc, anyhow::Error> {
+    // Copying to files returns a description of each file that was written,
+    // rather than the copied rows themselves.
+    if let CopyTarget::File(_) = target {
+        return Ok(StatementDesc::new(Some(
+            RelationDesc::empty()
+                .with_column("path", ScalarType::String.nullable(false))
+                .with_column("rows", ScalarType::Int64.nullable(false))
+                .with_column("bytes", ScalarType::Int64.nullable(false)),
+        )));
+    }
     Ok(match relation {
-        CopyRelation::Table { .. } => bail!("unsupported COPY relation {:?}", relation),
+        CopyRelation::Table { name, columns } => {
+            describe_select(scx, copy_relation_query(scx, name, columns)?)?
+        }
         CopyRelation::Select(stmt) => describe_select(scx, stmt)?,
         CopyRelation::Tail(stmt) => describe_tail(scx, stmt)?,
     }
     .with_is_copy())
 }
 
+/// Constructs the query whose results `COPY <name> [(<columns>)] TO` exports:
+/// the named columns of the relation, in the order they are named, or all of
+/// the relation's columns if none are named.
+fn copy_relation_query(
+    scx: &StatementContext,
+    name: ObjectName,
+    columns: Vec<Ident>,
+) -> Result<SelectStatement, anyhow::Error> {
+    let select = Select::default().from(TableWithJoins {
+        relation: TableFactor::Table {
+            name: name.clone(),
+            alias: None,
+        },
+        joins: vec![],
+    });
+    let stmt = |select: Select| SelectStatement {
+        query: Query::select(select),
+        locking: None,
+        as_of: None,
+    };
+    if columns.is_empty() {
+        return Ok(stmt(select.project(SelectItem::Wildcard)));
+    }
+
+    let desc = describe_select(scx, stmt(select.clone().project(SelectItem::Wildcard)))?
+        .relation_desc
+        .expect("SELECT * has a relation description");
+    let mut select = select;
+    let mut seen = vec![];
+    for column in columns {
+        let column_name = normalize::column_name(column.clone());
+        if desc.get_by_name(&column_name).is_none() {
+            bail!(
+                "column \"{}\" of relation \"{}\" does not exist",
+                column_name,
+                name
+            );
+        }
+        if seen.contains(&column_name) {
+            bail!("column \"{}\" specified more than once", column_name);
+        }
+        seen.push(column_name);
+        select = select.project(SelectItem::Expr {
+            expr: Expr::Identifier(vec![column]),
+            alias: None,
+        });
+    }
+    Ok(stmt(select))
+}
+
 pub fn plan_copy(
     scx: &StatementContext,
     CopyStatement {
@@ -309,24 +841,102 @@ pub fn plan_copy(
     }: CopyStatement,
 ) -> Result<Plan, anyhow::Error> {
     let options = CopyOptions::try_from(options)?;
-    let format = if let Some(format) = options.format {
-        match format.to_lowercase().as_str() {
-            "text" => CopyFormat::Text,
-            "csv" => CopyFormat::Csv,
-            "binary" => CopyFormat::Binary,
-            _ => bail!("unknown FORMAT: {}", format),
+    let format = match options.format {
+        Some(format) => format.to_lowercase(),
+        None => "text".into(),
+    };
+    if format != "csv" {
+        for (name, present) in &[
+            ("delimiter", options.delimiter.is_some()),
+            ("quote", options.quote.is_some()),
+            ("escape", options.escape.is_some()),
+            ("null", options.null.is_some()),
+            ("header", options.header.is_some()),
+            ("nonfinite_floats", options.nonfinite_floats.is_some()),
+        ] {
+            if *present {
+                bail!("COPY {} available only in CSV mode", name);
+            }
         }
-    } else {
-        CopyFormat::Text
+    }
+    let format = match format.as_str() {
+        "text" => CopyFormat::Text,
+        "csv" => {
+            let delimiter = match options.delimiter {
+                Some(delimiter) => copy_option_char("delimiter", delimiter)?,
+                None => b',',
+            };
+            let quote = match options.quote {
+                Some(quote) => copy_option_char("quote", quote)?,
+                None => b'"',
+            };
+            let escape = match options.escape {
+                Some(escape) => copy_option_char("escape", escape)?,
+                None => quote,
+            };
+            if delimiter == b'\r' || delimiter == b'\n' {
+                bail!("COPY delimiter cannot be newline or carriage return");
+            }
+            if delimiter == quote {
+                bail!("COPY delimiter and quote must be different");
+            }
+            let nonfinite_floats = match options.nonfinite_floats {
+                None => CopyNonfiniteFloats::Text,
+                Some(value) => match value.to_lowercase().as_str() {
+                    "text" => CopyNonfiniteFloats::Text,
+                    "null" => CopyNonfiniteFloats::Null,
+                    "error" => CopyNonfiniteFloats::Error,
+                    _ => bail!("COPY nonfinite_floats must be one of 'text', 'null', or 'error'"),
+                },
+            };
+            CopyFormat::Csv(CopyCsvFormatParams {
+                delimiter,
+                quote,
+                escape,
+                null: options.null.unwrap_or_default(),
+                header: options.header.unwrap_or(false),
+                nonfinite_floats,
+            })
+        }
+        "binary" => CopyFormat::Binary,
+        _ => bail!("unknown FORMAT: {}", format),
     };
-    match (&direction, &target) {
+    let relation = match relation {
+        CopyRelation::Table { name, columns } if direction == CopyDirection::To => {
+            CopyRelation::Select(copy_relation_query(scx, name, columns)?)
+        }
+        relation => relation,
+    };
+    match (&direction, target) {
         (CopyDirection::To, CopyTarget::Stdout) => match relation {
-            CopyRelation::Table { .. } => bail!("table with COPY TO unsupported"),
+            CopyRelation::Table { .. } => unreachable!(),
             CopyRelation::Select(stmt) => {
-                Ok(plan_select(scx, stmt, &Params::empty(), Some(format))?)
+                let copy_to = CopyTo {
+                    format,
+                    target: CopyToTarget::Stdout,
+                };
+                Ok(plan_select(scx, stmt, &Params::empty(), Some(copy_to))?)
             }
             CopyRelation::Tail(stmt) => Ok(plan_tail(scx, stmt, Some(format))?),
         },
-        _ => bail!("COPY {} {} not supported", direction, target),
+        (CopyDirection::To, CopyTarget::File(prefix)) => match relation {
+            CopyRelation::Table { .. } => unreachable!(),
+            CopyRelation::Select(stmt) => {
+                let desc = match describe_select(scx, stmt.clone())?.relation_desc {
+                    Some(desc) => desc,
+                    None => bail!("COPY (SELECT INTO) is not supported"),
+                };
+                let copy_to = CopyTo {
+                    format,
+                    target: CopyToTarget::File {
+                        prefix: PathBuf::from(prefix),
+                        desc,
+                    },
+                };
+                Ok(plan_select(scx, stmt, &Params::empty(), Some(copy_to))?)
+            }
+            CopyRelation::Tail(_) => bail!("COPY (TAIL) to a file is not supported"),
+        },
+        (direction, target) => bail!("COPY {} {} not supported", direction, target),
     }
 }
@@ -10,7 +10,7 @@
 //! Session control language (SCL).
 //!
 //! This module houses the handlers for statements that manipulate the session,
-//! like `DISCARD` and `SET`.
+//! like `DISCARD`, `SET`, and `LISTEN`.
 
 use std::convert::TryFrom;
 
@@ -21,8 +21,10 @@ use repr::{RelationDesc, ScalarType};
 
 use crate::ast::{
     CloseStatement, DeclareStatement, DiscardStatement, DiscardTarget, FetchStatement,
-    SetVariableStatement, SetVariableValue, ShowVariableStatement, Value,
+    ListenStatement, NotifyStatement, SetVariableStatement, SetVariableValue,
+    ShowVariableStatement, UnlistenStatement, Value,
 };
+use crate::normalize;
 use crate::plan::statement::{StatementContext, StatementDesc};
 use crate::plan::{ExecuteTimeout, Plan};
 
@@ -44,16 +46,33 @@ pub fn plan_set_variable(
     if local {
         unsupported!("SET LOCAL");
     }
+    // As in PostgreSQL, identifiers in the value of `search_path` keep their
+    // quoting, so that a schema named `"a,b"` is not mistaken for two schemas.
+    let quote_idents = variable.as_str() == unicase::Ascii::new("search_path");
     Ok(Plan::SetVariable {
         name: variable.to_string(),
-        value: match value {
-            SetVariableValue::Literal(Value::String(s)) => s,
-            SetVariableValue::Literal(lit) => lit.to_string(),
-            SetVariableValue::Ident(ident) => ident.into_string(),
-        },
+        value: set_variable_value_to_string(value, quote_idents),
     })
 }
 
+/// Converts a `SET` value into the string form expected by the session
+/// variables. Lists of values are joined with commas, as PostgreSQL does when
+/// reporting them. If `quote_idents` is set, identifiers are rendered with
+/// double quotes where necessary.
+fn set_variable_value_to_string(value: SetVariableValue, quote_idents: bool) -> String {
+    match value {
+        SetVariableValue::Literal(Value::String(s)) => s,
+        SetVariableValue::Literal(lit) => lit.to_string(),
+        SetVariableValue::Ident(ident) if quote_idents => ident.to_string(),
+        SetVariableValue::Ident(ident) => ident.into_string(),
+        SetVariableValue::List(values) => values
+            .into_iter()
+            .map(|value| set_variable_value_to_string(value, quote_idents))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
 pub fn describe_show_variable(
     _: &StatementContext,
     ShowVariableStatement { variable, .. }: ShowVariableStatement,
@@ -94,8 +113,8 @@ pub fn plan_discard(
     match target {
         DiscardTarget::All => Ok(Plan::DiscardAll),
         DiscardTarget::Temp => Ok(Plan::DiscardTemp),
-        DiscardTarget::Sequences => unsupported!("DISCARD SEQUENCES"),
-        DiscardTarget::Plans => unsupported!("DISCARD PLANS"),
+        DiscardTarget::Sequences => Ok(Plan::DiscardSequences),
+        DiscardTarget::Plans => Ok(Plan::DiscardPlans),
     }
 }
 
@@ -138,21 +157,11 @@ pub fn plan_fetch(
     }: FetchStatement,
 ) -> Result<Plan, anyhow::Error> {
     let options = FetchOptions::try_from(options)?;
-    let timeout = match options.timeout {
-        Some(timeout) => {
-            // Limit FETCH timeouts to 1 day. If users have a legitimate need it can be
-            // bumped. If we do bump it, ensure that the new upper limit is within the
-            // bounds of a tokio time future, otherwise it'll panic.
-            const SECS_PER_DAY: f64 = 60f64 * 60f64 * 24f64;
-            let timeout_secs = timeout.as_seconds();
-            if !timeout_secs.is_finite() || timeout_secs < 0f64 || timeout_secs > SECS_PER_DAY {
-                bail!("timeout out of range: {:#}", timeout);
-            }
-            ExecuteTimeout::Seconds(timeout_secs)
-        }
-        // FETCH defaults to WaitOnce.
-        None => ExecuteTimeout::WaitOnce,
-    };
+    // Without an explicit timeout, the session's `fetch_timeout` applies.
+    let timeout = options
+        .timeout
+        .map(ExecuteTimeout::from_interval)
+        .transpose()?;
     Ok(Plan::Fetch {
         name: name.to_string(),
         count,
@@ -175,3 +184,60 @@ pub fn plan_close(
         name: name.to_string(),
     })
 }
+
+pub fn describe_listen(
+    _: &StatementContext,
+    _: ListenStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_listen(
+    _: &StatementContext,
+    ListenStatement { channel }: ListenStatement,
+) -> Result<Plan, anyhow::Error> {
+    Ok(Plan::Listen {
+        channel: normalize::ident(channel),
+    })
+}
+
+pub fn describe_unlisten(
+    _: &StatementContext,
+    _: UnlistenStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_unlisten(
+    _: &StatementContext,
+    UnlistenStatement { channel }: UnlistenStatement,
+) -> Result<Plan, anyhow::Error> {
+    Ok(Plan::Unlisten {
+        channel: channel.map(normalize::ident),
+    })
+}
+
+/// The maximum length of a `NOTIFY` payload in bytes. This matches
+/// PostgreSQL's default limit.
+const MAX_NOTIFY_PAYLOAD_LEN: usize = 8000;
+
+pub fn describe_notify(
+    _: &StatementContext,
+    _: NotifyStatement,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_notify(
+    _: &StatementContext,
+    NotifyStatement { channel, payload }: NotifyStatement,
+) -> Result<Plan, anyhow::Error> {
+    let payload = payload.unwrap_or_default();
+    if payload.len() >= MAX_NOTIFY_PAYLOAD_LEN {
+        bail!("payload string too long");
+    }
+    Ok(Plan::Notify {
+        channel: normalize::ident(channel),
+        payload,
+    })
+}
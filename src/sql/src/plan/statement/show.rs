@@ -187,6 +187,7 @@ pub fn show_objects<'a>(
         ObjectType::Type => show_types(scx, extended, full, from, filter),
         ObjectType::Object => show_all_objects(scx, extended, full, from, filter),
         ObjectType::Index => unreachable!("SHOW INDEX handled separately"),
+        ObjectType::Function => unreachable!("SHOW FUNCTIONS is not supported"),
     }
 }
 
@@ -14,6 +14,7 @@
 
 use crate::ast::{
     CommitStatement, RollbackStatement, SetTransactionStatement, StartTransactionStatement,
+    TransactionAccessMode, TransactionMode,
 };
 use crate::plan::statement::{StatementContext, StatementDesc};
 use crate::plan::Plan;
@@ -27,9 +28,23 @@ pub fn describe_start_transaction(
 
 pub fn plan_start_transaction(
     _: &StatementContext,
-    _: StartTransactionStatement,
+    StartTransactionStatement { modes }: StartTransactionStatement,
 ) -> Result<Plan, anyhow::Error> {
-    Ok(Plan::StartTransaction)
+    // As in PostgreSQL, the last access mode and deferrable mode win.
+    let mut read_only = false;
+    let mut deferrable = false;
+    for mode in modes {
+        match mode {
+            TransactionMode::AccessMode(mode) => {
+                read_only = mode == TransactionAccessMode::ReadOnly;
+            }
+            TransactionMode::Deferrable(d) => deferrable = d,
+            TransactionMode::IsolationLevel(_) => (),
+        }
+    }
+    Ok(Plan::StartTransaction {
+        consistent_snapshot: read_only && deferrable,
+    })
 }
 
 pub fn describe_set_transaction(
@@ -14,6 +14,7 @@ use std::mem;
 
 use lazy_static::lazy_static;
 
+use expr::{Id, LocalId};
 use repr::{ColumnType, RelationType, ScalarType};
 
 use crate::plan::expr::{
@@ -27,7 +28,7 @@ use crate::plan::expr::{
 ///
 /// ```text
 /// Filter {
-///     predicates: [a = b AND EXISTS (<subquery 1>) AND c = d AND (<subquery 2>) = e]
+///     predicates: [a = b AND (<subquery 1>) = e AND c = d AND EXISTS (<subquery 2>)]
 /// }
 /// ```
 ///
@@ -37,19 +38,23 @@ use crate::plan::expr::{
 /// Filter {
 ///     predicates: [
 ///         a = b AND c = d,
-///         EXISTS (<subquery>),
-///         (<subquery 2>) = e,
+///         EXISTS (<subquery 2>),
+///         (<subquery 1>) = e,
 ///     ]
 /// }
 /// ```
 ///
 /// The rewrite causes decorrelation to incorporate prior predicates into
 /// the outer relation upon which the subquery is evaluated. In the above
-/// rewritten example, the `EXISTS (<subquery>)` will only be evaluated for
-/// outer rows where `a = b AND c = d`. The second subquery, `(<subquery 2>)
-/// = e`, will be further restricted to outer rows that match `A = b AND c =
-/// d AND EXISTS(<subquery>)`. This can vastly reduce the cost of the
+/// rewritten example, the `EXISTS (<subquery 2>)` will only be evaluated for
+/// outer rows where `a = b AND c = d`. The scalar subquery, `(<subquery 1>)
+/// = e`, will be further restricted to outer rows that match `a = b AND c =
+/// d AND EXISTS(<subquery 2>)`. This can vastly reduce the cost of the
 /// subquery, especially when the original conjunction contains join keys.
+///
+/// Extracted `EXISTS` and `NOT EXISTS` subqueries are always emitted before
+/// other subqueries, as they are cheap to decorrelate. Otherwise, subqueries
+/// are emitted in the order in which they appear.
 pub fn split_subquery_predicates(expr: &mut RelationExpr) {
     fn walk_relation(expr: &mut RelationExpr) {
         expr.visit_mut(&mut |expr| match expr {
@@ -69,11 +74,12 @@ pub fn split_subquery_predicates(expr: &mut RelationExpr) {
                     walk_scalar(predicate);
                     extract_conjuncted_subqueries(predicate, &mut subqueries);
                 }
-                // TODO(benesch): we could be smarter about the order in which
-                // we emit subqueries. At the moment we just emit in the order
-                // we discovered them, but ideally we'd emit them in an order
-                // that accounted for their cost/selectivity. E.g., low-cost,
-                // high-selectivity subqueries should go first.
+                // Emit `EXISTS` and `NOT EXISTS` subqueries first, as they
+                // decorrelate into cheap semijoins. The sort is stable, so
+                // subqueries of the same kind stay in discovery order. The
+                // ordering does not account for the selectivity of each
+                // subquery.
+                subqueries.sort_by_key(|subquery| !is_exists(subquery));
                 for subquery in subqueries {
                     predicates.push(subquery);
                 }
@@ -98,6 +104,18 @@ pub fn split_subquery_predicates(expr: &mut RelationExpr) {
         found
     }
 
+    /// Reports whether `expr` is an `EXISTS` or `NOT EXISTS` subquery.
+    fn is_exists(expr: &ScalarExpr) -> bool {
+        match expr {
+            ScalarExpr::Exists(_) => true,
+            ScalarExpr::CallUnary {
+                func: UnaryFunc::Not,
+                expr,
+            } => matches!(&**expr, ScalarExpr::Exists(_)),
+            _ => false,
+        }
+    }
+
     /// Extracts subqueries from a conjunction into `out`.
     ///
     /// For example, given an expression like
@@ -134,6 +152,64 @@ pub fn split_subquery_predicates(expr: &mut RelationExpr) {
     walk_relation(expr)
 }
 
+/// Unrolls a bounded recursive CTE into a fixed number of copies of its
+/// recursive term.
+///
+/// `step` is the recursive term of the CTE, planned with each reference to
+/// the CTE itself as a `Get` of the local identifier `id`. The result is
+///
+/// ```text
+/// R_0 UNION ALL R_1 UNION ALL ... UNION ALL R_n
+/// ```
+///
+/// where `R_0` is `base`, `R_{i+1}` is `step` with `R_i` substituted for `id`,
+/// and `n` is `max_iterations`. If `distinct` is set, the union is made
+/// distinct, as for `UNION` rather than `UNION ALL`.
+///
+/// Each `R_{i+1}` contains its own copy of `R_i`, so the result holds
+/// `n(n+1)/2` copies of `step` in total. Callers must keep `max_iterations`
+/// small.
+///
+/// Rows that would only be derived after more than `max_iterations`
+/// applications of the recursive term are silently omitted. The caller is
+/// responsible for ensuring that `id` only appears in `step` outside of any
+/// subquery, so that no column references need to be adjusted as copies are
+/// substituted.
+pub fn unroll_recursive_cte(
+    base: RelationExpr,
+    step: &RelationExpr,
+    id: LocalId,
+    max_iterations: usize,
+    distinct: bool,
+) -> RelationExpr {
+    let mut inputs: Vec<RelationExpr> = Vec::with_capacity(max_iterations);
+    for _ in 0..max_iterations {
+        let prev = inputs.last().unwrap_or(&base).clone();
+        let mut next = step.clone();
+        next.visit_mut(&mut |e| match e {
+            RelationExpr::Get {
+                id: Id::Local(local_id),
+                ..
+            } if *local_id == id => *e = prev.clone(),
+            _ => (),
+        });
+        inputs.push(next);
+    }
+    let expr = if inputs.is_empty() {
+        base
+    } else {
+        RelationExpr::Union {
+            base: Box::new(base),
+            inputs,
+        }
+    };
+    if distinct {
+        expr.distinct()
+    } else {
+        expr
+    }
+}
+
 /// Rewrites quantified comparisons into simpler EXISTS operators.
 ///
 /// Note that this transformation is only valid when the expression is
@@ -276,3 +352,68 @@ fn column_type(outers: &[RelationType], inner: &RelationExpr, expr: &ScalarExpr)
     let inner_type = inner.typ(&outers, &NO_PARAMS);
     expr.typ(&outers, &inner_type, &NO_PARAMS)
 }
+
+#[cfg(test)]
+mod tests {
+    use repr::Datum;
+
+    use super::*;
+
+    fn subquery(n: i32) -> RelationExpr {
+        RelationExpr::constant(
+            vec![vec![Datum::Int32(n)]],
+            RelationType::new(vec![ScalarType::Int32.nullable(false)]),
+        )
+    }
+
+    #[test]
+    fn test_split_subquery_predicates_order() {
+        let select1 = subquery(1).select().call_binary(
+            ScalarExpr::literal(Datum::Int32(1), ScalarType::Int32),
+            BinaryFunc::Eq,
+        );
+        let exists2 = subquery(2).exists();
+        let select3 = subquery(3).select().call_binary(
+            ScalarExpr::literal(Datum::Int32(3), ScalarType::Int32),
+            BinaryFunc::Eq,
+        );
+        let not_exists4 = subquery(4).exists().call_unary(UnaryFunc::Not);
+
+        let predicate = select1
+            .clone()
+            .call_binary(exists2.clone(), BinaryFunc::And)
+            .call_binary(select3.clone(), BinaryFunc::And)
+            .call_binary(not_exists4.clone(), BinaryFunc::And);
+        let mut expr = subquery(0).filter(vec![predicate]);
+        split_subquery_predicates(&mut expr);
+
+        match expr {
+            RelationExpr::Filter { predicates, .. } => {
+                assert_eq!(&predicates[1..], &[exists2, not_exists4, select1, select3])
+            }
+            _ => panic!("expected filter, got {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn test_unroll_recursive_cte() {
+        let id = LocalId::new(0);
+        let step = RelationExpr::Get {
+            id: Id::Local(id),
+            typ: RelationType::new(vec![ScalarType::Int32.nullable(true)]),
+        }
+        .distinct();
+
+        let expr = unroll_recursive_cte(subquery(0), &step, id, 2, false);
+        assert_eq!(
+            expr,
+            RelationExpr::Union {
+                base: Box::new(subquery(0)),
+                inputs: vec![subquery(0).distinct(), subquery(0).distinct().distinct()],
+            }
+        );
+
+        let expr = unroll_recursive_cte(subquery(0), &step, id, 0, true);
+        assert_eq!(expr, subquery(0).distinct());
+    }
+}
@@ -9,7 +9,7 @@
 
 //! Statement purification.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 
 use anyhow::{bail, Context};
 use tokio::io::AsyncBufReadExt;
@@ -147,9 +147,7 @@ async fn purify_format(
                     let csv_header = file.lines().next_line().await?;
                     match csv_header {
                         Some(csv_header) => {
-                            csv_header
-                                .split(*delimiter as char)
-                                .for_each(|v| col_names.push(Ident::from(v)));
+                            col_names.extend(parse_csv_header(&csv_header, *delimiter)?)
                         }
                         None => bail!("CSV file expected header line, but is empty"),
                     }
@@ -163,6 +161,36 @@ async fn purify_format(
     Ok(())
 }
 
+/// Parses the header line of a CSV file into column names.
+///
+/// Fields in the header may be quoted, like any other CSV record. The names
+/// must be non-empty and unique.
+fn parse_csv_header(header: &str, delimiter: char) -> Result<Vec<Ident>, anyhow::Error> {
+    if !delimiter.is_ascii() {
+        bail!("CSV delimiter must be an ASCII character");
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter as u8)
+        .from_reader(header.as_bytes());
+    let record = match reader.records().next() {
+        Some(record) => record.context("parsing CSV header")?,
+        None => bail!("CSV file expected header line, but is empty"),
+    };
+    let mut names = vec![];
+    let mut seen = HashSet::new();
+    for (i, name) in record.iter().enumerate() {
+        if name.is_empty() {
+            bail!("CSV header has an empty name for column {}", i + 1);
+        }
+        if !seen.insert(name) {
+            bail!("CSV header has duplicate column name \"{}\"", name);
+        }
+        names.push(Ident::from(name));
+    }
+    Ok(names)
+}
+
 #[derive(Debug)]
 pub struct Schema {
     pub key_schema: Option<String>,
@@ -502,8 +502,17 @@ impl Runner {
         let mz_config = materialized::Config {
             logging: None,
             timestamp_frequency: Duration::from_millis(10),
+            timestamp_quantum: Duration::from_millis(1),
             cache: None,
             logical_compaction_window: None,
+            logical_compaction_hysteresis: 0,
+            feedback_batch_window: None,
+            peek_admission: None,
+            max_connections: None,
+            idle_session_timeout: None,
+            max_indexes_per_relation: materialized::DEFAULT_MAX_INDEXES_PER_RELATION,
+            copy_to_file: None,
+            bootstrap_timeout: None,
             threads: config.workers,
             process: 0,
             addresses: vec![SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0)],
@@ -513,6 +522,9 @@ impl Runner {
             listen_addr: None,
             tls: None,
             experimental_mode: true,
+            default_source_materialization: false,
+            object_history: false,
+            skip_failed_sink_rebuilds: false,
             telemetry_url: None,
         };
         let server = materialized::serve(mz_config, config.runtime.clone()).await?;
@@ -24,8 +24,8 @@
 //! Symbiosis mode is only suitable for development. It is likely to be
 //! extremely slow and inefficient on large data sets.
 
-use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::convert::TryInto;
 use std::env;
 use std::rc::Rc;
@@ -123,6 +123,8 @@ END $$;
             pcx,
             catalog,
             param_types: Rc::new(RefCell::new(BTreeMap::new())),
+            func_uses: Rc::new(RefCell::new(BTreeSet::new())),
+            session_uses: Rc::new(Cell::new(false)),
         };
         Ok(match stmt {
             Statement::CreateTable(CreateTableStatement {
@@ -130,8 +132,12 @@ END $$;
                 columns,
                 constraints,
                 if_not_exists,
+                temporary,
                 ..
             }) => {
+                if *temporary {
+                    bail!("temporary tables are not supported in symbiosis mode");
+                }
                 let sql_types: Vec<_> = columns
                     .iter()
                     .map(|column| column.data_type.clone())
@@ -208,6 +214,8 @@ END $$;
                     create_sql: stmt.to_string(),
                     desc,
                     defaults,
+                    temporary: false,
+                    func_uses: vec![],
                 };
                 Plan::CreateTable {
                     name,
@@ -238,6 +246,7 @@ END $$;
                 Plan::DropItems {
                     items,
                     ty: ObjectType::Table,
+                    cascade: false,
                 }
             }
             Statement::Delete(DeleteStatement { table_name, .. }) => {
@@ -255,7 +264,14 @@ END $$;
                     kind: MutationKind::Delete,
                 }
             }
-            Statement::Insert(InsertStatement { table_name, .. }) => {
+            Statement::Insert(InsertStatement {
+                table_name,
+                returning,
+                ..
+            }) => {
+                if !returning.is_empty() {
+                    bail!("INSERT ... RETURNING is not supported in symbiosis mode");
+                }
                 let mut updates = vec![];
                 let table = scx.resolve_item(table_name.clone())?;
                 // RETURNING cannot return zero columns, but we might be
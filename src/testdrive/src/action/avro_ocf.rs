@@ -153,7 +153,7 @@ impl Action for VerifyAction {
                 .query_one(
                     "SELECT path FROM mz_catalog_names
                      JOIN mz_avro_ocf_sinks ON global_id = sink_id
-                     WHERE name = $1",
+                     WHERE name = $1 AND active",
                     &[&self.sink],
                 )
                 .await
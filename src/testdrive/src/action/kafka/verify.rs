@@ -20,30 +20,45 @@ use crate::action::{Action, State};
 use crate::format::avro;
 use crate::parser::BuiltinCommand;
 
+pub enum SinkFormat {
+    Avro,
+    Json,
+}
+
 pub enum SinkConsistencyFormat {
     Debezium,
 }
 
 pub struct VerifyAction {
+    format: SinkFormat,
     sink: String,
     consistency: Option<SinkConsistencyFormat>,
+    /// The `topic_by` value of the routed topic to verify, if any.
+    topic_suffix: Option<String>,
     expected_messages: Vec<String>,
 }
 
 pub fn build_verify(mut cmd: BuiltinCommand) -> Result<VerifyAction, String> {
-    let _format = cmd.args.string("format")?;
+    let format = match cmd.args.string("format")?.as_str() {
+        "avro" => SinkFormat::Avro,
+        "json" => SinkFormat::Json,
+        f => return Err(format!("unknown sink format {}", f)),
+    };
     let sink = cmd.args.string("sink")?;
     let consistency = match cmd.args.opt_string("consistency").as_deref() {
         Some("debezium") => Some(SinkConsistencyFormat::Debezium),
         Some(s) => return Err(format!("unknown sink consistency format {}", s)),
         None => None,
     };
+    let topic_suffix = cmd.args.opt_string("topic-suffix");
 
     let expected_messages = cmd.input;
     cmd.args.done()?;
     Ok(VerifyAction {
+        format,
         sink,
         consistency,
+        topic_suffix,
         expected_messages,
     })
 }
@@ -91,41 +106,41 @@ impl Action for VerifyAction {
             .map_err(|e| format!("retrieving topic name: {}", e))?
             .get("topic");
 
-        let topic = match self.consistency {
+        // Records routed by `topic_by` use the schemas registered for the
+        // sink's topic.
+        let schema_topic = match self.consistency {
             Some(SinkConsistencyFormat::Debezium) => format!("{}-consistency", topic_prefix),
             None => topic_prefix,
         };
+        let topic = match &self.topic_suffix {
+            Some(suffix) => format!("{}-{}", schema_topic, suffix),
+            None => schema_topic.clone(),
+        };
 
         println!("Verifying results in Kafka topic {}", topic);
 
-        let value_schema = state
-            .ccsr_client
-            .get_schema_by_subject(&format!("{}-value", topic))
-            .await
-            .map_err(|e| format!("fetching schema: {}", e))?
-            .raw;
-
-        let key_schema = state
-            .ccsr_client
-            .get_schema_by_subject(&format!("{}-key", topic))
-            .await
-            .ok()
-            .map(|key_schema| {
-                avro::parse_schema(&key_schema.raw)
-                    .map_err(|e| format!("parsing avro schema: {}", e))
-            })
-            .transpose()?;
+        let messages = self.consume_messages(state, &topic).await?;
+        match self.format {
+            SinkFormat::Avro => self.verify_avro(state, &schema_topic, messages).await,
+            SinkFormat::Json => self.verify_json(messages),
+        }
+    }
+}
 
+impl VerifyAction {
+    /// Consumes up to the expected number of messages from `topic`, returning
+    /// the raw key and payload of each.
+    async fn consume_messages(
+        &self,
+        state: &State,
+        topic: &str,
+    ) -> Result<Vec<(Option<Vec<u8>>, Vec<u8>)>, String> {
         let config = state.kafka_config.clone();
 
-        let value_schema =
-            avro::parse_schema(&value_schema).map_err(|e| format!("parsing avro schema: {}", e))?;
-        let value_schema = &value_schema;
-
         let consumer: StreamConsumer = config
             .create()
             .map_err(|e| format!("creating kafka consumer: {}", e))?;
-        consumer.subscribe(&[&topic]).map_err(|e| e.to_string())?;
+        consumer.subscribe(&[topic]).map_err(|e| e.to_string())?;
 
         // Wait up to 10 seconds for each message.
         let message_stream = consumer
@@ -134,7 +149,7 @@ impl Action for VerifyAction {
             .timeout(Duration::from_secs(15));
         pin!(message_stream);
 
-        let mut actual_messages = vec![];
+        let mut messages = vec![];
 
         // Collect all messages that arrive without timing out. If we trip
         // the timeout, suppress the error and return what we have. This
@@ -146,13 +161,50 @@ impl Action for VerifyAction {
 
             let bytes = match message.payload() {
                 None => return Err("empty message payload".into()),
-                Some(bytes) => bytes,
+                Some(bytes) => bytes.to_vec(),
             };
-            let value_datum = avro_from_bytes(value_schema, bytes)?;
+            messages.push((message.key().map(|key| key.to_vec()), bytes));
+        }
+        Ok(messages)
+    }
+
+    /// Verifies Avro-encoded `messages` against the schemas registered for
+    /// `topic`.
+    async fn verify_avro(
+        &self,
+        state: &State,
+        topic: &str,
+        messages: Vec<(Option<Vec<u8>>, Vec<u8>)>,
+    ) -> Result<(), String> {
+        let value_schema = state
+            .ccsr_client
+            .get_schema_by_subject(&format!("{}-value", topic))
+            .await
+            .map_err(|e| format!("fetching schema: {}", e))?
+            .raw;
+
+        let key_schema = state
+            .ccsr_client
+            .get_schema_by_subject(&format!("{}-key", topic))
+            .await
+            .ok()
+            .map(|key_schema| {
+                avro::parse_schema(&key_schema.raw)
+                    .map_err(|e| format!("parsing avro schema: {}", e))
+            })
+            .transpose()?;
+
+        let value_schema =
+            avro::parse_schema(&value_schema).map_err(|e| format!("parsing avro schema: {}", e))?;
+        let value_schema = &value_schema;
+
+        let mut actual_messages = vec![];
+        for (key, bytes) in messages {
+            let value_datum = avro_from_bytes(value_schema, &bytes)?;
             let key_datum = key_schema
                 .as_ref()
                 .map(|key_schema| {
-                    let bytes = match message.key() {
+                    let bytes = match &key {
                         Some(key) => key,
                         None => return Err("empty message key".into()),
                     };
@@ -169,4 +221,57 @@ impl Action for VerifyAction {
             &actual_messages,
         )
     }
+
+    fn verify_json(&self, messages: Vec<(Option<Vec<u8>>, Vec<u8>)>) -> Result<(), String> {
+        let parse = |bytes: &[u8]| -> Result<serde_json::Value, String> {
+            serde_json::from_slice(bytes).map_err(|e| format!("parsing json message: {}", e))
+        };
+        let actual_messages = messages
+            .iter()
+            .map(|(key, value)| Ok((key.as_deref().map(parse).transpose()?, parse(value)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        // Each expected line contains the expected value, optionally preceded
+        // by the expected key.
+        let expected_messages = self
+            .expected_messages
+            .iter()
+            .map(|line| {
+                let mut values = serde_json::Deserializer::from_str(line)
+                    .into_iter()
+                    .collect::<Result<Vec<serde_json::Value>, _>>()
+                    .map_err(|e| format!("parsing json: {}", e))?;
+                match values.len() {
+                    1 => Ok((None, values.remove(0))),
+                    2 => {
+                        let value = values.remove(1);
+                        Ok((Some(values.remove(0)), value))
+                    }
+                    _ => Err(format!("expected a value and an optional key: {}", line)),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        for (i, (e, a)) in expected_messages.iter().zip(&actual_messages).enumerate() {
+            if e != a {
+                return Err(format!(
+                    "record {} did not match\nexpected:\n{:#?}\n\nactual:\n{:#?}",
+                    i, e, a
+                ));
+            }
+        }
+        if expected_messages.len() > actual_messages.len() {
+            Err(format!(
+                "missing records:\n{:#?}",
+                &expected_messages[actual_messages.len()..]
+            ))
+        } else if actual_messages.len() > expected_messages.len() {
+            Err(format!(
+                "extra records:\n{:#?}",
+                &actual_messages[expected_messages.len()..]
+            ))
+        } else {
+            Ok(())
+        }
+    }
 }
@@ -164,7 +164,7 @@ fn inline_views(dataflow: &mut DataflowDesc) {
         // `InlineLet` which probably wants a reworking in any case.
         // Re-run all optimizations on the composite views.
         optimizer
-            .transform(object.relation_expr.as_mut(), &indexes)
+            .transform(object.relation_expr.as_mut(), &indexes, None)
             .unwrap();
     }
 }
@@ -60,6 +60,26 @@ pub struct TransformArgs<'a> {
     pub id_gen: &'a mut IdGen,
     /// The indexes accessible.
     pub indexes: &'a HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>>,
+    /// The number of transforms that may yet be applied, if limited.
+    ///
+    /// Transforms that apply other transforms, like [`Fixpoint`], spend one
+    /// unit of fuel per application, and fail with
+    /// [`TransformError::FuelExhausted`] once the fuel runs out.
+    pub fuel: Option<&'a mut usize>,
+}
+
+impl TransformArgs<'_> {
+    /// Spends one unit of fuel, if fuel is limited.
+    fn consume_fuel(&mut self) -> Result<(), TransformError> {
+        match self.fuel.as_deref_mut() {
+            Some(0) => Err(TransformError::FuelExhausted),
+            Some(fuel) => {
+                *fuel -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
 }
 
 /// Types capable of transforming relation expressions.
@@ -86,6 +106,8 @@ pub enum TransformError {
     Eval(EvalError),
     /// An unstructured error.
     Internal(String),
+    /// The fuel supplied in [`TransformArgs::fuel`] ran out.
+    FuelExhausted,
 }
 
 impl fmt::Display for TransformError {
@@ -93,6 +115,7 @@ impl fmt::Display for TransformError {
         match self {
             TransformError::Eval(e) => write!(f, "{}", e),
             TransformError::Internal(msg) => write!(f, "internal transform error: {}", msg),
+            TransformError::FuelExhausted => f.write_str("optimizer fuel exhausted"),
         }
     }
 }
@@ -116,16 +139,18 @@ impl Transform for Fixpoint {
     fn transform(
         &self,
         relation: &mut RelationExpr,
-        args: TransformArgs,
+        mut args: TransformArgs,
     ) -> Result<(), TransformError> {
         for _ in 0..self.limit {
             let original = relation.clone();
             for transform in self.transforms.iter() {
+                args.consume_fuel()?;
                 transform.transform(
                     relation,
                     TransformArgs {
                         id_gen: args.id_gen,
                         indexes: args.indexes,
+                        fuel: args.fuel.as_deref_mut(),
                     },
                 )?;
             }
@@ -135,11 +160,13 @@ impl Transform for Fixpoint {
         }
         let original = relation.clone();
         for transform in self.transforms.iter() {
+            args.consume_fuel()?;
             transform.transform(
                 relation,
                 TransformArgs {
                     id_gen: args.id_gen,
                     indexes: args.indexes,
+                    fuel: args.fuel.as_deref_mut(),
                 },
             )?;
         }
@@ -166,22 +193,51 @@ pub struct Optimizer {
 
 impl Optimizer {
     /// Optimizes the supplied relation expression.
+    ///
+    /// If `fuel` is provided, at most that many transforms are applied. Once
+    /// the fuel runs out, the remaining transforms are skipped, except that
+    /// joins are always assigned an implementation so that the relation
+    /// expression can be rendered. Returns whether the fuel ran out.
     fn transform(
         &self,
         relation: &mut RelationExpr,
         indexes: &HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>>,
-    ) -> Result<(), TransformError> {
+        fuel: Option<&mut usize>,
+    ) -> Result<bool, TransformError> {
         let mut id_gen = Default::default();
+        let mut args = TransformArgs {
+            id_gen: &mut id_gen,
+            indexes,
+            fuel,
+        };
         for transform in self.transforms.iter() {
-            transform.transform(
-                relation,
-                TransformArgs {
-                    id_gen: &mut id_gen,
-                    indexes,
-                },
-            )?;
+            let res = args.consume_fuel().and_then(|()| {
+                transform.transform(
+                    relation,
+                    TransformArgs {
+                        id_gen: args.id_gen,
+                        indexes: args.indexes,
+                        fuel: args.fuel.as_deref_mut(),
+                    },
+                )
+            });
+            match res {
+                Ok(()) => (),
+                Err(TransformError::FuelExhausted) => {
+                    crate::join_implementation::JoinImplementation.transform(
+                        relation,
+                        TransformArgs {
+                            id_gen: args.id_gen,
+                            indexes: args.indexes,
+                            fuel: None,
+                        },
+                    )?;
+                    return Ok(true);
+                }
+                Err(e) => return Err(e),
+            }
         }
-        Ok(())
+        Ok(false)
     }
 }
 
@@ -275,10 +331,25 @@ impl Optimizer {
         mut relation: RelationExpr,
         indexes: &HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>>,
     ) -> Result<OptimizedRelationExpr, TransformError> {
-        self.transform(&mut relation, indexes)?;
+        self.transform(&mut relation, indexes, None)?;
         Ok(expr::OptimizedRelationExpr(relation))
     }
 
+    /// Like [`Optimizer::optimize`], but applies at most `fuel` transforms,
+    /// stopping with the best relation expression found so far once the fuel
+    /// runs out.
+    ///
+    /// Also returns whether the fuel ran out.
+    pub fn optimize_with_fuel(
+        &mut self,
+        mut relation: RelationExpr,
+        indexes: &HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>>,
+        mut fuel: usize,
+    ) -> Result<(OptimizedRelationExpr, bool), TransformError> {
+        let exhausted = self.transform(&mut relation, indexes, Some(&mut fuel))?;
+        Ok((expr::OptimizedRelationExpr(relation), exhausted))
+    }
+
     /// Simple fusion and elision transformations to render the query readable.
     pub fn pre_optimization() -> Self {
         let transforms: Vec<Box<dyn crate::Transform + Send>> = vec![
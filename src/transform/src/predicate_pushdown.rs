@@ -49,6 +49,7 @@
 //! PredicatePushdown.transform(&mut expr, TransformArgs {
 //!   id_gen: &mut Default::default(),
 //!   indexes: &std::collections::HashMap::new(),
+//!   fuel: None,
 //! });
 //! ```
 
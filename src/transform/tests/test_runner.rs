@@ -505,6 +505,7 @@ mod tests {
                 TransformArgs {
                     id_gen: &mut id_gen,
                     indexes: &indexes,
+                    fuel: None,
                 },
             )?;
         }
@@ -535,6 +536,7 @@ mod tests {
                         TransformArgs {
                             id_gen: &mut id_gen,
                             indexes: &indexes,
+                            fuel: None,
                         },
                     )?;
 